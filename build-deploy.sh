@@ -53,7 +53,8 @@ echo "[TASK] Build the catalog"
 cargo run --bin chordr --release -- \
   build-catalog \
   webchordr/app/static/songs \
-  webchordr/app/static/catalog.json
+  webchordr/app/static/catalog.json \
+  --search-index webchordr/app/static/search-index.json
 
 echo "[TASK] Create deploy-build"
 pushd webchordr/app || exit 1
@@ -16,6 +16,14 @@ pub struct Configuration {
     /// Path to the output directory
     pub output_directory: PathBuf,
 
+    /// Path to the cache of parsed songs (defaults to `<catalog_file>.cache` next to the catalog)
+    #[serde(default)]
+    pub catalog_cache_file: Option<PathBuf>,
+
+    /// Path to the prebuilt search index (defaults to `search-index.json` next to the catalog)
+    #[serde(default)]
+    pub search_index_file: Option<PathBuf>,
+
     /// Online service configuration (dropbox, WebDAV)
     pub service: ServiceConfiguration,
 }
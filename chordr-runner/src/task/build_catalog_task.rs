@@ -1,8 +1,8 @@
 use crate::configuration::Configuration;
 use crate::error::Error;
 use crate::task::{RecurringTaskTrait, TaskTrait};
-use libchordr::prelude::{CatalogBuildResult, CatalogBuilder, FileType};
-use log::info;
+use libchordr::prelude::{CatalogBuildResult, CatalogBuilder, FileType, PrebuiltSearchIndex};
+use log::{info, warn};
 use std::fs;
 
 pub struct BuildCatalogTask {
@@ -15,7 +15,15 @@ impl TaskTrait for BuildCatalogTask {
     where
         Self: Sized,
     {
-        let catalog_builder = CatalogBuilder::new();
+        let cache_file = match &configuration.catalog_cache_file {
+            Some(cache_file) => cache_file.clone(),
+            None => {
+                let mut cache_file = configuration.catalog_file.clone().into_os_string();
+                cache_file.push(".cache");
+                cache_file.into()
+            }
+        };
+        let catalog_builder = CatalogBuilder::with_cache_file(cache_file);
         Ok(Self {
             catalog_builder,
             configuration,
@@ -32,6 +40,9 @@ impl RecurringTaskTrait for BuildCatalogTask {
             FileType::Chorddown,
             true,
         )?;
+        for error in &catalog.errors {
+            warn!("{}", error);
+        }
 
         let serialization_result = if pretty {
             serde_json::to_string_pretty(&catalog.catalog)
@@ -48,9 +59,32 @@ impl RecurringTaskTrait for BuildCatalogTask {
             "Write catalog to {}",
             self.configuration.catalog_file.as_path().to_string_lossy()
         );
-        Ok(fs::write(
-            self.configuration.catalog_file.as_path(),
-            output,
-        )?)
+        fs::write(self.configuration.catalog_file.as_path(), output)?;
+
+        self.write_search_index(&catalog)
+    }
+}
+
+impl BuildCatalogTask {
+    /// Write the prebuilt search index, so that the clients do not have to index the songs
+    fn write_search_index(&self, catalog: &CatalogBuildResult) -> Result<(), Error> {
+        let search_index_file = match &self.configuration.search_index_file {
+            Some(search_index_file) => search_index_file.clone(),
+            None => self
+                .configuration
+                .catalog_file
+                .with_file_name("search-index.json"),
+        };
+        let search_index = PrebuiltSearchIndex::build_for_catalog(&catalog.catalog);
+        let output = match serde_json::to_string(&search_index) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::serialization_error(format!("{}", e))),
+        };
+
+        info!(
+            "Write search index to {}",
+            search_index_file.to_string_lossy()
+        );
+        Ok(fs::write(search_index_file, output)?)
     }
 }
@@ -1,8 +1,8 @@
 use std::convert::TryFrom;
 use std::error::Error as StdError;
 use std::fs;
-use std::fs::File;
-use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
 use std::process::exit;
 
 use ansi_term::Colour;
@@ -12,6 +12,8 @@ use log::LevelFilter;
 use simplelog::{ColorChoice, Config, TermLogger, TerminalMode};
 
 use libchordr::models::chord::fmt::Formatting;
+use libchordr::models::chord::NoteDisplay;
+use libchordr::models::structure::SectionType;
 use libchordr::modification::transposition::TransposableTrait;
 use libchordr::prelude::Error;
 use libchordr::prelude::Result;
@@ -32,7 +34,7 @@ fn main() {
         .arg(
             Arg::with_name("input")
                 .required(true)
-                .help("Chorddown file to parse"),
+                .help("Chorddown, ChordPro, SongBeamer or OpenLyrics file to parse"),
         )
         .arg(output_arg.clone())
         .arg(Arg::with_name("format").help(&format_help))
@@ -42,6 +44,26 @@ fn main() {
                 .takes_value(true)
                 .help("Number of semitones to transpose the song"),
         )
+        .arg(
+            Arg::with_name("arrangement")
+                .long("arrangement")
+                .takes_value(true)
+                .help("Render the sections in the order of the song's arrangement with the given name"),
+        )
+        .arg(
+            Arg::with_name("expand")
+                .long("expand")
+                .takes_value(true)
+                .possible_values(&["none", "annotated", "collapsed", "full"])
+                .help("Render references and repeats as a linear performance with the referenced sections inlined"),
+        )
+        .arg(
+            Arg::with_name("languages")
+                .long("languages")
+                .takes_value(true)
+                .possible_values(&["primary", "secondary", "interleaved"])
+                .help("Languages to display for songs with translations (default: `interleaved`)"),
+        )
         .arg(
             Arg::with_name("b-notation")
                 .long("b-notation")
@@ -52,7 +74,32 @@ fn main() {
             Arg::with_name("semitone-notation")
                 .long("semitone-notation")
                 .takes_value(true)
-                .help("Define if `#` or `b` should be used"),
+                .help("Define if `#` or `b` should be used (default: `auto` to use the notation of the song's key)"),
+        )
+        .arg(
+            Arg::with_name("chord-notation")
+                .long("chord-notation")
+                .takes_value(true)
+                .possible_values(&["letter", "nashville", "roman"])
+                .help("Define if chords are written as letters or relative to the song's key as Nashville numbers or Roman numerals"),
+        )
+        .arg(
+            Arg::with_name("capo")
+                .long("capo")
+                .takes_value(true)
+                .help("Display the chord shapes to play with a capo at the given fret (`suggest` to pick the fret for the song's key)"),
+        )
+        .arg(
+            Arg::with_name("diagrams")
+                .long("diagrams")
+                .takes_value(true)
+                .possible_values(&["guitar", "guitar-drop-d", "ukulele", "baritone-ukulele", "mandolin", "piano"])
+                .help("Append a legend with the chord diagrams for the given instrument (HTML output only)"),
+        )
+        .arg(
+            Arg::with_name("normalize-chords")
+                .long("normalize-chords")
+                .help("Write the chord suffixes in normalized spelling (e.g. `Cadd2` for `C2`)"),
         )
         .arg(verbosity_arg.clone());
 
@@ -70,6 +117,126 @@ fn main() {
                 .short("p")
                 .help("Output indented JSON"),
         )
+        .arg(
+            Arg::with_name("cache")
+                .long("cache")
+                .takes_value(true)
+                .help(
+                "Path to a cache file to only re-parse the files that changed since the last build",
+            ),
+        )
+        .arg(
+            Arg::with_name("search-index")
+                .long("search-index")
+                .takes_value(true)
+                .help("Path to write the prebuilt search index for the catalog to"),
+        )
+        .arg(verbosity_arg.clone());
+
+    let subcommand_convert_setlist = SubCommand::with_name("convert-setlist")
+        .about("Render a setlist into a PDF document")
+        .arg(
+            Arg::with_name("setlist")
+                .required(true)
+                .help("Path to the setlist JSON file"),
+        )
+        .arg(
+            Arg::with_name("dir")
+                .required(true)
+                .help("Path to the directory of chorddown files"),
+        )
+        .arg(output_arg.clone())
+        .arg(verbosity_arg.clone());
+
+    let lint_rules = LintRule::get_all()
+        .iter()
+        .map(LintRule::as_str)
+        .collect::<Vec<&str>>();
+    let lint_rule_arg = |name: &'static str, help: &'static str| {
+        Arg::with_name(name)
+            .long(name)
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .possible_values(&lint_rules)
+            .help(help)
+    };
+    let subcommand_lint = SubCommand::with_name("lint")
+        .about("Check chorddown files for common problems")
+        .arg(
+            Arg::with_name("path")
+                .required(true)
+                .help("Path to a chorddown file or a directory of chorddown files"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .help("Output format"),
+        )
+        .arg(lint_rule_arg("allow", "Disable the given rule"))
+        .arg(lint_rule_arg(
+            "warn",
+            "Report violations of the given rule as warning",
+        ))
+        .arg(lint_rule_arg(
+            "deny",
+            "Report violations of the given rule as error",
+        ))
+        .arg(
+            Arg::with_name("deny-warnings")
+                .long("deny-warnings")
+                .help("Exit with a non-zero code if there are warnings"),
+        )
+        .arg(verbosity_arg.clone());
+
+    let subcommand_fmt = SubCommand::with_name("fmt")
+        .about("Format chorddown files in the canonical style")
+        .arg(
+            Arg::with_name("paths")
+                .required(true)
+                .multiple(true)
+                .help("Chorddown files or directories of chorddown files to format"),
+        )
+        .arg(Arg::with_name("check").long("check").help(
+            "Do not write the files, but exit with a non-zero code if they are not formatted",
+        ))
+        .arg(verbosity_arg.clone());
+
+    let subcommand_analyze = SubCommand::with_name("analyze")
+        .about("Show the chords, the estimated key, the difficulty and statistics of songs")
+        .arg(
+            Arg::with_name("paths")
+                .required(true)
+                .multiple(true)
+                .help("Song files or directories of chorddown files to analyze"),
+        )
+        .arg(
+            Arg::with_name("instrument")
+                .long("instrument")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&[
+                    "guitar",
+                    "guitar-drop-d",
+                    "ukulele",
+                    "baritone-ukulele",
+                    "mandolin",
+                    "piano",
+                ])
+                .help("Score the difficulty for the given instrument (default: all instruments)"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .help("Output format"),
+        )
         .arg(verbosity_arg);
 
     let args = App::new("chordr")
@@ -80,6 +247,10 @@ fn main() {
         .setting(AppSettings::ColoredHelp)
         .subcommand(subcommand_convert)
         .subcommand(subcommand_build_catalog)
+        .subcommand(subcommand_convert_setlist)
+        .subcommand(subcommand_lint)
+        .subcommand(subcommand_fmt)
+        .subcommand(subcommand_analyze)
         .get_matches();
 
     if let Err(error) = run(args) {
@@ -95,6 +266,18 @@ fn run(args: ArgMatches<'_>) -> Result<()> {
     } else if let Some(matches) = args.subcommand_matches("build-catalog") {
         configure_logging(matches)?;
         build_catalog(matches)
+    } else if let Some(matches) = args.subcommand_matches("convert-setlist") {
+        configure_logging(matches)?;
+        convert_setlist(matches)
+    } else if let Some(matches) = args.subcommand_matches("lint") {
+        configure_logging(matches)?;
+        lint(matches)
+    } else if let Some(matches) = args.subcommand_matches("fmt") {
+        configure_logging(matches)?;
+        format_files(matches)
+    } else if let Some(matches) = args.subcommand_matches("analyze") {
+        configure_logging(matches)?;
+        analyze(matches)
     } else {
         eprintln!("Missing argument subcommand");
         exit(1);
@@ -113,6 +296,22 @@ fn convert(args: &ArgMatches<'_>) -> Result<()> {
         None => SemitoneNotation::default(),
         Some(b) => SemitoneNotation::try_from(b)?,
     };
+    let chord_notation = match args.value_of("chord-notation") {
+        None => ChordNotation::default(),
+        Some(c) => ChordNotation::try_from(c)?,
+    };
+    let chord_diagrams = match args.value_of("diagrams") {
+        None => None,
+        Some(i) => Some(Instrument::try_from(i)?),
+    };
+    let expansion = match args.value_of("expand") {
+        None => Expansion::default(),
+        Some(e) => Expansion::try_from(e)?,
+    };
+    let language_display = match args.value_of("languages") {
+        None => LanguageDisplay::default(),
+        Some(l) => LanguageDisplay::try_from(l)?,
+    };
 
     let transpose: Option<isize> = match args.value_of("transpose") {
         None => None,
@@ -121,33 +320,81 @@ fn convert(args: &ArgMatches<'_>) -> Result<()> {
 
     let format = get_output_format(args);
 
-    let formatting = Formatting {
+    let mut formatting = Formatting {
         b_notation,
         semitone_notation,
         format,
+        chord_notation,
+        capo: None,
+        requested_semitone_notation: None,
+        capo_semitone_notation: None,
+        key: None,
+        chord_diagrams,
+        expansion,
+        language_display,
+        normalize_chords: args.is_present("normalize-chords"),
     };
 
-    let file = match File::open(input_file_path) {
+    let file_type = FileType::try_from(Path::new(input_file_path)).unwrap_or(FileType::Chorddown);
+    let source = match fs::read_to_string(input_file_path) {
         Ok(c) => c,
         Err(e) => return Err(Error::unknown_error(format!("Could not read file: {}", e))),
     };
-    log::debug!("Did open file");
+    log::debug!("Did read file");
 
-    let (tokens, _warnings) = build_tokenizer().tokenize(BufReader::new(file))?;
+    let (tokens, warnings) =
+        build_tokenizer_for_file_type(file_type).tokenize_with_spans(source.as_bytes())?;
     log::debug!("Did tokenize content");
+    for warning in &warnings {
+        print_diagnostic(&Diagnostic::from(warning), input_file_path, &source);
+    }
 
-    let parser_result = Parser::new().parse(tokens)?;
+    let parser_result = match Parser::new().parse_with_spans(tokens) {
+        Ok(p) => p,
+        Err(error) if error.span().is_some() => {
+            print_diagnostic(&Diagnostic::from(&error), input_file_path, &source);
+            exit(1);
+        }
+        Err(error) => return Err(error),
+    };
     log::debug!("Did parse content");
-    let ParserResult { meta, node } = parser_result;
-    let parser_result_node = match transpose {
-        None => node,
+    let parser_result = match args.value_of("arrangement") {
+        None => parser_result,
+        Some(name) => {
+            let arranged = parser_result.with_arrangement(name)?;
+            log::debug!("Did arrange");
+            arranged
+        }
+    };
+    let ParserResult { meta, node, .. } = parser_result;
+    let meta = match Path::new(input_file_path).file_stem() {
+        Some(file_stem) => meta.with_default_title(file_stem.to_string_lossy()),
+        None => meta,
+    };
+    let (parser_result_node, meta) = match transpose {
+        None => (node, meta),
         Some(t) => {
-            let transposed = node.transpose(t);
+            // Transpose the meta data as well, so the key matches the transposed chords
+            let transposed = (node.transpose(t), meta.transpose(t));
             log::debug!("Did transpose");
             transposed
         }
     };
 
+    formatting.capo = match args.value_of("capo") {
+        None => None,
+        Some("suggest") => meta.key().map(|key| Capo::suggest(&key)),
+        Some(raw) => Some(Capo::try_from(raw)?),
+    };
+
+    if format.is_binary() {
+        let converted =
+            Converter::new().convert_to_bytes(&parser_result_node, &meta, formatting)?;
+        log::debug!("Did convert content");
+
+        return handle_binary_output(output_file_path, converted);
+    }
+
     let converted = Converter::new().convert(&parser_result_node, &meta, formatting)?;
     log::debug!("Did convert content");
 
@@ -180,6 +427,278 @@ fn convert(args: &ArgMatches<'_>) -> Result<()> {
     handle_output(output_file_path, output)
 }
 
+fn lint(args: &ArgMatches<'_>) -> Result<()> {
+    let path = Path::new(args.value_of("path").unwrap());
+
+    let mut config = LintConfig::new();
+    for rule in args.values_of("allow").into_iter().flatten() {
+        config.disable(LintRule::try_from(rule)?);
+    }
+    for rule in args.values_of("warn").into_iter().flatten() {
+        config.set_severity(LintRule::try_from(rule)?, Severity::Warning);
+    }
+    for rule in args.values_of("deny").into_iter().flatten() {
+        config.set_severity(LintRule::try_from(rule)?, Severity::Error);
+    }
+
+    let linter = Linter::new(config);
+    let reports = if path.is_dir() {
+        linter.lint_directory(path)?
+    } else {
+        vec![linter.lint_file(path)?]
+    };
+
+    let messages = reports.iter().flat_map(|report| &report.messages);
+    let error_count = messages
+        .clone()
+        .filter(|message| message.diagnostic.severity() == Severity::Error)
+        .count();
+    let warning_count = messages.count() - error_count;
+
+    if args.value_of("format") == Some("json") {
+        let output = serde_json::to_string_pretty(&reports).map_err(|e| {
+            Error::unknown_error(format!("Could not serialize the lint report: {}", e))
+        })?;
+        println!("{}", output);
+    } else {
+        for report in &reports {
+            let path = report.path.to_string_lossy();
+            for message in &report.messages {
+                print_rendered_diagnostic(
+                    message.diagnostic.severity(),
+                    &message.render(&path, &report.source),
+                );
+                eprintln!();
+            }
+        }
+        eprintln!(
+            "Checked {} file(s): {} error(s), {} warning(s)",
+            reports.len(),
+            error_count,
+            warning_count
+        );
+    }
+
+    if error_count > 0 || (args.is_present("deny-warnings") && warning_count > 0) {
+        exit(1);
+    }
+
+    Ok(())
+}
+
+fn format_files(args: &ArgMatches<'_>) -> Result<()> {
+    let check = args.is_present("check");
+
+    let mut song_files = vec![];
+    for path in args.values_of("paths").unwrap().map(Path::new) {
+        if path.is_dir() {
+            song_files.extend(CatalogBuilder::new().find_song_files(
+                path,
+                FileType::Chorddown,
+                true,
+            )?);
+        } else {
+            song_files.push(path.to_path_buf());
+        }
+    }
+
+    let formatter = ChorddownFormatter::new();
+    let mut unformatted_count = 0;
+    let mut failed_count = 0;
+    for song_file in &song_files {
+        let path = song_file.to_string_lossy();
+        let source = fs::read_to_string(song_file)
+            .map_err(|e| Error::unknown_error(format!("Could not read file {}: {}", path, e)))?;
+        let formatted = match formatter.format(&source) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                print_diagnostic(&Diagnostic::from(&error), &path, &source);
+                failed_count += 1;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+
+        unformatted_count += 1;
+        if check {
+            println!("Would reformat {}", path);
+        } else {
+            fs::write(song_file, formatted).map_err(|e| {
+                Error::unknown_error(format!("Could not write file {}: {}", path, e))
+            })?;
+            println!("Formatted {}", path);
+        }
+    }
+
+    if failed_count > 0 || (check && unformatted_count > 0) {
+        exit(1);
+    }
+
+    Ok(())
+}
+
+fn analyze(args: &ArgMatches<'_>) -> Result<()> {
+    let mut song_files = vec![];
+    for path in args.values_of("paths").unwrap().map(Path::new) {
+        if path.is_dir() {
+            song_files.append(&mut CatalogBuilder::new().find_song_files(
+                path,
+                FileType::Chorddown,
+                true,
+            )?);
+        } else {
+            song_files.push(path.to_path_buf());
+        }
+    }
+
+    let analyzer = match args.values_of("instrument") {
+        None => Analyzer::new(),
+        Some(values) => Analyzer::with_instruments(
+            values
+                .map(Instrument::try_from)
+                .collect::<std::result::Result<_, _>>()?,
+        ),
+    };
+
+    let mut results = vec![];
+    for song_file in &song_files {
+        let path = song_file.to_string_lossy();
+        let source = fs::read_to_string(song_file)
+            .map_err(|e| Error::unknown_error(format!("Could not read file {}: {}", path, e)))?;
+        let file_type = FileType::try_from(song_file.as_path()).unwrap_or(FileType::Chorddown);
+        let parser_result = parse_content_for_file_type(source.as_bytes(), file_type)?;
+        let meta = parser_result.meta_as_ref();
+        let analysis = analyzer.analyze(parser_result.node_as_ref(), meta);
+        let statistics = analyzer.statistics(parser_result.node_as_ref(), meta);
+
+        results.push((
+            path.to_string(),
+            meta.title(),
+            meta.key(),
+            meta.b_notation(),
+            analysis,
+            statistics,
+        ));
+    }
+
+    if args.value_of("format") == Some("json") {
+        let output: Vec<_> = results
+            .iter()
+            .map(|(path, title, _, _, analysis, statistics)| {
+                serde_json::json!({
+                    "path": path,
+                    "title": title,
+                    "analysis": analysis,
+                    "statistics": statistics,
+                })
+            })
+            .collect();
+        let output = serde_json::to_string_pretty(&output).map_err(|e| {
+            Error::unknown_error(format!("Could not serialize the analysis: {}", e))
+        })?;
+        println!("{}", output);
+
+        return Ok(());
+    }
+
+    let mut songs = vec![];
+    for (path, title, key, b_notation, analysis, statistics) in &results {
+        let estimate = analysis.estimated_key();
+        // Write the chords with the accidentals of the song's (estimated) key
+        let formatting = Formatting {
+            b_notation: *b_notation,
+            ..Formatting::default()
+        }
+        .for_key(key.as_ref().or(estimate.map(|estimate| estimate.key())));
+        let mut rows = vec![("File:".to_owned(), path.to_owned())];
+        let key = match (key, estimate) {
+            (Some(key), _) => key.note_format(formatting),
+            (None, Some(estimate)) => format!(
+                "{} (estimated, {:.0}% of the chords fit)",
+                estimate.key().note_format(formatting),
+                estimate.confidence() * 100.0
+            ),
+            (None, None) => "unknown".to_owned(),
+        };
+        rows.push(("Key:".to_owned(), key));
+        let chords: Vec<String> = analysis
+            .chords()
+            .iter()
+            .map(|usage| {
+                format!(
+                    "{} ({}x)",
+                    usage.chord().note_format(formatting),
+                    usage.count()
+                )
+            })
+            .collect();
+        rows.push(("Chords:".to_owned(), chords.join(", ")));
+        let sections = statistics.sections();
+        rows.push((
+            "Sections:".to_owned(),
+            format!(
+                "{} verse(s), {} chorus(es), {} bridge(s), {} reference(s)",
+                sections.count(SectionType::Verse),
+                sections.count(SectionType::Chorus),
+                sections.count(SectionType::Bridge),
+                sections.count(SectionType::Reference)
+            ),
+        ));
+        rows.push((
+            "Lyrics:".to_owned(),
+            format!(
+                "{} line(s), {} word(s)",
+                statistics.line_count(),
+                statistics.word_count()
+            ),
+        ));
+        let duration = statistics.estimated_duration().as_secs();
+        rows.push((
+            "Duration:".to_owned(),
+            format!("{}:{:02} (estimated)", duration / 60, duration % 60),
+        ));
+        for difficulty in analysis.difficulties() {
+            let hard_chords: Vec<String> = difficulty
+                .hard_chords()
+                .iter()
+                .map(|chord| chord.note_format(formatting))
+                .collect();
+            let mut value = format!(
+                "{:.1} ({})",
+                difficulty.score(),
+                difficulty.level().as_str()
+            );
+            if !hard_chords.is_empty() {
+                value.push_str(&format!(", hard chords: {}", hard_chords.join(", ")));
+            }
+            rows.push((format!("{}:", difficulty.instrument()), value));
+        }
+        songs.push((title.as_deref().unwrap_or(path), rows));
+    }
+
+    // Align the values of all songs after the longest label
+    let width = songs
+        .iter()
+        .flat_map(|(_, rows)| rows.iter().map(|(label, _)| label.chars().count()))
+        .max()
+        .unwrap_or_default();
+    for (title, rows) in songs {
+        if atty::is(Stream::Stdout) {
+            println!("{}", Colour::White.bold().paint(title));
+        } else {
+            println!("{}", title);
+        }
+        for (label, value) in rows {
+            println!("  {:<width$} {}", label, value, width = width);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
 fn get_output_format_help() -> String {
     format!("Output format (one of {})", get_valid_output_format_help())
 }
@@ -214,9 +733,13 @@ fn build_catalog(args: &ArgMatches<'_>) -> Result<()> {
     let dir_path = args.value_of("dir").unwrap();
     let pretty = args.is_present("pretty");
     let output_file_path = args.value_of("output").unwrap();
+    let catalog_builder = match args.value_of("cache") {
+        Some(cache_file) => CatalogBuilder::with_cache_file(cache_file),
+        None => CatalogBuilder::new(),
+    };
 
     let catalog_result =
-        CatalogBuilder::new().build_catalog_for_directory(dir_path, FileType::Chorddown, true)?;
+        catalog_builder.build_catalog_for_directory(dir_path, FileType::Chorddown, true)?;
 
     let serialization_result = if pretty {
         serde_json::to_string_pretty(&catalog_result.catalog)
@@ -237,20 +760,61 @@ fn build_catalog(args: &ArgMatches<'_>) -> Result<()> {
     handle_output(output_file_path, output)?;
 
     if !output_to_stdout(output_file_path) {
-        let msg = format!(
+        print_success(format!(
             "Successfully saved the catalog revision '{}' at {}",
             catalog_result.catalog.revision(),
             output_file_path
-        );
-        if atty::is(Stream::Stdout) {
-            println!("{}", Colour::Green.paint(msg));
-        } else {
-            println!("{}", msg);
+        ));
+    }
+
+    if let Some(search_index_file_path) = args.value_of("search-index") {
+        let search_index = PrebuiltSearchIndex::build_for_catalog(&catalog_result.catalog);
+        let output = serde_json::to_string(&search_index)
+            .map_err(|e| Error::unknown_error(format!("{}", e)))?;
+        handle_output(search_index_file_path, output)?;
+
+        if !output_to_stdout(search_index_file_path) {
+            print_success(format!(
+                "Successfully saved the search index at {}",
+                search_index_file_path
+            ));
         }
     }
     Ok(())
 }
 
+fn print_success(msg: String) {
+    if atty::is(Stream::Stdout) {
+        println!("{}", Colour::Green.paint(msg));
+    } else {
+        println!("{}", msg);
+    }
+}
+
+fn convert_setlist(args: &ArgMatches<'_>) -> Result<()> {
+    let setlist_file_path = args.value_of("setlist").unwrap();
+    let dir_path = args.value_of("dir").unwrap();
+    let output_file_path = args.value_of("output").unwrap();
+
+    let setlist_content = fs::read_to_string(setlist_file_path)
+        .map_err(|e| Error::unknown_error(format!("Could not read file: {}", e)))?;
+    let setlist: Setlist = serde_json::from_str(&setlist_content)
+        .map_err(|e| Error::setlist_error(format!("Could not read setlist: {}", e)))?;
+    log::debug!("Did read setlist");
+
+    let catalog_result =
+        CatalogBuilder::new().build_catalog_for_directory(dir_path, FileType::Chorddown, true)?;
+    for error in catalog_result.errors {
+        handle_error_output(error)
+    }
+    log::debug!("Did build catalog");
+
+    let converted = PdfConverter {}.convert_setlist(&setlist, &catalog_result.catalog)?;
+    log::debug!("Did convert setlist");
+
+    handle_binary_output(output_file_path, converted)
+}
+
 fn configure_logging(matches: &ArgMatches<'_>) -> Result<()> {
     let level_filter = match matches.occurrences_of("verbosity") {
         0 => LevelFilter::Warn,
@@ -270,6 +834,11 @@ fn configure_logging(matches: &ArgMatches<'_>) -> Result<()> {
 }
 
 fn handle_error_output(error: CatalogBuildError) {
+    if let Some(rendered) = error.render_diagnostic() {
+        print_rendered_diagnostic(Severity::Error, &rendered);
+        return;
+    }
+
     let header = format!(
         "Error during analysis of file {}:",
         error.path().to_string_lossy()
@@ -288,6 +857,25 @@ fn handle_error_output(error: CatalogBuildError) {
     }
 }
 
+fn print_diagnostic(diagnostic: &Diagnostic, path: &str, source: &str) {
+    print_rendered_diagnostic(diagnostic.severity(), &diagnostic.render(path, source));
+}
+
+fn print_rendered_diagnostic(severity: Severity, rendered: &str) {
+    if !atty::is(Stream::Stderr) {
+        eprintln!("{}", rendered);
+        return;
+    }
+
+    let colour = match severity {
+        Severity::Error => Colour::Red,
+        Severity::Warning => Colour::Yellow,
+    };
+    let (header, excerpt) = rendered.split_once('\n').unwrap_or((rendered, ""));
+    eprintln!("{}", colour.bold().paint(header));
+    eprintln!("{}", Colour::Blue.paint(excerpt));
+}
+
 fn handle_output(output_file_path: &str, output: String) -> Result<(), Error> {
     if output_to_stdout(output_file_path) {
         println!("{}", output);
@@ -300,6 +888,19 @@ fn handle_output(output_file_path: &str, output: String) -> Result<(), Error> {
     }
 }
 
+fn handle_binary_output(output_file_path: &str, output: Vec<u8>) -> Result<(), Error> {
+    if output_to_stdout(output_file_path) {
+        std::io::stdout()
+            .write_all(&output)
+            .map_err(|e| Error::unknown_error(format!("Could not write output: {}", e)))
+    } else {
+        match fs::write(output_file_path, output) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::unknown_error(format!("Could not write file: {}", e))),
+        }
+    }
+}
+
 fn output_to_stdout(output_file_path: &str) -> bool {
     output_file_path == "-"
 }
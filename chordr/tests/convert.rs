@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const SONG: &str = "# Swing Low
+Key: C
+
+## Verse
+[C]Swing [A#]low, [F]sweet [Am]chari[G7]ot
+";
+
+fn write_song(name: &str, content: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("chordr-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("swing-low.chorddown");
+    fs::write(&path, content).unwrap();
+
+    path
+}
+
+fn analyze(name: &str, content: &str, args: &[&str]) -> String {
+    let song_file = write_song(name, content);
+    let output = Command::new(env!("CARGO_BIN_EXE_chordr"))
+        .arg("analyze")
+        .arg(&song_file)
+        .args(args)
+        .output()
+        .unwrap();
+    let _ = fs::remove_dir_all(song_file.parent().unwrap());
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn convert(name: &str, args: &[&str]) -> String {
+    convert_song(name, SONG, "chorddown", args)
+}
+
+fn convert_song(name: &str, content: &str, format: &str, args: &[&str]) -> String {
+    let song_file = write_song(name, content);
+    let output = Command::new(env!("CARGO_BIN_EXE_chordr"))
+        .arg("convert")
+        .arg(&song_file)
+        .args(["-", format])
+        .args(args)
+        .output()
+        .unwrap();
+    let _ = fs::remove_dir_all(song_file.parent().unwrap());
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_convert_with_transposition() {
+    let output = convert("transpose", &["--transpose", "5"]);
+
+    assert!(output.contains("Key: F"), "{}", output);
+    assert!(
+        output.contains("[F]Swing [Eb]low, [Bb]sweet [Dm]chari[C7]ot"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_convert_with_transposition_and_nashville_notation() {
+    let output = convert(
+        "nashville",
+        &["--transpose", "2", "--chord-notation", "nashville"],
+    );
+
+    assert!(output.contains("Key: D"), "{}", output);
+    assert!(
+        output.contains("[1]Swing [b7]low, [4]sweet [6m]chari[5^7]ot"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_convert_to_open_lyrics_without_title() {
+    let output = convert_song(
+        "open-lyrics",
+        "## Verse\n[D]Swing low, sweet chariot\n",
+        "openlyrics",
+        &[],
+    );
+
+    assert!(output.contains("<title>swing-low</title>"), "{}", output);
+}
+
+#[test]
+fn test_convert_with_chord_normalization() {
+    let song = "# Swing Low\n\n## Verse\n[C2]Swing [FΔ7]low\n";
+
+    let output = convert_song("written-chords", song, "chorddown", &[]);
+    assert!(output.contains("[C2]Swing [FΔ7]low"), "{}", output);
+
+    let output = convert_song(
+        "normalized-chords",
+        song,
+        "chorddown",
+        &["--normalize-chords"],
+    );
+    assert!(output.contains("[Cadd2]Swing [Fmaj7]low"), "{}", output);
+}
+
+#[test]
+fn test_analyze() {
+    let song = "# Swing Low\nKey: Eb\n\n## Verse\n[Ebmaj9]Swing [Bb]low, [Ab]sweet [Cm]chariot\n";
+    let output = analyze(
+        "analyze",
+        song,
+        &["--instrument", "guitar", "--instrument", "baritone-ukulele"],
+    );
+
+    assert!(output.contains("Ebmaj9 (1x)"), "{}", output);
+    assert!(!output.contains('#'), "{}", output);
+    // The values are aligned after the longest label
+    assert!(output.contains("\n  Key:              Eb\n"), "{}", output);
+    assert!(output.contains("\n  Baritone Ukulele: "), "{}", output);
+}
@@ -2,9 +2,7 @@ use std::env;
 use std::path::PathBuf;
 use std::rc::Rc;
 
-use libchordr::prelude::{
-    CatalogBuilder, FileType, ListEntryTrait, SearchIndex, SongData, SongSorting,
-};
+use libchordr::prelude::{CatalogBuilder, FileType, ListEntryTrait, SearchIndex, SongData};
 
 fn main() {
     let args = env::args().collect::<Vec<String>>();
@@ -29,8 +27,14 @@ fn main() {
     if verbose {
         println!("{:?}", search_results)
     } else {
-        for song in search_results.sort_by_title() {
-            println!("{} (ID: '{}')", song.title(), song.id())
+        for result in search_results {
+            let song = result.song();
+            println!(
+                "{} (ID: '{}', score: {:.2})",
+                song.title(),
+                song.id(),
+                result.score()
+            )
         }
     }
 }
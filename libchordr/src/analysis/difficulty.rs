@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::chord::{Chord, Note};
+use crate::models::diagram::{ChordDefinition, Diagram, Fingering, Instrument};
+use crate::models::meta::BNotation;
+
+use super::ChordUsage;
+
+/// Cost of a chord without a diagram
+const UNKNOWN_CHORD_COST: f32 = 3.0;
+
+/// Cost added for every distinct chord of the song
+const VOCABULARY_COST: f32 = 0.1;
+
+/// Minimum cost of a chord that is listed in `Difficulty::hard_chords`
+const HARD_CHORD_COST: f32 = 2.5;
+
+/// Rough classification of the difficulty score
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DifficultyLevel {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl DifficultyLevel {
+    fn from_score(score: f32) -> Self {
+        if score < 2.0 {
+            Self::Beginner
+        } else if score < 3.0 {
+            Self::Intermediate
+        } else {
+            Self::Advanced
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Beginner => "beginner",
+            Self::Intermediate => "intermediate",
+            Self::Advanced => "advanced",
+        }
+    }
+}
+
+/// Playability of a song on an instrument
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Difficulty {
+    instrument: Instrument,
+    score: f32,
+    level: DifficultyLevel,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hard_chords: Vec<Chord>,
+}
+
+impl Difficulty {
+    pub fn instrument(&self) -> Instrument {
+        self.instrument
+    }
+
+    /// Return the score (`1.0` for a song with a single easy chord, higher is harder)
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+
+    pub fn level(&self) -> DifficultyLevel {
+        self.level
+    }
+
+    /// Return the chords that are hard to play (e.g. barre chords on the guitar)
+    pub fn hard_chords(&self) -> &[Chord] {
+        &self.hard_chords
+    }
+}
+
+/// Score the chords for the instrument
+///
+/// The score is the average cost of the played chords plus a small amount for every distinct
+/// chord. The cost of a chord is based on the fingering the chord diagrams would show
+pub(super) fn difficulty(
+    usages: &[ChordUsage],
+    instrument: Instrument,
+    definitions: &[ChordDefinition],
+    b_notation: BNotation,
+) -> Difficulty {
+    let total: usize = usages.iter().map(ChordUsage::count).sum();
+    let mut weighted_cost = 0.0;
+    let mut hard_chords = vec![];
+    for usage in usages {
+        let cost = match Diagram::for_chord(usage.chord(), instrument, definitions, b_notation) {
+            Some(Diagram::Fretted(fingering)) => fingering_cost(&fingering),
+            Some(Diagram::Keyboard(keys)) => keyboard_cost(&keys),
+            None => UNKNOWN_CHORD_COST,
+        };
+        weighted_cost += cost * usage.count() as f32;
+        if cost >= HARD_CHORD_COST {
+            hard_chords.push(usage.chord().clone());
+        }
+    }
+
+    let score = if total == 0 {
+        0.0
+    } else {
+        weighted_cost / total as f32 + VOCABULARY_COST * usages.len() as f32
+    };
+    let score = (score * 10.0).round() / 10.0;
+
+    Difficulty {
+        instrument,
+        score,
+        level: DifficultyLevel::from_score(score),
+        hard_chords,
+    }
+}
+
+/// Return the cost of a fingering on a fretted instrument
+///
+/// Barre chords, wide stretches, high positions and chords with many fretted strings are harder
+/// to play
+fn fingering_cost(fingering: &Fingering) -> f32 {
+    let played: Vec<u8> = fingering.frets().iter().flatten().copied().collect();
+    let fretted: Vec<u8> = played.iter().copied().filter(|fret| *fret > 0).collect();
+    let (min, max) = match (fretted.iter().min(), fretted.iter().max()) {
+        (Some(min), Some(max)) => (*min, *max),
+        _ => return 1.0,
+    };
+
+    let mut cost = 1.0;
+    if is_barre(fingering, &played, min) {
+        cost += 2.0;
+    } else if fretted.len() >= 4 {
+        cost += 0.5;
+    }
+    if max - min >= 3 {
+        cost += 1.0;
+    }
+    if min > 4 {
+        cost += 0.5;
+    }
+
+    cost
+}
+
+/// Return if a finger has to press down multiple strings
+fn is_barre(fingering: &Fingering, played: &[u8], min: u8) -> bool {
+    let fingers: Vec<u8> = fingering.fingers().iter().flatten().copied().collect();
+    if !fingers.is_empty() {
+        return fingers
+            .iter()
+            .enumerate()
+            .any(|(index, finger)| fingers[index + 1..].contains(finger));
+    }
+
+    // Without open strings the lowest fret is usually played with the index finger
+    !played.contains(&0) && played.iter().filter(|fret| **fret == min).count() >= 2
+}
+
+/// Return the cost of a chord on the keyboard
+///
+/// Chords with black keys are harder to play
+fn keyboard_cost(keys: &[u8]) -> f32 {
+    let black_keys = keys
+        .iter()
+        .filter(|key| {
+            matches!(
+                Note::from(**key as isize + 1),
+                Note::Cis | Note::Dis | Note::Fis | Note::Gis | Note::Ais
+            )
+        })
+        .count();
+
+    1.0 + 0.5 * black_keys as f32
+}
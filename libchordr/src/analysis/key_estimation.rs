@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::chord::{Chord, Note, Quality};
+
+use super::ChordUsage;
+
+/// Triads of the major scale, indexed by the interval from the key's root
+///
+/// `None` marks roots outside of the scale
+const MAJOR_DEGREES: [Option<Triad>; 12] = [
+    Some(Triad::Major),
+    None,
+    Some(Triad::Minor),
+    None,
+    Some(Triad::Minor),
+    Some(Triad::Major),
+    None,
+    Some(Triad::Major),
+    None,
+    Some(Triad::Minor),
+    None,
+    Some(Triad::Diminished),
+];
+
+/// Degrees of the natural minor scale (the major fifth of harmonic minor is accepted as well)
+const MINOR_DEGREES: [Option<Triad>; 12] = [
+    Some(Triad::Minor),
+    None,
+    Some(Triad::Diminished),
+    Some(Triad::Major),
+    None,
+    Some(Triad::Minor),
+    None,
+    Some(Triad::Minor),
+    Some(Triad::Major),
+    None,
+    Some(Triad::Major),
+    None,
+];
+
+/// Bonus for a song starting or ending with the tonic chord
+const TONIC_BONUS: f32 = 0.15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Triad {
+    Major,
+    Minor,
+    Diminished,
+}
+
+/// Key estimated from the chords of a song
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct KeyEstimate {
+    key: Chord,
+    confidence: f32,
+}
+
+impl KeyEstimate {
+    /// Return the estimated key (`Am` for A minor)
+    pub fn key(&self) -> &Chord {
+        &self.key
+    }
+
+    /// Return the share of the played chords that belong to the key (`0.0` to `1.0`)
+    pub fn confidence(&self) -> f32 {
+        self.confidence
+    }
+}
+
+/// Estimate the key from the distribution of the chords
+///
+/// Every major and minor key is scored by the number of chords that belong to its scale. Songs
+/// usually start or end on the tonic, which decides between relative major and minor keys
+pub(super) fn estimate_key(
+    usages: &[ChordUsage],
+    first: Option<&Chord>,
+    last: Option<&Chord>,
+) -> Option<KeyEstimate> {
+    let total: usize = usages.iter().map(ChordUsage::count).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut best: Option<(f32, f32, Chord)> = None;
+    for minor in [false, true] {
+        for root in ALL_NOTES {
+            let degrees = if minor {
+                &MINOR_DEGREES
+            } else {
+                &MAJOR_DEGREES
+            };
+            let tonic = if minor { Triad::Minor } else { Triad::Major };
+
+            let diatonic: usize = usages
+                .iter()
+                .filter(|usage| fits(usage.chord(), root, degrees, minor))
+                .map(ChordUsage::count)
+                .sum();
+            let confidence = diatonic as f32 / total as f32;
+            let is_tonic = |chord: Option<&Chord>| {
+                chord.is_some_and(|c| c.root() == root && fits_triad(c, tonic))
+            };
+            let score = confidence
+                + if is_tonic(first) { TONIC_BONUS } else { 0.0 }
+                + if is_tonic(last) { TONIC_BONUS } else { 0.0 };
+
+            if best
+                .as_ref()
+                .is_none_or(|(best_score, ..)| score > *best_score)
+            {
+                let key = if minor {
+                    Chord::new_with_variant(root, "m")
+                } else {
+                    Chord::new_without_variant(root)
+                };
+                best = Some((score, confidence, key));
+            }
+        }
+    }
+
+    best.map(|(_, confidence, key)| KeyEstimate {
+        key,
+        confidence: (confidence * 100.0).round() / 100.0,
+    })
+}
+
+const ALL_NOTES: [Note; 12] = [
+    Note::C,
+    Note::Cis,
+    Note::D,
+    Note::Dis,
+    Note::E,
+    Note::F,
+    Note::Fis,
+    Note::G,
+    Note::Gis,
+    Note::A,
+    Note::Ais,
+    Note::B,
+];
+
+fn fits(chord: &Chord, root: Note, degrees: &[Option<Triad>; 12], minor: bool) -> bool {
+    let interval = (i32::from(chord.root()) - i32::from(root)).rem_euclid(12) as usize;
+    match degrees[interval] {
+        // The dominant of a minor key is often played as major chord (e.g. `E7` in A minor)
+        Some(Triad::Minor) if minor && interval == 7 => {
+            fits_triad(chord, Triad::Minor) || fits_triad(chord, Triad::Major)
+        }
+        Some(triad) => fits_triad(chord, triad),
+        None => false,
+    }
+}
+
+fn fits_triad(chord: &Chord, triad: Triad) -> bool {
+    match chord.quality() {
+        Quality::Major | Quality::Augmented => triad == Triad::Major,
+        Quality::Minor => triad == Triad::Minor,
+        Quality::Diminished => triad == Triad::Diminished,
+        // Chords without a third fit major and minor degrees
+        Quality::Suspended2 | Quality::Suspended4 | Quality::Power => triad != Triad::Diminished,
+    }
+}
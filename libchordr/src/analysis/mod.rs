@@ -0,0 +1,319 @@
+//! Analysis of songs
+//!
+//! The `Analyzer` collects the chord vocabulary of a song, estimates its key and scores how hard
+//! the song is to play on each instrument. It also computes statistics like the number of words
+//! and the estimated duration
+use serde::{Deserialize, Serialize};
+
+use crate::models::chord::{Chord, Chords};
+use crate::models::diagram::Instrument;
+use crate::models::meta::MetaTrait;
+use crate::parser::Node;
+
+pub use self::difficulty::{Difficulty, DifficultyLevel};
+pub use self::key_estimation::KeyEstimate;
+pub(crate) use self::statistics::{parse_duration, parse_tempo};
+pub use self::statistics::{SectionSummary, SongStatistics};
+
+mod difficulty;
+mod key_estimation;
+mod statistics;
+
+/// Number of times a chord is played in a song
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ChordUsage {
+    chord: Chord,
+    count: usize,
+}
+
+impl ChordUsage {
+    pub fn chord(&self) -> &Chord {
+        &self.chord
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Result of the `Analyzer`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SongAnalysis {
+    /// Used chords in the order of their first occurrence
+    chords: Vec<ChordUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    estimated_key: Option<KeyEstimate>,
+    /// Estimated key of a song without a `Key` header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    suggested_key: Option<Chord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    difficulties: Vec<Difficulty>,
+}
+
+impl SongAnalysis {
+    /// Return the used chords in the order of their first occurrence
+    pub fn chords(&self) -> &[ChordUsage] {
+        &self.chords
+    }
+
+    /// Return the key estimated from the chord distribution
+    pub fn estimated_key(&self) -> Option<&KeyEstimate> {
+        self.estimated_key.as_ref()
+    }
+
+    /// Return the estimated key, if the song does not declare a key
+    pub fn suggested_key(&self) -> Option<&Chord> {
+        self.suggested_key.as_ref()
+    }
+
+    pub fn difficulties(&self) -> &[Difficulty] {
+        &self.difficulties
+    }
+
+    /// Return the difficulty of the song on the given instrument
+    pub fn difficulty(&self, instrument: Instrument) -> Option<&Difficulty> {
+        self.difficulties
+            .iter()
+            .find(|difficulty| difficulty.instrument() == instrument)
+    }
+}
+
+/// Analyzer for the chords of a parsed song
+pub struct Analyzer {
+    instruments: Vec<Instrument>,
+}
+
+impl Analyzer {
+    /// Build an analyzer scoring the difficulty for all instruments
+    pub fn new() -> Self {
+        Self {
+            instruments: Instrument::get_all().to_vec(),
+        }
+    }
+
+    /// Build an analyzer scoring the difficulty for the given instruments only
+    pub fn with_instruments(instruments: Vec<Instrument>) -> Self {
+        Self { instruments }
+    }
+
+    /// Analyze the chords of the Node tree
+    ///
+    /// The song's chord definitions are used to score the difficulty and the key is only
+    /// suggested if the song does not declare one
+    pub fn analyze(&self, node: &Node, meta: &dyn MetaTrait) -> SongAnalysis {
+        let mut played = vec![];
+        collect_chords(node, &mut played);
+
+        let mut chords: Vec<ChordUsage> = vec![];
+        for chord in &played {
+            match chords.iter_mut().find(|usage| usage.chord == *chord) {
+                Some(usage) => usage.count += 1,
+                None => chords.push(ChordUsage {
+                    chord: chord.clone(),
+                    count: 1,
+                }),
+            }
+        }
+
+        let estimated_key = key_estimation::estimate_key(&chords, played.first(), played.last());
+        let suggested_key = match meta.key() {
+            Some(_) => None,
+            None => estimated_key
+                .as_ref()
+                .map(|estimate| estimate.key().clone()),
+        };
+        let difficulties = if chords.is_empty() {
+            vec![]
+        } else {
+            let definitions = meta.chord_definitions();
+            self.instruments
+                .iter()
+                .map(|instrument| {
+                    difficulty::difficulty(&chords, *instrument, &definitions, meta.b_notation())
+                })
+                .collect()
+        };
+
+        SongAnalysis {
+            chords,
+            estimated_key,
+            suggested_key,
+            difficulties,
+        }
+    }
+
+    /// Count the sections, lines, words and distinct chords and estimate the duration
+    ///
+    /// The duration is estimated from the `Tempo` and `Time` headers and the structure with all
+    /// references and repeats expanded. Missing headers are assumed to be 100 BPM in 4/4
+    pub fn statistics(&self, node: &Node, meta: &dyn MetaTrait) -> SongStatistics {
+        let mut played = vec![];
+        collect_chords(node, &mut played);
+        let mut distinct: Vec<&Chord> = vec![];
+        for chord in &played {
+            if !distinct.contains(&chord) {
+                distinct.push(chord);
+            }
+        }
+
+        statistics::statistics(node, meta, distinct.len())
+    }
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collect the chords in the order they are played
+///
+/// Both chords of a polychord (e.g. `C/Dm`) are collected
+fn collect_chords(node: &Node, played: &mut Vec<Chord>) {
+    let mut push = |chords: &Chords| {
+        played.push(chords.chord().clone());
+        if let Some(second) = chords.second_chord() {
+            played.push(second.clone());
+        }
+    };
+    match node {
+        Node::ChordTextPair { chords, .. } | Node::ChordStandalone(chords) => push(chords),
+        Node::Document(children) | Node::Section { children, .. } => {
+            for child in children {
+                collect_chords(child, played);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helper::parse_content;
+    use crate::models::meta::BNotation;
+    use crate::models::structure::SectionType;
+    use std::time::Duration;
+
+    fn analyze(content: &str) -> SongAnalysis {
+        let result = parse_content(content.as_bytes()).unwrap();
+
+        Analyzer::new().analyze(result.node_as_ref(), result.meta_as_ref())
+    }
+
+    fn chord(input: &str) -> Chord {
+        Chord::try_from(input, BNotation::B).unwrap()
+    }
+
+    #[test]
+    fn analyze_chord_vocabulary() {
+        let analysis = analyze(
+            "# Song\n\n## Verse\n[G]Swing [C]low, [G]sweet [D]chariot\n\n##! Chorus\n[G]Coming\n",
+        );
+        let chords: Vec<(Chord, usize)> = analysis
+            .chords()
+            .iter()
+            .map(|usage| (usage.chord().clone(), usage.count()))
+            .collect();
+
+        assert_eq!(
+            chords,
+            vec![(chord("G"), 3), (chord("C"), 1), (chord("D"), 1)]
+        );
+    }
+
+    #[test]
+    fn estimate_major_key() {
+        let analysis =
+            analyze("# Song\n\n## Verse\n[G]Swing [Em]low, [C]sweet [D7]chariot [G]home\n");
+        let estimate = analysis.estimated_key().unwrap();
+
+        assert_eq!(estimate.key(), &chord("G"));
+        assert_eq!(estimate.confidence(), 1.0);
+        assert_eq!(analysis.suggested_key(), Some(&chord("G")));
+    }
+
+    #[test]
+    fn estimate_minor_key() {
+        let analysis =
+            analyze("# Song\n\n## Verse\n[Am]Swing [F]low, [C]sweet [E7]chariot [Am]home\n");
+
+        assert_eq!(analysis.estimated_key().unwrap().key(), &chord("Am"));
+    }
+
+    #[test]
+    fn suggest_key_only_without_key_header() {
+        let analysis = analyze("# Song\nKey: G\n\n## Verse\n[G]Swing [C]low [D]home\n");
+
+        assert_eq!(analysis.estimated_key().unwrap().key(), &chord("G"));
+        assert_eq!(analysis.suggested_key(), None);
+    }
+
+    #[test]
+    fn analyze_song_without_chords() {
+        let analysis = analyze("# Song\n\n## Verse\nSwing low\n");
+
+        assert!(analysis.chords().is_empty());
+        assert!(analysis.estimated_key().is_none());
+        assert!(analysis.difficulties().is_empty());
+    }
+
+    #[test]
+    fn score_difficulty() {
+        let easy = analyze("# Song\n\n## Verse\n[G]Swing [C]low, [D]sweet [Em]chariot\n");
+        let guitar = easy.difficulty(Instrument::Guitar).unwrap();
+        assert_eq!(guitar.level(), DifficultyLevel::Beginner);
+        assert!(guitar.hard_chords().is_empty());
+
+        let hard = analyze("# Song\n\n## Verse\n[F]Swing [Bm]low, [F#m]sweet [Bb]chariot\n");
+        let guitar = hard.difficulty(Instrument::Guitar).unwrap();
+        assert_eq!(guitar.level(), DifficultyLevel::Advanced);
+        assert!(guitar.hard_chords().contains(&chord("F")));
+        assert!(guitar.score() > easy.difficulty(Instrument::Guitar).unwrap().score());
+    }
+
+    #[test]
+    fn compute_statistics() {
+        let content = "# Song
+Tempo: 120 BPM
+Time: 3/4
+
+## Verse 1
+[G]Swing low, sweet [C]chariot
+Coming for to carry me home
+
+##! Chorus
+[D]Swing [G]low
+
+> 2x Chorus
+";
+        let result = parse_content(content.as_bytes()).unwrap();
+        let statistics = Analyzer::new().statistics(result.node_as_ref(), result.meta_as_ref());
+
+        assert_eq!(statistics.sections().count(SectionType::Verse), 1);
+        assert_eq!(statistics.sections().count(SectionType::Chorus), 1);
+        assert_eq!(statistics.sections().count(SectionType::Reference), 1);
+        assert_eq!(statistics.sections().total(), 3);
+        assert_eq!(statistics.line_count(), 3);
+        assert_eq!(statistics.word_count(), 12);
+        assert_eq!(statistics.distinct_chords(), 3);
+        // Verse: 2 + 2 bars, chorus played three times: 3 * 2 bars = 10 bars of 3 beats at 120 BPM
+        assert_eq!(statistics.estimated_duration(), Duration::from_secs(15));
+    }
+
+    #[test]
+    fn score_difficulty_with_chord_definitions() {
+        let content = "# Song\n\n## Verse\n[F]Swing low\n";
+        let guitar = |analysis: SongAnalysis| analysis.difficulty(Instrument::Guitar).cloned();
+        assert_eq!(
+            guitar(analyze(content)).unwrap().hard_chords(),
+            &[chord("F")]
+        );
+
+        // The song defines a voicing of `F` without barre
+        let content = "# Song\nDefine: F base-fret 1 frets x x 3 2 1 x\n\n## Verse\n[F]Swing low\n";
+        let difficulty = guitar(analyze(content)).unwrap();
+        assert!(difficulty.hard_chords().is_empty());
+        assert_eq!(difficulty.level(), DifficultyLevel::Beginner);
+    }
+}
@@ -0,0 +1,251 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::meta::MetaTrait;
+use crate::models::structure::{SectionProvider, SectionType};
+use crate::modification::performance::performance;
+use crate::parser::Node;
+use crate::tokenizer::Token;
+
+/// Tempo assumed for songs without a (readable) `Tempo` header
+const DEFAULT_TEMPO: u32 = 100;
+
+/// Beats per bar assumed for songs without a (readable) `Time` header
+const DEFAULT_BEATS_PER_BAR: u32 = 4;
+
+/// Minimum number of bars assumed for a line (lines usually have less chord changes than bars)
+const MIN_BARS_PER_LINE: usize = 2;
+
+/// Number of sections of each `SectionType` as written in the song
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SectionSummary {
+    verses: usize,
+    choruses: usize,
+    bridges: usize,
+    references: usize,
+}
+
+impl SectionSummary {
+    /// Return the number of sections of the given type
+    pub fn count(&self, section_type: SectionType) -> usize {
+        match section_type {
+            SectionType::Verse => self.verses,
+            SectionType::Chorus => self.choruses,
+            SectionType::Bridge => self.bridges,
+            SectionType::Reference => self.references,
+        }
+    }
+
+    /// Return the number of all sections
+    pub fn total(&self) -> usize {
+        self.verses + self.choruses + self.bridges + self.references
+    }
+
+    fn add(&mut self, section_type: SectionType) {
+        match section_type {
+            SectionType::Verse => self.verses += 1,
+            SectionType::Chorus => self.choruses += 1,
+            SectionType::Bridge => self.bridges += 1,
+            SectionType::Reference => self.references += 1,
+        }
+    }
+}
+
+/// Statistics of a song
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SongStatistics {
+    sections: SectionSummary,
+    line_count: usize,
+    word_count: usize,
+    distinct_chords: usize,
+    /// Estimated duration of the performance in seconds
+    estimated_duration: u64,
+}
+
+impl SongStatistics {
+    pub fn sections(&self) -> SectionSummary {
+        self.sections
+    }
+
+    /// Return the number of lyrics lines as written in the song (translations are not counted)
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Return the number of words of the lyrics as written in the song
+    pub fn word_count(&self) -> usize {
+        self.word_count
+    }
+
+    pub fn distinct_chords(&self) -> usize {
+        self.distinct_chords
+    }
+
+    /// Return the estimated duration of the performance
+    pub fn estimated_duration(&self) -> Duration {
+        Duration::from_secs(self.estimated_duration)
+    }
+}
+
+/// Count the sections, lines and words and estimate the duration of the song
+///
+/// The duration is estimated from the number of lines played in the expanded structure. Each
+/// line is assumed to take one bar per chord, but at least `MIN_BARS_PER_LINE`
+pub(super) fn statistics(
+    node: &Node,
+    meta: &dyn MetaTrait,
+    distinct_chords: usize,
+) -> SongStatistics {
+    let mut sections = SectionSummary::default();
+    if let Node::Document(children) = node {
+        for section in children.iter().filter_map(SectionProvider::get_section) {
+            sections.add(if section.is_reference {
+                SectionType::Reference
+            } else {
+                section.section_type
+            });
+        }
+    }
+
+    let lines = lines(node);
+    let word_count = lines
+        .iter()
+        .map(|line| line.text.split_whitespace().count())
+        .sum();
+
+    let plays = performance(node);
+    let bars: usize = if plays.is_empty() {
+        lines.iter().map(Line::bars).sum()
+    } else {
+        plays
+            .iter()
+            .map(|play| play.count * self::lines(play.node).iter().map(Line::bars).sum::<usize>())
+            .sum()
+    };
+    let tempo = meta
+        .tempo()
+        .as_deref()
+        .and_then(parse_tempo)
+        .unwrap_or(DEFAULT_TEMPO);
+    let beats_per_bar = meta
+        .time()
+        .as_deref()
+        .and_then(parse_beats_per_bar)
+        .unwrap_or(DEFAULT_BEATS_PER_BAR);
+    let beats = bars as u64 * beats_per_bar as u64;
+
+    SongStatistics {
+        sections,
+        line_count: lines.len(),
+        word_count,
+        distinct_chords,
+        estimated_duration: (beats * 60 + tempo as u64 / 2) / tempo as u64,
+    }
+}
+
+/// Parse a duration written as `m:ss` or `h:mm:ss` (e.g. the `Duration` header)
+pub(crate) fn parse_duration(input: &str) -> Option<Duration> {
+    let parts = input
+        .trim()
+        .split(':')
+        .map(|part| part.trim().parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let seconds = match parts.as_slice() {
+        [minutes, seconds] if *seconds < 60 => minutes * 60 + seconds,
+        [hours, minutes, seconds] if *minutes < 60 && *seconds < 60 => {
+            hours * 3600 + minutes * 60 + seconds
+        }
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(seconds))
+}
+
+/// Read the beats per minute from the first number of the `Tempo` header (e.g. `96 BPM`)
+pub(crate) fn parse_tempo(input: &str) -> Option<u32> {
+    let digits: String = input
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(char::is_ascii_digit)
+        .collect();
+
+    digits.parse().ok().filter(|tempo| *tempo > 0)
+}
+
+/// Read the beats per bar from the `Time` header (e.g. `3` for `3/4`)
+fn parse_beats_per_bar(input: &str) -> Option<u32> {
+    let (beats, _) = input.split_once('/')?;
+
+    beats.trim().parse().ok().filter(|beats| *beats > 0)
+}
+
+/// Lyrics line with the number of chords played in it
+struct Line {
+    text: String,
+    chords: usize,
+}
+
+impl Line {
+    fn bars(&self) -> usize {
+        self.chords.max(MIN_BARS_PER_LINE)
+    }
+}
+
+/// Return the lyrics lines of the Node tree (lines with chords only are included)
+fn lines(node: &Node) -> Vec<Line> {
+    let mut lines = vec![];
+    let mut current: Option<Line> = None;
+    collect_lines(node, &mut lines, &mut current);
+    lines.extend(current);
+
+    lines
+}
+
+fn collect_lines(node: &Node, lines: &mut Vec<Line>, current: &mut Option<Line>) {
+    let line = || Line {
+        text: String::new(),
+        chords: 0,
+    };
+    match node {
+        Node::ChordTextPair { text, .. } => {
+            let line = current.get_or_insert_with(line);
+            line.chords += 1;
+            if let Token::Literal(text) = text {
+                line.text.push_str(text);
+            }
+        }
+        Node::ChordStandalone(_) => current.get_or_insert_with(line).chords += 1,
+        Node::Text(Token::Literal(text)) => current.get_or_insert_with(line).text.push_str(text),
+        Node::Newline => lines.extend(current.take()),
+        Node::Document(children) | Node::Section { children, .. } => {
+            for child in children {
+                collect_lines(child, lines, current);
+            }
+            lines.extend(current.take());
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("3:45"), Some(Duration::from_secs(225)));
+        assert_eq!(parse_duration(" 1:02:03 "), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_duration("3:75"), None);
+        assert_eq!(parse_duration("about 4 minutes"), None);
+    }
+
+    #[test]
+    fn parse_tempo_and_time() {
+        assert_eq!(parse_tempo("96"), Some(96));
+        assert_eq!(parse_tempo("♩ = 120 BPM"), Some(120));
+        assert_eq!(parse_tempo("slow"), None);
+        assert_eq!(parse_beats_per_bar("6/8"), Some(6));
+        assert_eq!(parse_beats_per_bar("common"), None);
+    }
+}
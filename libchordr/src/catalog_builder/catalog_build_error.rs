@@ -2,11 +2,16 @@ use std::error::Error;
 use std::fmt::{Display, Formatter, Result};
 use std::path::{Path, PathBuf};
 
+use crate::diagnostic::Diagnostic;
+use crate::span::Span;
+
 #[derive(Debug)]
 pub struct CatalogBuildError {
     message: String,
     path: PathBuf,
     inner: Option<Box<dyn Error + Send>>,
+    span: Option<Span>,
+    source_code: Option<Box<str>>,
 }
 
 impl CatalogBuildError {
@@ -15,8 +20,11 @@ impl CatalogBuildError {
             message: message.into(),
             path: path.into(),
             inner: None,
+            span: None,
+            source_code: None,
         }
     }
+
     pub(super) fn from_error<E: Error + Send + 'static, P: Into<PathBuf>>(
         error: E,
         path: P,
@@ -25,6 +33,24 @@ impl CatalogBuildError {
             message: error.to_string(),
             path: path.into(),
             inner: Some(Box::new(error)),
+            span: None,
+            source_code: None,
+        }
+    }
+
+    /// Build an error for a song that could not be parsed
+    ///
+    /// The song's source is kept to render an excerpt around the error's span
+    pub(super) fn from_parser_error<P: Into<PathBuf>>(
+        error: crate::error::Error,
+        path: P,
+        source_code: String,
+    ) -> Self {
+        let span = error.span();
+        Self {
+            span,
+            source_code: Some(source_code.into_boxed_str()),
+            ..Self::from_error(error, path)
         }
     }
 
@@ -35,6 +61,36 @@ impl CatalogBuildError {
     pub fn message(&self) -> &str {
         &self.message
     }
+
+    /// Return the position of the problem inside the file, if it is known
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Return the error as Diagnostic
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(self.description(), self.span)
+    }
+
+    /// Render the error in the style of a compiler message including an excerpt of the source
+    ///
+    /// Returns `None` if the position of the problem is unknown
+    pub fn render_diagnostic(&self) -> Option<String> {
+        match (&self.source_code, self.span) {
+            (Some(source_code), Some(_)) => Some(
+                self.diagnostic()
+                    .render(&self.path.to_string_lossy(), source_code),
+            ),
+            _ => None,
+        }
+    }
+
+    fn description(&self) -> String {
+        match self.source() {
+            Some(s) => s.to_string(),
+            None => self.message.to_owned(),
+        }
+    }
 }
 
 impl Error for CatalogBuildError {
@@ -48,16 +104,47 @@ impl Error for CatalogBuildError {
 
 impl Display for CatalogBuildError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let description = match self.source() {
-            Some(s) => s.to_string(),
-            None => self.message.to_owned(),
-        };
+        if let Some(diagnostic) = self.render_diagnostic() {
+            return f.write_str(&diagnostic);
+        }
 
         write!(
             f,
             "Error during analysis of file {}: {}",
             self.path.to_string_lossy(),
-            description
+            self.description()
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helper::parse_content;
+
+    #[test]
+    fn display_with_span() {
+        let source = "# Song\n\n[D]Swing [Xm/Q]low\n";
+        let error = parse_content(source.as_bytes()).err().unwrap();
+        let error = CatalogBuildError::from_parser_error(error, "song.chorddown", source.into());
+
+        assert!(error.span().is_some());
+        let output = error.to_string();
+        assert!(
+            output.starts_with("error: ") && output.contains(" --> song.chorddown:3:10\n"),
+            "{}",
+            output
+        );
+        assert!(output.ends_with("3 | [D]Swing [Xm/Q]low\n  |          ^^^^^^"));
+    }
+
+    #[test]
+    fn display_without_span() {
+        let error = CatalogBuildError::new("Given entry is not a file", "songs");
+
+        assert_eq!(
+            error.to_string(),
+            "Error during analysis of file songs: Given entry is not a file"
+        );
+    }
+}
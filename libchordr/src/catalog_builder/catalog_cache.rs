@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::list::ListEntryTrait;
+use crate::models::song::Song;
+
+use super::CatalogBuildError;
+
+/// Version of the cache file format
+///
+/// The crate version is part of it, because the songs' meta data (e.g. the analysis) may change
+/// between releases even if the sources did not
+const CACHE_VERSION: &str = concat!("1:", env!("CARGO_PKG_VERSION"));
+
+/// Cache of parsed songs used for incremental catalog builds
+#[derive(Deserialize, Serialize, Debug)]
+pub(super) struct CatalogCache {
+    version: String,
+    entries: BTreeMap<PathBuf, CacheEntry>,
+}
+
+/// Modification time and size of a file
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FileStamp {
+    modified: SystemTime,
+    size: u64,
+}
+
+impl FileStamp {
+    pub(super) fn for_path(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+
+        Ok(Self {
+            modified: metadata.modified()?,
+            size: metadata.len(),
+        })
+    }
+}
+
+/// Parsed song together with the state of the file it was built from
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub(super) struct CacheEntry {
+    stamp: FileStamp,
+    hash: u64,
+    song: Song,
+}
+
+impl CacheEntry {
+    pub(super) fn new(stamp: FileStamp, hash: u64, song: Song) -> Self {
+        Self { stamp, hash, song }
+    }
+
+    pub(super) fn song(&self) -> &Song {
+        &self.song
+    }
+}
+
+impl CatalogCache {
+    pub(super) fn new() -> Self {
+        Self {
+            version: CACHE_VERSION.to_string(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Load the cache from the given file
+    ///
+    /// A missing, unreadable or outdated cache file results in an empty cache
+    pub(super) fn load(path: &Path) -> Self {
+        let content = match fs::read(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Self::new(),
+            Err(e) => {
+                log::warn!("Could not read the catalog cache {}: {}", path.display(), e);
+                return Self::new();
+            }
+        };
+
+        match serde_json::from_slice::<Self>(&content) {
+            Ok(cache) if cache.version == CACHE_VERSION => cache,
+            Ok(_) => {
+                log::info!("Discard the outdated catalog cache {}", path.display());
+                Self::new()
+            }
+            Err(e) => {
+                log::warn!("Could not read the catalog cache {}: {}", path.display(), e);
+                Self::new()
+            }
+        }
+    }
+
+    /// Write the cache to the given file
+    ///
+    /// The cache is written to a temporary file first and then moved into place, so that
+    /// concurrent builds never read a partially written cache
+    pub(super) fn save(&self, path: &Path) -> Result<(), CatalogBuildError> {
+        let output =
+            serde_json::to_vec(self).map_err(|e| CatalogBuildError::from_error(e, path))?;
+
+        let temporary_path = temporary_path(path);
+        fs::write(&temporary_path, output)
+            .and_then(|_| fs::rename(&temporary_path, path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temporary_path);
+                CatalogBuildError::from_error(e, path)
+            })
+    }
+
+    /// Return the entry for the file, if the file's modification time and size did not change
+    pub(super) fn get_unmodified(&self, path: &Path, stamp: FileStamp) -> Option<&CacheEntry> {
+        self.entries.get(path).filter(|entry| entry.stamp == stamp)
+    }
+
+    /// Return the entry for the file, if the file's content did not change since it was cached
+    pub(super) fn get_unchanged(&self, path: &Path, hash: u64) -> Option<&CacheEntry> {
+        self.entries.get(path).filter(|entry| entry.hash == hash)
+    }
+
+    pub(super) fn insert(&mut self, path: PathBuf, entry: CacheEntry) {
+        self.entries.insert(path, entry);
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Return a unique path for a temporary file next to the given file
+fn temporary_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(format!(
+        ".{}-{}.tmp",
+        process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    path.with_file_name(file_name)
+}
+
+/// Return a stable hash of the content
+///
+/// Unlike `DefaultHasher` the result does not depend on the Rust release, so it can be persisted
+pub(super) fn content_hash(content: &[u8]) -> u64 {
+    let mut hasher = Fnv1aHasher::default();
+    hasher.write(content);
+
+    hasher.finish()
+}
+
+/// Build the catalog revision from the IDs and sources of the songs
+///
+/// The revision changes if a song was added, removed or modified. It also includes the
+/// `CACHE_VERSION`, because a new release may derive other meta data from the same sources
+pub(super) fn catalog_revision(songs: &[Song]) -> String {
+    let mut songs: Vec<&Song> = songs.iter().collect();
+    songs.sort_by_key(|song| song.id());
+
+    let mut hasher = Fnv1aHasher::default();
+    hasher.write(CACHE_VERSION.as_bytes());
+    hasher.write_u8(0);
+    for song in songs {
+        hasher.write(song.id().as_str().as_bytes());
+        hasher.write_u8(0);
+        hasher.write(song.src().as_bytes());
+        hasher.write_u8(0);
+    }
+
+    format!("{:016x}", hasher.finish())
+}
+
+/// 64-bit FNV-1a hash
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(content_hash(b"[G]Swing low"), content_hash(b"[C]Swing low"));
+    }
+
+    #[test]
+    fn test_catalog_revision_includes_version() {
+        let mut hasher = Fnv1aHasher::default();
+        hasher.write(CACHE_VERSION.as_bytes());
+        hasher.write_u8(0);
+
+        assert_eq!(format!("{:016x}", hasher.finish()), catalog_revision(&[]));
+        assert_ne!(format!("{:016x}", content_hash(b"")), catalog_revision(&[]));
+    }
+}
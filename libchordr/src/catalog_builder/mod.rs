@@ -4,8 +4,6 @@ use std::fs::{self, DirEntry};
 use std::path::Path;
 use std::path::PathBuf;
 
-use chrono::Local;
-
 use crate::error::{Error, Result};
 use crate::models::catalog::*;
 use crate::models::file_type::FileType;
@@ -13,8 +11,10 @@ use crate::models::list::ListEntryTrait;
 use crate::models::song::Song;
 
 pub use self::catalog_build_error::CatalogBuildError;
+use self::catalog_cache::{catalog_revision, content_hash, CacheEntry, CatalogCache, FileStamp};
 
 mod catalog_build_error;
+mod catalog_cache;
 mod song_from_dir_entry;
 
 pub struct CatalogBuildResult {
@@ -23,12 +23,26 @@ pub struct CatalogBuildResult {
 }
 
 /// Catalog Builder provides functions to build a Song Catalog from a given directory
+///
+/// The revision of the built `Catalog` is a hash of the songs' IDs and sources, so it only changes
+/// if a song was added, removed or modified
 #[derive(Default)]
-pub struct CatalogBuilder;
+pub struct CatalogBuilder {
+    cache_file: Option<PathBuf>,
+}
 
 impl CatalogBuilder {
     pub fn new() -> Self {
-        Self {}
+        Self { cache_file: None }
+    }
+
+    /// Build a Catalog Builder that keeps the parsed songs in the given cache file
+    ///
+    /// Subsequent builds only re-parse the files whose content changed since the previous build
+    pub fn with_cache_file<P: Into<PathBuf>>(cache_file: P) -> Self {
+        Self {
+            cache_file: Some(cache_file.into()),
+        }
     }
 
     pub fn build_catalog_for_directory<P: AsRef<Path>>(
@@ -48,38 +62,124 @@ impl CatalogBuilder {
         let song_files_r: Vec<Result<PathBuf, CatalogBuildError>> =
             self.collect_song_files(path_ref, file_type, recursive);
         let (song_file_results, io_errors): (Vec<_>, Vec<_>) = partition_results(song_files_r);
-        let song_results = self.build_songs_for_file_list(song_file_results);
+        let song_results = match &self.cache_file {
+            Some(cache_file) => self.build_songs_with_cache(song_file_results, cache_file),
+            None => self.build_songs_for_file_list(song_file_results),
+        };
         let (songs, mut parse_errors) = self.partition_songs(song_results);
 
         parse_errors.extend(io_errors.into_iter());
 
         Ok(CatalogBuildResult {
-            catalog: Catalog::new(Local::now().to_rfc2822(), songs),
+            catalog: Catalog::new(catalog_revision(&songs), songs),
             errors: parse_errors,
         })
     }
 
-    #[cfg(not(feature = "parallel_catalog_builder"))]
+    /// Return the paths of all song files of the given type inside the directory
+    pub fn find_song_files<P: AsRef<Path>>(
+        &self,
+        path: P,
+        file_type: FileType,
+        recursive: bool,
+    ) -> Result<Vec<PathBuf>> {
+        let path_ref = path.as_ref();
+        if !path_ref.is_dir() {
+            return Err(Error::catalog_builder_fatal_error(
+                "Given path is not a directory",
+                path_ref.to_path_buf(),
+            ));
+        }
+
+        let mut song_files = vec![];
+        for result in self.collect_song_files(path_ref, file_type, recursive) {
+            match result {
+                Ok(song_file) => song_files.push(song_file),
+                Err(error) => return Err(Error::unknown_error(error.to_string())),
+            }
+        }
+        song_files.sort();
+
+        Ok(song_files)
+    }
+
     fn build_songs_for_file_list(
         &self,
         song_file_results: Vec<PathBuf>,
     ) -> Vec<Result<Song, CatalogBuildError>> {
-        song_file_results
-            .into_iter()
-            .map(|e| Song::try_from(e.as_path()))
-            .collect()
+        map_files(song_file_results, |e| Song::try_from(e.as_path()))
     }
 
-    #[cfg(feature = "parallel_catalog_builder")]
-    fn build_songs_for_file_list(
+    /// Build the songs re-using the cached songs of unchanged files
+    ///
+    /// A file is only read if its modification time or size changed and only parsed if its content hash
+    /// changed. The cache file is only written if an entry changed. Failing to write the cache is
+    /// reported as an error, but does not affect the songs
+    fn build_songs_with_cache(
         &self,
         song_file_results: Vec<PathBuf>,
+        cache_file: &Path,
     ) -> Vec<Result<Song, CatalogBuildError>> {
-        use rayon::prelude::*;
-        song_file_results
-            .into_par_iter()
-            .map(|e| Song::try_from(e.as_path()))
-            .collect()
+        let previous_cache = CatalogCache::load(cache_file);
+        let mut cache = CatalogCache::new();
+        let mut changed = false;
+        let mut song_results = vec![];
+        let mut modified_files = vec![];
+        for path in song_file_results {
+            let stamp = match FileStamp::for_path(&path) {
+                Ok(s) => s,
+                Err(e) => {
+                    song_results.push(Err(CatalogBuildError::from_error(e, path)));
+                    continue;
+                }
+            };
+            if let Some(entry) = previous_cache.get_unmodified(&path, stamp) {
+                song_results.push(Ok(entry.song().clone()));
+                cache.insert(path, entry.clone());
+                continue;
+            }
+
+            let src = match fs::read_to_string(&path) {
+                Ok(src) => src,
+                Err(e) => {
+                    song_results.push(Err(CatalogBuildError::from_error(e, path)));
+                    continue;
+                }
+            };
+            let hash = content_hash(src.as_bytes());
+            match previous_cache.get_unchanged(&path, hash) {
+                Some(entry) => {
+                    let song = entry.song().clone();
+                    song_results.push(Ok(song.clone()));
+                    cache.insert(path, CacheEntry::new(stamp, hash, song));
+                    changed = true;
+                }
+                None => modified_files.push((path, stamp, hash, src)),
+            }
+        }
+
+        log::debug!("Parse {} modified song files", modified_files.len());
+        let parsed = map_files(modified_files, |(path, stamp, hash, src)| {
+            let result = song_from_dir_entry::song_from_source(&path, src);
+            (path, stamp, hash, result)
+        });
+        for (path, stamp, hash, result) in parsed {
+            if let Ok(song) = &result {
+                cache.insert(path, CacheEntry::new(stamp, hash, song.clone()));
+                changed = true;
+            }
+            song_results.push(result);
+        }
+
+        // All other entries were taken from the previous cache, so a different length means that
+        // files were removed
+        if changed || cache.len() != previous_cache.len() {
+            if let Err(e) = cache.save(cache_file) {
+                song_results.push(Err(e));
+            }
+        }
+
+        song_results
     }
 
     fn partition_songs(
@@ -149,6 +249,19 @@ impl CatalogBuilder {
     }
 }
 
+/// Apply `f` to all items (in parallel if the `parallel_catalog_builder` feature is enabled)
+#[cfg(not(feature = "parallel_catalog_builder"))]
+fn map_files<T, R, F: Fn(T) -> R>(items: Vec<T>, f: F) -> Vec<R> {
+    items.into_iter().map(f).collect()
+}
+
+/// Apply `f` to all items (in parallel if the `parallel_catalog_builder` feature is enabled)
+#[cfg(feature = "parallel_catalog_builder")]
+fn map_files<T: Send, R: Send, F: Fn(T) -> R + Sync + Send>(items: Vec<T>, f: F) -> Vec<R> {
+    use rayon::prelude::*;
+    items.into_par_iter().map(f).collect()
+}
+
 pub fn partition_results<T: Debug, E: Debug>(results: Vec<Result<T, E>>) -> (Vec<T>, Vec<E>) {
     let mut left: Vec<T> = Vec::with_capacity(results.len() / 2);
     let mut right: Vec<E> = Vec::with_capacity(results.len() / 2);
@@ -218,6 +331,25 @@ mod tests {
         assert_eq!(SongId::new(song_id), song.id());
     }
 
+    #[test]
+    fn test_build_catalog_for_test_directory_chord_pro() {
+        let songs_dir = format!("{}/tests/resources", env!("CARGO_MANIFEST_DIR"));
+        let songs_dir = Path::new(&songs_dir);
+        let result =
+            CatalogBuilder::new().build_catalog_for_directory(songs_dir, FileType::ChordPro, false);
+        assert!(result.is_ok());
+        let catalog_and_errors = result.unwrap();
+        assert!(catalog_and_errors.errors.is_empty());
+        let catalog = catalog_and_errors.catalog;
+        assert_eq!(1, catalog.len());
+
+        let song_id = "swing_low_sweet_chariot.cho";
+        assert!(catalog.contains_id(song_id));
+        let song = catalog.get(song_id).unwrap();
+        assert_eq!("Swing Low Sweet Chariot", song.title());
+        assert_eq!(FileType::ChordPro, song.file_type());
+    }
+
     #[test]
     fn test_build_catalog_for_test_directory_recursive() {
         let songs_dir = format!("{}/tests/resources", env!("CARGO_MANIFEST_DIR"));
@@ -242,4 +374,92 @@ mod tests {
         let song = catalog.get(song_id).unwrap();
         assert_eq!(SongId::new(song_id), song.id());
     }
+
+    #[test]
+    fn test_build_catalog_revision_is_deterministic() {
+        let songs_dir = format!("{}/tests/resources", env!("CARGO_MANIFEST_DIR"));
+        let build = || {
+            CatalogBuilder::new()
+                .build_catalog_for_directory(&songs_dir, FileType::Chorddown, true)
+                .unwrap()
+                .catalog
+        };
+
+        let revision = build().revision();
+        assert_eq!(16, revision.len());
+        assert_eq!(revision, build().revision());
+    }
+
+    #[test]
+    fn test_build_catalog_with_cache_file() {
+        let dir = std::env::temp_dir().join(format!("chordr-catalog-cache-{}", std::process::id()));
+        let songs_dir = dir.join("songs");
+        let cache_file = dir.join("cache.json");
+        fs::create_dir_all(&songs_dir).unwrap();
+        fs::write(
+            songs_dir.join("a.chorddown"),
+            "# Song A\n\n## Verse\n[G]Swing low\n",
+        )
+        .unwrap();
+        fs::write(
+            songs_dir.join("b.chorddown"),
+            "# Song B\n\n## Verse\n[C]Swing low\n",
+        )
+        .unwrap();
+
+        let builder = CatalogBuilder::with_cache_file(&cache_file);
+        let build = || {
+            builder
+                .build_catalog_for_directory(&songs_dir, FileType::Chorddown, false)
+                .unwrap()
+        };
+        let first = build();
+        assert!(first.errors.is_empty());
+        assert_eq!(2, first.catalog.len());
+        assert_eq!(2, CatalogCache::load(&cache_file).len());
+
+        // The cached songs are equal to freshly parsed ones
+        let uncached = CatalogBuilder::new()
+            .build_catalog_for_directory(&songs_dir, FileType::Chorddown, false)
+            .unwrap();
+        assert_eq!(uncached.catalog.revision(), first.catalog.revision());
+        assert_eq!(
+            uncached.catalog.get("a.chorddown"),
+            first.catalog.get("a.chorddown")
+        );
+
+        // The cache file is not written again if nothing changed
+        let old_time = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(86400);
+        fs::File::options()
+            .write(true)
+            .open(&cache_file)
+            .unwrap()
+            .set_modified(old_time)
+            .unwrap();
+        let second = build();
+        assert_eq!(first.catalog.revision(), second.catalog.revision());
+        assert_eq!(
+            old_time,
+            fs::metadata(&cache_file).unwrap().modified().unwrap()
+        );
+
+        fs::write(
+            songs_dir.join("b.chorddown"),
+            "# Song Bee\n\n## Verse\n[C]Swing low\n",
+        )
+        .unwrap();
+        fs::remove_file(songs_dir.join("a.chorddown")).unwrap();
+        let third = build();
+        assert_ne!(first.catalog.revision(), third.catalog.revision());
+        assert_eq!(1, third.catalog.len());
+        assert_eq!(
+            "Song Bee",
+            third.catalog.get("b.chorddown").unwrap().title()
+        );
+        assert_eq!(1, CatalogCache::load(&cache_file).len());
+        // No temporary files are left behind
+        assert_eq!(2, fs::read_dir(&dir).unwrap().count());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
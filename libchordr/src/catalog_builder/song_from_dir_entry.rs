@@ -3,7 +3,8 @@ use std::fs;
 use std::fs::DirEntry;
 use std::path::Path;
 
-use crate::helper::parse_content;
+use crate::analysis::Analyzer;
+use crate::helper::parse_content_for_file_type;
 use crate::models::file_type::FileType;
 use crate::models::song::Song;
 use crate::models::song_id::SongId;
@@ -23,35 +24,42 @@ impl TryFrom<&Path> for Song {
             ));
         }
 
-        let src = match fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(e) => return Err(CatalogBuildError::from_error(e, path_buf)),
-        };
-
-        let song_id = SongId::from(path);
-        let parser_result = match parse_content(src.as_bytes()) {
-            Ok(p) => p,
-            Err(e) => return Err(CatalogBuildError::from_error(e, path_buf)),
-        };
-        let title = parser_result
-            .meta()
-            .title
-            .unwrap_or_else(|| song_id.to_string());
-        let file_type = match FileType::try_from(path) {
-            Ok(f) => f,
-            Err(e) => return Err(CatalogBuildError::from_error(e, path_buf)),
-        };
-        //        let meta = SongMeta::new(song_id, title, file_type);
-        let meta = SongMeta::new_with_meta_information(
-            song_id,
-            title,
-            file_type,
-            parser_result.meta_as_ref(),
-        );
-        Ok(Song::new(meta, src))
+        match fs::read_to_string(path) {
+            Ok(src) => song_from_source(path, src),
+            Err(e) => Err(CatalogBuildError::from_error(e, path_buf)),
+        }
     }
 }
 
+/// Parse the source of the song file at `path`
+pub(super) fn song_from_source(path: &Path, src: String) -> Result<Song, CatalogBuildError> {
+    let path_buf = path.to_path_buf();
+    let song_id = SongId::from(path);
+    let file_type = match FileType::try_from(path) {
+        Ok(f) => f,
+        Err(e) => return Err(CatalogBuildError::from_error(e, path_buf)),
+    };
+    let parser_result = match parse_content_for_file_type(src.as_bytes(), file_type) {
+        Ok(p) => p,
+        Err(e) => return Err(CatalogBuildError::from_parser_error(e, path_buf, src)),
+    };
+    let title = parser_result
+        .meta()
+        .title
+        .unwrap_or_else(|| song_id.to_string());
+    let analyzer = Analyzer::new();
+    //        let meta = SongMeta::new(song_id, title, file_type);
+    let meta =
+        SongMeta::new_with_meta_information(song_id, title, file_type, parser_result.meta_as_ref())
+            .with_analysis(
+                analyzer.analyze(parser_result.node_as_ref(), parser_result.meta_as_ref()),
+            )
+            .with_statistics(
+                analyzer.statistics(parser_result.node_as_ref(), parser_result.meta_as_ref()),
+            );
+    Ok(Song::new(meta, src))
+}
+
 impl TryFrom<DirEntry> for Song {
     type Error = CatalogBuildError;
 
@@ -81,5 +89,59 @@ mod tests {
         assert_eq!("Swing Low Sweet Chariot", &song.title());
         assert_eq!(FileType::Chorddown, song.file_type());
         assert!(!song.src().is_empty());
+
+        let analysis = song.meta().analysis().cloned().unwrap();
+        assert!(!analysis.chords().is_empty());
+        assert!(!analysis.difficulties().is_empty());
+        // The song does not declare a key
+        assert!(song.meta().suggested_key().is_some());
+        let statistics = song.meta().statistics().cloned().unwrap();
+        assert!(statistics.word_count() > 0);
+        assert!(song.meta().running_time().is_some());
+    }
+
+    #[test]
+    fn test_try_from_chord_pro() {
+        let song_path = format!(
+            "{}/tests/resources/swing_low_sweet_chariot.cho",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let song_path = Path::new(&song_path);
+        let result = Song::try_from(song_path);
+        assert!(result.is_ok(), "{:?}", result.unwrap_err());
+        let song = result.unwrap();
+        assert_eq!(SongId::new("swing_low_sweet_chariot.cho"), song.id());
+        assert_eq!("Swing Low Sweet Chariot", &song.title());
+        assert_eq!(FileType::ChordPro, song.file_type());
+    }
+
+    #[test]
+    fn test_try_from_song_beamer() {
+        let song_path = format!(
+            "{}/tests/resources/swing_low_sweet_chariot.sng",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let song_path = Path::new(&song_path);
+        let result = Song::try_from(song_path);
+        assert!(result.is_ok(), "{:?}", result.unwrap_err());
+        let song = result.unwrap();
+        assert_eq!(SongId::new("swing_low_sweet_chariot.sng"), song.id());
+        assert_eq!("Swing Low Sweet Chariot", &song.title());
+        assert_eq!(FileType::SongBeamer, song.file_type());
+    }
+
+    #[test]
+    fn test_try_from_open_lyrics() {
+        let song_path = format!(
+            "{}/tests/resources/swing_low_sweet_chariot.xml",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let song_path = Path::new(&song_path);
+        let result = Song::try_from(song_path);
+        assert!(result.is_ok(), "{:?}", result.unwrap_err());
+        let song = result.unwrap();
+        assert_eq!(SongId::new("swing_low_sweet_chariot.xml"), song.id());
+        assert_eq!("Swing Low Sweet Chariot", &song.title());
+        assert_eq!(FileType::OpenLyrics, song.file_type());
     }
 }
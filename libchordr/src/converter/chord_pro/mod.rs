@@ -0,0 +1,395 @@
+use crate::error::Result;
+use crate::models::chord::fmt::*;
+use crate::models::chord::Chords;
+use crate::models::meta::MetaTrait;
+use crate::models::structure::{ReferenceResolver, Section, SectionProvider, SectionType};
+use crate::parser::Node;
+use crate::tokenizer::Token;
+
+use super::ConverterTrait;
+
+/// Converter to build [ChordPro](https://www.chordpro.org/) song files
+pub struct ChordProConverter {}
+
+impl ConverterTrait for ChordProConverter {
+    fn convert(&self, node: &Node, meta: &dyn MetaTrait, formatting: Formatting) -> Result<String> {
+        let sections = node.get_sections();
+        let output = format!(
+            "{}{}\n\n{}",
+            self.build_title(meta),
+            self.build_meta(meta, formatting),
+            self.build_node(node, &sections, formatting)?
+        );
+        Ok(cleanup_output(&output))
+    }
+}
+
+impl ChordProConverter {
+    fn build_node(
+        &self,
+        node: &Node,
+        sections: &[Section],
+        formatting: Formatting,
+    ) -> Result<String> {
+        match node {
+            Node::ChordTextPair {
+                chords,
+                text,
+                last_in_line: _,
+            } => Ok(format!(
+                "{}{}",
+                self.build_string_for_chords(chords, formatting),
+                self.build_token(text),
+            )),
+            Node::ChordStandalone(chords) => Ok(self.build_string_for_chords(chords, formatting)),
+            Node::Text(text) => Ok(self.build_token(text)),
+            Node::Document(children) => {
+                Ok(self.build_string_for_children(children, sections, formatting))
+            }
+            Node::Headline(_) => {
+                // Headlines are rendered as part of their section
+                Ok(String::new())
+            }
+            Node::Quote(token) => Ok(format!("{{comment: {}}}\n", self.build_token(token))),
+            // ChordPro has no syntax for translations, so they are rendered as italic comments
+            Node::Translation(token) => {
+                Ok(format!("{{comment_italic: {}}}", self.build_token(token)))
+            }
+            Node::Meta(_) => {
+                // Metadata must have already been rendered
+                Ok(String::new())
+            }
+            Node::Newline => Ok("\n".to_owned()),
+            Node::Section {
+                head,
+                children,
+                section_type,
+            } => {
+                let content = self.build_string_for_children(children, sections, formatting);
+                match head.as_ref() {
+                    Node::Quote(token) => Ok(format!(
+                        "{}{}",
+                        self.build_reference(node, token, sections),
+                        content
+                    )),
+                    Node::Headline(Token::Headline { level, text, .. }) if *level > 1 => {
+                        let environment = environment_for_section_type(*section_type);
+                        Ok(format!(
+                            "{{start_of_{}: {}}}\n{}\n{{end_of_{}}}\n\n",
+                            environment,
+                            text,
+                            content.trim_matches('\n'),
+                            environment
+                        ))
+                    }
+                    _ => Ok(content),
+                }
+            }
+        }
+    }
+
+    /// Build the directive for a reference to another section
+    ///
+    /// References to a chorus are rendered as `{chorus}` directive, all others as comment
+    fn build_reference(&self, node: &Node, token: &Token, sections: &[Section]) -> String {
+        let text = self.build_token(token);
+        let references_chorus = SectionProvider::get_section(node).and_then(|section| {
+            ReferenceResolver::new()
+                .resolve_reference(&section, sections)
+                .map(|referenced_section| referenced_section.section_type)
+        }) == Some(SectionType::Chorus);
+
+        if references_chorus {
+            format!("{{chorus: {}}}\n", text)
+        } else {
+            format!("{{comment: {}}}\n", text)
+        }
+    }
+
+    fn build_token(&self, token: &Token) -> String {
+        match token {
+            Token::Literal(c) => c.to_owned(),
+            Token::Quote(c) => c.to_owned(),
+            Token::Translation(c) => c.to_owned(),
+            Token::Headline { text, .. } => text.to_owned(),
+            Token::Chord(_) => unreachable!(),
+            Token::Newline => unreachable!(),
+            Token::Meta(_) => unreachable!(),
+        }
+    }
+
+    fn build_title(&self, meta: &dyn MetaTrait) -> String {
+        match meta.title() {
+            Some(t) => format!("{{title: {}}}\n", t),
+            None => String::new(),
+        }
+    }
+
+    fn build_meta(&self, meta: &dyn MetaTrait, formatting: Formatting) -> String {
+        let mut buffer: Vec<String> = vec![];
+
+        if let Some(v) = meta.subtitle() {
+            buffer.push(format!("{{subtitle: {}}}", v))
+        }
+        if let Some(v) = meta.original_title() {
+            buffer.push(format!("{{meta: original_title {}}}", v))
+        }
+        if let Some(v) = meta.alternative_title() {
+            buffer.push(format!("{{meta: alternative_title {}}}", v))
+        }
+        if let Some(v) = meta.artist() {
+            buffer.push(format!("{{artist: {}}}", v))
+        }
+        if let Some(v) = meta.composer() {
+            buffer.push(format!("{{composer: {}}}", v))
+        }
+        if let Some(v) = meta.lyricist() {
+            buffer.push(format!("{{lyricist: {}}}", v))
+        }
+        if let Some(v) = meta.copyright() {
+            buffer.push(format!("{{copyright: {}}}", v))
+        }
+        if let Some(v) = meta.album() {
+            buffer.push(format!("{{album: {}}}", v))
+        }
+        if let Some(v) = meta.year() {
+            buffer.push(format!("{{year: {}}}", v))
+        }
+        if let Some(v) = meta.key() {
+            buffer.push(format!("{{key: {}}}", v.note_format(formatting)))
+        }
+        if let Some(v) = meta.time() {
+            buffer.push(format!("{{time: {}}}", v))
+        }
+        if let Some(v) = meta.tempo() {
+            buffer.push(format!("{{tempo: {}}}", v))
+        }
+        if let Some(v) = meta.duration() {
+            buffer.push(format!("{{duration: {}}}", v))
+        }
+        if let Some(v) = formatting.capo_for_meta(meta) {
+            buffer.push(format!("{{capo: {}}}", v))
+        }
+        if let Some(v) = meta.ccli_song_id() {
+            buffer.push(format!("{{ccli: {}}}", v))
+        }
+        if let Some(v) = meta.language() {
+            buffer.push(format!("{{meta: language {}}}", v))
+        }
+        for arrangement in meta.arrangements() {
+            buffer.push(format!("{{meta: arrangement {}}}", arrangement))
+        }
+        for definition in meta.chord_definitions() {
+            buffer.push(format!("{{define: {}}}", definition))
+        }
+        buffer.join("\n")
+    }
+
+    fn build_string_for_chords(&self, chords: &Chords, formatting: Formatting) -> String {
+        format!("[{}]", chords.note_format(formatting))
+    }
+
+    fn build_string_for_children(
+        &self,
+        children: &[Node],
+        sections: &[Section],
+        formatting: Formatting,
+    ) -> String {
+        children
+            .iter()
+            .filter_map(|n| self.build_node(n, sections, formatting).ok())
+            .collect::<Vec<String>>()
+            .join("")
+    }
+}
+
+fn environment_for_section_type(section_type: SectionType) -> &'static str {
+    match section_type {
+        SectionType::Chorus => "chorus",
+        SectionType::Bridge => "bridge",
+        SectionType::Verse | SectionType::Reference => "verse",
+    }
+}
+
+fn cleanup_output(output: &str) -> String {
+    format!("{}\n", remove_double_blank_lines(output).trim())
+}
+
+fn remove_double_blank_lines(input: &str) -> String {
+    if input.contains("\n\n\n") {
+        remove_double_blank_lines(&input.replace("\n\n\n", "\n\n"))
+    } else {
+        input.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::Format;
+    use crate::models::file_type::FileType;
+    use crate::parser::{MetaInformation, Parser, ParserTrait};
+    use crate::test_helpers::get_test_metadata;
+    use crate::test_helpers::{
+        get_test_ast, get_test_ast_w_inline_metadata, get_test_ast_with_quote,
+    };
+    use crate::tokenizer::{build_tokenizer_for_file_type, Tokenizer};
+
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let converter = ChordProConverter {};
+        let result = converter.convert(
+            &get_test_ast(),
+            &MetaInformation::default(),
+            Formatting::with_format(Format::ChordPro),
+        );
+
+        assert!(result.is_ok());
+        let source = result.unwrap();
+
+        assert_eq!(
+            source,
+            r"{start_of_chorus: Chorus}
+Swing [D]low, sweet [G]chari[D]ot,
+Comin’ for to carry me [A7]home.
+Swing [D7]low, sweet [G]chari[D]ot,
+Comin’ for to [A7]carry me [D]home.
+{end_of_chorus}
+
+{start_of_verse: Verse 1}
+I [D]looked over Jordan, and [G]what did I [D]see,
+Comin’ for to carry me [A7]home.
+A [D]band of angels [G]comin’ after [D]me,
+Comin’ for to [A7]carry me [D]home.
+{end_of_verse}
+
+{chorus: Chorus}
+"
+        );
+    }
+
+    #[test]
+    fn test_convert_w_metadata() {
+        let converter = ChordProConverter {};
+        let result = converter.convert(
+            &get_test_ast(),
+            &get_test_metadata(),
+            Formatting::with_format(Format::ChordPro),
+        );
+
+        assert!(result.is_ok());
+        let source = result.unwrap();
+
+        assert_eq!(
+            source,
+            r#"{title: Great new song}
+{subtitle: Originally known as 'Swing low sweet chariot'}
+{artist: Me}
+{composer: Wallace Willis}
+{lyricist: Wallace Willis}
+{year: 1865}
+{capo: 1}
+
+{start_of_chorus: Chorus}
+Swing [D]low, sweet [G]chari[D]ot,
+Comin’ for to carry me [A7]home.
+Swing [D7]low, sweet [G]chari[D]ot,
+Comin’ for to [A7]carry me [D]home.
+{end_of_chorus}
+
+{start_of_verse: Verse 1}
+I [D]looked over Jordan, and [G]what did I [D]see,
+Comin’ for to carry me [A7]home.
+A [D]band of angels [G]comin’ after [D]me,
+Comin’ for to [A7]carry me [D]home.
+{end_of_verse}
+
+{chorus: Chorus}
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_inline_metadata() {
+        let converter = ChordProConverter {};
+        let ast = get_test_ast_w_inline_metadata();
+        let result = converter.convert(
+            &ast,
+            &get_test_metadata(),
+            Formatting::with_format(Format::ChordPro),
+        );
+
+        assert!(result.is_ok());
+        let source = result.unwrap();
+
+        assert_eq!(
+            source,
+            r#"{title: Great new song}
+{subtitle: Originally known as 'Swing low sweet chariot'}
+{artist: Me}
+{composer: Wallace Willis}
+{lyricist: Wallace Willis}
+{year: 1865}
+{capo: 1}
+
+{start_of_chorus: Chorus}
+Swing [D]low, sweet [G]chari[D]ot.
+{end_of_chorus}
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_content_after_quote() {
+        let converter = ChordProConverter {};
+        let ast = get_test_ast_with_quote();
+        let result = converter.convert(
+            &ast,
+            &MetaInformation::default(),
+            Formatting::with_format(Format::ChordPro),
+        );
+
+        assert!(result.is_ok());
+        let source = result.unwrap();
+
+        assert_eq!(
+            source,
+            r#"{chorus: Chorus 2x}
+
+{start_of_chorus: Chorus}
+Swing [D]low, sweet [G]chari[D]ot.
+{end_of_chorus}
+"#
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let converter = ChordProConverter {};
+        let source = converter
+            .convert(
+                &get_test_ast(),
+                &get_test_metadata(),
+                Formatting::with_format(Format::ChordPro),
+            )
+            .unwrap();
+
+        let (tokens, warnings) = build_tokenizer_for_file_type(FileType::ChordPro)
+            .tokenize(source.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        let parser_result = Parser::new().parse(tokens).unwrap();
+
+        assert_eq!(
+            converter
+                .convert(
+                    parser_result.node_as_ref(),
+                    parser_result.meta_as_ref(),
+                    Formatting::with_format(Format::ChordPro),
+                )
+                .unwrap(),
+            source
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use crate::error::{Error, Result};
+use crate::models::chord::{split_bass, Chord, Chords};
+use crate::models::file_type::FileType;
+use crate::models::meta::BNotation;
+use crate::parser::MetaParser;
+use crate::tokenizer::{
+    build_tokenizer_for_file_type, Meta, SpannedToken, Token, Tokenizer, TokenizerErrorKind,
+};
+
+use super::ChorddownConverter;
+
+/// Formatter rewriting chorddown sources in the canonical style
+///
+/// In contrast to the `ChorddownConverter` the formatter works on the tokens, so that blank lines
+/// inside of sections, comments and unknown meta lines are kept. The canonical style is:
+///
+/// - the title is followed by the meta information in the order of the `ChorddownConverter`.
+///   Custom meta fields (e.g. `Arranger: Jane`) keep their order. Meta lines inside the body are
+///   kept in place
+/// - chords keep their spelling, only the whitespace around the notes is removed and `B`/`H`
+///   notes are written in the song's B-notation
+/// - sections are separated by a single blank line and there are no blank lines after a headline
+/// - lines have no trailing whitespace and the file ends with a single newline
+///
+/// Sources with unbalanced chord brackets or invalid chords are not formatted, because the
+/// formatter could change the song
+#[derive(Default)]
+pub struct ChorddownFormatter {}
+
+impl ChorddownFormatter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Return the chorddown source in the canonical style
+    pub fn format(&self, source: &str) -> Result<String> {
+        let (tokens, warnings) = build_tokenizer_for_file_type(FileType::Chorddown)
+            .tokenize_with_spans(source.as_bytes())?;
+        // Formatting a source with unbalanced brackets would change the song
+        let unbalanced = warnings.iter().find(|warning| {
+            matches!(
+                warning.kind(),
+                TokenizerErrorKind::UnclosedChord
+                    | TokenizerErrorKind::NestedChord
+                    | TokenizerErrorKind::UnexpectedChordEnd
+            )
+        });
+        if let Some(warning) = unbalanced {
+            return Err(Error::parser_error(format!(
+                "Can not format source with problems: {}",
+                warning
+            ))
+            .with_span(warning.span()));
+        }
+
+        let meta = MetaParser::new().parse_iter(tokens.iter().map(|(token, _)| token))?;
+        let b_notation = meta.b_notation;
+
+        let lines = split_lines(tokens);
+        let mut output = vec![];
+        let mut body_start = 0;
+
+        // Title and meta information
+        let mut header = vec![];
+        let mut meta_lines: Vec<&Meta> = vec![];
+        if let Some([(Token::Headline { level: 1, text, .. }, _)]) =
+            lines.first().map(Vec::as_slice)
+        {
+            header.push(format!("# {}", text.trim()));
+            body_start = 1;
+        }
+        for line in &lines[body_start..] {
+            match line.as_slice() {
+                [(Token::Meta(meta), _)] => meta_lines.push(meta),
+                _ => break,
+            }
+            body_start += 1;
+        }
+        meta_lines.sort_by_key(|meta| meta_order(meta));
+        for meta in meta_lines {
+            header.push(self.format_meta(meta, b_notation));
+        }
+        if !header.is_empty() {
+            output.extend(header);
+            output.push(String::new());
+        }
+
+        // Song body
+        let mut after_headline = false;
+        for line in &lines[body_start..] {
+            let rendered = self.format_line(line, b_notation)?;
+            let starts_section = matches!(
+                line.first(),
+                Some((Token::Headline { .. }, _)) | Some((Token::Quote(_), _))
+            );
+            let previous_is_blank = output.last().is_none_or(String::is_empty);
+            let previous_is_quote = output.last().is_some_and(|l| l.starts_with("> "));
+
+            if rendered.is_empty() {
+                if !previous_is_blank && !after_headline {
+                    output.push(rendered);
+                }
+                continue;
+            }
+            if (starts_section || previous_is_quote) && !previous_is_blank {
+                output.push(String::new());
+            }
+            after_headline = matches!(line.first(), Some((Token::Headline { .. }, _)));
+            output.push(rendered);
+        }
+
+        while output.last().is_some_and(String::is_empty) {
+            output.pop();
+        }
+
+        Ok(format!("{}\n", output.join("\n")))
+    }
+
+    /// Return if the chorddown source is already in the canonical style
+    pub fn is_formatted(&self, source: &str) -> Result<bool> {
+        Ok(self.format(source)? == source)
+    }
+
+    fn format_meta(&self, meta: &Meta, b_notation: BNotation) -> String {
+        let content = match meta {
+            Meta::Key(raw) | Meta::OriginalKey(raw) => match Chord::try_from(raw, b_notation) {
+                Ok(_) => format_chord(raw, b_notation),
+                Err(_) => raw.trim().to_owned(),
+            },
+            _ => meta.content().trim().to_owned(),
+        };
+
+        format!("{}: {}", meta.keyword(), content)
+    }
+
+    fn format_line(&self, line: &[SpannedToken], b_notation: BNotation) -> Result<String> {
+        let converter = ChorddownConverter {};
+        let mut buffer = String::new();
+        for (token, span) in line {
+            match token {
+                Token::Chord(raw) => {
+                    Chords::try_from(raw, b_notation).map_err(|error| error.with_span(*span))?;
+                    buffer.push('[');
+                    buffer.push_str(&format_chord(raw, b_notation));
+                    buffer.push(']');
+                }
+                Token::Headline { level: 1, text, .. } => {
+                    buffer.push_str(&format!("# {}", text.trim()))
+                }
+                Token::Headline { .. } => buffer.push_str(converter.build_token(token).trim()),
+                Token::Quote(text) => buffer.push_str(&format!("> {}", text.trim())),
+                Token::Translation(text) => buffer.push_str(&format!("~ {}", text.trim())),
+                Token::Literal(text) => buffer.push_str(text),
+                Token::Meta(meta) => buffer.push_str(&self.format_meta(meta, b_notation)),
+                Token::Newline => {}
+            }
+        }
+
+        Ok(buffer.trim_end().to_owned())
+    }
+}
+
+/// Split the tokens into lines (without the `Newline` tokens)
+fn split_lines(tokens: Vec<SpannedToken>) -> Vec<Vec<SpannedToken>> {
+    let mut lines = vec![];
+    let mut line = vec![];
+    for (token, span) in tokens {
+        if token == Token::Newline {
+            lines.push(std::mem::take(&mut line));
+        } else {
+            line.push((token, span));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+fn meta_order(meta: &Meta) -> usize {
+    match meta {
+        Meta::Subtitle(_) => 0,
+        Meta::OriginalTitle(_) => 1,
+        Meta::AlternativeTitle(_) => 2,
+        Meta::Artist(_) => 3,
+        Meta::Composer(_) => 4,
+        Meta::Lyricist(_) => 5,
+        Meta::Copyright(_) => 6,
+        Meta::Album(_) => 7,
+        Meta::Year(_) => 8,
+        Meta::Key(_) => 9,
+        Meta::OriginalKey(_) => 10,
+        Meta::Time(_) => 11,
+        Meta::Tempo(_) => 12,
+        Meta::Duration(_) => 13,
+        Meta::Capo(_) => 14,
+        Meta::CCLISongId(_) => 15,
+        Meta::BNotation(_) => 16,
+        Meta::Tags(_) => 17,
+        Meta::Language(_) => 18,
+        Meta::Custom(_, _) => 19,
+        Meta::Arrangement(_) => 20,
+        Meta::Define(_) => 21,
+    }
+}
+
+/// Return the chord as written, without the whitespace around the notes and with the `B`/`H`
+/// notes in the given B-notation (e.g. `Bb/D` becomes `B/D` in the H notation)
+fn format_chord(raw: &str, b_notation: BNotation) -> String {
+    let (chord, second) = split_bass(raw);
+    let chord = format_b_note(chord.trim(), b_notation);
+    match second.map(str::trim) {
+        Some(second) if !second.is_empty() => {
+            format!("{}/{}", chord, format_b_note(second, b_notation))
+        }
+        _ => chord,
+    }
+}
+
+/// Write the `B`/`H` note at the start of the chord in the given B-notation
+fn format_b_note(chord: &str, b_notation: BNotation) -> String {
+    let mut chars = chord.chars();
+    let (first, second) = (chars.next(), chars.next());
+    let flat = matches!(second, Some('b' | '♭'));
+    let (note, rest) = match (first, b_notation) {
+        // B flat is written `B` in the H notation and `Bb` in the B notation
+        (Some('B' | 'H'), BNotation::H) if flat => ("B".to_owned(), 2),
+        (Some('b' | 'h'), BNotation::H) if flat => ("b".to_owned(), 2),
+        (Some('H'), BNotation::B) if flat => (format!("B{}", second.unwrap()), 2),
+        (Some('h'), BNotation::B) if flat => (format!("b{}", second.unwrap()), 2),
+        // B is written `H` in the H notation and `B` in the B notation
+        (Some('H'), BNotation::B) => ("B".to_owned(), 1),
+        (Some('h'), BNotation::B) => ("b".to_owned(), 1),
+        _ => return chord.to_owned(),
+    };
+
+    format!("{}{}", note, chord.chars().skip(rest).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        let source = "#   Swing Low Sweet Chariot\nArranger: Jane\nKey: D\nArtist: Traditional\n\n\n##! Chorus\n\nSwing [D]low, sweet [G]chari[D]ot,   \nComin’ for to carry me [A7]home.\n## Verse 1\nI [D]looked over Jordan\n\nA [Dmaj7]band of angels\n> Chorus\nTempo: 80\n";
+        assert_eq!(
+            ChorddownFormatter::new().format(source).unwrap(),
+            "# Swing Low Sweet Chariot
+Artist: Traditional
+Key: D
+Arranger: Jane
+
+##! Chorus
+Swing [D]low, sweet [G]chari[D]ot,
+Comin’ for to carry me [A7]home.
+
+## Verse 1
+I [D]looked over Jordan
+
+A [Dmaj7]band of angels
+
+> Chorus
+
+Tempo: 80
+"
+        );
+    }
+
+    #[test]
+    fn format_keeps_chord_spelling() {
+        let formatter = ChorddownFormatter::new();
+        let source = "# Song\nKey: C#\n\n## Verse\n[C#]Swing [F#m]low, sweet [G#7sus4]chari[Db/F]ot\nTempo: 80\n[A#min7/G#]Home\n";
+        assert_eq!(formatter.format(source).unwrap(), source);
+        assert!(formatter.is_formatted(source).unwrap());
+
+        assert_eq!(
+            formatter
+                .format("# Song\nKey: F\n\n[F]Swing [A#]low [ Amin7 / C ]\n")
+                .unwrap(),
+            "# Song\nKey: F\n\n[F]Swing [A#]low [Amin7/C]\n"
+        );
+    }
+
+    #[test]
+    fn format_b_notation() {
+        let formatter = ChorddownFormatter::new();
+        assert_eq!(
+            formatter
+                .format("# Song\n\n[H7]Swing [Eb]low [Bb/D] [Ebm/Bb]\n")
+                .unwrap(),
+            "# Song\n\n[H7]Swing [Eb]low [B/D] [Ebm/B]\n"
+        );
+        assert_eq!(
+            formatter
+                .format("# Song\nB-Notation: H\nKey: Bb\n\n[Bb]Swing [F]low\n")
+                .unwrap(),
+            "# Song\nKey: B\nB-Notation: H\n\n[B]Swing [F]low\n"
+        );
+        assert_eq!(
+            formatter.format("# Song\n\n[Bb]Swing [B7]low\n").unwrap(),
+            "# Song\n\n[Bb]Swing [B7]low\n"
+        );
+    }
+
+    #[test]
+    fn format_is_idempotent() {
+        let source = include_str!("../../../tests/resources/swing_low_sweet_chariot.chorddown");
+        let formatter = ChorddownFormatter::new();
+        let formatted = formatter.format(source).unwrap();
+        assert!(formatter.is_formatted(&formatted).unwrap());
+    }
+
+    #[test]
+    fn format_rejects_problems() {
+        let error = ChorddownFormatter::new()
+            .format("# Song\n\nSwing [D low\n")
+            .unwrap_err();
+        assert_eq!(error.span().map(|span| span.start().line()), Some(3));
+
+        let error = ChorddownFormatter::new()
+            .format("# Song\n\nSwing [Xm]low\n")
+            .unwrap_err();
+        assert_eq!(error.span().map(|span| span.start().line()), Some(3));
+    }
+}
@@ -7,6 +7,10 @@ use crate::tokenizer::Token;
 
 use super::ConverterTrait;
 
+pub use self::formatter::ChorddownFormatter;
+
+mod formatter;
+
 pub struct ChorddownConverter {}
 
 impl ConverterTrait for ChorddownConverter {
@@ -40,6 +44,7 @@ impl ChorddownConverter {
             Node::Document(children) => Ok(self.build_string_for_children(children, formatting)),
             Node::Headline(token) => Ok(self.build_token(token)),
             Node::Quote(token) => Ok(self.build_token(token)),
+            Node::Translation(token) => Ok(self.build_token(token)),
             Node::Meta(_) => {
                 // Metadata must have already been rendered
                 Ok(String::new())
@@ -61,6 +66,7 @@ impl ChorddownConverter {
         match token {
             Token::Literal(c) => c.to_owned(),
             Token::Quote(c) => format!("> {}\n", c),
+            Token::Translation(c) => format!("~ {}", c),
             Token::Headline {
                 level,
                 text,
@@ -152,9 +158,9 @@ impl ChorddownConverter {
             buffer.push_str(&v);
             buffer.push('\n')
         }
-        if let Some(v) = meta.capo() {
+        if let Some(v) = formatting.capo_for_meta(meta) {
             buffer.push_str("Capo: ");
-            buffer.push_str(&v);
+            buffer.push_str(&v.to_string());
             buffer.push('\n')
         }
         if let Some(v) = meta.ccli_song_id() {
@@ -162,6 +168,27 @@ impl ChorddownConverter {
             buffer.push_str(&v);
             buffer.push('\n')
         }
+        if let Some(v) = meta.language() {
+            buffer.push_str("Language: ");
+            buffer.push_str(&v);
+            buffer.push('\n')
+        }
+        for (keyword, value) in &meta.custom_fields() {
+            buffer.push_str(keyword);
+            buffer.push_str(": ");
+            buffer.push_str(value);
+            buffer.push('\n')
+        }
+        for arrangement in meta.arrangements() {
+            buffer.push_str("Arrangement: ");
+            buffer.push_str(&arrangement.to_string());
+            buffer.push('\n')
+        }
+        for definition in meta.chord_definitions() {
+            buffer.push_str("Define: ");
+            buffer.push_str(&definition.to_string());
+            buffer.push('\n')
+        }
         //        meta.b_notation()  // -> BNotation;
         buffer.trim_end().to_string()
     }
@@ -205,6 +232,7 @@ fn remove_double_blank_lines(input: &str) -> String {
 #[cfg(test)]
 mod tests {
     use crate::format::Format;
+    use crate::helper::parse_content;
     use crate::parser::MetaInformation;
     use crate::test_helpers::get_test_metadata;
     use crate::test_helpers::{
@@ -334,6 +362,119 @@ Swing [D]low, sweet [G]chari[D]ot.
 
 ##! Chorus
 Swing [D]low, sweet [G]chari[D]ot.
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_chord_definitions() {
+        let content = "# Song\n{define: Am 5 1 3 3 2 1 1}\n\n## Verse\n[Am]Swing low\n";
+        let parser_result = parse_content(content.as_bytes()).unwrap();
+        let result = ChorddownConverter {}.convert(
+            parser_result.node_as_ref(),
+            parser_result.meta_as_ref(),
+            Formatting::with_format(Format::Chorddown),
+        );
+
+        assert_eq!(
+            result.unwrap(),
+            r#"# Song
+Define: Am base-fret 5 frets 1 3 3 2 1 1
+
+## Verse
+[Am]Swing low
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_custom_meta() {
+        let content =
+            "# Song\nTheme: Grace\nArtist: Me\nBible reference: Psalm 23\n\n## Verse\nSwing low\n";
+        let parser_result = parse_content(content.as_bytes()).unwrap();
+        let result = ChorddownConverter {}.convert(
+            parser_result.node_as_ref(),
+            parser_result.meta_as_ref(),
+            Formatting::with_format(Format::Chorddown),
+        );
+
+        assert_eq!(
+            result.unwrap(),
+            r#"# Song
+Artist: Me
+Theme: Grace
+Bible reference: Psalm 23
+
+## Verse
+Swing low
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_custom_meta_round_trip() {
+        let content = "# Song\nTheme: Grace\nArranger: Jane\ntheme: Hope\nBible reference: Psalm 23\n\n## Verse\nSwing low\n";
+        let convert = |content: &str| {
+            let parser_result = parse_content(content.as_bytes()).unwrap();
+            ChorddownConverter {}
+                .convert(
+                    parser_result.node_as_ref(),
+                    parser_result.meta_as_ref(),
+                    Formatting::with_format(Format::Chorddown),
+                )
+                .unwrap()
+        };
+
+        // The last value of a repeated keyword is kept at the position of its first definition
+        let result = convert(content);
+        assert_eq!(
+            result,
+            "# Song\nTheme: Hope\nArranger: Jane\nBible reference: Psalm 23\n\n## Verse\nSwing low\n"
+        );
+        assert_eq!(convert(&result), result);
+    }
+
+    #[test]
+    fn test_convert_w_arrangement() {
+        let content = "# Song\nArrangement: Short = Verse,  2x Chorus\n\n## Verse\nSwing low\n";
+        let parser_result = parse_content(content.as_bytes()).unwrap();
+        assert_eq!(parser_result.meta_as_ref().arrangements.len(), 1);
+        let result = ChorddownConverter {}.convert(
+            parser_result.node_as_ref(),
+            parser_result.meta_as_ref(),
+            Formatting::with_format(Format::Chorddown),
+        );
+
+        assert_eq!(
+            result.unwrap(),
+            r#"# Song
+Arrangement: Short = Verse, 2x Chorus
+
+## Verse
+Swing low
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_translation() {
+        let content =
+            "# Song\nLanguage: English, German\n\n## Verse\n[D]Swing low\n~Schwing tief\n";
+        let parser_result = parse_content(content.as_bytes()).unwrap();
+        let result = ChorddownConverter {}.convert(
+            parser_result.node_as_ref(),
+            parser_result.meta_as_ref(),
+            Formatting::with_format(Format::Chorddown),
+        );
+
+        assert_eq!(
+            result.unwrap(),
+            r#"# Song
+Language: English, German
+
+## Verse
+[D]Swing low
+~ Schwing tief
 "#
         );
     }
@@ -0,0 +1,73 @@
+use crate::html::escape::Escape;
+use crate::models::chord::fmt::Formatting;
+use crate::models::chord::{Chord, NoteDisplay, TransposableTrait};
+use crate::models::diagram::{Diagram, Instrument};
+use crate::models::meta::{ChordNotation, MetaTrait};
+use crate::parser::Node;
+
+/// Build the legend with the diagrams of all chords used in the song
+///
+/// If a capo is set, the diagrams show the chord shapes played with the capo
+pub(super) fn build_diagram_legend(
+    node: &Node,
+    meta: &dyn MetaTrait,
+    instrument: Instrument,
+    formatting: Formatting,
+) -> String {
+    let mut chords = vec![];
+    collect_chords(node, &mut chords);
+
+    let fret = formatting.capo.map_or(0, |capo| capo.fret());
+    let caption_formatting = Formatting {
+        semitone_notation: formatting
+            .capo_semitone_notation
+            .unwrap_or(formatting.semitone_notation),
+        chord_notation: ChordNotation::Letter,
+        capo: None,
+        ..formatting
+    };
+    let definitions = meta.chord_definitions();
+
+    let mut figures = String::new();
+    for chord in chords {
+        let shape = chord.transpose(-(fret as isize));
+        if let Some(diagram) =
+            Diagram::for_chord(&shape, instrument, &definitions, meta.b_notation())
+        {
+            figures.push_str(&format!(
+                r#"<figure class="chord-diagram">{}<figcaption>{}</figcaption></figure>"#,
+                diagram.to_svg(),
+                Escape(&shape.note_format(caption_formatting))
+            ));
+        }
+    }
+
+    if figures.is_empty() {
+        String::new()
+    } else {
+        format!(r#"<div class="chord-diagrams">{}</div>"#, figures)
+    }
+}
+
+/// Collect the distinct chords in the order of their first appearance
+fn collect_chords(node: &Node, chords: &mut Vec<Chord>) {
+    let mut add = |chord: &Chord| {
+        if !chords.contains(chord) {
+            chords.push(chord.clone())
+        }
+    };
+    match node {
+        Node::ChordTextPair { chords: c, .. } | Node::ChordStandalone(c) => {
+            add(c.chord());
+            if let Some(second) = c.second_chord() {
+                add(second);
+            }
+        }
+        Node::Document(children) | Node::Section { children, .. } => {
+            for child in children {
+                collect_chords(child, chords);
+            }
+        }
+        _ => {}
+    }
+}
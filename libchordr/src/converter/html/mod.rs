@@ -1,3 +1,4 @@
+use diagram_legend::build_diagram_legend;
 use tag_provider::TagProvider;
 
 use crate::converter::ConverterTrait;
@@ -6,6 +7,7 @@ use crate::models::chord::NoteDisplay;
 use crate::models::song_meta_trait::SongMetaTrait;
 use crate::prelude::*;
 
+mod diagram_legend;
 mod tag_provider;
 
 pub struct HtmlConverter {}
@@ -19,13 +21,23 @@ impl HtmlConverter {
         formatting: Formatting,
     ) -> Result<String> {
         let tag = tag_builder.build_tag_for_node(node, meta, formatting);
+        let legend = match formatting.chord_diagrams {
+            Some(instrument) => {
+                format!(
+                    "\n{}",
+                    build_diagram_legend(node, meta, instrument, formatting)
+                )
+            }
+            None => String::new(),
+        };
 
         Ok(format!(
             r#"<div id="chordr">
-{}
+{}{}
 {}
 </div>"#,
             tag,
+            legend,
             self.format_meta(meta, formatting)
         ))
     }
@@ -74,7 +86,9 @@ CCLI Song #:        {}
             meta.time().unwrap_or_else(|| none_text.to_owned()),
             meta.tempo().unwrap_or_else(|| none_text.to_owned()),
             meta.duration().unwrap_or_else(|| none_text.to_owned()),
-            meta.capo().unwrap_or_else(|| none_text.to_owned()),
+            formatting
+                .capo_for_meta(meta)
+                .map_or_else(|| none_text.to_owned(), |capo| capo.to_string()),
             meta.ccli_song_id().unwrap_or_else(|| none_text.to_owned()),
         )
     }
@@ -116,4 +130,63 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), content.trim())
     }
+
+    #[test]
+    fn test_convert_with_chord_diagrams() {
+        let content = "# Song\nDefine: Am 5 1 3 3 2 1 1\n\n## Verse\n[Am]Swing [C]low, [Am]sweet [G/B]chariot\n";
+        let parser_result = parse_content(content.as_bytes()).unwrap();
+        let convert = |formatting| {
+            HtmlConverter {}
+                .convert(
+                    parser_result.node_as_ref(),
+                    parser_result.meta_as_ref(),
+                    formatting,
+                )
+                .unwrap()
+        };
+
+        let html = convert(Formatting::with_format(Format::HTML));
+        assert!(!html.contains("chord-diagrams"));
+        assert!(!html.contains("Define"));
+
+        let html = convert(Formatting {
+            chord_diagrams: Some(Instrument::Guitar),
+            ..Formatting::with_format(Format::HTML)
+        });
+        assert!(html.contains(r#"<div class="chord-diagrams">"#));
+        assert_eq!(html.matches(r#"<figure class="chord-diagram">"#).count(), 3);
+        assert!(html.contains("<figcaption>Am</figcaption>"));
+        assert!(html.contains("<figcaption>G/B</figcaption>"));
+        // The user definition is played at the fifth fret
+        assert!(html.contains(r#"text-anchor="end" fill="currentColor">5</text>"#));
+
+        // With a capo the diagrams show the chord shapes
+        let html = convert(
+            Formatting {
+                chord_diagrams: Some(Instrument::Guitar),
+                capo: Some(Capo::new(2).unwrap()),
+                ..Formatting::with_format(Format::HTML)
+            }
+            .for_key(None),
+        );
+        assert!(html.contains("<figcaption>Gm</figcaption>"));
+        assert!(html.contains("<figcaption>A#</figcaption>"));
+    }
+
+    #[test]
+    fn test_convert_with_translation() {
+        let content =
+            "# Song\nLanguage: English, German\n\n## Verse\n[D]Swing low\n~ Schwing tief\n";
+        let parser_result = parse_content(content.as_bytes()).unwrap();
+        let html = HtmlConverter {}
+            .convert(
+                parser_result.node_as_ref(),
+                parser_result.meta_as_ref(),
+                Formatting::with_format(Format::HTML),
+            )
+            .unwrap();
+
+        assert!(html.contains("<div class='translation-row'>Schwing tief</div>"));
+        assert!(html.contains("English, German"));
+    }
 }
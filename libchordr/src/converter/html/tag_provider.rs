@@ -42,6 +42,11 @@ impl TagProvider {
                 .build(),
             Node::Headline(token) => self.build_tag_for_token(token, formatting),
             Node::Quote(token) => self.build_tag_for_token(token, formatting),
+            Node::Translation(token) => self.build_tag_for_token(token, formatting),
+            // Chord definitions are shown in the diagram legend
+            Node::Meta(Meta::Define(_)) => Tag::blank(),
+            // Arrangements only define the order of the sections
+            Node::Meta(Meta::Arrangement(_)) => Tag::blank(),
             Node::Meta(m) => self.build_tag_for_meta(m, meta, formatting),
             Node::Newline => Tag::raw(format!("{}\n", Tag::hr())),
             Node::Section {
@@ -101,6 +106,7 @@ impl TagProvider {
         match token {
             Token::Literal(c) => Tag::span(Content::from_string(c), None),
             Token::Quote(c) => Tag::blockquote(Content::from_string(c), None),
+            Token::Translation(c) => Tag::div(Content::from_string(c), Some("translation-row")),
             Token::Headline {
                 level,
                 text: c,
@@ -200,7 +206,9 @@ fn get_song_metadata_content(
         Meta::Time(_) => song_metadata.time(),
         Meta::Tempo(_) => song_metadata.tempo(),
         Meta::Duration(_) => song_metadata.duration(),
-        Meta::Capo(_) => song_metadata.capo(),
+        Meta::Capo(_) => formatting
+            .capo_for_meta(song_metadata)
+            .map(|capo| capo.to_string()),
         Meta::OriginalTitle(_) => song_metadata.original_title(),
         Meta::AlternativeTitle(_) => song_metadata.alternative_title(),
         Meta::CCLISongId(_) => song_metadata.ccli_song_id(),
@@ -212,6 +220,10 @@ fn get_song_metadata_content(
                 None
             }
         }
+        Meta::Language(_) => song_metadata.language(),
+        Meta::Define(_) => None,
+        Meta::Arrangement(_) => None,
+        Meta::Custom(keyword, _) => song_metadata.custom_field(keyword),
     }
 }
 
@@ -244,6 +256,10 @@ const fn class_name_for_metadata_keyword(metadata_token: &Meta) -> &'static str
         Meta::CCLISongId(_) => "meta-keyword -ccli-song-id",
         Meta::BNotation(_) => "meta-keyword -b-notation",
         Meta::Tags(_) => "meta-keyword -tags",
+        Meta::Language(_) => "meta-keyword -language",
+        Meta::Define(_) => "meta-keyword -define",
+        Meta::Arrangement(_) => "meta-keyword -arrangement",
+        Meta::Custom(_, _) => "meta-keyword -custom",
     }
 }
 
@@ -267,5 +283,9 @@ const fn class_name_for_metadata_value(metadata_token: &Meta) -> &'static str {
         Meta::CCLISongId(_) => "meta-value -ccli-song-id",
         Meta::BNotation(_) => "meta-value -b-notation",
         Meta::Tags(_) => "meta-value -tags",
+        Meta::Language(_) => "meta-value -language",
+        Meta::Define(_) => "meta-value -define",
+        Meta::Arrangement(_) => "meta-value -arrangement",
+        Meta::Custom(_, _) => "meta-value -custom",
     }
 }
@@ -1,14 +1,27 @@
+use self::chord_pro::ChordProConverter;
 use self::chorddown::ChorddownConverter;
+pub use self::chorddown::ChorddownFormatter;
 use self::html::HtmlConverter;
+use self::open_lyrics::OpenLyricsConverter;
+#[cfg(feature = "pdf")]
+pub use self::pdf::PdfConverter;
 use crate::converter::songbeamer::SongBeamerConverter;
 use crate::converter::text::TextConverter;
 use crate::error::Result;
 use crate::models::chord::fmt::Formatting;
+use crate::models::meta::LanguageDisplay;
 use crate::models::song_meta_trait::SongMetaTrait;
+use crate::models::structure::Expansion;
+use crate::modification::language::LanguageSelectableTrait;
+use crate::modification::performance::ExpandableTrait;
 use crate::prelude::*;
 
+mod chord_pro;
 mod chorddown;
 mod html;
+mod open_lyrics;
+#[cfg(feature = "pdf")]
+mod pdf;
 mod songbeamer;
 mod text;
 
@@ -22,6 +35,16 @@ pub trait ConverterTrait {
     ) -> Result<String>;
 }
 
+/// Trait for converting into binary formats (e.g. PDF)
+pub trait BinaryConverterTrait {
+    fn convert_to_bytes(
+        &self,
+        node: &Node,
+        meta: &dyn SongMetaTrait,
+        formatting: Formatting,
+    ) -> Result<Vec<u8>>;
+}
+
 pub struct Converter {}
 
 impl Converter {
@@ -32,13 +55,52 @@ impl Converter {
     /// Build a Converter for the given format
     ///
     /// Factory method to build a `ConverterTrait` implementor instance to convert a [`Node`]
-    /// structure into the output format
+    /// structure into the output format. Binary formats (see `Format::get_all_binary()`) are
+    /// converted with `BinaryConverterTrait`, their `ConverterTrait` returns an error
     pub fn get_converter(format: Format) -> Box<dyn ConverterTrait> {
         match format {
             Format::HTML => Box::new(HtmlConverter {}),
             Format::Chorddown => Box::new(ChorddownConverter {}),
+            Format::ChordPro => Box::new(ChordProConverter {}),
             Format::SongBeamer => Box::new(SongBeamerConverter {}),
+            Format::OpenLyrics => Box::new(OpenLyricsConverter {}),
             Format::Text => Box::new(TextConverter {}),
+            Format::PDF => Box::new(UnsupportedFormatConverter { format }),
+        }
+    }
+}
+
+/// Placeholder for formats that can not be converted into a `String`
+struct UnsupportedFormatConverter {
+    format: Format,
+}
+
+impl ConverterTrait for UnsupportedFormatConverter {
+    fn convert(&self, _: &Node, _: &dyn SongMetaTrait, _: Formatting) -> Result<String> {
+        Err(Error::unknown_error(format!(
+            "Format {} is a binary format and can not be converted into a string",
+            self.format
+        )))
+    }
+}
+
+impl BinaryConverterTrait for Converter {
+    fn convert_to_bytes(
+        &self,
+        node: &Node,
+        meta: &dyn SongMetaTrait,
+        formatting: Formatting,
+    ) -> Result<Vec<u8>> {
+        let formatting = formatting.for_key(meta.key().as_ref());
+        match formatting.format {
+            #[cfg(feature = "pdf")]
+            Format::PDF => {
+                let expanded = prepare_for_format(node, formatting);
+                let node = expanded.as_ref().unwrap_or(node);
+
+                PdfConverter {}.convert_to_bytes(node, meta, formatting)
+            }
+            _ => Ok(self.convert(node, meta, formatting)?.into_bytes()),
         }
     }
 }
@@ -50,10 +112,34 @@ impl ConverterTrait for Converter {
         meta: &dyn SongMetaTrait,
         formatting: Formatting,
     ) -> Result<String> {
+        let formatting = formatting.for_key(meta.key().as_ref());
+        let expanded = prepare_for_format(node, formatting);
+        let node = expanded.as_ref().unwrap_or(node);
+
         Converter::get_converter(formatting.format).convert(node, meta, formatting)
     }
 }
 
+/// Return the Node tree to convert, if the `Formatting` requests an `Expansion` or a subset of the
+/// languages
+///
+/// SongBeamer files contain each section only once and list the performance in the verse order,
+/// so the `SongBeamerConverter` handles the expansion itself
+fn prepare_for_format(node: &Node, formatting: Formatting) -> Option<Node> {
+    let expand = formatting.expansion != Expansion::None && formatting.format != Format::SongBeamer;
+    let select_languages = formatting.language_display != LanguageDisplay::Interleaved;
+    if !expand && !select_languages {
+        return None;
+    }
+
+    let mut node = node.clone();
+    if expand {
+        node = node.expand(formatting.expansion);
+    }
+
+    Some(node.select_languages(formatting.language_display))
+}
+
 impl Default for Converter {
     fn default() -> Self {
         Self::new()
@@ -63,7 +149,7 @@ impl Default for Converter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_helpers::get_test_tokens;
+    use crate::test_helpers::{get_test_ast, get_test_metadata, get_test_tokens};
 
     #[test]
     fn test_convert() {
@@ -80,4 +166,151 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), content.trim())
     }
+
+    #[test]
+    fn test_get_converter() {
+        let (node, meta) = (get_test_ast(), get_test_metadata());
+        for format in Format::get_all_text() {
+            let result = Converter::get_converter(*format).convert(
+                &node,
+                &meta,
+                Formatting::with_format(*format),
+            );
+            assert!(result.is_ok(), "{}", format);
+        }
+        let result =
+            Converter::get_converter(Format::PDF).convert(&node, &meta, Formatting::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_convert_uses_notation_of_key() {
+        let content = "# Song\nKey: C\n\n[C]Swing [F]low, sweet [Am]chari[G7]ot\n";
+        let result = transpose_content(content.as_bytes(), 5).unwrap();
+        let convert = |semitone_notation| {
+            Converter::new()
+                .convert(
+                    result.node_as_ref(),
+                    result.meta_as_ref(),
+                    Formatting {
+                        semitone_notation,
+                        ..Formatting::with_format(Format::Chorddown)
+                    },
+                )
+                .unwrap()
+        };
+
+        let converted = convert(SemitoneNotation::Auto);
+        assert!(converted.contains("Key: F"));
+        assert!(converted.contains("[Bb]low"));
+
+        // An explicit notation overrides the key's notation
+        let converted = convert(SemitoneNotation::Sharp);
+        assert!(converted.contains("[A#]low"));
+
+        // Transposing into a sharp key
+        let result = transpose_content(content.as_bytes(), 4).unwrap();
+        let converted = Converter::new()
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting::with_format(Format::Chorddown),
+            )
+            .unwrap();
+        assert!(converted.contains("Key: E"));
+        assert!(converted.contains("[C#m]chari"));
+    }
+
+    #[test]
+    fn test_convert_relative_to_key() {
+        let content = "# Song\nKey: C\n\n[C]Swing [F]low, sweet [Am]chari[G7/B]ot\n";
+        let result = transpose_content(content.as_bytes(), 2).unwrap();
+        let convert = |chord_notation, format| {
+            Converter::new()
+                .convert(
+                    result.node_as_ref(),
+                    result.meta_as_ref(),
+                    Formatting {
+                        chord_notation,
+                        ..Formatting::with_format(format)
+                    },
+                )
+                .unwrap()
+        };
+
+        let converted = convert(ChordNotation::Nashville, Format::Chorddown);
+        assert!(converted.contains("Key: D"));
+        assert!(converted.contains("[1]Swing [4]low, sweet [6m]chari[5^7/7]ot"));
+
+        let converted = convert(ChordNotation::Roman, Format::HTML);
+        assert!(converted.contains(">IV<"));
+        assert!(converted.contains(">vi<"));
+        assert!(converted.contains(">V7/vii<"));
+
+        // Songs without a key are written as letters
+        let content = "# Song\n\n[C]Swing [F]low\n";
+        let result = transpose_content(content.as_bytes(), 0).unwrap();
+        let converted = Converter::new()
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting {
+                    chord_notation: ChordNotation::Nashville,
+                    ..Formatting::with_format(Format::Chorddown)
+                },
+            )
+            .unwrap();
+        assert!(converted.contains("[C]Swing [F]low"));
+    }
+
+    #[test]
+    fn test_convert_with_capo() {
+        let content = "# Song\nKey: Bb\nCapo: 1\n\n[Bb]Swing [Eb]low, sweet [Gm]chari[F/A]ot\n";
+        let result = transpose_content(content.as_bytes(), 0).unwrap();
+        let convert = |capo| {
+            Converter::new()
+                .convert(
+                    result.node_as_ref(),
+                    result.meta_as_ref(),
+                    Formatting {
+                        capo,
+                        ..Formatting::with_format(Format::Chorddown)
+                    },
+                )
+                .unwrap()
+        };
+
+        // The key stays the sounding key
+        let converted = convert(Some(Capo::new(3).unwrap()));
+        assert!(converted.contains("Key: Bb"));
+        assert!(converted.contains("Capo: 3"));
+        assert!(converted.contains("[G]Swing [C]low, sweet [Em]chari[D/F#]ot"));
+
+        let converted = convert(None);
+        assert!(converted.contains("Capo: 1"));
+        assert!(converted.contains("[Bb]Swing [Eb]low"));
+
+        let converted = convert(Some(Capo::new(0).unwrap()));
+        assert!(!converted.contains("Capo:"));
+        assert!(converted.contains("[Bb]Swing [Eb]low"));
+    }
+
+    #[test]
+    fn test_convert_w_expansion() {
+        let content = "# Swing Low\n\n##! Chorus\nSwing low\n\n> 2x Chorus\n";
+        let parser_result = crate::helper::parse_content(content.as_bytes()).unwrap();
+        let result = Converter::new().convert(
+            parser_result.node_as_ref(),
+            parser_result.meta_as_ref(),
+            Formatting {
+                expansion: Expansion::Annotated,
+                ..Formatting::with_format(Format::Chorddown)
+            },
+        );
+
+        assert_eq!(
+            result.unwrap(),
+            "# Swing Low\n\n##! Chorus\nSwing low\n\n##! Chorus (2x)\nSwing low\n"
+        );
+    }
 }
@@ -0,0 +1,478 @@
+use crate::error::Result;
+use crate::models::chord::fmt::*;
+use crate::models::chord::Chords;
+use crate::models::meta::MetaTrait;
+use crate::models::structure::{
+    ReferenceResolver, Section, SectionIdentifier, SectionProvider, SectionType,
+};
+use crate::parser::Node;
+use crate::tokenizer::{structure_for_chord_suffix, Token};
+
+use super::ConverterTrait;
+
+const NAMESPACE: &str = "http://openlyrics.info/namespace/2009/song";
+const VERSION: &str = "0.9";
+
+/// OpenLyrics requires at least one title
+const FALLBACK_TITLE: &str = "Untitled";
+
+/// Converter to build [OpenLyrics](https://docs.openlyrics.org/) XML files as used by
+/// [OpenLP](https://openlp.org/)
+pub struct OpenLyricsConverter {}
+
+impl ConverterTrait for OpenLyricsConverter {
+    fn convert(&self, node: &Node, meta: &dyn MetaTrait, formatting: Formatting) -> Result<String> {
+        let verses = self.collect_verses(node);
+
+        Ok(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<song xmlns="{}" version="{}" createdIn="Chordr" modifiedIn="Chordr">
+  <properties>
+{}
+  </properties>
+  <lyrics>
+{}
+  </lyrics>
+</song>
+"#,
+            NAMESPACE,
+            VERSION,
+            self.build_properties(node, meta, &verses, formatting),
+            self.build_lyrics(&verses, formatting)
+        ))
+    }
+}
+
+/// A section of the song with the name used in the OpenLyrics document (e.g. `v1` or `c1`)
+struct Verse<'a> {
+    name: String,
+    identifier: SectionIdentifier,
+    children: &'a [Node],
+}
+
+impl OpenLyricsConverter {
+    fn collect_verses<'a>(&self, node: &'a Node) -> Vec<Verse<'a>> {
+        let mut verses: Vec<Verse> = vec![];
+        if let Node::Document(children) = node {
+            for child in children {
+                if let Node::Section { children, .. } = child {
+                    match SectionProvider::get_section(child) {
+                        Some(section) if !section.is_reference => {
+                            let name = build_verse_name(&section, &verses);
+                            verses.push(Verse {
+                                name,
+                                identifier: section.identifier,
+                                children,
+                            })
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        verses
+    }
+
+    fn build_properties(
+        &self,
+        node: &Node,
+        meta: &dyn MetaTrait,
+        verses: &[Verse],
+        formatting: Formatting,
+    ) -> String {
+        let mut buffer: Vec<String> = vec![];
+
+        let mut titles: Vec<String> = vec![];
+        if let Some(v) = meta.title() {
+            titles.push(format!("      <title>{}</title>", escape(&v)))
+        }
+        if let Some(v) = meta.alternative_title() {
+            titles.push(format!("      <title>{}</title>", escape(&v)))
+        }
+        if let Some(v) = meta.original_title() {
+            titles.push(format!(
+                r#"      <title original="true">{}</title>"#,
+                escape(&v)
+            ))
+        }
+        if titles.is_empty() {
+            titles.push(format!("      <title>{}</title>", FALLBACK_TITLE))
+        }
+        buffer.push("    <titles>".to_owned());
+        buffer.append(&mut titles);
+        buffer.push("    </titles>".to_owned());
+
+        let mut authors: Vec<String> = vec![];
+        if let Some(v) = meta.artist() {
+            authors.push(format!("      <author>{}</author>", escape(&v)))
+        }
+        if let Some(v) = meta.composer() {
+            authors.push(format!(
+                r#"      <author type="music">{}</author>"#,
+                escape(&v)
+            ))
+        }
+        if let Some(v) = meta.lyricist() {
+            authors.push(format!(
+                r#"      <author type="words">{}</author>"#,
+                escape(&v)
+            ))
+        }
+        if !authors.is_empty() {
+            buffer.push("    <authors>".to_owned());
+            buffer.append(&mut authors);
+            buffer.push("    </authors>".to_owned());
+        }
+
+        if let Some(v) = meta.copyright() {
+            buffer.push(format!("    <copyright>{}</copyright>", escape(&v)))
+        }
+        if let Some(v) = meta.ccli_song_id() {
+            buffer.push(format!("    <ccliNo>{}</ccliNo>", escape(&v)))
+        }
+        if let Some(v) = meta.year() {
+            buffer.push(format!("    <released>{}</released>", escape(&v)))
+        }
+        if let Some(v) = meta.key() {
+            buffer.push(format!(
+                "    <key>{}</key>",
+                escape(&v.note_format(formatting))
+            ))
+        }
+        if let Some(v) = meta.tempo() {
+            let tempo_type = if v.trim().parse::<u32>().is_ok() {
+                "bpm"
+            } else {
+                "text"
+            };
+            buffer.push(format!(
+                r#"    <tempo type="{}">{}</tempo>"#,
+                tempo_type,
+                escape(v.trim())
+            ))
+        }
+
+        let verse_order = self.build_verse_order(node, verses);
+        if !verse_order.is_empty() {
+            buffer.push(format!("    <verseOrder>{}</verseOrder>", verse_order))
+        }
+
+        let tags = meta.tags();
+        if !tags.is_empty() {
+            buffer.push("    <themes>".to_owned());
+            for tag in tags.iter() {
+                buffer.push(format!(
+                    "      <theme>{}</theme>",
+                    escape(&tag.to_string_without_hashtag())
+                ))
+            }
+            buffer.push("    </themes>".to_owned());
+        }
+
+        buffer.join("\n")
+    }
+
+    fn build_verse_order(&self, node: &Node, verses: &[Verse]) -> String {
+        let reference_resolver = ReferenceResolver::new();
+        let sections = node.get_sections();
+        let get_verse_name = |section: &Section| {
+            let identifier = if section.is_reference {
+                &reference_resolver
+                    .resolve_reference(section, &sections)?
+                    .identifier
+            } else {
+                &section.identifier
+            };
+
+            verses
+                .iter()
+                .find(|verse| &verse.identifier == identifier)
+                .map(|verse| verse.name.clone())
+        };
+
+        sections
+            .iter()
+            .filter_map(get_verse_name)
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn build_lyrics(&self, verses: &[Verse], formatting: Formatting) -> String {
+        verses
+            .iter()
+            .map(|verse| {
+                format!(
+                    "    <verse name=\"{}\">\n{}\n    </verse>",
+                    verse.name,
+                    self.build_lines(verse.children, formatting)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Build the `<lines>` elements for the given section content
+    ///
+    /// Each block of lines separated by an empty line is rendered as separate `<lines>` element
+    fn build_lines(&self, children: &[Node], formatting: Formatting) -> String {
+        let mut blocks: Vec<Vec<String>> = vec![vec![]];
+        let mut current_line = String::new();
+        for child in children {
+            match child {
+                Node::Newline => {
+                    if current_line.trim().is_empty() {
+                        if blocks.last().is_some_and(|block| !block.is_empty()) {
+                            blocks.push(vec![]);
+                        }
+                    } else if let Some(block) = blocks.last_mut() {
+                        block.push(current_line.trim_end().to_owned());
+                    }
+                    current_line.clear();
+                }
+                _ => current_line.push_str(&self.build_node(child, formatting)),
+            }
+        }
+        if !current_line.trim().is_empty() {
+            if let Some(block) = blocks.last_mut() {
+                block.push(current_line.trim_end().to_owned());
+            }
+        }
+
+        blocks
+            .iter()
+            .filter(|block| !block.is_empty())
+            .map(|block| format!("      <lines>{}</lines>", block.join("<br/>")))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn build_node(&self, node: &Node, formatting: Formatting) -> String {
+        match node {
+            Node::ChordTextPair {
+                chords,
+                text,
+                last_in_line: _,
+            } => format!(
+                "{}{}",
+                self.build_string_for_chords(chords, formatting),
+                self.build_token(text)
+            ),
+            Node::ChordStandalone(chords) => self.build_string_for_chords(chords, formatting),
+            Node::Text(text) => self.build_token(text),
+            // OpenLyrics has no inline translations, so they are written as separate lines
+            Node::Translation(text) => self.build_token(text),
+            _ => String::new(),
+        }
+    }
+
+    fn build_token(&self, token: &Token) -> String {
+        match token {
+            Token::Literal(c) | Token::Translation(c) => escape(c),
+            _ => String::new(),
+        }
+    }
+
+    /// Build the `<chord>` element with the `root`, `structure` and `bass` attributes
+    ///
+    /// Chords without a known structure (e.g. polychords or relative chord notations) fall back to
+    /// the `name` attribute
+    fn build_string_for_chords(&self, chords: &Chords, formatting: Formatting) -> String {
+        let name = chords.note_format(formatting);
+        let parts = split_chord_name(&name).and_then(|(root, suffix, bass)| {
+            Some((root, structure_for_chord_suffix(suffix)?, bass))
+        });
+        match parts {
+            Some((root, structure, bass)) => {
+                let mut element = format!(r#"<chord root="{}""#, escape(root));
+                if structure != "3-5" {
+                    element.push_str(&format!(r#" structure="{}""#, structure));
+                }
+                if let Some(bass) = bass {
+                    element.push_str(&format!(r#" bass="{}""#, escape(bass)));
+                }
+                element.push_str("/>");
+
+                element
+            }
+            None => format!(r#"<chord name="{}"/>"#, escape(&name)),
+        }
+    }
+}
+
+/// Split the displayed chord into root, suffix and bass (e.g. `F#`, `m7` and `A` for `F#m7/A`)
+fn split_chord_name(name: &str) -> Option<(&str, &str, Option<&str>)> {
+    let mut characters = name.char_indices();
+    match characters.next() {
+        Some((_, 'A'..='H')) => {}
+        _ => return None,
+    }
+    let root_end = match characters.next() {
+        Some((index, '#' | 'b')) => index + 1,
+        Some((index, _)) => index,
+        None => name.len(),
+    };
+    let (root, rest) = name.split_at(root_end);
+    match rest.split_once('/') {
+        // The bass must be a single note (e.g. not `6/9` or the polychord `C/Dm`)
+        Some((suffix, bass)) => match split_chord_name(bass) {
+            Some((_, "", None)) => Some((root, suffix, Some(bass))),
+            _ => None,
+        },
+        None => Some((root, rest, None)),
+    }
+}
+
+/// Build a unique OpenLyrics verse name for the section (`v1`, `c1`, `b1`, ...)
+fn build_verse_name(section: &Section, verses: &[Verse]) -> String {
+    let prefix = verse_name_prefix(section);
+    let number_from_title = section
+        .title
+        .rsplit(' ')
+        .next()
+        .and_then(|part| part.parse::<usize>().ok());
+
+    let mut number = number_from_title.unwrap_or(1);
+    loop {
+        let name = format!("{}{}", prefix, number);
+        if !verses.iter().any(|verse| verse.name == name) {
+            return name;
+        }
+        number += 1;
+    }
+}
+
+fn verse_name_prefix(section: &Section) -> char {
+    let title = section.title.to_lowercase();
+    if title.starts_with("pre") {
+        'p'
+    } else if title.starts_with("intro") {
+        'i'
+    } else if title.starts_with("outro") || title.starts_with("ending") || title.starts_with("coda")
+    {
+        'e'
+    } else {
+        match section.section_type {
+            SectionType::Chorus => 'c',
+            SectionType::Bridge => 'b',
+            SectionType::Verse | SectionType::Reference => 'v',
+        }
+    }
+}
+
+fn escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::Format;
+    use crate::models::meta::BNotation;
+    use crate::parser::MetaInformation;
+    use crate::test_helpers::get_test_metadata;
+    use crate::test_helpers::{get_test_ast, get_test_ast_with_quote};
+
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let converter = OpenLyricsConverter {};
+        let result = converter.convert(
+            &get_test_ast(),
+            &MetaInformation::default(),
+            Formatting::with_format(Format::OpenLyrics),
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<song xmlns="http://openlyrics.info/namespace/2009/song" version="0.9" createdIn="Chordr" modifiedIn="Chordr">
+  <properties>
+    <titles>
+      <title>Untitled</title>
+    </titles>
+    <verseOrder>c1 v1 c1</verseOrder>
+  </properties>
+  <lyrics>
+    <verse name="c1">
+      <lines>Swing <chord root="D"/>low, sweet <chord root="G"/>chari<chord root="D"/>ot,<br/>Comin’ for to carry me <chord root="A" structure="3-5-m7"/>home.<br/>Swing <chord root="D" structure="3-5-m7"/>low, sweet <chord root="G"/>chari<chord root="D"/>ot,<br/>Comin’ for to <chord root="A" structure="3-5-m7"/>carry me <chord root="D"/>home.</lines>
+    </verse>
+    <verse name="v1">
+      <lines>I <chord root="D"/>looked over Jordan, and <chord root="G"/>what did I <chord root="D"/>see,<br/>Comin’ for to carry me <chord root="A" structure="3-5-m7"/>home.<br/>A <chord root="D"/>band of angels <chord root="G"/>comin’ after <chord root="D"/>me,<br/>Comin’ for to <chord root="A" structure="3-5-m7"/>carry me <chord root="D"/>home.</lines>
+    </verse>
+  </lyrics>
+</song>
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_metadata() {
+        let converter = OpenLyricsConverter {};
+        let result = converter.convert(
+            &get_test_ast(),
+            &get_test_metadata(),
+            Formatting::with_format(Format::OpenLyrics),
+        );
+
+        assert!(result.is_ok());
+        let source = result.unwrap();
+        assert!(source.contains(
+            r#"  <properties>
+    <titles>
+      <title>Great new song</title>
+    </titles>
+    <authors>
+      <author>Me</author>
+      <author type="music">Wallace Willis</author>
+      <author type="words">Wallace Willis</author>
+    </authors>
+    <released>1865</released>
+    <verseOrder>c1 v1 c1</verseOrder>
+    <themes>
+      <theme>oldie</theme>
+      <theme>Jordan</theme>
+    </themes>
+  </properties>"#
+        ));
+    }
+
+    #[test]
+    fn test_convert_w_content_after_quote() {
+        let converter = OpenLyricsConverter {};
+        let result = converter.convert(
+            &get_test_ast_with_quote(),
+            &MetaInformation::default(),
+            Formatting::with_format(Format::OpenLyrics),
+        );
+
+        assert!(result.is_ok());
+        let source = result.unwrap();
+        assert!(source.contains("<verseOrder>c1 c1</verseOrder>"));
+        assert!(source.contains(r#"<verse name="c1">"#));
+    }
+
+    #[test]
+    fn test_build_string_for_chords() {
+        let converter = OpenLyricsConverter {};
+        let build = |chord: &str| {
+            converter.build_string_for_chords(
+                &Chords::try_from(chord, BNotation::B).unwrap(),
+                Formatting::with_format(Format::OpenLyrics),
+            )
+        };
+        assert_eq!(build("C"), r#"<chord root="C"/>"#);
+        assert_eq!(
+            build("F#m7/A"),
+            r#"<chord root="F#" structure="m3-5-m7" bass="A"/>"#
+        );
+        assert_eq!(build("Bbmaj7"), r#"<chord root="A#" structure="3-5-7"/>"#);
+        assert_eq!(build("D6/9"), r#"<chord name="D6/9"/>"#);
+        assert_eq!(build("C/Dm"), r#"<chord name="C/Dm"/>"#);
+    }
+}
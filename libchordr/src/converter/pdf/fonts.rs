@@ -0,0 +1,99 @@
+use ttf_parser::Face;
+
+use crate::error::{Error, Result};
+
+const REGULAR: &[u8] = include_bytes!("../../../assets/fonts/LibreBaskerville-Regular.ttf");
+const BOLD: &[u8] = include_bytes!("../../../assets/fonts/LibreBaskerville-Bold.ttf");
+const ITALIC: &[u8] = include_bytes!("../../../assets/fonts/LibreBaskerville-Italic.ttf");
+
+/// Millimeters per typographic point
+const MM_PER_POINT: f32 = 0.352_778;
+
+/// The font variants used in the document
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(super) enum FontStyle {
+    Regular,
+    Bold,
+    Italic,
+}
+
+impl FontStyle {
+    pub(super) fn all() -> &'static [FontStyle] {
+        &[FontStyle::Regular, FontStyle::Bold, FontStyle::Italic]
+    }
+
+    /// Return the raw TrueType data to embed into the document
+    pub(super) fn data(&self) -> &'static [u8] {
+        match self {
+            FontStyle::Regular => REGULAR,
+            FontStyle::Bold => BOLD,
+            FontStyle::Italic => ITALIC,
+        }
+    }
+}
+
+/// Font metrics to measure the rendered width of text
+pub(super) struct Fonts {
+    regular: Face<'static>,
+    bold: Face<'static>,
+    italic: Face<'static>,
+}
+
+impl Fonts {
+    pub(super) fn new() -> Result<Self> {
+        Ok(Self {
+            regular: parse_face(FontStyle::Regular)?,
+            bold: parse_face(FontStyle::Bold)?,
+            italic: parse_face(FontStyle::Italic)?,
+        })
+    }
+
+    /// Return the width of `text` in millimeters
+    pub(super) fn text_width(&self, text: &str, style: FontStyle, size: f32) -> f32 {
+        let face = self.face(style);
+        let units_per_em = face.units_per_em() as f32;
+        let advance: u32 = text
+            .chars()
+            .map(|c| {
+                face.glyph_index(c)
+                    .and_then(|glyph| face.glyph_hor_advance(glyph))
+                    .unwrap_or(0) as u32
+            })
+            .sum();
+
+        points_to_mm(advance as f32 / units_per_em * size)
+    }
+
+    fn face(&self, style: FontStyle) -> &Face<'static> {
+        match style {
+            FontStyle::Regular => &self.regular,
+            FontStyle::Bold => &self.bold,
+            FontStyle::Italic => &self.italic,
+        }
+    }
+}
+
+pub(super) fn points_to_mm(points: f32) -> f32 {
+    points * MM_PER_POINT
+}
+
+fn parse_face(style: FontStyle) -> Result<Face<'static>> {
+    Face::parse(style.data(), 0)
+        .map_err(|e| Error::pdf_error(format!("Could not load the {:?} font: {}", style, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_width() {
+        let fonts = Fonts::new().unwrap();
+        assert_eq!(0.0, fonts.text_width("", FontStyle::Regular, 11.0));
+
+        let width = fonts.text_width("Swing low", FontStyle::Regular, 11.0);
+        assert!(width > 10.0 && width < 30.0, "{}", width);
+        assert!(fonts.text_width("Swing low", FontStyle::Regular, 22.0) > width * 1.9);
+        assert!(fonts.text_width("Swing low", FontStyle::Bold, 11.0) > width);
+    }
+}
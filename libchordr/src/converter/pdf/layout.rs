@@ -0,0 +1,741 @@
+use crate::models::chord::fmt::{Formatting, NoteDisplay};
+use crate::models::meta::MetaTrait;
+use crate::parser::{Node, SectionType};
+use crate::tokenizer::Token;
+
+use super::fonts::{points_to_mm, FontStyle, Fonts};
+
+pub(super) const PAGE_WIDTH: f32 = 210.0;
+pub(super) const PAGE_HEIGHT: f32 = 297.0;
+pub(super) const MARGIN_LEFT: f32 = 20.0;
+pub(super) const MARGIN_RIGHT: f32 = 20.0;
+pub(super) const MARGIN_TOP: f32 = 20.0;
+pub(super) const MARGIN_BOTTOM: f32 = 22.0;
+
+const TITLE_SIZE: f32 = 20.0;
+const SUBTITLE_SIZE: f32 = 12.0;
+const INFO_SIZE: f32 = 9.5;
+const HEADLINE_SIZE: f32 = 12.0;
+const LYRIC_SIZE: f32 = 11.0;
+const CHORD_SIZE: f32 = 10.0;
+const TOC_SIZE: f32 = 11.0;
+
+/// Horizontal space between a chord and the next chord
+const CHORD_GAP: f32 = 1.5;
+/// Indention of chorus and bridge sections
+const SECTION_INDENT: f32 = 6.0;
+/// Indention of the continuation of a wrapped line
+const WRAP_INDENT: f32 = 4.0;
+const LINE_HEIGHT_FACTOR: f32 = 1.35;
+const CHORD_LINE_HEIGHT_FACTOR: f32 = 1.2;
+const BLANK_LINE_HEIGHT: f32 = 2.5;
+const SECTION_SPACING: f32 = 4.0;
+const CHORUS_BAR_WIDTH: f32 = 0.8;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(super) enum Color {
+    Text,
+    Chord,
+    Muted,
+    ChorusBar,
+}
+
+/// A positioned element
+///
+/// The coordinates are in millimeters, measured from the left and the top of the surrounding
+/// `Block` (or `Page` after pagination). The `y` coordinate of `Text` is the baseline
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Item {
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        style: FontStyle,
+        size: f32,
+        color: Color,
+    },
+    Bar {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: Color,
+    },
+}
+
+impl Item {
+    fn moved_down(self, offset: f32) -> Self {
+        match self {
+            Item::Text {
+                x,
+                y,
+                text,
+                style,
+                size,
+                color,
+            } => Item::Text {
+                x,
+                y: y + offset,
+                text,
+                style,
+                size,
+                color,
+            },
+            Item::Bar {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => Item::Bar {
+                x,
+                y: y + offset,
+                width,
+                height,
+                color,
+            },
+        }
+    }
+}
+
+/// A horizontal strip of content that will not be split across pages
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Block {
+    pub(super) height: f32,
+    pub(super) items: Vec<Item>,
+}
+
+impl Block {
+    fn spacer(height: f32) -> Self {
+        Self {
+            height,
+            items: vec![],
+        }
+    }
+
+    pub(super) fn text<S: Into<String>>(
+        text: S,
+        x: f32,
+        style: FontStyle,
+        size: f32,
+        color: Color,
+    ) -> Self {
+        Self {
+            height: points_to_mm(size) * LINE_HEIGHT_FACTOR,
+            items: vec![Item::Text {
+                x,
+                y: points_to_mm(size),
+                text: text.into(),
+                style,
+                size,
+                color,
+            }],
+        }
+    }
+}
+
+/// A list of `Block`s that should be kept on the same page if possible (e.g. a section)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(super) struct Group {
+    pub(super) blocks: Vec<Block>,
+}
+
+impl Group {
+    fn height(&self) -> f32 {
+        self.blocks.iter().map(|b| b.height).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(super) struct Page {
+    pub(super) items: Vec<Item>,
+}
+
+/// Build the layout of a single song
+pub(super) struct SongLayouter<'a> {
+    fonts: &'a Fonts,
+    formatting: Formatting,
+}
+
+impl<'a> SongLayouter<'a> {
+    pub(super) fn new(fonts: &'a Fonts, formatting: Formatting) -> Self {
+        Self { fonts, formatting }
+    }
+
+    pub(super) fn layout(&self, node: &Node, meta: &dyn MetaTrait) -> Vec<Group> {
+        let mut groups = vec![self.layout_header(meta)];
+        if let Node::Document(children) = node {
+            for child in children {
+                groups.append(&mut self.layout_top_level_node(child));
+            }
+        }
+
+        groups
+            .into_iter()
+            .filter(|group| !group.blocks.is_empty())
+            .collect()
+    }
+
+    fn layout_header(&self, meta: &dyn MetaTrait) -> Group {
+        let mut blocks = vec![];
+        if let Some(title) = meta.title() {
+            blocks.push(Block::text(
+                title,
+                MARGIN_LEFT,
+                FontStyle::Bold,
+                TITLE_SIZE,
+                Color::Text,
+            ));
+        }
+        if let Some(subtitle) = meta.subtitle() {
+            blocks.push(Block::text(
+                subtitle,
+                MARGIN_LEFT,
+                FontStyle::Italic,
+                SUBTITLE_SIZE,
+                Color::Text,
+            ));
+        }
+
+        let mut people: Vec<String> = vec![];
+        for person in [meta.artist(), meta.composer(), meta.lyricist()]
+            .into_iter()
+            .flatten()
+        {
+            if !people.contains(&person) {
+                people.push(person)
+            }
+        }
+        if !people.is_empty() {
+            blocks.push(self.info_block(people.join(" · ")));
+        }
+
+        let mut details: Vec<String> = vec![];
+        if let Some(key) = meta.key() {
+            details.push(format!("Key: {}", key.note_format(self.formatting)));
+        }
+        if let Some(capo) = self.formatting.capo_for_meta(meta) {
+            details.push(format!("Capo: {}", capo));
+        }
+        if let Some(time) = meta.time() {
+            details.push(format!("Time: {}", time));
+        }
+        if let Some(tempo) = meta.tempo() {
+            details.push(format!("Tempo: {}", tempo));
+        }
+        if let Some(copyright) = meta.copyright() {
+            details.push(format!("© {}", copyright));
+        }
+        if !details.is_empty() {
+            blocks.push(self.info_block(details.join(" · ")));
+        }
+        if !blocks.is_empty() {
+            blocks.push(Block::spacer(SECTION_SPACING));
+        }
+
+        Group { blocks }
+    }
+
+    fn info_block(&self, text: String) -> Block {
+        Block::text(
+            text,
+            MARGIN_LEFT,
+            FontStyle::Italic,
+            INFO_SIZE,
+            Color::Muted,
+        )
+    }
+
+    fn layout_top_level_node(&self, node: &Node) -> Vec<Group> {
+        match node {
+            Node::Section {
+                head,
+                children,
+                section_type,
+            } => match head.as_ref() {
+                Node::Quote(Token::Quote(text)) => {
+                    let mut blocks = vec![Block::text(
+                        text,
+                        MARGIN_LEFT,
+                        FontStyle::Italic,
+                        LYRIC_SIZE,
+                        Color::Muted,
+                    )];
+                    blocks.append(&mut self.layout_lines(children, SectionType::Verse));
+                    blocks.push(Block::spacer(SECTION_SPACING));
+                    vec![Group { blocks }]
+                }
+                Node::Headline(Token::Headline { level, text, .. }) if *level > 1 => {
+                    let mut blocks = vec![Block::text(
+                        text,
+                        MARGIN_LEFT,
+                        FontStyle::Bold,
+                        HEADLINE_SIZE,
+                        Color::Text,
+                    )];
+                    blocks.append(&mut self.layout_lines(children, *section_type));
+                    blocks.push(Block::spacer(SECTION_SPACING));
+                    vec![Group { blocks }]
+                }
+                _ => children
+                    .iter()
+                    .flat_map(|child| self.layout_top_level_node(child))
+                    .collect(),
+            },
+            Node::Document(children) => children
+                .iter()
+                .flat_map(|child| self.layout_top_level_node(child))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Build the `Block`s for the lyric lines of a section
+    fn layout_lines(&self, children: &[Node], section_type: SectionType) -> Vec<Block> {
+        let mut lines: Vec<Vec<&Node>> = vec![vec![]];
+        for child in children {
+            match child {
+                Node::Newline => lines.push(vec![]),
+                Node::Meta(_) => {}
+                _ => {
+                    if let Some(line) = lines.last_mut() {
+                        line.push(child)
+                    }
+                }
+            }
+        }
+
+        // Remove leading and trailing blank lines and collapse multiple blank lines
+        let mut blocks: Vec<Block> = vec![];
+        let mut pending_blank = false;
+        for line in lines {
+            if line.is_empty() {
+                pending_blank = !blocks.is_empty();
+                continue;
+            }
+            if pending_blank {
+                blocks.push(Block::spacer(BLANK_LINE_HEIGHT));
+                pending_blank = false;
+            }
+            if let [Node::Translation(Token::Translation(text))] = line.as_slice() {
+                blocks.push(Block::text(
+                    text,
+                    line_start_x(section_type),
+                    FontStyle::Italic,
+                    LYRIC_SIZE,
+                    Color::Muted,
+                ));
+                continue;
+            }
+            blocks.append(&mut self.layout_line(&line, section_type));
+        }
+
+        if section_type == SectionType::Chorus {
+            for block in &mut blocks {
+                block.items.push(Item::Bar {
+                    x: MARGIN_LEFT + 1.0,
+                    y: 0.0,
+                    width: CHORUS_BAR_WIDTH,
+                    height: block.height,
+                    color: Color::ChorusBar,
+                });
+            }
+        }
+
+        blocks
+    }
+
+    /// Build the `Block`s for a single line
+    ///
+    /// Chords are placed above the beginning of the text they belong to. If the line is wider
+    /// than the page it will be wrapped at word boundaries into multiple `Block`s. A chord always
+    /// stays together with the first word of its text
+    fn layout_line(&self, nodes: &[&Node], section_type: SectionType) -> Vec<Block> {
+        let start_x = line_start_x(section_type);
+        let lyric_style = match section_type {
+            SectionType::Bridge => FontStyle::Italic,
+            _ => FontStyle::Regular,
+        };
+        let max_x = PAGE_WIDTH - MARGIN_RIGHT;
+        let text_width = |text: &str| self.fonts.text_width(text, lyric_style, LYRIC_SIZE);
+
+        let mut rows: Vec<Vec<(f32, Option<String>, String)>> = vec![vec![]];
+        // Width of the chord of the last entry in the current row
+        let mut chord_width = 0.0;
+        let mut x = start_x;
+        for node in nodes {
+            let (mut chord, text) = match node {
+                Node::ChordTextPair { chords, text, .. } => (
+                    Some(chords.note_format(self.formatting)),
+                    literal_text(text),
+                ),
+                Node::ChordStandalone(chords) => {
+                    (Some(chords.note_format(self.formatting)), String::new())
+                }
+                Node::Text(text) => (None, literal_text(text)),
+                _ => continue,
+            };
+
+            // A standalone chord is placed like a chord with an empty word
+            let words: Vec<&str> = if text.is_empty() {
+                vec![""]
+            } else {
+                text.split_inclusive(char::is_whitespace).collect()
+            };
+            for (index, word) in words.into_iter().enumerate() {
+                let word_chord = chord.take();
+                let word_chord_width = word_chord.as_ref().map_or(0.0, |chord| {
+                    self.fonts.text_width(chord, FontStyle::Bold, CHORD_SIZE) + CHORD_GAP
+                });
+                // Trailing whitespace may extend beyond the right margin
+                let visible_width = text_width(word.trim_end()).max(word_chord_width);
+
+                let row_is_empty = rows.last().is_none_or(Vec::is_empty);
+                let wraps = x + visible_width > max_x && !row_is_empty;
+                if wraps {
+                    rows.push(vec![]);
+                    x = start_x + WRAP_INDENT;
+                }
+                let row = match rows.last_mut() {
+                    Some(row) => row,
+                    None => continue,
+                };
+                match row.last_mut() {
+                    // Continue the text of the node in the same entry
+                    Some((entry_x, _, entry_text)) if index > 0 && !wraps => {
+                        entry_text.push_str(word);
+                        x = *entry_x + text_width(entry_text).max(chord_width);
+                    }
+                    _ => {
+                        row.push((x, word_chord, word.to_owned()));
+                        chord_width = word_chord_width;
+                        x += text_width(word).max(word_chord_width);
+                    }
+                }
+            }
+        }
+
+        rows.into_iter()
+            .filter(|row| !row.is_empty())
+            .map(|row| self.build_line_block(row, lyric_style))
+            .collect()
+    }
+
+    fn build_line_block(&self, row: Vec<(f32, Option<String>, String)>, style: FontStyle) -> Block {
+        let has_chords = row.iter().any(|(_, chord, _)| chord.is_some());
+        let chord_row_height = if has_chords {
+            points_to_mm(CHORD_SIZE) * CHORD_LINE_HEIGHT_FACTOR
+        } else {
+            0.0
+        };
+        let lyric_baseline = chord_row_height + points_to_mm(LYRIC_SIZE);
+
+        let mut items = vec![];
+        for (x, chord, text) in row {
+            if let Some(chord) = chord {
+                items.push(Item::Text {
+                    x,
+                    y: points_to_mm(CHORD_SIZE),
+                    text: chord,
+                    style: FontStyle::Bold,
+                    size: CHORD_SIZE,
+                    color: Color::Chord,
+                });
+            }
+            if !text.trim().is_empty() {
+                items.push(Item::Text {
+                    x,
+                    y: lyric_baseline,
+                    text,
+                    style,
+                    size: LYRIC_SIZE,
+                    color: Color::Text,
+                });
+            }
+        }
+
+        Block {
+            height: chord_row_height + points_to_mm(LYRIC_SIZE) * LINE_HEIGHT_FACTOR,
+            items,
+        }
+    }
+}
+
+/// Distribute the `Group`s onto pages
+///
+/// A `Group` is moved to the next page, if it does not fit onto the current page but would fit
+/// onto an empty one. Otherwise the `Block`s are distributed one by one
+pub(super) fn paginate(groups: Vec<Group>) -> Vec<Page> {
+    let bottom = PAGE_HEIGHT - MARGIN_BOTTOM;
+    let content_height = bottom - MARGIN_TOP;
+
+    let mut pages = vec![Page::default()];
+    let mut y = MARGIN_TOP;
+    for group in groups {
+        let group_height = group.height();
+        if y + group_height > bottom && group_height <= content_height && y > MARGIN_TOP {
+            pages.push(Page::default());
+            y = MARGIN_TOP;
+        }
+
+        for block in group.blocks {
+            if y + block.height > bottom && y > MARGIN_TOP {
+                pages.push(Page::default());
+                y = MARGIN_TOP;
+                if block.items.is_empty() {
+                    // Do not start a page with a spacer
+                    continue;
+                }
+            }
+            if let Some(page) = pages.last_mut() {
+                page.items
+                    .extend(block.items.into_iter().map(|item| item.moved_down(y)));
+            }
+            y += block.height;
+        }
+    }
+
+    pages
+}
+
+/// Build the table of contents for a setlist
+///
+/// `entries` contains the title and (one based) page number of each song
+pub(super) fn layout_table_of_contents(
+    fonts: &Fonts,
+    title: &str,
+    entries: &[(String, usize)],
+) -> Vec<Group> {
+    let mut groups = vec![Group {
+        blocks: vec![
+            Block::text(title, MARGIN_LEFT, FontStyle::Bold, TITLE_SIZE, Color::Text),
+            Block::spacer(SECTION_SPACING),
+        ],
+    }];
+
+    let right = PAGE_WIDTH - MARGIN_RIGHT;
+    for (index, (song_title, page)) in entries.iter().enumerate() {
+        let page_number = page.to_string();
+        let page_number_width = fonts.text_width(&page_number, FontStyle::Regular, TOC_SIZE);
+        let mut block = Block::text(
+            format!("{}. {}", index + 1, song_title),
+            MARGIN_LEFT,
+            FontStyle::Regular,
+            TOC_SIZE,
+            Color::Text,
+        );
+        block.items.push(Item::Text {
+            x: right - page_number_width,
+            y: points_to_mm(TOC_SIZE),
+            text: page_number,
+            style: FontStyle::Regular,
+            size: TOC_SIZE,
+            color: Color::Text,
+        });
+        groups.push(Group {
+            blocks: vec![block],
+        });
+    }
+
+    groups
+}
+
+fn line_start_x(section_type: SectionType) -> f32 {
+    match section_type {
+        SectionType::Chorus | SectionType::Bridge => MARGIN_LEFT + SECTION_INDENT,
+        _ => MARGIN_LEFT,
+    }
+}
+
+fn literal_text(token: &Token) -> String {
+    match token {
+        Token::Literal(text) => text.clone(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::Format;
+    use crate::parser::MetaInformation;
+    use crate::test_helpers::{get_test_ast, get_test_metadata};
+
+    use super::*;
+
+    fn get_texts(items: &[Item]) -> Vec<(f32, f32, String)> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Text { x, y, text, .. } => Some((*x, *y, text.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_layout() {
+        let fonts = Fonts::new().unwrap();
+        let layouter = SongLayouter::new(&fonts, Formatting::with_format(Format::PDF));
+        let groups = layouter.layout(&get_test_ast(), &get_test_metadata());
+
+        // Header, Chorus, Verse 1 and the reference to the Chorus
+        assert_eq!(4, groups.len());
+
+        let header = get_texts(
+            &groups[0]
+                .blocks
+                .iter()
+                .flat_map(|b| b.items.clone())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!("Great new song", header[0].2);
+        assert_eq!("Originally known as 'Swing low sweet chariot'", header[1].2);
+        assert_eq!("Me · Wallace Willis", header[2].2);
+        assert_eq!("Capo: 1", header[3].2);
+
+        let chorus = &groups[1].blocks;
+        assert_eq!(
+            vec![(
+                MARGIN_LEFT,
+                points_to_mm(HEADLINE_SIZE),
+                "Chorus".to_owned()
+            )],
+            get_texts(&chorus[0].items)
+        );
+        assert!(chorus[1]
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Bar { .. })));
+
+        // The chord must be placed above the text it belongs to
+        let line = get_texts(&chorus[1].items);
+        assert_eq!("Swing ", line[0].2);
+        assert_eq!("D", line[1].2);
+        assert_eq!("low, sweet ", line[2].2);
+        assert_eq!(line[1].0, line[2].0);
+        assert!(line[1].1 < line[2].1);
+        assert!(line[0].0 < line[1].0);
+
+        // Verses are not indented and have no bar
+        let verse = &groups[2].blocks;
+        assert_eq!(MARGIN_LEFT, get_texts(&verse[1].items)[0].0);
+        assert!(!verse[1]
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Bar { .. })));
+    }
+
+    #[test]
+    fn test_layout_wide_chord() {
+        let fonts = Fonts::new().unwrap();
+        let layouter = SongLayouter::new(&fonts, Formatting::with_format(Format::PDF));
+        let node = Node::chord_text_pair("Cmaj7", "a").unwrap();
+        let next = Node::Text(Token::literal("b"));
+        let blocks = layouter.layout_line(&[&node, &next], SectionType::Verse);
+        let texts = get_texts(&blocks[0].items);
+        let chord_width = fonts.text_width("Cmaj7", FontStyle::Bold, CHORD_SIZE);
+
+        assert_eq!("b", texts[2].2);
+        assert!(texts[2].0 >= MARGIN_LEFT + chord_width);
+    }
+
+    #[test]
+    fn test_layout_wraps_long_lines() {
+        let fonts = Fonts::new().unwrap();
+        let layouter = SongLayouter::new(&fonts, Formatting::with_format(Format::PDF));
+        let text = Node::Text(Token::literal("Swing low, sweet chariot ".repeat(8)));
+        let chord = Node::chord_text_pair("G", "chariot, ".repeat(8)).unwrap();
+        let blocks = layouter.layout_line(&[&text, &chord], SectionType::Verse);
+
+        assert!(blocks.len() > 2);
+        for block in &blocks {
+            for (x, _, text) in get_texts(&block.items) {
+                assert!(
+                    x + fonts.text_width(text.trim_end(), FontStyle::Regular, LYRIC_SIZE)
+                        <= PAGE_WIDTH - MARGIN_RIGHT
+                );
+            }
+        }
+
+        // The chord stays on its syllable
+        let texts: Vec<(f32, f32, String)> =
+            blocks.iter().flat_map(|b| get_texts(&b.items)).collect();
+        let chord_index = texts.iter().position(|(_, _, text)| text == "G").unwrap();
+        assert_eq!(texts[chord_index].0, texts[chord_index + 1].0);
+        assert!(texts[chord_index + 1].2.starts_with("chariot, "));
+        // All the words are kept
+        let lyrics: String = texts
+            .iter()
+            .filter(|(_, _, text)| text != "G")
+            .map(|(_, _, text)| text.as_str())
+            .collect();
+        assert_eq!(
+            lyrics,
+            format!(
+                "{}{}",
+                "Swing low, sweet chariot ".repeat(8),
+                "chariot, ".repeat(8)
+            )
+        );
+    }
+
+    #[test]
+    fn test_paginate() {
+        let group = |count: usize| Group {
+            blocks: (0..count)
+                .map(|i| {
+                    Block::text(
+                        format!("Line {}", i),
+                        MARGIN_LEFT,
+                        FontStyle::Regular,
+                        LYRIC_SIZE,
+                        Color::Text,
+                    )
+                })
+                .collect(),
+        };
+
+        assert_eq!(1, paginate(vec![group(10)]).len());
+
+        // The second group does not fit onto the first page and is moved to the next one
+        let pages = paginate(vec![group(30), group(30)]);
+        assert_eq!(2, pages.len());
+        assert_eq!(30, pages[0].items.len());
+        assert_eq!(30, pages[1].items.len());
+
+        // Groups larger than a page are split
+        let pages = paginate(vec![group(100)]);
+        assert_eq!(3, pages.len());
+        assert_eq!(100, pages.iter().map(|p| p.items.len()).sum::<usize>());
+    }
+
+    #[test]
+    fn test_layout_table_of_contents() {
+        let fonts = Fonts::new().unwrap();
+        let groups = layout_table_of_contents(
+            &fonts,
+            "My setlist",
+            &[("Song 1".to_owned(), 2), ("Song 2".to_owned(), 14)],
+        );
+
+        assert_eq!(3, groups.len());
+        assert_eq!("My setlist", get_texts(&groups[0].blocks[0].items)[0].2);
+
+        let entry = get_texts(&groups[2].blocks[0].items);
+        assert_eq!("2. Song 2", entry[0].2);
+        assert_eq!("14", entry[1].2);
+        assert_eq!(
+            PAGE_WIDTH - MARGIN_RIGHT,
+            entry[1].0 + fonts.text_width("14", FontStyle::Regular, TOC_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_layout_without_meta() {
+        let fonts = Fonts::new().unwrap();
+        let layouter = SongLayouter::new(&fonts, Formatting::with_format(Format::PDF));
+        let groups = layouter.layout(&get_test_ast(), &MetaInformation::default());
+
+        assert_eq!(3, groups.len());
+    }
+}
@@ -0,0 +1,248 @@
+use std::io::BufReader;
+
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::helper::{apply_settings, parse_content_for_file_type};
+use crate::models::catalog::{Catalog, CatalogTrait};
+use crate::models::chord::fmt::Formatting;
+use crate::models::list::ListEntryTrait;
+use crate::models::meta::MetaTrait;
+use crate::models::setlist::Setlist;
+use crate::models::song_data::SongData;
+use crate::modification::language::LanguageSelectableTrait;
+use crate::modification::performance::ExpandableTrait;
+use crate::parser::{Node, ParserResult};
+
+use self::fonts::Fonts;
+use self::layout::{layout_table_of_contents, paginate, Page, SongLayouter};
+use self::renderer::{render, Bookmark};
+
+use super::BinaryConverterTrait;
+
+mod fonts;
+mod layout;
+mod renderer;
+
+/// Converter to render songs and setlists as PDF documents
+///
+/// The fonts are embedded into the document. Chords are placed above the lyrics they belong to
+pub struct PdfConverter {}
+
+impl BinaryConverterTrait for PdfConverter {
+    fn convert_to_bytes(
+        &self,
+        node: &Node,
+        meta: &dyn MetaTrait,
+        formatting: Formatting,
+    ) -> Result<Vec<u8>> {
+        let fonts = Fonts::new()?;
+        let pages = self.layout_song(&fonts, node, meta, formatting);
+
+        render(&meta.title().unwrap_or_default(), pages, &[], &fonts)
+    }
+}
+
+impl PdfConverter {
+    fn layout_song(
+        &self,
+        fonts: &Fonts,
+        node: &Node,
+        meta: &dyn MetaTrait,
+        formatting: Formatting,
+    ) -> Vec<Page> {
+        let formatting = formatting.for_key(meta.key().as_ref());
+
+        paginate(SongLayouter::new(fonts, formatting).layout(node, meta))
+    }
+
+    /// Render all songs of the `Setlist` into a single PDF document
+    ///
+    /// The document starts with a table of contents. Each song starts on a new page and is
+    /// transposed and arranged according to the `SongSettings` of the `SetlistEntry`
+    pub fn convert_setlist(&self, setlist: &Setlist, catalog: &Catalog) -> Result<Vec<u8>> {
+        let fonts = Fonts::new()?;
+
+        let mut songs: Vec<(String, Vec<Page>)> = vec![];
+        for entry in setlist.iter() {
+            let song = catalog.get(entry.id()).ok_or_else(|| {
+                Error::setlist_error(format!("Song '{}' not found in the catalog", entry.id()))
+            })?;
+            let parser_result = parse_content_for_file_type(
+                BufReader::new(song.src().as_bytes()),
+                song.file_type(),
+            )?;
+
+            let (ParserResult { node, meta, .. }, formatting) = match entry.settings() {
+                Some(settings) => (
+                    apply_settings(parser_result, &settings),
+                    Formatting {
+                        format: Format::PDF,
+                        ..settings.formatting()
+                    },
+                ),
+                None => (parser_result, Formatting::with_format(Format::PDF)),
+            };
+
+            let node = node
+                .expand(formatting.expansion)
+                .select_languages(formatting.language_display);
+            let title = meta.title().unwrap_or_else(|| entry.title());
+            let pages = self.layout_song(&fonts, &node, &meta, formatting);
+            songs.push((title, pages));
+        }
+
+        // The number of pages of the table of contents does not depend on the page numbers
+        let toc_page_count = paginate(layout_table_of_contents(
+            &fonts,
+            setlist.name(),
+            &songs
+                .iter()
+                .map(|(title, _)| (title.clone(), 0))
+                .collect::<Vec<_>>(),
+        ))
+        .len();
+
+        let mut entries = vec![];
+        let mut next_page = toc_page_count;
+        for (title, pages) in &songs {
+            entries.push((title.clone(), next_page + 1));
+            next_page += pages.len();
+        }
+
+        let mut pages = paginate(layout_table_of_contents(&fonts, setlist.name(), &entries));
+        let bookmarks: Vec<Bookmark> = entries
+            .into_iter()
+            .map(|(title, page)| Bookmark {
+                title,
+                page: page - 1,
+            })
+            .collect();
+        for (_, mut song_pages) in songs {
+            pages.append(&mut song_pages);
+        }
+
+        render(setlist.name(), pages, &bookmarks, &fonts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::helper::parse_content;
+    use crate::models::file_type::FileType;
+    use crate::models::meta::Capo;
+    use crate::models::setlist::SetlistEntry;
+    use crate::models::song_settings::SongSettings;
+    use crate::parser::MetaInformation;
+    use crate::test_helpers::{get_test_ast, get_test_catalog, get_test_metadata, get_test_user};
+
+    use super::*;
+
+    fn build_setlist(song_ids: &[&str]) -> Setlist {
+        let now = Utc::now();
+        let entries = song_ids
+            .iter()
+            .map(|id| {
+                SetlistEntry::new(
+                    *id,
+                    FileType::Chorddown,
+                    format!("Entry {}", id),
+                    Some(SongSettings::new(
+                        2,
+                        Formatting::with_format(Format::HTML),
+                        "",
+                    )),
+                )
+            })
+            .collect();
+
+        Setlist::new(
+            "My setlist",
+            1,
+            get_test_user(),
+            None,
+            None,
+            now,
+            now,
+            entries,
+        )
+    }
+
+    #[test]
+    fn test_convert_to_bytes() {
+        let converter = PdfConverter {};
+        let result = converter.convert_to_bytes(
+            &get_test_ast(),
+            &get_test_metadata(),
+            Formatting::with_format(Format::PDF),
+        );
+
+        assert!(result.is_ok());
+        assert!(result.unwrap().starts_with(b"%PDF"));
+
+        let result = converter.convert_to_bytes(
+            &get_test_ast(),
+            &MetaInformation::default(),
+            Formatting::with_format(Format::PDF),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_convert_with_capo() {
+        let content = "# Song\nKey: A\n\n## Verse\n[A]Swing [D]low, sweet [F#m]chari[E]ot\n";
+        let result = parse_content(content.as_bytes()).unwrap();
+        let fonts = Fonts::new().unwrap();
+        // `Converter` resolves the formatting for the key before passing it to the `PdfConverter`
+        let formatting = Formatting {
+            capo: Some(Capo::new(1).unwrap()),
+            ..Formatting::with_format(Format::PDF)
+        }
+        .for_key(result.meta.key().as_ref());
+
+        let pages = PdfConverter {}.layout_song(&fonts, &result.node, &result.meta, formatting);
+        let texts: Vec<String> = pages
+            .iter()
+            .flat_map(|page| page.items.iter())
+            .filter_map(|item| match item {
+                layout::Item::Text { text, .. } => Some(text.trim().to_owned()),
+                _ => None,
+            })
+            .collect();
+
+        // Capo 1 in `A` means `Ab` shapes
+        for chord in ["Ab", "Db", "Fm", "Eb"] {
+            assert!(
+                texts.contains(&chord.to_owned()),
+                "{} in {:?}",
+                chord,
+                texts
+            );
+        }
+        assert!(!texts.iter().any(|text| text.contains('#')), "{:?}", texts);
+    }
+
+    #[test]
+    fn test_convert_setlist() {
+        let converter = PdfConverter {};
+        let result = converter.convert_setlist(
+            &build_setlist(&["song-1", "song-40", "song-50"]),
+            &get_test_catalog(),
+        );
+
+        assert!(result.is_ok());
+        assert!(result.unwrap().starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn test_convert_setlist_w_missing_song() {
+        let converter = PdfConverter {};
+        let result = converter.convert_setlist(
+            &build_setlist(&["song-1", "not-found"]),
+            &get_test_catalog(),
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use printpdf::path::PaintMode;
+use printpdf::{
+    Color as PdfColor, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    Rect, Rgb,
+};
+
+use crate::error::{Error, Result};
+
+use super::fonts::{FontStyle, Fonts};
+use super::layout::{Color, Item, Page, MARGIN_BOTTOM, PAGE_HEIGHT, PAGE_WIDTH};
+
+const PAGE_NUMBER_SIZE: f32 = 9.0;
+
+/// A bookmark pointing to the page with the given (zero based) index
+pub(super) struct Bookmark {
+    pub(super) title: String,
+    pub(super) page: usize,
+}
+
+/// Render the laid out pages into a PDF document
+///
+/// Every page receives a centered page number in the footer
+pub(super) fn render(
+    title: &str,
+    pages: Vec<Page>,
+    bookmarks: &[Bookmark],
+    fonts: &Fonts,
+) -> Result<Vec<u8>> {
+    let (document, first_page, first_layer) =
+        PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    let font_refs = embed_fonts(&document)?;
+
+    let mut layers = vec![document.get_page(first_page).get_layer(first_layer)];
+    for _ in 1..pages.len() {
+        let (page, layer) = document.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        layers.push(document.get_page(page).get_layer(layer));
+    }
+    let page_indexes: Vec<_> = layers.iter().map(|layer| layer.page).collect();
+
+    for (index, (page, layer)) in pages.into_iter().zip(layers.iter()).enumerate() {
+        for item in page.items {
+            render_item(layer, item, &font_refs);
+        }
+
+        let page_number = (index + 1).to_string();
+        let width = fonts.text_width(&page_number, FontStyle::Regular, PAGE_NUMBER_SIZE);
+        render_item(
+            layer,
+            Item::Text {
+                x: (PAGE_WIDTH - width) / 2.0,
+                y: PAGE_HEIGHT - MARGIN_BOTTOM / 2.0,
+                text: page_number,
+                style: FontStyle::Regular,
+                size: PAGE_NUMBER_SIZE,
+                color: Color::Muted,
+            },
+            &font_refs,
+        );
+    }
+
+    for bookmark in bookmarks {
+        if let Some(page) = page_indexes.get(bookmark.page) {
+            document.add_bookmark(bookmark.title.as_str(), *page);
+        }
+    }
+
+    document
+        .save_to_bytes()
+        .map_err(|e| Error::pdf_error(format!("Could not save the document: {}", e)))
+}
+
+fn embed_fonts(document: &PdfDocumentReference) -> Result<Vec<(FontStyle, IndirectFontRef)>> {
+    FontStyle::all()
+        .iter()
+        .map(|style| {
+            document
+                .add_external_font(style.data())
+                .map(|font| (*style, font))
+                .map_err(|e| {
+                    Error::pdf_error(format!("Could not embed the {:?} font: {}", style, e))
+                })
+        })
+        .collect()
+}
+
+fn render_item(layer: &PdfLayerReference, item: Item, font_refs: &[(FontStyle, IndirectFontRef)]) {
+    match item {
+        Item::Text {
+            x,
+            y,
+            text,
+            style,
+            size,
+            color,
+        } => {
+            if let Some((_, font)) = font_refs.iter().find(|(s, _)| *s == style) {
+                layer.set_fill_color(pdf_color(color));
+                layer.use_text(text, size, Mm(x), Mm(PAGE_HEIGHT - y), font);
+            }
+        }
+        Item::Bar {
+            x,
+            y,
+            width,
+            height,
+            color,
+        } => {
+            layer.set_fill_color(pdf_color(color));
+            layer.add_rect(
+                Rect::new(
+                    Mm(x),
+                    Mm(PAGE_HEIGHT - y - height),
+                    Mm(x + width),
+                    Mm(PAGE_HEIGHT - y),
+                )
+                .with_mode(PaintMode::Fill),
+            );
+        }
+    }
+}
+
+fn pdf_color(color: Color) -> PdfColor {
+    let (r, g, b) = match color {
+        Color::Text => (0.0, 0.0, 0.0),
+        Color::Chord => (0.66, 0.13, 0.13),
+        Color::Muted => (0.4, 0.4, 0.4),
+        Color::ChorusBar => (0.75, 0.75, 0.75),
+    };
+
+    PdfColor::Rgb(Rgb::new(r, g, b, None))
+}
@@ -2,7 +2,8 @@ use crate::error::Result;
 use crate::models::chord::fmt::Formatting;
 use crate::models::chord::NoteDisplay;
 use crate::models::meta::MetaTrait;
-use crate::models::structure::{ReferenceResolver, Section, SectionProvider};
+use crate::models::structure::{Expansion, ReferenceResolver, Section, SectionProvider};
+use crate::modification::performance::performance;
 use crate::parser::Node;
 use crate::tokenizer::Token;
 
@@ -14,12 +15,14 @@ use super::ConverterTrait;
 pub struct SongBeamerConverter {}
 
 const BOM: &str = "\u{feff}";
+/// Prefix of the lines in the second language (see `#LangCount`)
+const TRANSLATION_PREFIX: &str = "##2";
 
 impl ConverterTrait for SongBeamerConverter {
     fn convert(&self, node: &Node, meta: &dyn MetaTrait, formatting: Formatting) -> Result<String> {
         let output = format!(
             "{}\n{}\n{}",
-            self.build_std_meta(node),
+            self.build_std_meta(node, formatting),
             self.build_meta(meta, formatting),
             self.build_node(node).unwrap_or_default()
         );
@@ -37,6 +40,9 @@ impl SongBeamerConverter {
                 last_in_line: _,
             } => Some(self.build_text(text)),
             Node::Text(text) => Some(self.build_text(text)),
+            Node::Translation(text) => {
+                Some(format!("{} {}", TRANSLATION_PREFIX, self.build_text(text)))
+            }
             Node::Document(children) => self.build_content_for_children(children),
             Node::Newline => Some("\n".to_owned()),
             Node::Section {
@@ -61,20 +67,20 @@ impl SongBeamerConverter {
     }
 
     fn build_text(&self, token: &Token) -> String {
-        if let Token::Literal(c) = token {
-            c.to_owned()
-        } else {
-            unreachable!("Invalid Token given")
+        match token {
+            Token::Literal(c) | Token::Translation(c) => c.to_owned(),
+            _ => unreachable!("Invalid Token given"),
         }
     }
 
-    fn build_std_meta(&self, node: &Node) -> String {
+    fn build_std_meta(&self, node: &Node, formatting: Formatting) -> String {
         format!(
-            r"#LangCount=1
+            r"#LangCount={}
 #Editor=Chordr
 #Version=3
 #VerseOrder={}",
-            self.build_verse_order(node)
+            if has_translations(node) { 2 } else { 1 },
+            self.build_verse_order(node, formatting)
         )
     }
 
@@ -120,7 +126,7 @@ impl SongBeamerConverter {
         if let Some(v) = meta.duration() {
             buffer.push(format!("#Duration={}", v))
         }
-        if let Some(v) = meta.capo() {
+        if let Some(v) = formatting.capo_for_meta(meta) {
             buffer.push(format!("#Capo={}", v))
         }
         if let Some(v) = meta.ccli_song_id() {
@@ -145,7 +151,16 @@ impl SongBeamerConverter {
         }
     }
 
-    fn build_verse_order(&self, node: &Node) -> String {
+    fn build_verse_order(&self, node: &Node, formatting: Formatting) -> String {
+        if formatting.expansion != Expansion::None {
+            // Every repeat needs its own slide
+            return performance(node)
+                .iter()
+                .flat_map(|play| std::iter::repeat_n(play.section.title.as_str(), play.count))
+                .collect::<Vec<&str>>()
+                .join(",");
+        }
+
         let reference_resolver = ReferenceResolver::new();
         let sections = node.get_sections();
         let get_section_title = |section: &Section| {
@@ -167,6 +182,16 @@ impl SongBeamerConverter {
     }
 }
 
+fn has_translations(node: &Node) -> bool {
+    match node {
+        Node::Translation(_) => true,
+        Node::Document(children) | Node::Section { children, .. } => {
+            children.iter().any(has_translations)
+        }
+        _ => false,
+    }
+}
+
 fn cleanup_output(output: &str) -> String {
     remove_blank_lines(output)
 }
@@ -323,4 +348,117 @@ Chorus
 Swing low, sweet chariot."#
         );
     }
+
+    #[test]
+    fn test_convert_w_arrangement() {
+        let content = "# Swing Low
+Arrangement: Short = Verse 1, Chorus, Verse 1
+
+##! Chorus
+Swing low
+
+## Verse 1
+I looked over Jordan
+";
+        let result = crate::helper::parse_content(content.as_bytes())
+            .unwrap()
+            .with_arrangement("short")
+            .unwrap();
+        let source = SongBeamerConverter {}
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting::with_format(Format::SongBeamer),
+            )
+            .unwrap();
+
+        assert_eq!(
+            source,
+            BOM.to_owned()
+                + r#"#LangCount=1
+#Editor=Chordr
+#Version=3
+#VerseOrder=Verse 1,Chorus,Verse 1
+#Title=Swing Low
+---
+Verse 1
+I looked over Jordan
+---
+Chorus
+Swing low"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_expansion() {
+        let content = "# Swing Low
+
+##! Chorus
+Swing low
+
+## Verse 1
+I looked over Jordan
+
+> 2x Chorus
+";
+        let result = crate::helper::parse_content(content.as_bytes()).unwrap();
+        let source = SongBeamerConverter {}
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting {
+                    expansion: Expansion::Full,
+                    ..Formatting::with_format(Format::SongBeamer)
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            source,
+            BOM.to_owned()
+                + r#"#LangCount=1
+#Editor=Chordr
+#Version=3
+#VerseOrder=Chorus,Verse 1,Chorus,Chorus
+#Title=Swing Low
+---
+Chorus
+Swing low
+---
+Verse 1
+I looked over Jordan"#
+        );
+    }
+
+    #[test]
+    fn test_convert_w_translation() {
+        let content = "# Swing Low
+
+## Verse 1
+Swing low
+~ Schwing tief
+";
+        let result = crate::helper::parse_content(content.as_bytes()).unwrap();
+        let source = SongBeamerConverter {}
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting::with_format(Format::SongBeamer),
+            )
+            .unwrap();
+
+        assert_eq!(
+            source,
+            BOM.to_owned()
+                + r#"#LangCount=2
+#Editor=Chordr
+#Version=3
+#VerseOrder=Verse 1
+#Title=Swing Low
+---
+Verse 1
+Swing low
+##2 Schwing tief"#
+        );
+    }
 }
@@ -32,6 +32,7 @@ impl TextConverter {
                 last_in_line: _,
             } => Ok(self.build_text(text)),
             Node::Text(text) => Ok(self.build_text(text)),
+            Node::Translation(text) => Ok(self.build_text(text)),
             Node::Document(children) => Ok(self.build_tag_for_children(children)),
             Node::Newline => Ok("\n".to_owned()),
             Node::Section {
@@ -51,10 +52,9 @@ impl TextConverter {
     }
 
     fn build_text<'a>(&'a self, token: &'a Token) -> String {
-        if let Token::Literal(c) = token {
-            c.to_owned()
-        } else {
-            unreachable!("Invalid Token given")
+        match token {
+            Token::Literal(c) | Token::Translation(c) => c.to_owned(),
+            _ => unreachable!("Invalid Token given"),
         }
     }
 
@@ -102,7 +102,7 @@ impl TextConverter {
         if let Some(v) = meta.duration() {
             buffer.push(format!("Duration: {}", v))
         }
-        if let Some(v) = meta.capo() {
+        if let Some(v) = formatting.capo_for_meta(meta) {
             buffer.push(format!("Capo: {}", v))
         }
         if let Some(v) = meta.ccli_song_id() {
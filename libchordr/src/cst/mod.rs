@@ -0,0 +1,696 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use crate::error::Result;
+use crate::format::Format;
+use crate::models::chord::fmt::{Formatting, NoteDisplay};
+use crate::models::chord::{Chord, Chords, Note};
+use crate::models::meta::{BNotation, SemitoneNotation};
+use crate::modification::transposition::TransposableTrait;
+use crate::parser::{Parser, ParserResult};
+use crate::span::{Position, Span};
+use crate::tokenizer::{build_tokenizer, Meta, Modifier, SpannedToken, Tokenizer};
+
+pub use self::syntax_line::{LineKind, Segment, SyntaxLine};
+
+mod syntax_line;
+
+/// Lossless concrete syntax tree of a chorddown source
+///
+/// In contrast to the `Parser` the `SyntaxTree` keeps whitespace, blank lines, line endings and
+/// meta lines with unknown keywords, so that printing the tree returns the source byte-for-byte.
+/// The editing methods only touch the affected lines, which allows tools to change a song without
+/// rewriting the user's formatting.
+///
+/// The lines are classified by the tokens of the `ChorddownTokenizer`, and the `Node` tree is
+/// derived from the same tokens with `to_parser_result()`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SyntaxTree {
+    lines: Vec<SyntaxLine>,
+    tokens: Vec<SpannedToken>,
+}
+
+/// Section of the song from its headline (or quote) up to the next section
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxSection {
+    pub title: String,
+    pub level: u8,
+    pub modifier: Modifier,
+    /// `true` if the section is a reference to another section (`> Chorus`)
+    pub is_reference: bool,
+    /// Indexes of the lines belonging to the section (including trailing blank lines)
+    pub lines: Range<usize>,
+}
+
+/// Chord inside of a lyrics line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxChord {
+    /// Index of the line
+    pub line: usize,
+    /// Index of the chord inside of the line
+    pub index: usize,
+    /// Raw chord without the surrounding brackets
+    pub raw: String,
+    pub span: Span,
+}
+
+impl SyntaxTree {
+    /// Build the tree for the chorddown source
+    ///
+    /// Parsing never fails, problems in the source are kept as they are
+    pub fn parse(source: &str) -> Self {
+        let lines = source
+            .split_inclusive('\n')
+            .map(|line| {
+                let (text, line_ending) = match line.strip_suffix("\r\n") {
+                    Some(text) => (text, "\r\n"),
+                    None => match line.strip_suffix('\n') {
+                        Some(text) => (text, "\n"),
+                        None => (line, ""),
+                    },
+                };
+                SyntaxLine::new(text, line_ending)
+            })
+            .collect();
+        let mut tree = Self {
+            lines,
+            tokens: vec![],
+        };
+        tree.tokenize();
+
+        tree
+    }
+
+    pub fn lines(&self) -> &[SyntaxLine] {
+        &self.lines
+    }
+
+    /// Derive the `Node` tree and meta information
+    ///
+    /// The spans of the result refer to the printed tree
+    pub fn to_parser_result(&self) -> Result<ParserResult> {
+        Parser::new().parse_with_spans(self.tokens.clone())
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.lines
+            .iter()
+            .find(|line| line.kind() == LineKind::Title)
+            .and_then(SyntaxLine::heading)
+    }
+
+    /// Change the title or insert a title line at the beginning of the song
+    pub fn set_title(&mut self, title: &str) {
+        match self.find_line(|line| line.kind() == LineKind::Title) {
+            Some(index) => self.replace_heading(index, title),
+            None => self.insert_line(0, format!("# {}", title)),
+        }
+    }
+
+    /// Return the value of the first meta line with the given keyword
+    ///
+    /// Unknown keywords (e.g. `Arranger`) are supported inside of the song's header
+    pub fn meta(&self, keyword: &str) -> Option<&str> {
+        self.lines
+            .iter()
+            .find(|line| line.is_meta(keyword))
+            .and_then(SyntaxLine::meta_value)
+    }
+
+    /// Change the value of the meta line with the given keyword
+    ///
+    /// The keyword and the whitespace of an existing line are kept. Otherwise a new line is added
+    /// after the song's header
+    pub fn set_meta(&mut self, keyword: &str, value: &str) {
+        match self.find_line(|line| line.is_meta(keyword)) {
+            Some(index) => {
+                let line = &self.lines[index];
+                let text = line.text();
+                // The value is a sub-slice of the text
+                let old_value = line.meta_value().unwrap_or_default();
+                let start = old_value.as_ptr() as usize - text.as_ptr() as usize;
+                let prefix = &text[..start];
+                let separator = if prefix.ends_with(':') { " " } else { "" };
+                let suffix = &text[start + old_value.len()..];
+                let text = format!("{}{}{}{}", prefix, separator, value, suffix);
+                self.lines[index].set_text(text);
+                self.tokenize();
+            }
+            None => {
+                let keyword = match Meta::from_keyword_and_content(keyword, "") {
+                    Some(meta) => meta.keyword().to_owned(),
+                    None => keyword.trim().to_owned(),
+                };
+                let index = self.header_end();
+                self.insert_line(index, format!("{}: {}", keyword, value));
+            }
+        }
+    }
+
+    /// Remove all meta lines with the given keyword and return if a line was removed
+    pub fn remove_meta(&mut self, keyword: &str) -> bool {
+        let count = self.lines.len();
+        self.lines.retain(|line| !line.is_meta(keyword));
+        self.tokenize();
+
+        count != self.lines.len()
+    }
+
+    /// Add the tag to the song's `Tags` and return if it was not already defined
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim().trim_start_matches('#').trim();
+        if tag.is_empty() {
+            return false;
+        }
+        match self.meta("Tags") {
+            Some(tags) if tags.split('#').any(|existing| existing.trim() == tag) => false,
+            Some("") | None => {
+                self.set_meta("Tags", &format!("#{}", tag));
+                true
+            }
+            Some(tags) => {
+                let tags = format!("{} #{}", tags, tag);
+                self.set_meta("Tags", &tags);
+                true
+            }
+        }
+    }
+
+    /// Return the sections in the order of the source
+    pub fn sections(&self) -> Vec<SyntaxSection> {
+        let mut sections: Vec<SyntaxSection> = vec![];
+        for (index, line) in self.lines.iter().enumerate() {
+            let (level, modifier, is_reference) = match line.kind() {
+                LineKind::Headline { level, modifier } => (level, modifier, false),
+                LineKind::Quote => (2, Modifier::None, true),
+                _ => continue,
+            };
+            if let Some(previous) = sections.last_mut() {
+                previous.lines.end = index;
+            }
+            sections.push(SyntaxSection {
+                title: line.heading().unwrap_or_default().to_owned(),
+                level,
+                modifier,
+                is_reference,
+                lines: index..self.lines.len(),
+            });
+        }
+
+        sections
+    }
+
+    /// Change the title of the section with the given index and return if it exists
+    pub fn rename_section(&mut self, index: usize, title: &str) -> bool {
+        match self.sections().get(index) {
+            Some(section) => {
+                self.replace_heading(section.lines.start, title);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the section with the given index and return if it existed
+    pub fn remove_section(&mut self, index: usize) -> bool {
+        match self.sections().get(index) {
+            Some(section) => {
+                self.lines.drain(section.lines.clone());
+                self.tokenize();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Append a new section with the given lines to the end of the song
+    pub fn append_section(&mut self, level: u8, modifier: Modifier, title: &str, lines: &[&str]) {
+        if self
+            .lines
+            .last()
+            .is_some_and(|line| line.kind() != LineKind::Blank)
+        {
+            self.insert_line(self.lines.len(), String::new());
+        }
+        let headline = format!(
+            "{}{} {}",
+            "#".repeat(level.max(2) as usize),
+            modifier,
+            title
+        );
+        self.insert_line(self.lines.len(), headline);
+        for line in lines {
+            self.insert_line(self.lines.len(), (*line).to_owned());
+        }
+    }
+
+    /// Return all chords in the order of the source
+    pub fn chords(&self) -> Vec<SyntaxChord> {
+        let mut chords = vec![];
+        for (line_index, line) in self.lines.iter().enumerate() {
+            let mut column = 1;
+            let mut index = 0;
+            for segment in line.segments() {
+                let width = segment.to_string().chars().count();
+                if let Segment::Chord(raw) = segment {
+                    chords.push(SyntaxChord {
+                        line: line_index,
+                        index,
+                        raw: raw.to_owned(),
+                        span: Span::new(
+                            Position::new(line_index + 1, column),
+                            Position::new(line_index + 1, column + width),
+                        ),
+                    });
+                    index += 1;
+                }
+                column += width;
+            }
+        }
+
+        chords
+    }
+
+    /// Replace the raw chord with the given index inside the line and return if it exists
+    pub fn replace_chord(&mut self, line: usize, index: usize, raw: &str) -> bool {
+        let text = match self.lines.get(line) {
+            Some(syntax_line) => {
+                let mut chord_index = 0;
+                let mut replaced = false;
+                let text: String = syntax_line
+                    .segments()
+                    .into_iter()
+                    .map(|segment| match segment {
+                        Segment::Chord(_) => {
+                            chord_index += 1;
+                            if chord_index - 1 == index {
+                                replaced = true;
+                                Segment::Chord(raw).to_string()
+                            } else {
+                                segment.to_string()
+                            }
+                        }
+                        Segment::Text(_) => segment.to_string(),
+                    })
+                    .collect();
+                if !replaced {
+                    return false;
+                }
+                text
+            }
+            None => return false,
+        };
+        self.lines[line].set_text(text);
+        self.tokenize();
+
+        true
+    }
+
+    /// Transpose all chords and the song's key
+    ///
+    /// Chords keep the accidentals they are written with (e.g. `F#` becomes `G#`, not `Ab`),
+    /// unless the new key requires the other accidental (e.g. sharps in `D` or flats in `F`).
+    /// If a chord is invalid nothing is changed and the error contains the chord's span
+    pub fn transpose(&mut self, semitones: isize) -> Result<()> {
+        let b_notation = self
+            .meta("B-Notation")
+            .and_then(|value| BNotation::try_from(value).ok())
+            .unwrap_or_default();
+        let original_key = self
+            .meta("Key")
+            .and_then(|raw| Chord::try_from(raw, b_notation).ok());
+
+        let mut chords = vec![];
+        for chord in self.chords() {
+            let parsed = Chords::try_from(&chord.raw, b_notation)
+                .map_err(|error| error.with_span(Some(chord.span)))?;
+            chords.push((chord, parsed));
+        }
+
+        // The accidentals of the song are those of its key, or the ones used by most chords
+        let style = original_key
+            .as_ref()
+            .and_then(Chord::accidental)
+            .or_else(|| predominant_accidental(chords.iter().map(|(_, parsed)| parsed.chord())));
+        let key = original_key.map(|key| key.transpose(semitones));
+        // Keys with a black root (e.g. `C#` and `Db`) can be written with both accidentals
+        let key_notation = key
+            .as_ref()
+            .filter(|key| !is_black_key(key.root()))
+            .and_then(SemitoneNotation::for_key);
+        let formatting = |notation: Option<SemitoneNotation>| Formatting {
+            b_notation,
+            semitone_notation: key_notation.or(notation).or(style).unwrap_or_else(|| {
+                key.as_ref()
+                    .and_then(SemitoneNotation::for_key)
+                    .unwrap_or(SemitoneNotation::Sharp)
+            }),
+            ..Formatting::with_format(Format::Chorddown)
+        };
+
+        // Replace the chords from back to front, so that the ranges of the preceding chords
+        // stay valid
+        for (chord, parsed) in chords.into_iter().rev() {
+            let range = self.lines[chord.line].chord_ranges()[chord.index].clone();
+            let notation = parsed.chord().accidental();
+            let raw = parsed
+                .transpose(semitones)
+                .note_format(formatting(notation));
+            let line = &mut self.lines[chord.line];
+            let mut text = line.text().to_owned();
+            text.replace_range(range, &Segment::Chord(&raw).to_string());
+            line.set_text(text);
+        }
+        self.tokenize();
+        if let Some(key) = &key {
+            self.set_meta("Key", &key.note_format(formatting(None)));
+        }
+
+        Ok(())
+    }
+
+    fn find_line<F: Fn(&SyntaxLine) -> bool>(&self, predicate: F) -> Option<usize> {
+        self.lines.iter().position(predicate)
+    }
+
+    /// Replace the text of a title, headline or quote line, keeping the markers and whitespace
+    fn replace_heading(&mut self, index: usize, heading: &str) {
+        let line = &self.lines[index];
+        let text = line.text();
+        // The heading is a sub-slice of the text
+        let old_heading = line.heading().unwrap_or_default();
+        let start = old_heading.as_ptr() as usize - text.as_ptr() as usize;
+        let prefix = &text[..start];
+        let separator = if prefix.ends_with(' ') || prefix.ends_with('\t') {
+            ""
+        } else {
+            " "
+        };
+        let suffix = &text[start + old_heading.len()..];
+        let text = format!("{}{}{}{}", prefix, separator, heading, suffix);
+        self.lines[index].set_text(text);
+        self.tokenize();
+    }
+
+    /// Return the index after the last title or meta line of the song's header
+    fn header_end(&self) -> usize {
+        let mut end = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            match line.kind() {
+                LineKind::Title | LineKind::Meta { .. } => end = index + 1,
+                LineKind::Blank => {}
+                _ => break,
+            }
+        }
+
+        end
+    }
+
+    /// Insert a new line using the line ending of the source
+    fn insert_line(&mut self, index: usize, text: String) {
+        let line_ending = self.line_ending();
+        if index == self.lines.len() {
+            // Keep a missing newline at the end of the file
+            if let Some(last) = self.lines.last_mut() {
+                if last.line_ending().is_empty() {
+                    last.set_line_ending(&line_ending);
+                    self.lines.push(SyntaxLine::new(&text, ""));
+                    self.tokenize();
+                    return;
+                }
+            }
+        }
+        self.lines
+            .insert(index, SyntaxLine::new(&text, &line_ending));
+        self.tokenize();
+    }
+
+    fn line_ending(&self) -> String {
+        self.lines
+            .iter()
+            .map(SyntaxLine::line_ending)
+            .find(|line_ending| !line_ending.is_empty())
+            .unwrap_or("\n")
+            .to_owned()
+    }
+
+    /// Tokenize the printed tree and update the kinds and chords of the lines from the tokens
+    ///
+    /// Each token belongs to the line its span starts on
+    fn tokenize(&mut self) {
+        // Reading from a string can not fail
+        let (tokens, _warnings) = build_tokenizer()
+            .tokenize_with_spans(self.to_string().as_bytes())
+            .unwrap_or_default();
+        for line in &mut self.lines {
+            line.reset_tokens();
+        }
+        for (token, span) in &tokens {
+            if let Some(span) = span {
+                if let Some(line) = self.lines.get_mut(span.start().line() - 1) {
+                    line.add_token(token, *span);
+                }
+            }
+        }
+        self.tokens = tokens;
+    }
+}
+
+/// Return the accidental used by most of the chords written with an accidental
+fn predominant_accidental<'a>(chords: impl Iterator<Item = &'a Chord>) -> Option<SemitoneNotation> {
+    let (sharps, flats) = chords.fold((0, 0), |(sharps, flats), chord| match chord.accidental() {
+        Some(SemitoneNotation::Sharp) => (sharps + 1, flats),
+        Some(SemitoneNotation::Flat) => (sharps, flats + 1),
+        _ => (sharps, flats),
+    });
+    match sharps.cmp(&flats) {
+        Ordering::Less => Some(SemitoneNotation::Flat),
+        Ordering::Greater => Some(SemitoneNotation::Sharp),
+        Ordering::Equal => None,
+    }
+}
+
+fn is_black_key(note: Note) -> bool {
+    matches!(
+        note,
+        Note::Cis | Note::Dis | Note::Fis | Note::Gis | Note::Ais
+    )
+}
+
+impl Display for SyntaxTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            Display::fmt(line, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helper::parse_content;
+    use crate::parser::Node;
+    use crate::tokenizer::Token;
+
+    const FIXTURES: [&str; 5] = [
+        include_str!("../../tests/resources/swing_low_sweet_chariot.chorddown"),
+        include_str!("../../tests/resources/german-test.chorddown"),
+        include_str!("../../tests/resources/catalog/song-1.chorddown"),
+        include_str!("../../tests/resources/catalog/song-2.chorddown"),
+        include_str!("../../tests/resources/catalog/song-3.chorddown"),
+    ];
+
+    const SOURCE: &str = "#  Swing Low Sweet Chariot\nArtist:   Traditional\nArranger: Jane\nKey:D  \n\n##! Chorus\nSwing [D]low, sweet [G]chari[D]ot,   \n\n\n## Verse 1\nI [D]looked over Jordan\n> Chorus\n";
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(SyntaxTree::parse(SOURCE).to_string(), SOURCE);
+        let crlf = "# Song\r\nKey: D\r\n\r\n[D]Swing\tlow [A";
+        assert_eq!(SyntaxTree::parse(crlf).to_string(), crlf);
+        assert_eq!(SyntaxTree::parse("").to_string(), "");
+    }
+
+    #[test]
+    fn meta() {
+        let mut tree = SyntaxTree::parse(SOURCE);
+        assert_eq!(tree.title(), Some("Swing Low Sweet Chariot"));
+        assert_eq!(tree.meta("Arranger"), Some("Jane"));
+        assert_eq!(tree.meta("key"), Some("D"));
+
+        tree.set_meta("Key", "E");
+        tree.set_meta("Artist", "The Fisk Jubilee Singers");
+        tree.set_meta("Original Key", "C");
+        assert!(tree.remove_meta("Arranger"));
+        assert!(!tree.remove_meta("Arranger"));
+        assert_eq!(
+            tree.to_string(),
+            SOURCE
+                .replace("Key:D  ", "Key: E  \nOriginal Key: C")
+                .replace("Traditional\nArranger: Jane", "The Fisk Jubilee Singers")
+        );
+    }
+
+    #[test]
+    fn add_tag() {
+        let mut tree = SyntaxTree::parse("# Song\r\nKey: D");
+        assert!(tree.add_tag("gospel"));
+        assert!(!tree.add_tag("#gospel"));
+        assert!(tree.add_tag("#spiritual"));
+        assert_eq!(
+            tree.to_string(),
+            "# Song\r\nKey: D\r\nTags: #gospel #spiritual"
+        );
+    }
+
+    #[test]
+    fn sections() {
+        let mut tree = SyntaxTree::parse(SOURCE);
+        let sections = tree.sections();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].title, "Chorus");
+        assert_eq!(sections[0].modifier, Modifier::Chorus);
+        assert_eq!(sections[0].lines, 5..9);
+        assert!(sections[2].is_reference);
+
+        assert!(tree.rename_section(1, "Verse"));
+        assert!(tree.remove_section(0));
+        assert!(!tree.remove_section(2));
+        tree.append_section(2, Modifier::Bridge, "Bridge", &["[G]Sweet"]);
+        assert_eq!(
+            tree.to_string(),
+            "#  Swing Low Sweet Chariot\nArtist:   Traditional\nArranger: Jane\nKey:D  \n\n## Verse\nI [D]looked over Jordan\n> Chorus\n\n##- Bridge\n[G]Sweet\n"
+        );
+    }
+
+    #[test]
+    fn chords() {
+        let mut tree = SyntaxTree::parse(SOURCE);
+        let chords = tree.chords();
+        assert_eq!(chords.len(), 4);
+        assert_eq!(chords[1].raw, "G");
+        assert_eq!(chords[1].span.start(), Position::new(7, 21));
+        assert!(tree.replace_chord(6, 1, "Em"));
+        assert!(!tree.replace_chord(6, 3, "Em"));
+        assert_eq!(
+            tree.lines()[6].text(),
+            "Swing [D]low, sweet [Em]chari[D]ot,   "
+        );
+    }
+
+    #[test]
+    fn transpose() {
+        let mut tree = SyntaxTree::parse("# Song\nKey: D\n\n[D]Swing  [F#m7/A]low\n");
+        tree.transpose(3).unwrap();
+        assert_eq!(tree.to_string(), "# Song\nKey: F\n\n[F]Swing  [Am7/C]low\n");
+
+        // The accidentals of the source are kept unless the key requires the other ones
+        let mut tree = SyntaxTree::parse("# Song\nKey: C#\n\n[C#]Swing [F#m]low [G#]\n");
+        tree.transpose(2).unwrap();
+        assert_eq!(
+            tree.to_string(),
+            "# Song\nKey: D#\n\n[D#]Swing [G#m]low [A#]\n"
+        );
+        let mut tree = SyntaxTree::parse("# Song\nKey: C#\n\n[C#]Swing [F#m]low [G#]\n");
+        tree.transpose(4).unwrap();
+        assert_eq!(
+            tree.to_string(),
+            "# Song\nKey: F\n\n[F]Swing [Bbm]low [C]\n"
+        );
+        let mut tree = SyntaxTree::parse("# Song\n\n[F#]Swing [Bb]low [G]\n");
+        tree.transpose(2).unwrap();
+        assert_eq!(tree.to_string(), "# Song\n\n[G#]Swing [C]low [A]\n");
+        let mut tree = SyntaxTree::parse("# Song\n\n[Bb]Swing [Eb]low [G]\n");
+        tree.transpose(1).unwrap();
+        assert_eq!(tree.to_string(), "# Song\n\n[B]Swing [E]low [Ab]\n");
+
+        let mut tree = SyntaxTree::parse("# Song\n\n[D]Swing [Xm]low\n");
+        let error = tree.transpose(1).unwrap_err();
+        assert_eq!(
+            error.span().map(|span| span.start()),
+            Some(Position::new(3, 10))
+        );
+        assert_eq!(tree.to_string(), "# Song\n\n[D]Swing [Xm]low\n");
+    }
+
+    #[test]
+    fn to_parser_result() {
+        let mut tree = SyntaxTree::parse(SOURCE);
+        tree.set_meta("Key", "E");
+        let result = tree.to_parser_result().unwrap();
+        assert_eq!(
+            result.meta_as_ref().title.as_deref(),
+            Some("Swing Low Sweet Chariot")
+        );
+        assert_eq!(result.meta_as_ref().key_raw.as_deref(), Some("E"));
+        match result.node_as_ref() {
+            Node::Document(children) => assert_eq!(children.len(), 5),
+            node => panic!("Unexpected node {:?}", node),
+        }
+    }
+
+    #[test]
+    fn kinds_match_tokenizer() {
+        for fixture in FIXTURES {
+            let tree = SyntaxTree::parse(fixture);
+            let (tokens, _warnings) = build_tokenizer()
+                .tokenize_with_spans(fixture.as_bytes())
+                .unwrap();
+            for (index, line) in tree.lines().iter().enumerate() {
+                let first_token = tokens
+                    .iter()
+                    .filter(|(_, span)| span.unwrap().start().line() == index + 1)
+                    .map(|(token, _)| token)
+                    .find(|token| match token {
+                        Token::Newline => false,
+                        Token::Literal(text) => !text.trim().is_empty(),
+                        _ => true,
+                    });
+                let matches = match (line.kind(), first_token) {
+                    (LineKind::Blank, None) => true,
+                    (LineKind::Title, Some(Token::Headline { level: 1, .. })) => true,
+                    (
+                        LineKind::Headline { level, modifier },
+                        Some(Token::Headline {
+                            level: token_level,
+                            modifier: token_modifier,
+                            ..
+                        }),
+                    ) => level == *token_level && modifier == *token_modifier,
+                    (LineKind::Quote, Some(Token::Quote(_))) => true,
+                    (LineKind::Translation, Some(Token::Translation(_))) => true,
+                    (LineKind::Meta { known }, Some(Token::Meta(meta))) => {
+                        known != matches!(meta, Meta::Custom(_, _))
+                    }
+                    (LineKind::Lyrics, Some(Token::Literal(_) | Token::Chord(_))) => true,
+                    _ => false,
+                };
+                assert!(
+                    matches,
+                    "Line {} `{}` is {:?} but starts with {:?}",
+                    index + 1,
+                    line.text(),
+                    line.kind(),
+                    first_token
+                );
+            }
+
+            let chords: Vec<String> = tree.chords().into_iter().map(|chord| chord.raw).collect();
+            let token_chords: Vec<String> = tokens
+                .iter()
+                .filter_map(|(token, _)| match token {
+                    Token::Chord(raw) => Some(raw.to_owned()),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(chords, token_chords);
+            assert_eq!(
+                tree.to_parser_result().unwrap().node_as_ref(),
+                parse_content(fixture.as_bytes()).unwrap().node_as_ref()
+            );
+        }
+    }
+}
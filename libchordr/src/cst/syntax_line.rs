@@ -0,0 +1,265 @@
+use std::fmt::{Display, Formatter, Result};
+use std::ops::Range;
+
+use crate::span::Span;
+use crate::tokenizer::{Meta, Modifier, Token};
+
+/// Kind of a line in the `SyntaxTree`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineKind {
+    /// Empty line or line containing only whitespace
+    Blank,
+    /// Song title (`# Title`)
+    Title,
+    /// Section headline (`## Verse`, `##! Chorus`)
+    Headline { level: u8, modifier: Modifier },
+    /// Reference to another section (`> Chorus`)
+    Quote,
+    /// Translation of the previous lyrics line (`~ Swing low`)
+    Translation,
+    /// Meta information (`Key: D`). `known` is `false` for keywords the `Parser` does not support
+    Meta { known: bool },
+    /// Lyrics and chords
+    Lyrics,
+}
+
+/// Part of a lyrics line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    /// Raw chord without the surrounding brackets
+    Chord(&'a str),
+}
+
+impl Display for Segment<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Segment::Text(text) => f.write_str(text),
+            Segment::Chord(raw) => write!(f, "[{}]", raw),
+        }
+    }
+}
+
+/// Single line of the source including its line ending
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxLine {
+    text: String,
+    line_ending: String,
+    kind: LineKind,
+    /// Byte ranges of the closed chords including the brackets
+    chords: Vec<Range<usize>>,
+}
+
+impl SyntaxLine {
+    /// Build a line from the text (without the line ending)
+    ///
+    /// The kind and the chords are set by the `SyntaxTree` from the tokens of the line
+    pub(super) fn new(text: &str, line_ending: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            line_ending: line_ending.to_owned(),
+            kind: LineKind::Blank,
+            chords: vec![],
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Return the line ending (`"\n"`, `"\r\n"` or `""` for the last line without a newline)
+    pub fn line_ending(&self) -> &str {
+        &self.line_ending
+    }
+
+    pub fn kind(&self) -> LineKind {
+        self.kind
+    }
+
+    /// Return the text of a title, headline or quote line without the markers
+    pub fn heading(&self) -> Option<&str> {
+        match self.kind {
+            LineKind::Title => Some(self.text.trim_start().trim_start_matches('#').trim()),
+            LineKind::Headline { .. } => {
+                let rest = self.text.trim_start().trim_start_matches('#');
+                let (_, rest) = Modifier::split(rest);
+                Some(rest.trim())
+            }
+            LineKind::Quote => Some(self.text.trim_start()[1..].trim()),
+            _ => None,
+        }
+    }
+
+    /// Return the keyword of a meta line as written in the source
+    pub fn meta_keyword(&self) -> Option<&str> {
+        match self.kind {
+            LineKind::Meta { .. } => self.text.split_once(':').map(|(keyword, _)| keyword.trim()),
+            _ => None,
+        }
+    }
+
+    /// Return the value of a meta line without the surrounding whitespace
+    pub fn meta_value(&self) -> Option<&str> {
+        match self.kind {
+            LineKind::Meta { .. } => self.text.split_once(':').map(|(_, value)| value.trim()),
+            _ => None,
+        }
+    }
+
+    /// Return if the line is a meta line with the given keyword
+    ///
+    /// Known keywords match all their spellings (e.g. `Original Key` and `original-key`)
+    pub fn is_meta(&self, keyword: &str) -> bool {
+        self.meta_keyword()
+            .is_some_and(|own| canonical_keyword(own) == canonical_keyword(keyword))
+    }
+
+    /// Split the line into text and chords
+    ///
+    /// Concatenating the displayed segments returns the line's text
+    pub fn segments(&self) -> Vec<Segment<'_>> {
+        let mut segments = vec![];
+        let mut position = 0;
+        for chord in &self.chords {
+            if chord.start > position {
+                segments.push(Segment::Text(&self.text[position..chord.start]));
+            }
+            segments.push(Segment::Chord(&self.text[chord.start + 1..chord.end - 1]));
+            position = chord.end;
+        }
+        if position < self.text.len() || segments.is_empty() {
+            segments.push(Segment::Text(&self.text[position..]));
+        }
+
+        segments
+    }
+
+    /// Return the byte ranges of the closed chords including the brackets
+    pub(super) fn chord_ranges(&self) -> &[Range<usize>] {
+        &self.chords
+    }
+
+    /// Replace the text of the line
+    ///
+    /// The kind and the chords are not updated, because they depend on the surrounding lines
+    pub(super) fn set_text(&mut self, text: String) {
+        self.text = text;
+    }
+
+    /// Forget the kind and the chords before the line is tokenized again
+    pub(super) fn reset_tokens(&mut self) {
+        self.kind = LineKind::Blank;
+        self.chords.clear();
+    }
+
+    /// Update the kind and the chords with a token starting on this line
+    ///
+    /// The first token that is not blank defines the kind of the line
+    pub(super) fn add_token(&mut self, token: &Token, span: Span) {
+        if let Token::Chord(_) = token {
+            let chord =
+                self.byte_offset(span.start().column())..self.byte_offset(span.end().column());
+            // Unclosed chords are kept as text
+            if self.text[chord.clone()].ends_with(']') {
+                self.chords.push(chord);
+            }
+        }
+        if self.kind == LineKind::Blank {
+            self.kind = kind_for_token(token);
+        }
+    }
+
+    pub(super) fn set_line_ending(&mut self, line_ending: &str) {
+        self.line_ending = line_ending.to_owned();
+    }
+
+    /// Return the byte offset of the (1-based) character column
+    fn byte_offset(&self, column: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map_or(self.text.len(), |(offset, _)| offset)
+    }
+}
+
+impl Display for SyntaxLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}{}", self.text, self.line_ending)
+    }
+}
+
+/// Return the keyword used to compare meta lines
+pub(super) fn canonical_keyword(keyword: &str) -> String {
+    match Meta::from_keyword_and_content(keyword, "") {
+        Some(meta) => meta.keyword().to_owned(),
+        None => keyword.trim().to_lowercase(),
+    }
+}
+
+fn kind_for_token(token: &Token) -> LineKind {
+    match token {
+        Token::Newline => LineKind::Blank,
+        Token::Literal(text) if text.trim().is_empty() => LineKind::Blank,
+        Token::Headline { level: 1, .. } => LineKind::Title,
+        Token::Headline {
+            level, modifier, ..
+        } => LineKind::Headline {
+            level: *level,
+            modifier: *modifier,
+        },
+        Token::Quote(_) => LineKind::Quote,
+        Token::Translation(_) => LineKind::Translation,
+        Token::Meta(Meta::Custom(_, _)) => LineKind::Meta { known: false },
+        Token::Meta(_) => LineKind::Meta { known: true },
+        Token::Literal(_) | Token::Chord(_) => LineKind::Lyrics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SyntaxTree;
+    use super::*;
+
+    #[test]
+    fn kind() {
+        let tree = SyntaxTree::parse(
+            "# Swing Low\nOriginal-Key: D\nArranger: Jane\n  \n##! Chorus\n> Chorus\n~ Swing low\nArranger: Jane\n[D]Swing: low\n",
+        );
+        let kinds: Vec<LineKind> = tree.lines().iter().map(SyntaxLine::kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LineKind::Title,
+                LineKind::Meta { known: true },
+                LineKind::Meta { known: false },
+                LineKind::Blank,
+                LineKind::Headline {
+                    level: 2,
+                    modifier: Modifier::Chorus
+                },
+                LineKind::Quote,
+                LineKind::Translation,
+                LineKind::Lyrics,
+                LineKind::Lyrics,
+            ]
+        );
+    }
+
+    #[test]
+    fn segments() {
+        let tree = SyntaxTree::parse("Swing [D]low, [[G]sweet [A");
+        let line = &tree.lines()[0];
+        assert_eq!(
+            line.segments(),
+            vec![
+                Segment::Text("Swing "),
+                Segment::Chord("D"),
+                Segment::Text("low, "),
+                Segment::Chord("[G"),
+                Segment::Text("sweet [A"),
+            ]
+        );
+        let joined: String = line.segments().iter().map(ToString::to_string).collect();
+        assert_eq!(joined, line.text());
+    }
+}
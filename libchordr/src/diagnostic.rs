@@ -0,0 +1,224 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter, Result};
+
+use crate::error::Error;
+use crate::span::Span;
+use crate::tokenizer::TokenizerError;
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// Problem in a song's source that can be reported to the user
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    message: String,
+    span: Option<Span>,
+}
+
+impl Diagnostic {
+    pub fn error<S: Into<String>>(message: S, span: Option<Span>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: None,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning<S: Into<String>>(message: S, span: Option<Span>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code: None,
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// Attach a code identifying the kind of problem (e.g. the name of a lint rule)
+    pub fn with_code<S: Into<String>>(self, code: S) -> Self {
+        Self {
+            code: Some(code.into()),
+            ..self
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Render the Diagnostic in the style of a compiler message
+    ///
+    /// ```text
+    /// error: Chord `Xm` is not valid
+    ///  --> song.chorddown:7:7
+    ///   |
+    /// 7 | Swing [Xm]low
+    ///   |       ^^^^
+    /// ```
+    ///
+    /// `source` must be the text the span refers to. If the span is unknown only the message and
+    /// the path are printed
+    pub fn render(&self, path: &str, source: &str) -> String {
+        let header = match &self.code {
+            Some(code) => format!("{}[{}]: {}", self.severity, code, self.message),
+            None => format!("{}: {}", self.severity, self.message),
+        };
+        let span = match self.span {
+            Some(span) => span,
+            None => return format!("{}\n --> {}", header, path),
+        };
+
+        let start = span.start();
+        let line_number = start.line().to_string();
+        let gutter = " ".repeat(line_number.len());
+        let location = format!("{}--> {}:{}", gutter, path, start);
+
+        let line = match source.lines().nth(start.line() - 1) {
+            Some(line) => line.trim_end_matches('\r'),
+            None => return format!("{}\n{}", header, location),
+        };
+
+        // Keep tabs in the indentation, so that the carets line up with the excerpt
+        let indentation: String = line
+            .chars()
+            .take(start.column() - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = if span.end().line() == start.line() {
+            span.end().column().saturating_sub(start.column())
+        } else {
+            (line.chars().count() + 1).saturating_sub(start.column())
+        };
+
+        format!(
+            "{header}\n{location}\n{gutter} |\n{line_number} | {line}\n{gutter} | {indentation}{carets}",
+            carets = "^".repeat(width.max(1))
+        )
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.span {
+            Some(span) => write!(f, "{}: {} at {}", self.severity, self.message, span),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+impl From<&TokenizerError> for Diagnostic {
+    fn from(error: &TokenizerError) -> Self {
+        Diagnostic::warning(error.to_string(), error.span())
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        Diagnostic::error(error.to_string(), error.span())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::Position;
+    use crate::tokenizer::TokenizerErrorKind;
+
+    const SOURCE: &str = "# Swing Low\n\n[D]Swing low\n\tSweet [Xm]chariot\n";
+
+    #[test]
+    fn render() {
+        let diagnostic = Diagnostic::error(
+            "Chord `Xm` is not valid",
+            Some(Span::new(Position::new(4, 8), Position::new(4, 12))),
+        );
+        assert_eq!(
+            diagnostic.render("song.chorddown", SOURCE),
+            "error: Chord `Xm` is not valid
+ --> song.chorddown:4:8
+  |
+4 | \tSweet [Xm]chariot
+  | \t      ^^^^"
+        );
+    }
+
+    #[test]
+    fn render_multi_line_span() {
+        let diagnostic = Diagnostic::warning(
+            "Unclosed chord",
+            Some(Span::new(Position::new(3, 5), Position::new(4, 2))),
+        );
+        assert_eq!(
+            diagnostic.render("song.chorddown", SOURCE),
+            "warning: Unclosed chord
+ --> song.chorddown:3:5
+  |
+3 | [D]Swing low
+  |     ^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn render_with_code() {
+        let diagnostic = Diagnostic::warning("Song has no key", None).with_code("missing-key");
+        assert_eq!(
+            diagnostic.render("song.chorddown", SOURCE),
+            "warning[missing-key]: Song has no key\n --> song.chorddown"
+        );
+    }
+
+    #[test]
+    fn render_without_span() {
+        let diagnostic = Diagnostic::error("Something went wrong", None);
+        assert_eq!(
+            diagnostic.render("song.chorddown", SOURCE),
+            "error: Something went wrong\n --> song.chorddown"
+        );
+    }
+
+    #[test]
+    fn from_tokenizer_error() {
+        let span = Span::new(Position::new(10, 1), Position::new(10, 3));
+        let diagnostic = Diagnostic::from(&TokenizerError::new(
+            TokenizerErrorKind::NestedChord,
+            Some(span),
+        ));
+        assert_eq!(diagnostic.severity(), Severity::Warning);
+        assert_eq!(diagnostic.message(), "Nested chord");
+
+        // The gutter grows with the line number
+        let source = format!("{}[[A]", "\n".repeat(9));
+        assert_eq!(
+            diagnostic.render("a.chorddown", &source),
+            "warning: Nested chord\n  --> a.chorddown:10:1\n   |\n10 | [[A]\n   | ^^"
+        );
+    }
+}
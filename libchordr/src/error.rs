@@ -2,8 +2,17 @@ use std::error::Error as StdError;
 use std::fmt::{Display, Error as FmtError, Formatter};
 use std::path::PathBuf;
 
+use crate::lint::LintRuleError;
+use crate::models::catalog::CatalogPatchError;
+use crate::models::diagram::InstrumentError;
 use crate::models::meta::b_notation::NotationError;
+use crate::models::meta::capo::CapoError;
+use crate::models::meta::chord_notation::ChordNotationError;
+use crate::models::meta::language_display::LanguageDisplayError;
 use crate::models::meta::semitone_notation::SemitoneNotationError;
+use crate::models::structure::{ArrangementError, ExpansionError};
+use crate::search::SearchIndexError;
+use crate::span::Span;
 
 /// Shorthand for chord library results
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -12,6 +21,7 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 #[derive(Debug)]
 pub struct Error {
     inner: Kind,
+    span: Option<Span>,
 }
 
 #[doc(hidden)]
@@ -40,6 +50,10 @@ impl Error {
         Error::new(Kind::Setlist(description.into()))
     }
 
+    pub fn pdf_error<S: Into<String>>(description: S) -> Self {
+        Error::new(Kind::Pdf(description.into()))
+    }
+
     pub fn invalid_username_error<S1: Into<String>, S2: Into<String>>(
         invalid_username: S1,
         message: S2,
@@ -79,6 +93,42 @@ impl Error {
         Self::new(Kind::SemitoneNotation(error))
     }
 
+    pub fn chord_notation_error(error: ChordNotationError) -> Self {
+        Self::new(Kind::ChordNotation(error))
+    }
+
+    pub fn capo_error(error: CapoError) -> Self {
+        Self::new(Kind::Capo(error))
+    }
+
+    pub fn instrument_error(error: InstrumentError) -> Self {
+        Self::new(Kind::Instrument(error))
+    }
+
+    pub fn lint_rule_error(error: LintRuleError) -> Self {
+        Self::new(Kind::LintRule(error))
+    }
+
+    pub fn arrangement_error(error: ArrangementError) -> Self {
+        Self::new(Kind::Arrangement(error))
+    }
+
+    pub fn expansion_error(error: ExpansionError) -> Self {
+        Self::new(Kind::Expansion(error))
+    }
+
+    pub fn language_display_error(error: LanguageDisplayError) -> Self {
+        Self::new(Kind::LanguageDisplay(error))
+    }
+
+    pub fn catalog_patch_error(error: CatalogPatchError) -> Self {
+        Self::new(Kind::CatalogPatch(error))
+    }
+
+    pub fn search_index_error(error: SearchIndexError) -> Self {
+        Self::new(Kind::SearchIndex(error))
+    }
+
     pub fn parse_int_error(error: std::num::ParseIntError) -> Self {
         Self::new(Kind::ParseInt(error))
     }
@@ -88,7 +138,21 @@ impl Error {
     }
 
     fn new(kind: Kind) -> Self {
-        Error { inner: kind }
+        Error {
+            inner: kind,
+            span: None,
+        }
+    }
+}
+
+impl Error {
+    /// Return the position in the source that caused the error, if it is known
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub(crate) fn with_span(self, span: Option<Span>) -> Self {
+        Self { span, ..self }
     }
 }
 
@@ -122,6 +186,60 @@ impl From<SemitoneNotationError> for Error {
     }
 }
 
+impl From<ChordNotationError> for Error {
+    fn from(error: ChordNotationError) -> Self {
+        Self::new(Kind::ChordNotation(error))
+    }
+}
+
+impl From<CapoError> for Error {
+    fn from(error: CapoError) -> Self {
+        Self::new(Kind::Capo(error))
+    }
+}
+
+impl From<InstrumentError> for Error {
+    fn from(error: InstrumentError) -> Self {
+        Self::new(Kind::Instrument(error))
+    }
+}
+
+impl From<LintRuleError> for Error {
+    fn from(error: LintRuleError) -> Self {
+        Self::new(Kind::LintRule(error))
+    }
+}
+
+impl From<ArrangementError> for Error {
+    fn from(error: ArrangementError) -> Self {
+        Self::new(Kind::Arrangement(error))
+    }
+}
+
+impl From<ExpansionError> for Error {
+    fn from(error: ExpansionError) -> Self {
+        Self::new(Kind::Expansion(error))
+    }
+}
+
+impl From<LanguageDisplayError> for Error {
+    fn from(error: LanguageDisplayError) -> Self {
+        Self::new(Kind::LanguageDisplay(error))
+    }
+}
+
+impl From<CatalogPatchError> for Error {
+    fn from(error: CatalogPatchError) -> Self {
+        Self::new(Kind::CatalogPatch(error))
+    }
+}
+
+impl From<SearchIndexError> for Error {
+    fn from(error: SearchIndexError) -> Self {
+        Self::new(Kind::SearchIndex(error))
+    }
+}
+
 impl From<std::num::ParseIntError> for Error {
     fn from(error: std::num::ParseIntError) -> Self {
         Self::new(Kind::ParseInt(error))
@@ -136,6 +254,7 @@ enum Kind {
     FileType(String),
     Chord(String),
     Setlist(String),
+    Pdf(String),
     Unknown(String),
     InvalidUsername(String, String),
     InvalidPassword(String, String),
@@ -143,6 +262,15 @@ enum Kind {
     Io(std::io::Error),
     Notation(NotationError),
     SemitoneNotation(SemitoneNotationError),
+    ChordNotation(ChordNotationError),
+    Capo(CapoError),
+    Instrument(InstrumentError),
+    LintRule(LintRuleError),
+    Arrangement(ArrangementError),
+    Expansion(ExpansionError),
+    LanguageDisplay(LanguageDisplayError),
+    CatalogPatch(CatalogPatchError),
+    SearchIndex(SearchIndexError),
     ParseInt(std::num::ParseIntError),
 }
 
@@ -159,6 +287,7 @@ impl Display for Kind {
             Kind::FileType(s) => f.write_str(s),
             Kind::Chord(s) => f.write_str(s),
             Kind::Setlist(s) => f.write_str(s),
+            Kind::Pdf(s) => f.write_str(s),
             Kind::Unknown(s) => f.write_str(s),
             Kind::InvalidUsername(_name, message) => f.write_str(message),
             Kind::InvalidPassword(_password, message) => f.write_str(message),
@@ -168,6 +297,15 @@ impl Display for Kind {
             Kind::Io(i) => write!(f, "{}", i),
             Kind::Notation(i) => write!(f, "{}", i),
             Kind::SemitoneNotation(i) => write!(f, "{}", i),
+            Kind::ChordNotation(i) => write!(f, "{}", i),
+            Kind::Capo(i) => write!(f, "{}", i),
+            Kind::Instrument(i) => write!(f, "{}", i),
+            Kind::LintRule(i) => write!(f, "{}", i),
+            Kind::Arrangement(i) => write!(f, "{}", i),
+            Kind::Expansion(i) => write!(f, "{}", i),
+            Kind::LanguageDisplay(i) => write!(f, "{}", i),
+            Kind::CatalogPatch(i) => write!(f, "{}", i),
+            Kind::SearchIndex(i) => write!(f, "{}", i),
             Kind::ParseInt(i) => write!(f, "{}", i),
         }
     }
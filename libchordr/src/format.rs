@@ -8,13 +8,53 @@ use serde::{Deserialize, Serialize};
 pub enum Format {
     HTML,
     Chorddown,
+    ChordPro,
     SongBeamer,
+    OpenLyrics,
+    PDF,
     Text,
 }
 
 impl Format {
+    /// Return all supported formats (text and binary)
     pub fn get_all() -> &'static [Format] {
-        &[Self::HTML, Self::Chorddown, Self::SongBeamer, Self::Text]
+        &[
+            Self::HTML,
+            Self::Chorddown,
+            Self::ChordPro,
+            Self::SongBeamer,
+            Self::OpenLyrics,
+            #[cfg(feature = "pdf")]
+            Self::PDF,
+            Self::Text,
+        ]
+    }
+
+    /// Return the formats that can be converted into a `String` (see `ConverterTrait`)
+    pub fn get_all_text() -> &'static [Format] {
+        &[
+            Self::HTML,
+            Self::Chorddown,
+            Self::ChordPro,
+            Self::SongBeamer,
+            Self::OpenLyrics,
+            Self::Text,
+        ]
+    }
+
+    /// Return the supported formats that can only be converted into bytes (see
+    /// `BinaryConverterTrait`)
+    ///
+    /// `Format::PDF` requires the `pdf` feature
+    pub fn get_all_binary() -> &'static [Format] {
+        &[
+            #[cfg(feature = "pdf")]
+            Self::PDF,
+        ]
+    }
+
+    pub fn is_binary(self) -> bool {
+        matches!(self, Self::PDF)
     }
 }
 
@@ -23,7 +63,10 @@ impl Display for Format {
         match self {
             Self::HTML => f.write_str("HTML"),
             Self::Chorddown => f.write_str("Chorddown"),
+            Self::ChordPro => f.write_str("ChordPro"),
             Self::SongBeamer => f.write_str("SongBeamer"),
+            Self::OpenLyrics => f.write_str("OpenLyrics"),
+            Self::PDF => f.write_str("PDF"),
             Self::Text => f.write_str("Text"),
         }
     }
@@ -44,9 +87,32 @@ impl FromStr for Format {
         match value.to_lowercase().as_str() {
             "html" => Ok(Self::HTML),
             "chorddown" => Ok(Self::Chorddown),
+            "chordpro" => Ok(Self::ChordPro),
             "songbeamer" => Ok(Self::SongBeamer),
+            "openlyrics" => Ok(Self::OpenLyrics),
+            "pdf" => Ok(Self::PDF),
             "text" => Ok(Self::Text),
             _ => Err(()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_formats() {
+        assert!(Format::PDF.is_binary());
+        assert!(!Format::HTML.is_binary());
+        assert!(!Format::get_all_text().contains(&Format::PDF));
+        assert_eq!(
+            Format::get_all().len(),
+            Format::get_all_text().len() + Format::get_all_binary().len()
+        );
+        assert_eq!(
+            cfg!(feature = "pdf"),
+            Format::get_all().contains(&Format::PDF)
+        );
+    }
+}
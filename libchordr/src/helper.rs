@@ -3,10 +3,13 @@ use std::io::BufRead;
 use crate::converter::{Converter, ConverterTrait};
 use crate::error::Result;
 use crate::models::chord::fmt::Formatting;
+use crate::models::file_type::FileType;
 use crate::models::song_meta_trait::SongMetaTrait;
+use crate::models::song_settings::SongSettings;
+use crate::modification::arrangement::ArrangeableTrait;
 use crate::modification::transposition::TransposableTrait;
-use crate::parser::{Parser, ParserResult, ParserTrait};
-use crate::tokenizer::{build_tokenizer, Token, Tokenizer};
+use crate::parser::{Parser, ParserResult};
+use crate::tokenizer::{build_tokenizer, build_tokenizer_for_file_type, Token, Tokenizer};
 
 #[deprecated(note = "Please use the `Token`s directly")]
 pub fn token_lines_to_tokens(token_lines: Vec<Vec<Token>>) -> Vec<Token> {
@@ -21,17 +24,29 @@ pub fn token_lines_to_tokens(token_lines: Vec<Vec<Token>>) -> Vec<Token> {
 }
 
 pub fn parse_content<R: BufRead>(contents: R) -> Result<ParserResult> {
-    let (tokens, _warnings) = build_tokenizer().tokenize(contents)?;
-    Parser::new().parse(tokens)
+    let (tokens, _warnings) = build_tokenizer().tokenize_with_spans(contents)?;
+    Parser::new().parse_with_spans(tokens)
+}
+
+pub fn parse_content_for_file_type<R: BufRead>(
+    contents: R,
+    file_type: FileType,
+) -> Result<ParserResult> {
+    let (tokens, _warnings) =
+        build_tokenizer_for_file_type(file_type).tokenize_with_spans(contents)?;
+    Parser::new().parse_with_spans(tokens)
 }
 
 pub fn transpose_content<R: BufRead>(contents: R, semitones: isize) -> Result<ParserResult> {
-    let ParserResult { node, meta } = parse_content(contents)?;
+    let parser_result = parse_content(contents)?;
+    // Transposition keeps the shape of the Node tree, so the spans stay valid
+    let spans = parser_result.spans().to_vec();
+    let ParserResult { node, meta, .. } = parser_result;
 
     let transposed_node = node.transpose(semitones);
     let transposed_meta = meta.transpose(semitones);
 
-    Ok(ParserResult::new(transposed_node, transposed_meta))
+    Ok(ParserResult::new(transposed_node, transposed_meta).with_spans(spans))
 }
 
 pub fn convert_to_format<R: BufRead>(
@@ -52,6 +67,59 @@ pub fn transpose_and_convert_to_format<R: BufRead>(
     Converter::new().convert(result.node_as_ref(), result.meta_as_ref(), formatting)
 }
 
+/// Parse the song and apply the transposition and the arrangement of the `SongSettings`
+pub fn parse_content_with_settings<R: BufRead>(
+    contents: R,
+    file_type: FileType,
+    settings: &SongSettings,
+) -> Result<ParserResult> {
+    Ok(apply_settings(
+        parse_content_for_file_type(contents, file_type)?,
+        settings,
+    ))
+}
+
+/// Parse the song, apply the `SongSettings` and convert it to the settings' format
+pub fn convert_with_settings<R: BufRead>(
+    contents: R,
+    file_type: FileType,
+    settings: &SongSettings,
+) -> Result<String> {
+    let result = parse_content_with_settings(contents, file_type, settings)?;
+    Converter::new().convert(
+        result.node_as_ref(),
+        result.meta_as_ref(),
+        settings.formatting(),
+    )
+}
+
+/// Apply the transposition and the arrangement of the `SongSettings`
+///
+/// If the song does not declare the selected arrangement (anymore), the sections are kept in the
+/// order of the document
+pub(crate) fn apply_settings(parser_result: ParserResult, settings: &SongSettings) -> ParserResult {
+    let semitones = settings.transpose_semitone();
+    let parser_result = if semitones != 0 {
+        let spans = parser_result.spans().to_vec();
+        let ParserResult { node, meta, .. } = parser_result;
+        ParserResult::new(node.transpose(semitones), meta.transpose(semitones)).with_spans(spans)
+    } else {
+        parser_result
+    };
+
+    let name = match settings.arrangement() {
+        Some(name) => name,
+        None => return parser_result,
+    };
+    match parser_result.meta.arrangement(name) {
+        Some(arrangement) => parser_result.arrange(&arrangement),
+        None => {
+            log::warn!("Arrangement '{}' not found", name);
+            parser_result
+        }
+    }
+}
+
 #[allow(unused)]
 pub(crate) fn is_valid_model_identifier(id: &str) -> bool {
     validate_model_identifier(id).is_ok()
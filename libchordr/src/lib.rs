@@ -1,16 +1,21 @@
+mod analysis;
 mod catalog_builder;
 mod converter;
+mod cst;
 pub mod data_exchange;
+mod diagnostic;
 mod error;
 mod format;
 mod helper;
 mod html;
+mod lint;
 pub mod models;
 pub mod modification;
 mod parser;
 pub mod prelude;
 mod repeat_detector;
-mod search;
+pub mod search;
+mod span;
 #[doc(hidden)]
 pub mod test_helpers;
 mod tokenizer;
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use super::LintRule;
+use crate::diagnostic::Severity;
+
+/// Configuration of the rules checked by the `Linter`
+///
+/// Rules that are not configured use their default severity
+#[derive(Clone, Debug, Default)]
+pub struct LintConfig {
+    levels: HashMap<LintRule, Option<Severity>>,
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report violations of the rule with the given severity
+    pub fn set_severity(&mut self, rule: LintRule, severity: Severity) {
+        self.levels.insert(rule, Some(severity));
+    }
+
+    /// Do not check the rule
+    pub fn disable(&mut self, rule: LintRule) {
+        self.levels.insert(rule, None);
+    }
+
+    /// Return the severity of the rule or `None` if the rule is disabled
+    pub fn severity(&self, rule: LintRule) -> Option<Severity> {
+        match self.levels.get(&rule) {
+            Some(level) => *level,
+            None => Some(rule.default_severity()),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::LintRule;
+use crate::diagnostic::{Diagnostic, Severity};
+
+/// Violation of a `LintRule`
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct LintMessage {
+    pub rule: LintRule,
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+}
+
+impl LintMessage {
+    pub(super) fn new(rule: LintRule, diagnostic: Diagnostic) -> Self {
+        Self { rule, diagnostic }
+    }
+
+    /// Render the message in the style of a compiler message
+    pub fn render(&self, path: &str, source: &str) -> String {
+        self.diagnostic
+            .clone()
+            .with_code(self.rule.as_str())
+            .render(path, source)
+    }
+}
+
+/// Result of linting a single file
+#[derive(Serialize, Debug, Clone)]
+pub struct LintReport {
+    pub path: PathBuf,
+    pub messages: Vec<LintMessage>,
+    #[serde(skip)]
+    pub source: String,
+}
+
+impl LintReport {
+    /// Return if any of the messages is an error
+    pub fn has_errors(&self) -> bool {
+        self.messages
+            .iter()
+            .any(|message| message.diagnostic.severity() == Severity::Error)
+    }
+
+    /// Render all messages in the style of compiler messages
+    pub fn render(&self) -> String {
+        let path = self.path.to_string_lossy();
+        self.messages
+            .iter()
+            .map(|message| message.render(&path, &self.source))
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+use crate::diagnostic::Severity;
+
+/// Rule checked by the `Linter`
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum LintRule {
+    /// Files that can not be read or parsed
+    InvalidFile,
+    /// Warnings raised by the tokenizer (e.g. unclosed chords)
+    TokenizerWarning,
+    /// Chords that can not be parsed
+    InvalidChord,
+    /// References (`> Chorus`) that do not match any section
+    UnresolvedReference,
+    /// Songs without a title
+    MissingTitle,
+    /// Songs with chords, but without a (valid) key
+    MissingKey,
+    /// Sections with the same name
+    DuplicateSection,
+    /// Songs mixing the `B` and `H` notation
+    InconsistentBNotation,
+}
+
+impl LintRule {
+    pub fn get_all() -> &'static [LintRule] {
+        &[
+            Self::InvalidFile,
+            Self::TokenizerWarning,
+            Self::InvalidChord,
+            Self::UnresolvedReference,
+            Self::MissingTitle,
+            Self::MissingKey,
+            Self::DuplicateSection,
+            Self::InconsistentBNotation,
+        ]
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::InvalidFile => "invalid-file",
+            Self::TokenizerWarning => "tokenizer-warning",
+            Self::InvalidChord => "invalid-chord",
+            Self::UnresolvedReference => "unresolved-reference",
+            Self::MissingTitle => "missing-title",
+            Self::MissingKey => "missing-key",
+            Self::DuplicateSection => "duplicate-section",
+            Self::InconsistentBNotation => "inconsistent-b-notation",
+        }
+    }
+
+    /// Return the severity used if the rule is not configured
+    ///
+    /// Problems that lose information when the song is rendered are errors
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Self::InvalidFile | Self::InvalidChord | Self::UnresolvedReference => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LintRule {
+    type Err = LintRuleError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::get_all()
+            .iter()
+            .find(|rule| rule.as_str() == value)
+            .copied()
+            .ok_or_else(|| LintRuleError(value.to_owned()))
+    }
+}
+
+impl TryFrom<&str> for LintRule {
+    type Error = LintRuleError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl Serialize for LintRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct LintRuleError(String);
+
+impl std::error::Error for LintRuleError {}
+
+impl Display for LintRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Invalid lint rule '{}'", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        for rule in LintRule::get_all() {
+            assert_eq!(LintRule::from_str(rule.as_str()).unwrap(), *rule);
+        }
+        assert!(LintRule::from_str("no-such-rule").is_err());
+    }
+}
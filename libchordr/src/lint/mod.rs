@@ -0,0 +1,469 @@
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+use crate::catalog_builder::CatalogBuilder;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::{Error, Result};
+use crate::models::chord::Chords;
+use crate::models::file_type::FileType;
+use crate::models::meta::BNotation;
+use crate::models::structure::{ReferenceResolver, Section, SectionProvider};
+use crate::parser::{MetaParser, Node, Parser, ParserResult};
+use crate::span::Span;
+use crate::tokenizer::{build_tokenizer_for_file_type, Meta, SpannedToken, Token, Tokenizer};
+
+pub use self::lint_config::LintConfig;
+pub use self::lint_report::{LintMessage, LintReport};
+pub use self::lint_rule::{LintRule, LintRuleError};
+
+mod lint_config;
+mod lint_report;
+mod lint_rule;
+
+/// Linter checking songs for common problems
+#[derive(Default)]
+pub struct Linter {
+    config: LintConfig,
+}
+
+impl Linter {
+    pub fn new(config: LintConfig) -> Self {
+        Self { config }
+    }
+
+    /// Lint all chorddown files in the given directory (recursively)
+    ///
+    /// Files that can not be read or parsed are reported with a `LintRule::InvalidFile` message,
+    /// so that the remaining files are still checked
+    pub fn lint_directory<P: AsRef<Path>>(&self, path: P) -> Result<Vec<LintReport>> {
+        let song_files = CatalogBuilder::new().find_song_files(path, FileType::Chorddown, true)?;
+
+        Ok(song_files
+            .iter()
+            .map(|song_file| {
+                self.lint_file(song_file)
+                    .unwrap_or_else(|error| self.invalid_file_report(song_file, error))
+            })
+            .collect())
+    }
+
+    /// Lint the given song file
+    pub fn lint_file<P: AsRef<Path>>(&self, path: P) -> Result<LintReport> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        let file_type = FileType::try_from(path).unwrap_or(FileType::Chorddown);
+
+        Ok(LintReport {
+            path: path.to_path_buf(),
+            messages: self.lint(&source, file_type)?,
+            source,
+        })
+    }
+
+    /// Lint the song source of the given file type
+    ///
+    /// The messages are sorted by their position in the source
+    pub fn lint(&self, source: &str, file_type: FileType) -> Result<Vec<LintMessage>> {
+        let mut messages = vec![];
+        let (tokens, warnings) =
+            build_tokenizer_for_file_type(file_type).tokenize_with_spans(source.as_bytes())?;
+        for warning in warnings {
+            self.report(
+                &mut messages,
+                LintRule::TokenizerWarning,
+                warning.to_string(),
+                warning.span(),
+            );
+        }
+
+        let meta = MetaParser::new().parse_iter(tokens.iter().map(|(token, _)| token))?;
+        self.check_b_notation(&mut messages, &tokens);
+
+        // Drop invalid chords so that the remaining rules can be checked
+        let mut valid_tokens = Vec::with_capacity(tokens.len());
+        for (token, span) in tokens {
+            if let Token::Chord(raw) = &token {
+                if let Err(error) = Chords::try_from(raw, meta.b_notation) {
+                    self.report(
+                        &mut messages,
+                        LintRule::InvalidChord,
+                        format!("Invalid chord `{}`: {}", raw, error),
+                        span,
+                    );
+                    continue;
+                }
+            }
+            valid_tokens.push((token, span));
+        }
+
+        let parser_result = Parser::new().parse_with_spans(valid_tokens)?;
+        self.check_meta(&mut messages, &parser_result);
+        self.check_sections(&mut messages, &parser_result);
+
+        // Messages without a position refer to the whole song and are listed first
+        messages.sort_by_key(|message| message.diagnostic.span());
+
+        Ok(messages)
+    }
+
+    fn invalid_file_report(&self, path: &Path, error: Error) -> LintReport {
+        let mut messages = vec![];
+        self.report(
+            &mut messages,
+            LintRule::InvalidFile,
+            format!("Could not lint the file: {}", error),
+            error.span(),
+        );
+
+        LintReport {
+            path: path.to_path_buf(),
+            messages,
+            source: fs::read_to_string(path).unwrap_or_default(),
+        }
+    }
+
+    fn report(
+        &self,
+        messages: &mut Vec<LintMessage>,
+        rule: LintRule,
+        message: String,
+        span: Option<Span>,
+    ) {
+        let diagnostic = match self.config.severity(rule) {
+            Some(Severity::Error) => Diagnostic::error(message, span),
+            Some(Severity::Warning) => Diagnostic::warning(message, span),
+            None => return,
+        };
+        messages.push(LintMessage::new(rule, diagnostic));
+    }
+
+    fn check_meta(&self, messages: &mut Vec<LintMessage>, parser_result: &ParserResult) {
+        let meta = parser_result.meta_as_ref();
+        if meta.title.is_none() {
+            self.report(
+                messages,
+                LintRule::MissingTitle,
+                "Song has no title".to_owned(),
+                None,
+            );
+        }
+
+        match (&meta.key, &meta.key_raw) {
+            (Some(_), _) => {}
+            (None, Some(key_raw)) => self.report(
+                messages,
+                LintRule::MissingKey,
+                format!("Key `{}` is not a valid chord", key_raw),
+                None,
+            ),
+            (None, None) if contains_chords(parser_result.node_as_ref()) => self.report(
+                messages,
+                LintRule::MissingKey,
+                "Song has chords, but no key".to_owned(),
+                None,
+            ),
+            (None, None) => {}
+        }
+    }
+
+    fn check_sections(&self, messages: &mut Vec<LintMessage>, parser_result: &ParserResult) {
+        let children = match parser_result.node_as_ref() {
+            Node::Document(children) => children,
+            _ => return,
+        };
+        let sections: Vec<(Section, Option<Span>)> = children
+            .iter()
+            .filter_map(|node| {
+                let section = SectionProvider::get_section(node)?;
+                let span = match node {
+                    Node::Section { head, .. } => parser_result.span_of(head),
+                    _ => None,
+                };
+                Some((section, span))
+            })
+            .collect();
+        let all_sections: Vec<Section> = sections.iter().map(|(s, _)| s.clone()).collect();
+
+        let resolver = ReferenceResolver::new();
+        for (index, (section, span)) in sections.iter().enumerate() {
+            if section.is_reference {
+                if resolver.resolve_reference(section, &all_sections).is_none() {
+                    self.report(
+                        messages,
+                        LintRule::UnresolvedReference,
+                        format!("Reference `{}` does not match any section", section.title),
+                        *span,
+                    );
+                }
+                continue;
+            }
+
+            let previous = sections[..index]
+                .iter()
+                .find(|(other, _)| !other.is_reference && other.identifier == section.identifier);
+            if let Some((previous, previous_span)) = previous {
+                let message = match previous_span {
+                    Some(previous_span) => format!(
+                        "Section `{}` is already defined at line {}",
+                        previous.title,
+                        previous_span.start().line()
+                    ),
+                    None => format!("Section `{}` is already defined", previous.title),
+                };
+                self.report(messages, LintRule::DuplicateSection, message, *span);
+            }
+        }
+    }
+
+    /// Check that the chords do not mix the `B` and `H` notation
+    ///
+    /// With the `H` notation a `B` denotes a B flat. Songs declaring the `H` notation or using `H`
+    /// chords without a declaration must not write B flat as `Bb`. Songs declaring the `B`
+    /// notation must not use `H`
+    fn check_b_notation(&self, messages: &mut Vec<LintMessage>, tokens: &[SpannedToken]) {
+        let declared = tokens.iter().rev().find_map(|(token, _)| match token {
+            Token::Meta(Meta::BNotation(b_notation)) => Some(*b_notation),
+            _ => None,
+        });
+        let mut chords = tokens.iter().filter_map(|(token, span)| match token {
+            Token::Chord(raw) => Some((raw.as_str(), *span)),
+            _ => None,
+        });
+        let first_h = chords
+            .clone()
+            .find(|(raw, _)| chord_b_spellings(raw).contains(&BSpelling::H));
+
+        let (conflicting, reason) = match (declared, first_h) {
+            (Some(BNotation::B), _) => {
+                (BSpelling::H, "the song declares the B notation".to_owned())
+            }
+            (Some(BNotation::H), _) => (
+                BSpelling::BFlat,
+                "the song declares the H notation (where B flat is written `B`)".to_owned(),
+            ),
+            (None, Some((h_raw, h_span))) => {
+                let location = match h_span {
+                    Some(h_span) => format!(" at {}", h_span),
+                    None => String::new(),
+                };
+                (
+                    BSpelling::BFlat,
+                    format!(
+                        "`{}`{} uses the H notation (where B flat is written `B`)",
+                        h_raw, location
+                    ),
+                )
+            }
+            (None, None) => return,
+        };
+
+        if let Some((raw, span)) =
+            chords.find(|(raw, _)| chord_b_spellings(raw).contains(&conflicting))
+        {
+            self.report(
+                messages,
+                LintRule::InconsistentBNotation,
+                format!(
+                    "Chord `{}` uses the {} notation, but {}",
+                    raw,
+                    conflicting.b_notation(),
+                    reason
+                ),
+                span,
+            );
+        }
+    }
+}
+
+/// Spelling of a `B`/`H` note in a chord
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum BSpelling {
+    /// `H`: B in the H notation
+    H,
+    /// `B`: B in the B notation or B flat in the H notation
+    B,
+    /// `Bb`: B flat in the B notation
+    BFlat,
+}
+
+impl BSpelling {
+    /// Return the notation the spelling belongs to
+    fn b_notation(self) -> BNotation {
+        match self {
+            Self::H => BNotation::H,
+            Self::B | Self::BFlat => BNotation::B,
+        }
+    }
+}
+
+/// Return the spellings of the `B`/`H` notes in the raw chord
+fn chord_b_spellings(raw: &str) -> Vec<BSpelling> {
+    raw.split('/')
+        .filter_map(|part| {
+            let mut chars = part.trim().chars();
+            match (chars.next()?, chars.next()) {
+                ('B', Some('b' | '♭')) => Some(BSpelling::BFlat),
+                ('B', _) => Some(BSpelling::B),
+                ('H' | 'h', _) => Some(BSpelling::H),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn contains_chords(node: &Node) -> bool {
+    match node {
+        Node::ChordTextPair { .. } | Node::ChordStandalone(_) => true,
+        Node::Document(children) | Node::Section { children, .. } => {
+            children.iter().any(contains_chords)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(source: &str) -> Vec<(LintRule, Severity, Option<usize>)> {
+        Linter::default()
+            .lint(source, FileType::Chorddown)
+            .unwrap()
+            .into_iter()
+            .map(|message| {
+                (
+                    message.rule,
+                    message.diagnostic.severity(),
+                    message.diagnostic.span().map(|span| span.start().line()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lint_valid_song() {
+        let source =
+            "# Swing Low\nKey: D\n\n##! Chorus\n[D]Swing low\n\n## Verse\n[G]Sweet\n\n> Chorus\n";
+        assert_eq!(lint(source), vec![]);
+    }
+
+    #[test]
+    fn lint_song_with_tags() {
+        let source = include_str!("../../tests/resources/swing_low_sweet_chariot.chorddown");
+        assert_eq!(
+            lint(source),
+            vec![(LintRule::MissingKey, Severity::Warning, None)]
+        );
+        assert_eq!(
+            lint("# Song\nKey: D\n\n## Verse\nSwing #low\n"),
+            vec![(LintRule::TokenizerWarning, Severity::Warning, Some(5))]
+        );
+    }
+
+    #[test]
+    fn lint_tokenizer_warning_and_invalid_chord() {
+        let source = "# Swing Low\nKey: D\n\n## Verse\n[D low\n[Xm]Sweet [G]chariot\n";
+        assert_eq!(
+            lint(source),
+            vec![
+                (LintRule::TokenizerWarning, Severity::Warning, Some(5)),
+                (LintRule::InvalidChord, Severity::Error, Some(6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_meta() {
+        assert_eq!(
+            lint("## Verse\n[D]Swing low\n"),
+            vec![
+                (LintRule::MissingTitle, Severity::Warning, None),
+                (LintRule::MissingKey, Severity::Warning, None),
+            ]
+        );
+        // Songs without chords do not need a key
+        assert_eq!(lint("# Swing Low\n\n## Verse\nSwing low\n"), vec![]);
+        assert_eq!(
+            lint("# Swing Low\nKey: X\n"),
+            vec![(LintRule::MissingKey, Severity::Warning, None)]
+        );
+    }
+
+    #[test]
+    fn lint_sections() {
+        let source = "# Swing Low\n\n## Verse\nSwing low\n\n## Verse\nSweet\n\n> Bridge\n";
+        assert_eq!(
+            lint(source),
+            vec![
+                (LintRule::DuplicateSection, Severity::Warning, Some(6)),
+                (LintRule::UnresolvedReference, Severity::Error, Some(9)),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_b_notation() {
+        // Songs with `H` chords use the H notation, where `B` denotes B flat
+        let source = "# Swing Low\nKey: D\n\n## Verse\n[H7]Swing [B]low\n";
+        assert_eq!(lint(source), vec![]);
+        let source = "# Swing Low\nKey: D\nB Notation: H\n\n## Verse\n[H7]Swing [B]low\n";
+        assert_eq!(lint(source), vec![]);
+        assert_eq!(lint("# Swing Low\nKey: D\n\n[H7]Swing [Hm]low\n"), vec![]);
+        assert_eq!(lint("# Swing Low\nKey: D\n\n[B7]Swing [Bbm]low\n"), vec![]);
+
+        let source = "# Swing Low\nKey: D\nB Notation: B\n\n## Verse\n[H7]Swing low\n";
+        assert_eq!(
+            lint(source),
+            vec![(LintRule::InconsistentBNotation, Severity::Warning, Some(6))]
+        );
+        let source = "# Swing Low\nKey: D\n\n## Verse\n[H7]Swing [Bbmaj7]low\n";
+        assert_eq!(
+            lint(source),
+            vec![(LintRule::InconsistentBNotation, Severity::Warning, Some(5))]
+        );
+        let source = "# Swing Low\nKey: D\nB Notation: H\n\n## Verse\n[Bb]Swing low\n";
+        assert_eq!(
+            lint(source),
+            vec![(LintRule::InconsistentBNotation, Severity::Warning, Some(6))]
+        );
+    }
+
+    #[test]
+    fn lint_directory_with_invalid_file() {
+        let dir = std::env::temp_dir().join(format!("chordr-lint-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("a.chorddown"),
+            "# Song A\nKey: G\n\n[G]Swing low\n",
+        )
+        .unwrap();
+        fs::write(dir.join("b.chorddown"), b"# Song B\n\xff\xfe\n").unwrap();
+        fs::write(dir.join("c.chorddown"), "## Verse\nSwing low\n").unwrap();
+
+        let mut reports = Linter::default().lint_directory(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        reports.sort_by(|a, b| a.path.cmp(&b.path));
+
+        assert_eq!(reports.len(), 3);
+        assert!(reports[0].messages.is_empty());
+        assert_eq!(reports[1].messages.len(), 1);
+        assert_eq!(reports[1].messages[0].rule, LintRule::InvalidFile);
+        assert!(reports[1].has_errors());
+        assert_eq!(reports[2].messages[0].rule, LintRule::MissingTitle);
+    }
+
+    #[test]
+    fn lint_with_config() {
+        let mut config = LintConfig::new();
+        config.disable(LintRule::MissingTitle);
+        config.set_severity(LintRule::MissingKey, Severity::Error);
+
+        let messages = Linter::new(config)
+            .lint("[D]Swing low\n", FileType::Chorddown)
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].rule, LintRule::MissingKey);
+        assert_eq!(messages[0].diagnostic.severity(), Severity::Error);
+    }
+}
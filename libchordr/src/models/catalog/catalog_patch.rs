@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::song::Song;
+use crate::models::song_id::SongId;
+
+/// Changes between two revisions of a `Catalog`
+///
+/// The patch is built with `Catalog::diff()` and turns the catalog with the `base_revision` into
+/// the one with the `revision` using `Catalog::apply()`
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct CatalogPatch {
+    base_revision: String,
+    revision: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    added: Vec<Song>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    changed: Vec<Song>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    removed: Vec<SongId>,
+}
+
+impl CatalogPatch {
+    pub(super) fn new(
+        base_revision: String,
+        revision: String,
+        added: Vec<Song>,
+        changed: Vec<Song>,
+        removed: Vec<SongId>,
+    ) -> Self {
+        Self {
+            base_revision,
+            revision,
+            added,
+            changed,
+            removed,
+        }
+    }
+
+    /// Return the revision of the `Catalog` the patch has to be applied to
+    pub fn base_revision(&self) -> &str {
+        &self.base_revision
+    }
+
+    /// Return the revision of the `Catalog` after the patch was applied
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// Return the songs that are not part of the base `Catalog`
+    pub fn added(&self) -> &[Song] {
+        &self.added
+    }
+
+    /// Return the new versions of the songs that changed since the base `Catalog`
+    pub fn changed(&self) -> &[Song] {
+        &self.changed
+    }
+
+    /// Return the IDs of the songs that have been removed since the base `Catalog`
+    pub fn removed(&self) -> &[SongId] {
+        &self.removed
+    }
+
+    /// Return if the patch does not contain any song changes
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Error raised if a `CatalogPatch` can not be applied to a `Catalog`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogPatchError {
+    /// The patch was built for another revision (expected, actual)
+    RevisionMismatch(String, String),
+    /// An added song is already part of the `Catalog`
+    DuplicateSong(SongId),
+    /// A changed or removed song is not part of the `Catalog`
+    SongNotFound(SongId),
+}
+
+impl Display for CatalogPatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatalogPatchError::RevisionMismatch(expected, actual) => write!(
+                f,
+                "Catalog patch for revision '{}' can not be applied to revision '{}'",
+                expected, actual
+            ),
+            CatalogPatchError::DuplicateSong(id) => {
+                write!(f, "Song '{}' is already part of the catalog", id)
+            }
+            CatalogPatchError::SongNotFound(id) => {
+                write!(f, "Song '{}' is not part of the catalog", id)
+            }
+        }
+    }
+}
+
+impl Error for CatalogPatchError {}
@@ -1,15 +1,19 @@
+use std::collections::HashMap;
 use std::slice::Iter;
 use std::vec::IntoIter;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::Result;
 use crate::models::list::ListEntryTrait;
 use crate::models::song::Song;
 use crate::models::song_id::SongId;
 use crate::prelude::RecordTrait;
 
+pub use self::catalog_patch::{CatalogPatch, CatalogPatchError};
 pub use self::catalog_trait::CatalogTrait;
 
+mod catalog_patch;
 pub mod catalog_trait;
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -25,6 +29,74 @@ impl Catalog {
             songs,
         }
     }
+
+    /// Return the changes that turn this `Catalog` into the `other`
+    pub fn diff(&self, other: &Catalog) -> CatalogPatch {
+        let songs: HashMap<SongId, &Song> = self.songs.iter().map(|s| (s.id(), s)).collect();
+        let other_songs: HashMap<SongId, &Song> = other.songs.iter().map(|s| (s.id(), s)).collect();
+
+        let mut added = vec![];
+        let mut changed = vec![];
+        for song in &other.songs {
+            match songs.get(&song.id()) {
+                None => added.push(song.clone()),
+                Some(previous) if *previous != song => changed.push(song.clone()),
+                Some(_) => {}
+            }
+        }
+        let removed = self
+            .songs
+            .iter()
+            .map(|song| song.id())
+            .filter(|id| !other_songs.contains_key(id))
+            .collect();
+
+        CatalogPatch::new(
+            self.revision.clone(),
+            other.revision.clone(),
+            added,
+            changed,
+            removed,
+        )
+    }
+
+    /// Build the `Catalog` of the patch's revision
+    ///
+    /// An error is returned if the patch was built for another revision or does not match the
+    /// songs of this `Catalog`
+    pub fn apply(&self, patch: &CatalogPatch) -> Result<Catalog> {
+        if patch.base_revision() != self.revision {
+            return Err(CatalogPatchError::RevisionMismatch(
+                patch.base_revision().to_string(),
+                self.revision.clone(),
+            )
+            .into());
+        }
+
+        let mut songs: HashMap<SongId, Song> =
+            self.songs.iter().map(|s| (s.id(), s.clone())).collect();
+        for id in patch.removed() {
+            if songs.remove(id).is_none() {
+                return Err(CatalogPatchError::SongNotFound(id.clone()).into());
+            }
+        }
+        for song in patch.changed() {
+            match songs.get_mut(&song.id()) {
+                Some(previous) => *previous = song.clone(),
+                None => return Err(CatalogPatchError::SongNotFound(song.id()).into()),
+            }
+        }
+        for song in patch.added() {
+            if songs.insert(song.id(), song.clone()).is_some() {
+                return Err(CatalogPatchError::DuplicateSong(song.id()).into());
+            }
+        }
+
+        let mut songs: Vec<Song> = songs.into_values().collect();
+        songs.sort_by_key(|a| a.id());
+
+        Ok(Catalog::new(patch.revision(), songs))
+    }
 }
 
 impl CatalogTrait<Song> for Catalog {
@@ -67,3 +139,74 @@ impl RecordTrait for Catalog {
         self.revision.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::file_type::FileType;
+    use crate::models::song_meta::SongMeta;
+    use crate::test_helpers::get_test_catalog;
+
+    fn song(id: &str, src: &str) -> Song {
+        Song::new(
+            SongMeta::new(id.into(), id.to_string(), FileType::Chorddown),
+            src,
+        )
+    }
+
+    fn updated_catalog() -> Catalog {
+        let mut songs: Vec<Song> = get_test_catalog()
+            .into_iter()
+            .filter(|s| s.id() != SongId::new("song-2"))
+            .map(|s| match s.id().as_str() {
+                "song-3" => song("song-3", "# Song 3\n\n## Verse\n[C]Changed\n"),
+                _ => s,
+            })
+            .collect();
+        songs.push(song("song-4", "# Song 4\n"));
+        songs.sort_by_key(|a| a.id());
+
+        Catalog::new("updated-catalog", songs)
+    }
+
+    #[test]
+    fn diff_test() {
+        let patch = get_test_catalog().diff(&updated_catalog());
+
+        assert_eq!(patch.base_revision(), "test-catalog");
+        assert_eq!(patch.revision(), "updated-catalog");
+        assert_eq!(patch.added(), &[song("song-4", "# Song 4\n")]);
+        assert_eq!(patch.changed().len(), 1);
+        assert_eq!(patch.changed()[0].id(), SongId::new("song-3"));
+        assert_eq!(patch.removed(), &[SongId::new("song-2")]);
+        assert!(get_test_catalog().diff(&get_test_catalog()).is_empty());
+    }
+
+    #[test]
+    fn apply_test() {
+        let catalog = get_test_catalog();
+        let updated = updated_catalog();
+        let patch = catalog.diff(&updated);
+
+        assert_eq!(catalog.apply(&patch).unwrap(), updated);
+        assert_eq!(
+            catalog.apply(&catalog.diff(&catalog)).unwrap().revision(),
+            catalog.revision()
+        );
+    }
+
+    #[test]
+    fn apply_to_wrong_revision_test() {
+        let updated = updated_catalog();
+        let patch = get_test_catalog().diff(&updated);
+
+        let error = updated.apply(&patch).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Catalog patch for revision 'test-catalog' can not be applied to revision 'updated-catalog'"
+        );
+
+        let other = Catalog::new("test-catalog", vec![song("song-2", "")]);
+        assert!(other.apply(&patch).is_err());
+    }
+}
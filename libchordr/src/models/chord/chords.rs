@@ -1,8 +1,13 @@
 use crate::error::Error;
 use crate::models::chord::fmt::Formatting;
-use crate::models::chord::{Chord, NoteDisplay, TransposableTrait};
-use crate::models::meta::BNotation;
+use crate::models::chord::{split_bass, Chord, Note, NoteDisplay, TransposableTrait};
+use crate::models::meta::{BNotation, ChordNotation};
 
+/// The content of a chord bracket
+///
+/// Usually this is a single `Chord` (which may have a bass note, e.g. `C/E`). If the part after
+/// the slash is not a plain note, the input is treated as polychord (e.g. `C/Dm`) and the second
+/// chord is stored separately
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub struct Chords(Chord, Option<Chord>);
 
@@ -12,22 +17,34 @@ impl Chords {
             return Err(Error::chord_error("Chord brackets are empty"));
         }
 
-        let mut inner: Vec<Chord> = Vec::with_capacity(2);
-        for p in value.splitn(2, '/') {
-            let trimmed_input = p.trim();
-            if !trimmed_input.is_empty() {
-                inner.push(Chord::try_from(p.trim(), b_notation)?);
-            }
+        let (chord_raw, second_raw) = split_bass(value);
+        let chord_raw = chord_raw.trim();
+        let second_raw = second_raw.map(str::trim).unwrap_or_default();
+        if chord_raw.is_empty() {
+            return Ok(Chords(Chord::try_from(second_raw, b_notation)?, None));
+        }
+        if second_raw.is_empty() {
+            return Ok(Chords(Chord::try_from(chord_raw, b_notation)?, None));
         }
 
-        let chord1 = inner.remove(0);
-        let chord2 = if !inner.is_empty() {
-            Some(inner.remove(0))
-        } else {
-            None
-        };
+        let chord = Chord::try_from(chord_raw, b_notation)?;
+        match Note::try_from(second_raw, b_notation) {
+            Ok(bass) => Ok(Chords(chord.with_bass(Some(bass)), None)),
+            Err(_) => Ok(Chords(
+                chord,
+                Some(Chord::try_from(second_raw, b_notation)?),
+            )),
+        }
+    }
+
+    /// Return the (first) chord
+    pub fn chord(&self) -> &Chord {
+        &self.0
+    }
 
-        Ok(Chords(chord1, chord2))
+    /// Return the second chord of a polychord (e.g. `Dm` for `C/Dm`)
+    pub fn second_chord(&self) -> Option<&Chord> {
+        self.1.as_ref()
     }
 }
 
@@ -41,7 +58,35 @@ impl TransposableTrait for Chords {
 }
 
 impl NoteDisplay for Chords {
+    /// Format the chords according to `formatting`
+    ///
+    /// If a `ChordNotation` other than `ChordNotation::Letter` is selected and the key is known,
+    /// the chords are written relative to the key (e.g. `4` or `IV`). If a capo is set, the chord
+    /// shapes to play with the capo are written
     fn note_format(&self, formatting: Formatting) -> String {
+        if let Some(key) = formatting.key {
+            if formatting.chord_notation != ChordNotation::Letter {
+                let format = |chord: &Chord| {
+                    chord.relative_format(key, formatting.chord_notation, formatting)
+                };
+                return match &self.1 {
+                    None => format(&self.0),
+                    Some(c) => format!("{}/{}", format(&self.0), format(c)),
+                };
+            }
+        }
+
+        if let Some(capo) = formatting.capo.filter(|capo| capo.fret() > 0) {
+            let shapes = self.clone().transpose(-(capo.fret() as isize));
+            return shapes.note_format(Formatting {
+                semitone_notation: formatting
+                    .capo_semitone_notation
+                    .unwrap_or(formatting.semitone_notation),
+                capo: None,
+                ..formatting
+            });
+        }
+
         match &self.1 {
             None => NoteDisplay::note_format(&self.0, formatting),
             Some(c) => format!(
@@ -56,7 +101,7 @@ impl NoteDisplay for Chords {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::chord::Note;
+    use crate::models::meta::Capo;
 
     #[test]
     fn try_from() {
@@ -64,121 +109,223 @@ mod tests {
         assert!(chord_result.is_ok(), "{}", chord_result.unwrap_err());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::A);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         let chord_result = Chords::try_from("A#", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Ais);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
 
         let chord_result = Chords::try_from("C#madd2add4/D", BNotation::H);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Cis);
-        assert_eq!(chord.0.variant, Some("madd2add4".to_owned()));
-        let chord1 = chord.1.unwrap();
-        assert_eq!(chord1.root, Note::D);
-        assert_eq!(chord1.variant, None);
+        assert_eq!(chord.0.variant(), Some("madd2add4".to_owned()));
+        assert_eq!(chord.0.bass(), Some(Note::D));
+        assert!(chord.1.is_none());
 
         let chord_result = Chords::try_from("C#madd2add4/B", BNotation::H);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Cis);
-        assert_eq!(chord.0.variant, Some("madd2add4".to_owned()));
-        let chord1 = chord.1.unwrap();
-        assert_eq!(chord1.root, Note::Ais);
-        assert_eq!(chord1.variant, None);
+        assert_eq!(chord.0.variant(), Some("madd2add4".to_owned()));
+        assert_eq!(chord.0.bass(), Some(Note::Ais));
+        assert!(chord.1.is_none());
 
         let chord_result = Chords::try_from("Bbmaj7/C#", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Ais);
-        assert_eq!(chord.0.variant, Some("maj7".to_owned()));
-        let chord1 = chord.1.unwrap();
-        assert_eq!(chord1.root, Note::Cis);
-        assert_eq!(chord1.variant, None);
+        assert_eq!(chord.0.variant(), Some("maj7".to_owned()));
+        assert_eq!(chord.0.bass(), Some(Note::Cis));
+        assert!(chord.1.is_none());
 
         let chord_result = Chords::try_from("Bbmaj7/D#m", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Ais);
-        assert_eq!(chord.0.variant, Some("maj7".to_owned()));
+        assert_eq!(chord.0.variant(), Some("maj7".to_owned()));
         let chord1 = chord.1.unwrap();
         assert_eq!(chord1.root, Note::Dis);
-        assert_eq!(chord1.variant, Some("m".to_owned()));
+        assert_eq!(chord1.variant(), Some("m".to_owned()));
 
         let chord_result = Chords::try_from("C#", BNotation::H);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Cis);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
 
         let chord_result = Chords::try_from("Bb", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Ais);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         let chord_result = Chords::try_from("bb", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Ais);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
 
         let chord_result = Chords::try_from("B", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::B);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         let chord_result = Chords::try_from("B", BNotation::H);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Ais);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         let chord_result = Chords::try_from("B", BNotation::H);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Ais);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         assert!(Chords::try_from("H#", BNotation::H).is_err());
 
         let chord_result = Chords::try_from("F", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::F);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         let chord_result = Chords::try_from("F#", BNotation::B);
         assert!(chord_result.is_ok());
         let chord = chord_result.unwrap();
         assert_eq!(chord.0.root, Note::Fis);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         assert!(Chords::try_from("Fb", BNotation::H).is_err());
+
+        let chord = Chords::try_from("C6/9", BNotation::B).unwrap();
+        assert_eq!(chord.0.variant(), Some("6/9".to_owned()));
+        assert_eq!(chord.0.bass(), None);
+        assert!(chord.1.is_none());
+
+        let chord = Chords::try_from("E-E/D#", BNotation::B).unwrap();
+        assert_eq!(chord.0.unknown_suffix(), Some("-E"));
+        assert_eq!(chord.0.bass(), Some(Note::Dis));
+    }
+
+    #[test]
+    fn note_format_relative() {
+        let key = Chord::try_from("C", BNotation::B).unwrap();
+        let nashville = Formatting {
+            chord_notation: ChordNotation::Nashville,
+            ..Formatting::default()
+        }
+        .for_key(Some(&key));
+        let roman = Formatting {
+            chord_notation: ChordNotation::Roman,
+            ..nashville
+        };
+        let test_cases = [
+            ("C", "1", "I"),
+            ("F", "4", "IV"),
+            ("G/B", "5/7", "V/vii"),
+            ("Am", "6m", "vi"),
+            ("Dm7", "2m7", "ii7"),
+            ("Bdim", "7dim", "vii°"),
+            ("Bm7b5", "7m7b5", "vii7b5"),
+            ("Fmaj7", "4maj7", "IVmaj7"),
+            ("Bb", "b7", "bVII"),
+            ("Eaug", "3aug", "III+"),
+            ("G7sus4", "5^7sus4", "V7sus4"),
+            ("C/Dm", "1/2m", "I/ii"),
+            ("D9Daniel", "2^9Daniel", "II9Daniel"),
+        ];
+        for (input, expected_nashville, expected_roman) in test_cases {
+            let chords = Chords::try_from(input, BNotation::B).unwrap();
+            assert_eq!(chords.note_format(nashville), expected_nashville);
+            assert_eq!(chords.note_format(roman), expected_roman);
+        }
+
+        // Without a key the chords are written as letters
+        let without_key = Formatting {
+            chord_notation: ChordNotation::Nashville,
+            ..Formatting::default()
+        };
+        let chords = Chords::try_from("Am", BNotation::B).unwrap();
+        assert_eq!(chords.note_format(without_key), "Am");
+    }
+
+    #[test]
+    fn note_format_capo() {
+        let key = Chord::try_from("A", BNotation::B).unwrap();
+        let formatting = Formatting {
+            capo: Some(Capo::new(2).unwrap()),
+            ..Formatting::default()
+        }
+        .for_key(Some(&key));
+        let format = |input| {
+            Chords::try_from(input, BNotation::B)
+                .unwrap()
+                .note_format(formatting)
+        };
+        assert_eq!(format("A"), "G");
+        assert_eq!(format("F#m7"), "Em7");
+        assert_eq!(format("E/G#"), "D/F#");
+
+        let roman = Formatting {
+            chord_notation: ChordNotation::Roman,
+            ..formatting
+        };
+        let chords = Chords::try_from("E/G#", BNotation::B).unwrap();
+        assert_eq!(chords.note_format(roman), "V/vii");
+    }
+
+    #[test]
+    fn note_format() {
+        let formatting = Formatting::default();
+        let test_cases = [
+            ("C/E", "C/E"),
+            ("CΔ7/E", "CΔ7/E"),
+            ("C2", "C2"),
+            ("Bbmaj7/D#m", "A#maj7/D#m"),
+            ("E-E/D#", "E-E/D#"),
+            ("Esus-E", "Esus-E"),
+            ("Am7 / G", "Am7/G"),
+        ];
+        for (input, expected) in test_cases {
+            let chords = Chords::try_from(input, BNotation::B).unwrap();
+            assert_eq!(chords.note_format(formatting), expected);
+        }
+    }
+
+    #[test]
+    fn note_format_normalized() {
+        let formatting = Formatting {
+            normalize_chords: true,
+            ..Formatting::default()
+        };
+        let test_cases = [("CΔ7/E", "Cmaj7/E"), ("C2", "Cadd2"), ("Amin7", "Am7")];
+        for (input, expected) in test_cases {
+            let chords = Chords::try_from(input, BNotation::B).unwrap();
+            assert_eq!(chords.note_format(formatting), expected);
+        }
     }
 
     #[test]
     fn transpose_test() {
         let chord = Chords::try_from("C#", BNotation::H).unwrap().transpose(3);
         assert_eq!(chord.0.root, Note::E);
-        assert_eq!(chord.0.variant, None);
+        assert_eq!(chord.0.variant(), None);
         assert!(chord.1.is_none());
 
         let chord = Chords::try_from("C#madd2add4/B", BNotation::H)
             .unwrap()
             .transpose(3);
         assert_eq!(chord.0.root, Note::E);
-        assert_eq!(chord.0.variant, Some("madd2add4".to_owned()));
-        assert!(chord.1.is_some());
-        let chord1 = chord.1.unwrap();
-        assert_eq!(chord1.root, Note::Cis);
-        assert_eq!(chord1.variant, None);
+        assert_eq!(chord.0.variant(), Some("madd2add4".to_owned()));
+        assert_eq!(chord.0.bass(), Some(Note::Cis));
+        assert!(chord.1.is_none());
 
         let chord = Chords::try_from("Bbmaj7/C#m", BNotation::B)
             .unwrap()
             .transpose(-2);
         assert_eq!(chord.0.root, Note::Gis);
-        assert_eq!(chord.0.variant, Some("maj7".to_owned()));
+        assert_eq!(chord.0.variant(), Some("maj7".to_owned()));
         assert!(chord.1.is_some());
         let chord1 = chord.1.unwrap();
         assert_eq!(chord1.root, Note::B);
-        assert_eq!(chord1.variant, Some("m".to_owned()));
+        assert_eq!(chord1.variant(), Some("m".to_owned()));
     }
 }
@@ -0,0 +1,70 @@
+use std::fmt::{Display, Formatter};
+
+/// Notes added on top of the chord's triad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Extension {
+    Sixth,
+    SixNine,
+    Seventh,
+    Ninth,
+    Eleventh,
+    Thirteenth,
+    MajorSeventh,
+    MajorNinth,
+    MajorEleventh,
+    MajorThirteenth,
+    /// An added note without the implied seventh (e.g. `add9`)
+    Add(u8),
+}
+
+impl Extension {
+    /// Return if the extension implies a seventh (e.g. `9` or `maj7`)
+    pub fn has_seventh(&self) -> bool {
+        !matches!(
+            self,
+            Extension::Sixth | Extension::SixNine | Extension::Add(_)
+        )
+    }
+}
+
+impl Display for Extension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Extension::Sixth => f.write_str("6"),
+            Extension::SixNine => f.write_str("6/9"),
+            Extension::Seventh => f.write_str("7"),
+            Extension::Ninth => f.write_str("9"),
+            Extension::Eleventh => f.write_str("11"),
+            Extension::Thirteenth => f.write_str("13"),
+            Extension::MajorSeventh => f.write_str("maj7"),
+            Extension::MajorNinth => f.write_str("maj9"),
+            Extension::MajorEleventh => f.write_str("maj11"),
+            Extension::MajorThirteenth => f.write_str("maj13"),
+            Extension::Add(degree) => write!(f, "add{}", degree),
+        }
+    }
+}
+
+/// A raised or lowered chord tone (e.g. `b5` or `#11`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Alteration {
+    Flat(u8),
+    Sharp(u8),
+}
+
+impl Alteration {
+    pub fn degree(&self) -> u8 {
+        match self {
+            Alteration::Flat(degree) | Alteration::Sharp(degree) => *degree,
+        }
+    }
+}
+
+impl Display for Alteration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Alteration::Flat(degree) => write!(f, "b{}", degree),
+            Alteration::Sharp(degree) => write!(f, "#{}", degree),
+        }
+    }
+}
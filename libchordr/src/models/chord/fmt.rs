@@ -1,5 +1,10 @@
 use crate::format::Format;
-use crate::models::meta::{BNotation, SemitoneNotation};
+use crate::models::chord::{Chord, Note};
+use crate::models::diagram::Instrument;
+use crate::models::meta::{
+    BNotation, Capo, ChordNotation, LanguageDisplay, MetaTrait, SemitoneNotation,
+};
+use crate::models::structure::Expansion;
 use serde::{Deserialize, Serialize};
 
 #[derive(Copy, Clone, PartialEq, Debug, Deserialize, Serialize)]
@@ -7,6 +12,45 @@ pub struct Formatting {
     pub b_notation: BNotation,
     pub semitone_notation: SemitoneNotation,
     pub format: Format,
+    #[serde(default)]
+    pub chord_notation: ChordNotation,
+    /// Display the chord shapes to play with the capo at the given fret
+    ///
+    /// The chords are transposed down by the capo's fret, while the song's key stays the sounding
+    /// key
+    #[serde(default)]
+    pub capo: Option<Capo>,
+    /// Semitone notation requested before it was resolved by `Formatting::for_key()`
+    ///
+    /// Keeps `SemitoneNotation::Auto`, so that resolving the formatting again (e.g. for another
+    /// key) yields the same result as resolving it once
+    #[serde(skip)]
+    pub requested_semitone_notation: Option<SemitoneNotation>,
+    /// Semitone notation of the chord shapes if a capo is set
+    ///
+    /// This is filled in by `Formatting::for_key()`
+    #[serde(skip)]
+    pub capo_semitone_notation: Option<SemitoneNotation>,
+    /// Root of the key used for `ChordNotation::Nashville` and `ChordNotation::Roman`
+    ///
+    /// This is filled in by `Formatting::for_key()`
+    #[serde(skip)]
+    pub key: Option<Note>,
+    /// Append a legend with the diagrams of all chords for the given instrument
+    ///
+    /// Only supported by the HTML converter
+    #[serde(default)]
+    pub chord_diagrams: Option<Instrument>,
+    /// Render references and repeats as a linear performance
+    #[serde(default)]
+    pub expansion: Expansion,
+    /// Languages to display for songs with translations
+    #[serde(default)]
+    pub language_display: LanguageDisplay,
+    /// Write the chord suffixes in their normalized spelling (e.g. `maj7` for `Δ7` and `add2` for
+    /// `2`) instead of the spelling of the source
+    #[serde(default)]
+    pub normalize_chords: bool,
 }
 
 impl Formatting {
@@ -15,8 +59,59 @@ impl Formatting {
             b_notation: BNotation::default(),
             semitone_notation: Default::default(),
             format,
+            chord_notation: Default::default(),
+            capo: None,
+            requested_semitone_notation: None,
+            capo_semitone_notation: None,
+            key: None,
+            chord_diagrams: None,
+            expansion: Expansion::None,
+            language_display: LanguageDisplay::Interleaved,
+            normalize_chords: false,
         }
     }
+
+    /// Prepare the formatting for a song in the given key
+    ///
+    /// `SemitoneNotation::Auto` is resolved to the notation conventionally used for the key. An
+    /// explicit `SemitoneNotation::Sharp` or `SemitoneNotation::Flat` is kept as override.
+    /// If a capo is set, the notation of the chord shapes' key is resolved as well.
+    /// Calling this method on an already resolved formatting resolves the requested notation again
+    pub fn for_key(self, key: Option<&Chord>) -> Self {
+        let requested = self
+            .requested_semitone_notation
+            .unwrap_or(self.semitone_notation);
+        let resolve = |key: Option<&Chord>| {
+            if requested == SemitoneNotation::Auto {
+                key.and_then(SemitoneNotation::for_key)
+                    .unwrap_or(SemitoneNotation::Sharp)
+            } else {
+                requested
+            }
+        };
+        let capo_semitone_notation = match (key, self.capo) {
+            (Some(key), Some(capo)) => Some(resolve(Some(&capo.shape_key(key)))),
+            _ => None,
+        };
+
+        Self {
+            semitone_notation: resolve(key),
+            requested_semitone_notation: Some(requested),
+            capo_semitone_notation,
+            key: key.map(Chord::root),
+            ..self
+        }
+    }
+
+    /// Return the capo to display in the song's meta information
+    ///
+    /// If a capo is set, it replaces the song's `Capo` meta, because the chord shapes are written
+    /// for it. A capo at fret 0 is not displayed
+    pub fn capo_for_meta(&self, meta: &dyn MetaTrait) -> Option<Capo> {
+        self.capo
+            .or_else(|| meta.capo_position())
+            .filter(|capo| capo.fret() > 0)
+    }
 }
 
 impl Default for Formatting {
@@ -25,6 +120,15 @@ impl Default for Formatting {
             b_notation: BNotation::default(),
             semitone_notation: SemitoneNotation::default(),
             format: Format::HTML,
+            chord_notation: ChordNotation::default(),
+            capo: None,
+            requested_semitone_notation: None,
+            capo_semitone_notation: None,
+            key: None,
+            chord_diagrams: None,
+            expansion: Expansion::None,
+            language_display: LanguageDisplay::Interleaved,
+            normalize_chords: false,
         }
     }
 }
@@ -32,3 +136,59 @@ impl Default for Formatting {
 pub trait NoteDisplay {
     fn note_format(&self, format: Formatting) -> String;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn for_key() {
+        let key = Chord::try_from("Eb", BNotation::B).unwrap();
+        let formatting = Formatting::with_format(Format::HTML);
+        assert_eq!(
+            formatting.for_key(Some(&key)).semitone_notation,
+            SemitoneNotation::Flat
+        );
+        assert_eq!(formatting.for_key(Some(&key)).key, Some(Note::Dis));
+        assert_eq!(
+            formatting.for_key(None).semitone_notation,
+            SemitoneNotation::Sharp
+        );
+
+        let formatting = Formatting {
+            semitone_notation: SemitoneNotation::Sharp,
+            ..formatting
+        };
+        assert_eq!(
+            formatting.for_key(Some(&key)).semitone_notation,
+            SemitoneNotation::Sharp
+        );
+    }
+
+    #[test]
+    fn for_key_with_capo() {
+        let key = Chord::try_from("A", BNotation::B).unwrap();
+        let formatting = Formatting {
+            capo: Some(Capo::new(1).unwrap()),
+            ..Formatting::with_format(Format::HTML)
+        };
+        // Capo 1 in `A` means `Ab` shapes, while the key is still written with sharps
+        let formatting = formatting.for_key(Some(&key));
+        assert_eq!(formatting.semitone_notation, SemitoneNotation::Sharp);
+        assert_eq!(
+            formatting.capo_semitone_notation,
+            Some(SemitoneNotation::Flat)
+        );
+        assert_eq!(formatting.key, Some(Note::A));
+
+        // Resolving again must not turn the resolved notation of the key into an override
+        let resolved_again = formatting.for_key(Some(&key));
+        assert_eq!(resolved_again, formatting);
+
+        let other_key = Chord::try_from("F", BNotation::B).unwrap();
+        assert_eq!(
+            formatting.for_key(Some(&other_key)).semitone_notation,
+            SemitoneNotation::Flat
+        );
+    }
+}
@@ -1,27 +1,43 @@
 use serde::de::{self, Visitor};
 use serde::ser::Serializer;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Ordering;
 
 use crate::error::Error;
 use crate::format::Format;
 use crate::models::chord::fmt::Formatting;
-use crate::models::meta::BNotation;
+use crate::models::meta::{BNotation, ChordNotation, LanguageDisplay};
+use crate::models::structure::Expansion;
 #[deprecated(note = "Use ::modification::transposition::TransposableTrait")]
 pub use crate::modification::transposition::TransposableTrait;
 use crate::prelude::SemitoneNotation;
 
 pub use self::chords::Chords;
+pub use self::extension::{Alteration, Extension};
 pub use self::fmt::NoteDisplay;
 pub use self::note::Note;
+pub use self::quality::Quality;
+use self::suffix::Suffix;
 
 mod chords;
+mod extension;
 pub mod fmt;
 mod note;
+mod quality;
+mod relative;
+mod suffix;
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+/// A single chord consisting of the root note, the quality, extensions, alterations and an
+/// optional bass note (e.g. `Am7b5/G`)
+#[derive(Debug, Clone)]
 pub struct Chord {
     root: Note,
-    variant: Option<String>,
+    suffix: Suffix,
+    bass: Option<Note>,
+    /// Accidental written for the root in the source (e.g. `Flat` for `Db`)
+    ///
+    /// Ignored when comparing chords (`C#` equals `Db`)
+    accidental: Option<SemitoneNotation>,
 }
 
 impl Chord {
@@ -30,10 +46,13 @@ impl Chord {
         Self::new_with_variant(root, variant.into())
     }
 
+    /// Build a new chord with the given suffix (e.g. `m7`)
     pub fn new_with_variant<S: Into<String>>(root: Note, variant: S) -> Self {
         Self {
             root,
-            variant: Some(variant.into()),
+            suffix: Suffix::parse(&variant.into()),
+            bass: None,
+            accidental: None,
         }
     }
 
@@ -45,7 +64,9 @@ impl Chord {
     pub fn new_without_variant(root: Note) -> Self {
         Self {
             root,
-            variant: None,
+            suffix: Suffix::default(),
+            bass: None,
+            accidental: None,
         }
     }
 
@@ -56,39 +77,154 @@ impl Chord {
     pub fn with_root(self, root: Note) -> Self {
         Self {
             root,
-            variant: self.variant,
+            accidental: None,
+            ..self
         }
     }
 
-    pub fn variant(&self) -> Option<&str> {
-        self.variant.as_deref()
+    /// Return the accidental written for the root in the source (e.g. `Flat` for `Db`)
+    ///
+    /// `None` is returned for natural roots and for chords that were transposed or built
+    /// programmatically
+    pub fn accidental(&self) -> Option<SemitoneNotation> {
+        self.accidental
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.suffix.quality
+    }
+
+    pub fn extensions(&self) -> &[Extension] {
+        &self.suffix.extensions
+    }
+
+    pub fn alterations(&self) -> &[Alteration] {
+        &self.suffix.alterations
+    }
+
+    /// Return the bass note of a slash chord (e.g. `E` for `C/E`)
+    pub fn bass(&self) -> Option<Note> {
+        self.bass
+    }
+
+    pub fn with_bass(self, bass: Option<Note>) -> Self {
+        Self { bass, ..self }
+    }
+
+    /// Return the part of the input after the root note that could not be interpreted
+    ///
+    /// If the suffix is not understood, quality, extensions and alterations are left empty and
+    /// the suffix is displayed verbatim
+    pub fn unknown_suffix(&self) -> Option<&str> {
+        self.suffix.unknown.as_deref()
+    }
+
+    /// Return the normalized suffix after the root note (e.g. `maj7` for `CΔ7`)
+    pub fn variant(&self) -> Option<String> {
+        if self.suffix.is_empty() {
+            None
+        } else {
+            Some(self.suffix.to_string())
+        }
+    }
+
+    /// Return the notes of the chord, starting with the root
+    ///
+    /// The bass note of a slash chord is not included. `None` is returned if the suffix is not
+    /// understood
+    pub fn notes(&self) -> Option<Vec<Note>> {
+        Some(
+            self.suffix
+                .intervals()?
+                .into_iter()
+                .map(|interval| self.root.transpose(interval as isize))
+                .collect(),
+        )
+    }
+
+    /// Format the chord relative to the root of the song's key
+    ///
+    /// E.g. `Am/E` in the key of `C` is written as `6m/3` in the Nashville Number System and as
+    /// `vi/iii` as Roman numeral. Nashville numbers are separated from suffixes starting with a
+    /// digit by `^` (e.g. `5^7` for `G7`). `ChordNotation::Letter` returns the regular chord name
+    pub fn relative_format(
+        &self,
+        key: Note,
+        notation: ChordNotation,
+        formatting: Formatting,
+    ) -> String {
+        let (mut output, bass) = match notation {
+            ChordNotation::Letter => return self.note_format(formatting),
+            ChordNotation::Nashville => (
+                relative::nashville_chord(
+                    relative::nashville_degree(self.root, key),
+                    &self.suffix.format(formatting.normalize_chords),
+                ),
+                self.bass
+                    .map(|bass| relative::nashville_degree(bass, key).to_owned()),
+            ),
+            ChordNotation::Roman => (
+                format!(
+                    "{}{}",
+                    relative::roman_degree(self.root, key, self.quality().is_minor()),
+                    self.suffix
+                        .format_with_prefix(relative::roman_quality_prefix(self.quality()))
+                ),
+                self.bass
+                    .map(|bass| relative::roman_degree(bass, key, true)),
+            ),
+        };
+        if let Some(bass) = bass {
+            output.push('/');
+            output.push_str(&bass);
+        }
+
+        output
     }
 
     pub fn try_from(value: &str, b_notation: BNotation) -> Result<Self, Error> {
-        if value.is_empty() {
+        let (chord_raw, bass_raw) = split_bass(value);
+        let bass = match bass_raw.map(str::trim) {
+            Some(bass_raw) if !bass_raw.is_empty() => Some(Note::try_from(bass_raw, b_notation)?),
+            _ => None,
+        };
+        let chord_raw = chord_raw.trim();
+        if chord_raw.is_empty() {
             return Err(Error::chord_error("Given chord brackets are empty"));
         }
 
-        if value.len() < 2 {
-            let note = Note::try_from(value, b_notation)?;
+        if chord_raw.len() < 2 {
+            let note = Note::try_from(chord_raw, b_notation)?;
 
-            return Ok(note.into());
+            return Ok(Self {
+                accidental: Self::written_accidental(chord_raw, b_notation),
+                ..Chord::from(note).with_bass(bass)
+            });
         }
 
-        let (node_raw, variant_raw) = Self::split_note_characters(value)?;
-        let variant_raw_trimmed = variant_raw.trim();
-        let variant = if variant_raw_trimmed.is_empty() {
-            None
-        } else {
-            Some(variant_raw_trimmed.to_owned())
-        };
+        let (node_raw, variant_raw) = Self::split_note_characters(chord_raw)?;
 
         Ok(Self {
             root: Note::try_from(&node_raw, b_notation)?,
-            variant,
+            suffix: Suffix::parse(&variant_raw),
+            bass,
+            accidental: Self::written_accidental(&node_raw, b_notation),
         })
     }
 
+    /// Return the accidental of the written root note (`B` is `B♭` in `BNotation::H`)
+    fn written_accidental(note_raw: &str, b_notation: BNotation) -> Option<SemitoneNotation> {
+        let mut chars = note_raw.chars();
+        let first = chars.next()?;
+        match chars.next() {
+            Some(accidental) => SemitoneNotation::try_from(accidental).ok(),
+            None if b_notation == BNotation::H && matches!(first, 'B' | 'b') => {
+                Some(SemitoneNotation::Flat)
+            }
+            None => None,
+        }
+    }
+
     fn split_note_characters(value: &str) -> Result<(String, String), Error> {
         if value.is_empty() {
             return Err(Error::chord_error("Given root is empty"));
@@ -121,13 +257,20 @@ impl Chord {
             variant_raw.iter().collect::<String>(),
         ))
     }
+}
 
-    //    fn to_string(&self, b_notation: BNotation, sn: SemitoneNotation) -> &str {
-    //        match self.variant {
-    //            Some(ref v) => format!("{}{}", NoteDisplay::to_string(&self.root, b_notation, sn), v).as_str(),
-    //            None => format!("{}", NoteDisplay::to_string(&self.root, b_notation, sn)).as_str(),
-    //        }
-    //    }
+/// Split the input at the first slash that does not belong to the suffix (e.g. `6/9`)
+pub(crate) fn split_bass(value: &str) -> (&str, Option<&str>) {
+    for (index, character) in value.char_indices() {
+        if character == '/' {
+            let rest = &value[index + 1..];
+            if !rest.trim_start().starts_with(|c: char| c.is_ascii_digit()) {
+                return (&value[..index], Some(rest));
+            }
+        }
+    }
+
+    (value, None)
 }
 
 impl From<Note> for Chord {
@@ -140,17 +283,39 @@ impl TransposableTrait for Chord {
     fn transpose(self, semitones: isize) -> Self {
         Self {
             root: self.root.transpose(semitones),
-            variant: self.variant,
+            suffix: self.suffix,
+            bass: self.bass.map(|bass| bass.transpose(semitones)),
+            accidental: None,
         }
     }
 }
 
+impl PartialEq for Chord {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root && self.suffix == other.suffix && self.bass == other.bass
+    }
+}
+
+impl PartialOrd for Chord {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (&self.root, &self.suffix, &self.bass).partial_cmp(&(
+            &other.root,
+            &other.suffix,
+            &other.bass,
+        ))
+    }
+}
+
 impl NoteDisplay for Chord {
     fn note_format(&self, formatting: Formatting) -> String {
-        match self.variant {
-            Some(ref v) => format!("{}{}", NoteDisplay::note_format(&self.root, formatting), v),
-            None => NoteDisplay::note_format(&self.root, formatting),
+        let mut output = NoteDisplay::note_format(&self.root, formatting);
+        output.push_str(&self.suffix.format(formatting.normalize_chords));
+        if let Some(bass) = self.bass {
+            output.push('/');
+            output.push_str(&NoteDisplay::note_format(&bass, formatting));
         }
+
+        output
     }
 }
 
@@ -163,6 +328,15 @@ fn get_serialization_formatting() -> Formatting {
         b_notation: get_serialization_b_notation(),
         semitone_notation: SemitoneNotation::Sharp,
         format: Format::HTML,
+        chord_notation: ChordNotation::Letter,
+        capo: None,
+        requested_semitone_notation: None,
+        capo_semitone_notation: None,
+        key: None,
+        chord_diagrams: None,
+        expansion: Expansion::None,
+        language_display: LanguageDisplay::Interleaved,
+        normalize_chords: false,
     }
 }
 
@@ -171,7 +345,12 @@ impl Serialize for Chord {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.note_format(get_serialization_formatting()))
+        // Keep the written accidental, so that e.g. the key `Db` is not read back as `C#`
+        let formatting = Formatting {
+            semitone_notation: self.accidental.unwrap_or(SemitoneNotation::Sharp),
+            ..get_serialization_formatting()
+        };
+        serializer.serialize_str(&self.note_format(formatting))
     }
 }
 
@@ -243,7 +422,7 @@ mod tests {
         for case in without_variant_cases() {
             let chord = Chord::try_from(case.0, case.1).unwrap();
             assert_eq!(chord.root, case.2);
-            assert_eq!(chord.variant, None);
+            assert_eq!(chord.variant(), None);
         }
         assert!(Chord::try_from("H#", BNotation::H).is_err());
         assert!(Chord::try_from("Fb", BNotation::H).is_err());
@@ -254,7 +433,7 @@ mod tests {
         for case in with_variant_cases() {
             let chord = Chord::try_from(case.0, case.1).unwrap();
             assert_eq!(chord.root, case.2);
-            assert_eq!(chord.variant, Some(case.3.to_string()));
+            assert_eq!(chord.variant(), Some(case.3.to_string()));
         }
         assert!(Chord::try_from("H#", BNotation::H).is_err());
         assert!(Chord::try_from("Fb", BNotation::H).is_err());
@@ -266,16 +445,16 @@ mod tests {
             ("A", "A"),
             ("A#", "A#"),
             ("C#", "C#"),
-            ("Bb", "A#"),
-            ("bb", "A#"),
+            ("Bb", "Bb"),
+            ("bb", "Bb"),
             ("B", "B"),
             ("F", "F"),
             ("F#", "F#"),
             ("Am", "Am"),
             ("A♯dim", "A#dim"),
             ("C#madd2add4", "C#madd2add4"),
-            ("Bbmaj7", "A#maj7"),
-            ("bbm", "A#m"),
+            ("Bbmaj7", "Bbmaj7"),
+            ("bbm", "Bbm"),
             ("B7", "B7"),
             ("B9", "B9"),
             ("Bm#5", "Bm#5"),
@@ -308,7 +487,7 @@ mod tests {
             let chord: Chord =
                 serde_json::from_str(&format!("\"{}\"", case.0)).expect(&failure_msg);
             assert_eq!(chord.root, case.1, "{}", failure_msg);
-            assert_eq!(chord.variant, None, "{}", failure_msg);
+            assert_eq!(chord.variant(), None, "{}", failure_msg);
         }
     }
 
@@ -331,10 +510,36 @@ mod tests {
             let chord: Chord =
                 serde_json::from_str(&format!("\"{}\"", case.0)).expect(&failure_msg);
             assert_eq!(chord.root, case.1, "{}", failure_msg);
-            assert_eq!(chord.variant, Some(case.2.to_string()), "{}", failure_msg);
+            assert_eq!(chord.variant(), Some(case.2.to_string()), "{}", failure_msg);
         }
     }
 
+    #[test]
+    fn notes() {
+        let notes = |input| Chord::try_from(input, BNotation::B).unwrap().notes();
+        assert_eq!(notes("C"), Some(vec![Note::C, Note::E, Note::G]));
+        assert_eq!(notes("Am7"), Some(vec![Note::A, Note::C, Note::E, Note::G]));
+        assert_eq!(
+            notes("Bdim7"),
+            Some(vec![Note::B, Note::D, Note::F, Note::Gis])
+        );
+        assert_eq!(
+            notes("Bm7b5"),
+            Some(vec![Note::B, Note::D, Note::F, Note::A])
+        );
+        assert_eq!(notes("Dsus4"), Some(vec![Note::D, Note::G, Note::A]));
+        assert_eq!(notes("G/B"), Some(vec![Note::G, Note::B, Note::D]));
+        assert_eq!(
+            notes("Cmaj9"),
+            Some(vec![Note::C, Note::E, Note::G, Note::B, Note::D])
+        );
+        assert_eq!(
+            notes("E7#9"),
+            Some(vec![Note::E, Note::Gis, Note::B, Note::D, Note::G])
+        );
+        assert_eq!(notes("C9Daniel"), None);
+    }
+
     #[test]
     fn transpose_test() {
         let map = vec![
@@ -406,9 +611,11 @@ mod tests {
                 input, semitones
             );
             assert_eq!(
-                transposed.variant, None,
+                transposed.variant(),
+                None,
                 "Transpose changed the variant for {:?} {}",
-                input, semitones
+                input,
+                semitones
             );
         }
         for (semitones, input, expected_root) in &map {
@@ -421,7 +628,7 @@ mod tests {
                 input, semitones
             );
             assert_eq!(
-                transposed.variant,
+                transposed.variant(),
                 Some("dim".to_owned()),
                 "Transpose changed the variant for {:?} {}",
                 input,
@@ -137,7 +137,7 @@ impl Debug for Note {
 
 impl NoteDisplay for Note {
     fn note_format(&self, formatting: Formatting) -> String {
-        let sharp = formatting.semitone_notation == SemitoneNotation::Sharp;
+        let sharp = formatting.semitone_notation != SemitoneNotation::Flat;
         match self {
             Self::C => "C",
             Self::Cis if sharp => "C#",
@@ -380,21 +380,25 @@ mod tests {
             b_notation: BNotation::B,
             semitone_notation: SemitoneNotation::Sharp,
             format: Format::HTML,
+            ..Formatting::default()
         };
         let flat_b = Formatting {
             b_notation: BNotation::B,
             semitone_notation: SemitoneNotation::Flat,
             format: Format::HTML,
+            ..Formatting::default()
         };
         let sharp_europe = Formatting {
             b_notation: BNotation::H,
             semitone_notation: SemitoneNotation::Sharp,
             format: Format::HTML,
+            ..Formatting::default()
         };
         let flat_europe = Formatting {
             b_notation: BNotation::H,
             semitone_notation: SemitoneNotation::Flat,
             format: Format::HTML,
+            ..Formatting::default()
         };
         assert_eq!(Note::C.note_format(sharp_b), "C".to_owned());
         assert_eq!(Note::C.note_format(flat_b), "C".to_owned());
@@ -0,0 +1,56 @@
+use std::fmt::{Display, Formatter};
+
+/// The basic type of a chord's triad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Quality {
+    #[default]
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Suspended2,
+    Suspended4,
+    /// Root and fifth only (e.g. `C5`)
+    Power,
+}
+
+impl Quality {
+    /// Return if the chord contains a minor third
+    pub fn is_minor(&self) -> bool {
+        matches!(self, Quality::Minor | Quality::Diminished)
+    }
+
+    /// Return the prefix written directly after the root note (e.g. `m` for `Am7`)
+    pub(super) fn prefix(&self) -> &'static str {
+        match self {
+            Quality::Major | Quality::Suspended2 | Quality::Suspended4 => "",
+            Quality::Minor => "m",
+            Quality::Diminished => "dim",
+            Quality::Augmented => "aug",
+            Quality::Power => "5",
+        }
+    }
+
+    /// Return the suspension written after the extensions (e.g. `sus4` for `D7sus4`)
+    pub(super) fn suspension(&self) -> &'static str {
+        match self {
+            Quality::Suspended2 => "sus2",
+            Quality::Suspended4 => "sus4",
+            _ => "",
+        }
+    }
+}
+
+impl Display for Quality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Quality::Major => "major",
+            Quality::Minor => "minor",
+            Quality::Diminished => "diminished",
+            Quality::Augmented => "augmented",
+            Quality::Suspended2 => "suspended 2",
+            Quality::Suspended4 => "suspended 4",
+            Quality::Power => "power",
+        })
+    }
+}
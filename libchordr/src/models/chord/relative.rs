@@ -0,0 +1,80 @@
+use super::note::Note;
+use super::quality::Quality;
+
+const NASHVILLE_DEGREES: [&str; 12] = [
+    "1", "b2", "2", "b3", "3", "4", "#4", "5", "b6", "6", "b7", "7",
+];
+const ROMAN_DEGREES: [&str; 12] = [
+    "I", "bII", "II", "bIII", "III", "IV", "#IV", "V", "bVI", "VI", "bVII", "VII",
+];
+
+/// Return the number of semitones from `key` up to `note`
+fn interval(note: Note, key: Note) -> usize {
+    (i32::from(note) - i32::from(key)).rem_euclid(12) as usize
+}
+
+/// Return the scale degree of `note` in the Nashville Number System (e.g. `b7`)
+pub(super) fn nashville_degree(note: Note, key: Note) -> &'static str {
+    NASHVILLE_DEGREES[interval(note, key)]
+}
+
+/// Join the Nashville degree and the chord's suffix
+///
+/// Suffixes starting with a digit are separated by `^` (the extension is written superscript), so
+/// that they can not be mistaken for the degree (e.g. `5^7` instead of `57`)
+pub(super) fn nashville_chord(degree: &str, suffix: &str) -> String {
+    if suffix.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("{}^{}", degree, suffix)
+    } else {
+        format!("{}{}", degree, suffix)
+    }
+}
+
+/// Return the scale degree of `note` as Roman numeral
+///
+/// Minor and diminished chords are written in lowercase (e.g. `vi` or `vii`)
+pub(super) fn roman_degree(note: Note, key: Note, lowercase: bool) -> String {
+    let degree = ROMAN_DEGREES[interval(note, key)];
+    if lowercase {
+        degree.to_lowercase()
+    } else {
+        degree.to_owned()
+    }
+}
+
+/// Return the quality prefix used with Roman numerals
+///
+/// The case of the numeral already denotes minor chords, so only the other qualities are marked
+pub(super) fn roman_quality_prefix(quality: Quality) -> &'static str {
+    match quality {
+        Quality::Diminished => "°",
+        Quality::Augmented => "+",
+        Quality::Minor => "",
+        _ => quality.prefix(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn degrees() {
+        assert_eq!(nashville_degree(Note::C, Note::C), "1");
+        assert_eq!(nashville_degree(Note::F, Note::C), "4");
+        assert_eq!(nashville_degree(Note::B, Note::C), "7");
+        assert_eq!(nashville_degree(Note::D, Note::E), "b7");
+        assert_eq!(nashville_degree(Note::Cis, Note::E), "6");
+        assert_eq!(roman_degree(Note::G, Note::C, false), "V");
+        assert_eq!(roman_degree(Note::A, Note::C, true), "vi");
+        assert_eq!(roman_degree(Note::Ais, Note::C, false), "bVII");
+    }
+
+    #[test]
+    fn nashville_chords() {
+        assert_eq!(nashville_chord("5", "7"), "5^7");
+        assert_eq!(nashville_chord("b7", "6/9"), "b7^6/9");
+        assert_eq!(nashville_chord("6", "m7"), "6m7");
+        assert_eq!(nashville_chord("1", ""), "1");
+    }
+}
@@ -0,0 +1,425 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use super::extension::{Alteration, Extension};
+use super::quality::Quality;
+
+/// Everything written after the root note of a chord (e.g. `m7b5` for `Bm7b5`)
+///
+/// Suffixes that can not be interpreted are kept verbatim in `unknown`. The spelling of the
+/// source is kept in `written`, but is ignored when comparing suffixes (`CΔ7` equals `Cmaj7`)
+#[derive(Debug, Clone, Default)]
+pub(super) struct Suffix {
+    pub(super) quality: Quality,
+    pub(super) extensions: Vec<Extension>,
+    pub(super) alterations: Vec<Alteration>,
+    pub(super) unknown: Option<String>,
+    pub(super) written: String,
+}
+
+impl Suffix {
+    pub(super) fn parse(input: &str) -> Self {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return Self::default();
+        }
+
+        let suffix = parse_structured(trimmed).unwrap_or_else(|| Self {
+            unknown: Some(trimmed.to_owned()),
+            ..Self::default()
+        });
+
+        Self {
+            written: trimmed.to_owned(),
+            ..suffix
+        }
+    }
+
+    /// Format the suffix in the normalized spelling or as written in the source
+    pub(super) fn format(&self, normalize: bool) -> String {
+        if normalize {
+            self.to_string()
+        } else {
+            self.written.clone()
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Return the chord tones as semitones above the root (e.g. `[0, 3, 7, 10]` for `m7`)
+    ///
+    /// `None` is returned for unknown suffixes
+    pub(super) fn intervals(&self) -> Option<Vec<u8>> {
+        if self.unknown.is_some() {
+            return None;
+        }
+
+        let mut intervals: Vec<u8> = match self.quality {
+            Quality::Major => vec![0, 4, 7],
+            Quality::Minor => vec![0, 3, 7],
+            Quality::Diminished => vec![0, 3, 6],
+            Quality::Augmented => vec![0, 4, 8],
+            Quality::Suspended2 => vec![0, 2, 7],
+            Quality::Suspended4 => vec![0, 5, 7],
+            Quality::Power => vec![0, 7],
+        };
+        let seventh = if self.quality == Quality::Diminished {
+            9
+        } else {
+            10
+        };
+        for extension in &self.extensions {
+            let added: &[u8] = match extension {
+                Extension::Sixth => &[9],
+                Extension::SixNine => &[9, 2],
+                Extension::Seventh => &[seventh],
+                Extension::Ninth => &[seventh, 2],
+                Extension::Eleventh => &[seventh, 2, 5],
+                Extension::Thirteenth => &[seventh, 2, 9],
+                Extension::MajorSeventh => &[11],
+                Extension::MajorNinth => &[11, 2],
+                Extension::MajorEleventh => &[11, 2, 5],
+                Extension::MajorThirteenth => &[11, 2, 9],
+                Extension::Add(2 | 9) => &[2],
+                Extension::Add(4 | 11) => &[5],
+                Extension::Add(_) => &[9],
+            };
+            intervals.extend_from_slice(added);
+        }
+        for alteration in &self.alterations {
+            let (replaced, interval) = match alteration {
+                Alteration::Flat(5) => (7, 6),
+                Alteration::Sharp(5) => (7, 8),
+                Alteration::Flat(9) => (2, 1),
+                Alteration::Sharp(9) => (2, 3),
+                Alteration::Flat(11) => (5, 4),
+                Alteration::Sharp(11) => (5, 6),
+                Alteration::Flat(13) => (9, 8),
+                Alteration::Sharp(13) => (9, 10),
+                _ => continue,
+            };
+            match intervals.iter().position(|i| *i == replaced) {
+                Some(position) => intervals[position] = interval,
+                None => intervals.push(interval),
+            }
+        }
+
+        let mut unique = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            if !unique.contains(&interval) {
+                unique.push(interval);
+            }
+        }
+
+        Some(unique)
+    }
+}
+
+impl Suffix {
+    /// Format the suffix with the given quality prefix instead of the default one (e.g. `m`)
+    pub(super) fn format_with_prefix(&self, prefix: &str) -> String {
+        if let Some(unknown) = &self.unknown {
+            return unknown.clone();
+        }
+
+        let mut output = prefix.to_owned();
+        for extension in self.extensions.iter().filter(|e| !is_add(e)) {
+            output.push_str(&extension.to_string());
+        }
+        output.push_str(self.quality.suspension());
+        for extension in self.extensions.iter().filter(|e| is_add(e)) {
+            output.push_str(&extension.to_string());
+        }
+        for alteration in &self.alterations {
+            output.push_str(&alteration.to_string());
+        }
+
+        output
+    }
+}
+
+impl PartialEq for Suffix {
+    fn eq(&self, other: &Self) -> bool {
+        self.quality == other.quality
+            && self.extensions == other.extensions
+            && self.alterations == other.alterations
+            && self.unknown == other.unknown
+    }
+}
+
+impl PartialOrd for Suffix {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (
+            &self.quality,
+            &self.extensions,
+            &self.alterations,
+            &self.unknown,
+        )
+            .partial_cmp(&(
+                &other.quality,
+                &other.extensions,
+                &other.alterations,
+                &other.unknown,
+            ))
+    }
+}
+
+impl Display for Suffix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.format_with_prefix(self.quality.prefix()))
+    }
+}
+
+fn is_add(extension: &Extension) -> bool {
+    matches!(extension, Extension::Add(_))
+}
+
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn eat_any(&mut self, prefixes: &[&str]) -> bool {
+        prefixes.iter().any(|prefix| self.eat(prefix))
+    }
+
+    fn number(&mut self) -> Option<u8> {
+        let length = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        if length == 0 {
+            return None;
+        }
+        let (digits, rest) = self.rest.split_at(length);
+        self.rest = rest;
+
+        digits.parse().ok()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+}
+
+fn parse_structured(input: &str) -> Option<Suffix> {
+    let cleaned: String = input
+        .chars()
+        .filter(|c| !matches!(c, '(' | ')' | ',' | ' '))
+        .collect();
+    let mut cursor = Cursor { rest: &cleaned };
+    let mut quality = Quality::Major;
+    let mut extensions = vec![];
+    let mut alterations = vec![];
+    // `Δ` alone denotes a major seventh chord, `maj` alone a major triad
+    let mut implied_major_seventh = false;
+    let mut major_marker = false;
+    let mut half_diminished = false;
+
+    if cursor.eat("Δ") {
+        implied_major_seventh = true;
+        major_marker = true;
+    } else if cursor.eat_any(&["maj", "Maj", "MAJ", "M"]) {
+        major_marker = true;
+    } else if cursor.eat_any(&["min", "mi", "m", "-"]) {
+        quality = Quality::Minor;
+        if cursor.eat("Δ") {
+            implied_major_seventh = true;
+            major_marker = true;
+        } else if cursor.eat_any(&["maj", "Maj", "M"]) {
+            major_marker = true;
+        }
+    } else if cursor.eat_any(&["dim", "°", "o"]) {
+        quality = Quality::Diminished;
+    } else if cursor.eat_any(&["aug", "+"]) {
+        quality = Quality::Augmented;
+    } else if cursor.eat("ø") {
+        quality = Quality::Minor;
+        half_diminished = true;
+    }
+
+    match cursor.number() {
+        Some(number) if major_marker => extensions.push(match number {
+            7 => Extension::MajorSeventh,
+            9 => Extension::MajorNinth,
+            11 => Extension::MajorEleventh,
+            13 => Extension::MajorThirteenth,
+            _ => return None,
+        }),
+        Some(5) if quality == Quality::Major && cursor.is_empty() => quality = Quality::Power,
+        Some(6) if cursor.eat("/9") => extensions.push(Extension::SixNine),
+        Some(number) => extensions.push(match number {
+            6 => Extension::Sixth,
+            69 => Extension::SixNine,
+            7 => Extension::Seventh,
+            9 => Extension::Ninth,
+            11 => Extension::Eleventh,
+            13 => Extension::Thirteenth,
+            2 | 4 => Extension::Add(number),
+            _ => return None,
+        }),
+        None if implied_major_seventh => extensions.push(Extension::MajorSeventh),
+        None => {}
+    }
+
+    if half_diminished {
+        if extensions.is_empty() {
+            extensions.push(Extension::Seventh);
+        }
+        alterations.push(Alteration::Flat(5));
+    }
+
+    while !cursor.is_empty() {
+        if cursor.eat("sus") {
+            quality = match (quality, cursor.number().unwrap_or(4)) {
+                (Quality::Major, 2) => Quality::Suspended2,
+                (Quality::Major, 4) => Quality::Suspended4,
+                _ => return None,
+            };
+        } else if cursor.eat("add") {
+            match cursor.number() {
+                Some(degree @ (2 | 4 | 6 | 9 | 11 | 13)) => extensions.push(Extension::Add(degree)),
+                _ => return None,
+            }
+        } else if cursor.eat_any(&["b", "♭", "-"]) {
+            alterations.push(Alteration::Flat(alteration_degree(&mut cursor)?));
+        } else if cursor.eat_any(&["#", "♯", "+"]) {
+            alterations.push(Alteration::Sharp(alteration_degree(&mut cursor)?));
+        } else {
+            return None;
+        }
+    }
+
+    Some(Suffix {
+        quality,
+        extensions,
+        alterations,
+        unknown: None,
+        written: String::new(),
+    })
+}
+
+fn alteration_degree(cursor: &mut Cursor<'_>) -> Option<u8> {
+    match cursor.number() {
+        Some(degree @ (5 | 9 | 11 | 13)) => Some(degree),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let suffix = Suffix::parse("m7b5");
+        assert_eq!(suffix.quality, Quality::Minor);
+        assert_eq!(suffix.extensions, vec![Extension::Seventh]);
+        assert_eq!(suffix.alterations, vec![Alteration::Flat(5)]);
+        assert_eq!(suffix.unknown, None);
+
+        let suffix = Suffix::parse("7sus4");
+        assert_eq!(suffix.quality, Quality::Suspended4);
+        assert_eq!(suffix.extensions, vec![Extension::Seventh]);
+
+        let suffix = Suffix::parse("madd2add4");
+        assert_eq!(suffix.quality, Quality::Minor);
+        assert_eq!(
+            suffix.extensions,
+            vec![Extension::Add(2), Extension::Add(4)]
+        );
+
+        let suffix = Suffix::parse("maj13#11");
+        assert_eq!(suffix.quality, Quality::Major);
+        assert_eq!(suffix.extensions, vec![Extension::MajorThirteenth]);
+        assert_eq!(suffix.alterations, vec![Alteration::Sharp(11)]);
+
+        let suffix = Suffix::parse("m(maj7)");
+        assert_eq!(suffix.quality, Quality::Minor);
+        assert_eq!(suffix.extensions, vec![Extension::MajorSeventh]);
+
+        assert_eq!(Suffix::parse("5").quality, Quality::Power);
+        assert_eq!(Suffix::parse("dim7").quality, Quality::Diminished);
+        assert_eq!(Suffix::parse("+").quality, Quality::Augmented);
+        assert!(Suffix::parse("").is_empty());
+        assert!(Suffix::parse("maj").is_empty());
+    }
+
+    #[test]
+    fn parse_unknown() {
+        for input in ["9Daniel", "-E", "sus-E", "m#3", "7add8", "msus4"] {
+            let suffix = Suffix::parse(input);
+            assert_eq!(suffix.unknown, Some(input.to_owned()), "{}", input);
+            assert_eq!(suffix.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn display_normalizes_spelling() {
+        let test_cases = [
+            ("m", "m"),
+            ("min", "m"),
+            ("-", "m"),
+            ("-7", "m7"),
+            ("mi7", "m7"),
+            ("maj7", "maj7"),
+            ("M7", "maj7"),
+            ("Δ", "maj7"),
+            ("Δ7", "maj7"),
+            ("Maj9", "maj9"),
+            ("mM7", "mmaj7"),
+            ("m(maj7)", "mmaj7"),
+            ("mΔ7", "mmaj7"),
+            ("°", "dim"),
+            ("°7", "dim7"),
+            ("o7", "dim7"),
+            ("ø", "m7b5"),
+            ("ø7", "m7b5"),
+            ("m7♭5", "m7b5"),
+            ("+", "aug"),
+            ("+7", "aug7"),
+            ("7+5", "7#5"),
+            ("7(b9)", "7b9"),
+            ("7-9", "7b9"),
+            ("sus", "sus4"),
+            ("sus2", "sus2"),
+            ("7sus4", "7sus4"),
+            ("sus4add9", "sus4add9"),
+            ("add9", "add9"),
+            ("2", "add2"),
+            ("6", "6"),
+            ("69", "6/9"),
+            ("6/9", "6/9"),
+            ("m6", "m6"),
+            ("5", "5"),
+            ("13", "13"),
+            ("maj13#11", "maj13#11"),
+            ("m#5", "m#5"),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(Suffix::parse(input).to_string(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn format_keeps_written_spelling() {
+        for input in ["2", "Δ7", "-7", "m(maj7)", "sus", "9Daniel"] {
+            let suffix = Suffix::parse(input);
+            assert_eq!(suffix.format(false), input);
+            assert_eq!(suffix.format(true), suffix.to_string());
+        }
+        assert_eq!(Suffix::parse("Δ7"), Suffix::parse("maj7"));
+    }
+}
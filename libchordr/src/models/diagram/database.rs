@@ -0,0 +1,155 @@
+use super::fingering::Fingering;
+use super::instrument::Instrument;
+use crate::models::chord::Chord;
+use crate::models::meta::BNotation;
+
+/// Common open and first position guitar chords in standard tuning
+const GUITAR: &[(&str, &str)] = &[
+    ("C", "x32010"),
+    ("Cm", "x35543"),
+    ("C7", "x32310"),
+    ("Cmaj7", "x32000"),
+    ("Cadd9", "x32030"),
+    ("Csus2", "x30013"),
+    ("Csus4", "x33011"),
+    ("D", "xx0232"),
+    ("Dm", "xx0231"),
+    ("D7", "xx0212"),
+    ("Dm7", "xx0211"),
+    ("Dmaj7", "xx0222"),
+    ("Dsus2", "xx0230"),
+    ("Dsus4", "xx0233"),
+    ("D/F#", "2x0232"),
+    ("E", "022100"),
+    ("Em", "022000"),
+    ("E7", "020100"),
+    ("Em7", "022030"),
+    ("Emaj7", "021100"),
+    ("Esus4", "022200"),
+    ("F", "133211"),
+    ("Fm", "133111"),
+    ("F7", "131211"),
+    ("Fmaj7", "xx3210"),
+    ("F#m", "244222"),
+    ("G", "320003"),
+    ("Gm", "355333"),
+    ("G7", "320001"),
+    ("Gmaj7", "320002"),
+    ("Gsus4", "330013"),
+    ("G/B", "x20003"),
+    ("A", "x02220"),
+    ("Am", "x02210"),
+    ("A7", "x02020"),
+    ("Am7", "x02010"),
+    ("Amaj7", "x02120"),
+    ("Asus2", "x02200"),
+    ("Asus4", "x02230"),
+    ("Bb", "x13331"),
+    ("B", "x24442"),
+    ("Bm", "x24432"),
+    ("B7", "x21202"),
+    ("Bm7", "x20202"),
+];
+
+/// Common chords on a ukulele in standard (`G C E A`) tuning
+const UKULELE: &[(&str, &str)] = &[
+    ("C", "0003"),
+    ("Cm", "0333"),
+    ("C7", "0001"),
+    ("Cmaj7", "0002"),
+    ("D", "2220"),
+    ("Dm", "2210"),
+    ("D7", "2223"),
+    ("Dm7", "2213"),
+    ("E", "1402"),
+    ("Em", "0432"),
+    ("E7", "1202"),
+    ("F", "2010"),
+    ("Fm", "1013"),
+    ("F7", "2313"),
+    ("G", "0232"),
+    ("Gm", "0231"),
+    ("G7", "0212"),
+    ("A", "2100"),
+    ("Am", "2000"),
+    ("A7", "0100"),
+    ("Am7", "0000"),
+    ("Bb", "3211"),
+    ("B", "4322"),
+    ("Bm", "4222"),
+    ("B7", "2322"),
+];
+
+/// Look up the fingering of the chord in the built-in voicing database
+pub(super) fn lookup(chord: &Chord, instrument: Instrument) -> Option<Fingering> {
+    let table = match instrument {
+        Instrument::Guitar => GUITAR,
+        Instrument::Ukulele => UKULELE,
+        _ => return None,
+    };
+
+    table
+        .iter()
+        .find(|(name, _)| match Chord::try_from(name, BNotation::B) {
+            Ok(entry) => {
+                entry.root() == chord.root()
+                    && entry.variant() == chord.variant()
+                    && entry.bass() == chord.bass()
+            }
+            Err(_) => false,
+        })
+        .and_then(|(_, frets)| Fingering::from_compact(frets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::chord::{Note, TransposableTrait};
+
+    /// Make sure every entry sounds the chord it is listed for
+    fn assert_table_is_valid(table: &[(&str, &str)], instrument: Instrument) {
+        let tuning = instrument.tuning().unwrap();
+        for (name, frets) in table {
+            let chord = Chord::try_from(name, BNotation::B).unwrap();
+            let fingering = Fingering::from_compact(frets).unwrap();
+            assert_eq!(fingering.frets().len(), tuning.len(), "{}", name);
+
+            let sounding: Vec<Note> = fingering.notes(tuning).into_iter().flatten().collect();
+            let mut expected = chord.notes().unwrap();
+            expected.extend(chord.bass());
+            for note in &sounding {
+                assert!(expected.contains(note), "{} contains {:?}", name, note);
+            }
+            // The fifth may be omitted from chords with four or more notes
+            let fifth = chord.root().transpose(7);
+            for note in &expected {
+                if *note == fifth && expected.len() >= 4 {
+                    continue;
+                }
+                assert!(sounding.contains(note), "{} misses {:?}", name, note);
+            }
+        }
+    }
+
+    #[test]
+    fn tables_are_valid() {
+        assert_table_is_valid(GUITAR, Instrument::Guitar);
+        assert_table_is_valid(UKULELE, Instrument::Ukulele);
+    }
+
+    #[test]
+    fn lookup_normalized_chord() {
+        let chord = Chord::try_from("Amin7", BNotation::B).unwrap();
+        assert_eq!(
+            lookup(&chord, Instrument::Guitar),
+            Fingering::from_compact("x02010")
+        );
+        let chord = Chord::try_from("A#", BNotation::B).unwrap();
+        assert_eq!(
+            lookup(&chord, Instrument::Ukulele),
+            Fingering::from_compact("3211")
+        );
+        let chord = Chord::try_from("C#", BNotation::B).unwrap();
+        assert_eq!(lookup(&chord, Instrument::Guitar), None);
+    }
+}
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+use super::fingering::{parse_fret, Fingering};
+use crate::models::chord::Chord;
+use crate::models::meta::BNotation;
+
+/// User defined fingering of a chord
+///
+/// Definitions use the syntax of ChordPro's `define` directive:
+///
+/// ```text
+/// {define: Am base-fret 1 frets x 0 2 2 1 0 fingers 0 0 2 3 1 0}
+/// ```
+///
+/// The frets are relative to the `base-fret` (`1` is the topmost fret of the diagram). `x` marks
+/// a muted string, `0` an open string. The short form `{define: Am 1 x 0 2 2 1 0}` is supported
+/// too
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChordDefinition {
+    name: String,
+    fingering: Fingering,
+}
+
+impl ChordDefinition {
+    pub fn new<S: Into<String>>(name: S, fingering: Fingering) -> Self {
+        Self {
+            name: name.into(),
+            fingering,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn fingering(&self) -> &Fingering {
+        &self.fingering
+    }
+
+    /// Return if the definition describes the given chord
+    ///
+    /// Chords are compared by their meaning, so a definition for `CΔ7` applies to `Cmaj7`
+    pub fn matches(&self, chord: &Chord, b_notation: BNotation) -> bool {
+        match Chord::try_from(&self.name, b_notation) {
+            Ok(defined) => {
+                defined.root() == chord.root()
+                    && defined.variant() == chord.variant()
+                    && defined.bass() == chord.bass()
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl FromStr for ChordDefinition {
+    type Err = ChordDefinitionError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || ChordDefinitionError(value.to_string());
+        let mut words = value.split_whitespace();
+        let name = words.next().ok_or_else(error)?;
+        let words: Vec<&str> = words.collect();
+
+        let mut base_fret = 1;
+        let mut frets = vec![];
+        let mut fingers = vec![];
+        if words.first().is_some_and(|w| w.parse::<u8>().is_ok()) {
+            // Short form: the base fret followed by the frets
+            base_fret = words[0].parse().map_err(|_| error())?;
+            frets = words[1..].to_vec();
+        } else {
+            let mut target = None;
+            for word in words {
+                match word.to_lowercase().as_str() {
+                    "base-fret" | "base_fret" => target = Some(0),
+                    "frets" => target = Some(1),
+                    "fingers" => target = Some(2),
+                    _ => match target {
+                        Some(0) => base_fret = word.parse().map_err(|_| error())?,
+                        Some(1) => frets.push(word),
+                        Some(2) => fingers.push(word),
+                        _ => return Err(error()),
+                    },
+                }
+            }
+        }
+        if frets.is_empty() || base_fret == 0 {
+            return Err(error());
+        }
+
+        let frets = frets
+            .into_iter()
+            .map(|fret| {
+                parse_fret(fret).map(|fret| match fret {
+                    Some(relative) if relative > 0 => Some(base_fret + relative - 1),
+                    fret => fret,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(error)?;
+        let fingers = fingers
+            .into_iter()
+            .map(|finger| match finger.parse::<u8>() {
+                Ok(0) => Some(None),
+                Ok(finger) => Some(Some(finger)),
+                Err(_) => parse_fret(finger),
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(error)?;
+        if !fingers.is_empty() && fingers.len() != frets.len() {
+            return Err(error());
+        }
+
+        Ok(Self::new(name, Fingering::new(frets).with_fingers(fingers)))
+    }
+}
+
+impl Display for ChordDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let base_fret = self.fingering.base_fret();
+        write!(f, "{} base-fret {} frets", self.name, base_fret)?;
+        for fret in self.fingering.frets() {
+            match fret {
+                None => f.write_str(" x")?,
+                Some(0) => f.write_str(" 0")?,
+                Some(fret) => write!(f, " {}", fret + 1 - base_fret)?,
+            }
+        }
+        if !self.fingering.fingers().is_empty() {
+            f.write_str(" fingers")?;
+            for finger in self.fingering.fingers() {
+                write!(f, " {}", finger.unwrap_or(0))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ChordDefinitionError(String);
+
+impl std::error::Error for ChordDefinitionError {}
+
+impl Display for ChordDefinitionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Invalid chord definition '{}'", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        let definition =
+            ChordDefinition::from_str("Am base-fret 1 frets x 0 2 2 1 0 fingers 0 0 2 3 1 0")
+                .unwrap();
+        assert_eq!(definition.name(), "Am");
+        assert_eq!(
+            definition.fingering().frets(),
+            &[None, Some(0), Some(2), Some(2), Some(1), Some(0)]
+        );
+        assert_eq!(
+            definition.fingering().fingers(),
+            &[None, None, Some(2), Some(3), Some(1), None]
+        );
+
+        let definition = ChordDefinition::from_str("Bb base-fret 6 frets 1 1 3 3 3 1").unwrap();
+        assert_eq!(
+            definition.fingering().frets(),
+            &[Some(6), Some(6), Some(8), Some(8), Some(8), Some(6)]
+        );
+
+        let definition = ChordDefinition::from_str("D/F# 1 2 x 0 2 3 2").unwrap();
+        assert_eq!(definition.fingering().frets()[0], Some(2));
+
+        assert!(ChordDefinition::from_str("Am").is_err());
+        assert!(ChordDefinition::from_str("Am frets x 0 y").is_err());
+        assert!(ChordDefinition::from_str("Am frets x 0 2 fingers 1").is_err());
+    }
+
+    #[test]
+    fn display() {
+        let input = "Bb base-fret 6 frets 1 1 3 3 3 1";
+        assert_eq!(ChordDefinition::from_str(input).unwrap().to_string(), input);
+        let input = "Am base-fret 1 frets x 0 2 2 1 0 fingers 0 0 2 3 1 0";
+        assert_eq!(ChordDefinition::from_str(input).unwrap().to_string(), input);
+    }
+
+    #[test]
+    fn matches() {
+        let definition = ChordDefinition::from_str("CΔ7 1 x 3 2 0 0 0").unwrap();
+        let chord = Chord::try_from("Cmaj7", BNotation::B).unwrap();
+        assert!(definition.matches(&chord, BNotation::B));
+        let chord = Chord::try_from("C7", BNotation::B).unwrap();
+        assert!(!definition.matches(&chord, BNotation::B));
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::chord::{Note, TransposableTrait};
+
+/// Number of frets shown in a chord diagram
+pub const VISIBLE_FRETS: u8 = 4;
+
+/// Fingering of a chord on a fretted instrument
+///
+/// The frets are stored per string, from the lowest to the highest string. `None` marks a muted
+/// string, `Some(0)` an open string. Fret numbers are absolute (i.e. not relative to the
+/// `base_fret`)
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Fingering {
+    frets: Vec<Option<u8>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fingers: Vec<Option<u8>>,
+}
+
+impl Fingering {
+    pub fn new(frets: Vec<Option<u8>>) -> Self {
+        Self {
+            frets,
+            fingers: vec![],
+        }
+    }
+
+    pub fn with_fingers(self, fingers: Vec<Option<u8>>) -> Self {
+        Self { fingers, ..self }
+    }
+
+    /// Build a fingering from the compact notation used for open chords (e.g. `x32010`)
+    ///
+    /// Frets above 9 can be written space separated (e.g. `x 10 12 12 11 10`)
+    pub fn from_compact(input: &str) -> Option<Self> {
+        let parts: Vec<String> = if input.contains(' ') {
+            input.split_whitespace().map(ToOwned::to_owned).collect()
+        } else {
+            input.chars().map(String::from).collect()
+        };
+
+        parts
+            .iter()
+            .map(|part| parse_fret(part))
+            .collect::<Option<Vec<_>>>()
+            .map(Self::new)
+    }
+
+    pub fn frets(&self) -> &[Option<u8>] {
+        &self.frets
+    }
+
+    /// Return the finger (1 = index finger) used for each string, if known
+    pub fn fingers(&self) -> &[Option<u8>] {
+        &self.fingers
+    }
+
+    /// Return the fret shown at the top of the diagram
+    ///
+    /// Fingerings that fit into the first frets start at fret 1 and show the nut
+    pub fn base_fret(&self) -> u8 {
+        let fretted = self.frets.iter().flatten().filter(|fret| **fret > 0);
+        match (fretted.clone().min(), fretted.max()) {
+            (Some(_), Some(max)) if *max <= VISIBLE_FRETS => 1,
+            (Some(min), Some(_)) => *min,
+            _ => 1,
+        }
+    }
+
+    /// Return the sounding note of each string for the given tuning
+    pub fn notes(&self, tuning: &[Note]) -> Vec<Option<Note>> {
+        self.frets
+            .iter()
+            .zip(tuning)
+            .map(|(fret, open)| fret.map(|fret| open.transpose(fret as isize)))
+            .collect()
+    }
+}
+
+/// Parse a single fret (`x` marks a muted string)
+pub(super) fn parse_fret(input: &str) -> Option<Option<u8>> {
+    match input {
+        "x" | "X" | "-1" | "N" | "-" => Some(None),
+        _ => input.parse::<u8>().ok().map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_compact() {
+        let fingering = Fingering::from_compact("x32010").unwrap();
+        assert_eq!(
+            fingering.frets(),
+            &[None, Some(3), Some(2), Some(0), Some(1), Some(0)]
+        );
+        assert_eq!(fingering.base_fret(), 1);
+
+        let fingering = Fingering::from_compact("x 10 12 12 11 10").unwrap();
+        assert_eq!(fingering.frets()[2], Some(12));
+        assert_eq!(fingering.base_fret(), 10);
+
+        assert!(Fingering::from_compact("x3y010").is_none());
+    }
+
+    #[test]
+    fn notes() {
+        let fingering = Fingering::from_compact("x02210").unwrap();
+        let tuning = [Note::E, Note::A, Note::D, Note::G, Note::B, Note::E];
+        assert_eq!(
+            fingering.notes(&tuning),
+            vec![
+                None,
+                Some(Note::A),
+                Some(Note::E),
+                Some(Note::A),
+                Some(Note::C),
+                Some(Note::E)
+            ]
+        );
+    }
+}
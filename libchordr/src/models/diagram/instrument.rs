@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+use crate::models::chord::Note;
+
+/// Instrument for which chord diagrams are drawn
+#[derive(Deserialize, Serialize, PartialEq, Eq, PartialOrd, Clone, Copy, Debug)]
+pub enum Instrument {
+    /// Six-string guitar in standard tuning (`E A D G B E`)
+    Guitar,
+    /// Six-string guitar in drop D tuning (`D A D G B E`)
+    GuitarDropD,
+    /// Soprano, concert or tenor ukulele (`G C E A`)
+    Ukulele,
+    /// Baritone ukulele (`D G B E`)
+    BaritoneUkulele,
+    /// Mandolin (`G D A E`)
+    Mandolin,
+    Piano,
+}
+
+impl Instrument {
+    pub fn get_all() -> &'static [Instrument] {
+        &[
+            Self::Guitar,
+            Self::GuitarDropD,
+            Self::Ukulele,
+            Self::BaritoneUkulele,
+            Self::Mandolin,
+            Self::Piano,
+        ]
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Guitar => "guitar",
+            Self::GuitarDropD => "guitar-drop-d",
+            Self::Ukulele => "ukulele",
+            Self::BaritoneUkulele => "baritone-ukulele",
+            Self::Mandolin => "mandolin",
+            Self::Piano => "piano",
+        }
+    }
+
+    /// Return the open strings from the lowest to the highest string
+    ///
+    /// `None` is returned for instruments without strings
+    pub fn tuning(&self) -> Option<&'static [Note]> {
+        match self {
+            Self::Guitar => Some(&[Note::E, Note::A, Note::D, Note::G, Note::B, Note::E]),
+            Self::GuitarDropD => Some(&[Note::D, Note::A, Note::D, Note::G, Note::B, Note::E]),
+            Self::Ukulele => Some(&[Note::G, Note::C, Note::E, Note::A]),
+            Self::BaritoneUkulele => Some(&[Note::D, Note::G, Note::B, Note::E]),
+            Self::Mandolin => Some(&[Note::G, Note::D, Note::A, Note::E]),
+            Self::Piano => None,
+        }
+    }
+
+    /// Return if the lowest sounding string has to play the chord's bass note
+    ///
+    /// This is not the case for re-entrant tunings like the ukulele's, where the lowest string is
+    /// not the leftmost one
+    pub(super) fn requires_bass_on_lowest_string(&self) -> bool {
+        matches!(
+            self,
+            Self::Guitar | Self::GuitarDropD | Self::BaritoneUkulele
+        )
+    }
+
+    /// Return the maximum number of strings that may be muted
+    pub(super) fn max_muted_strings(&self) -> usize {
+        match self {
+            Self::Guitar | Self::GuitarDropD => 2,
+            _ => 0,
+        }
+    }
+}
+
+impl TryFrom<&str> for Instrument {
+    type Error = InstrumentError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl FromStr for Instrument {
+    type Err = InstrumentError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().replace([' ', '_'], "-").as_str() {
+            "guitar" => Ok(Self::Guitar),
+            "guitar-drop-d" | "drop-d" => Ok(Self::GuitarDropD),
+            "ukulele" | "uke" => Ok(Self::Ukulele),
+            "baritone-ukulele" | "baritone-uke" => Ok(Self::BaritoneUkulele),
+            "mandolin" => Ok(Self::Mandolin),
+            "piano" | "keyboard" | "keys" => Ok(Self::Piano),
+            _ => Err(InstrumentError(value.to_string())),
+        }
+    }
+}
+
+impl Display for Instrument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(match self {
+            Self::Guitar => "Guitar",
+            Self::GuitarDropD => "Guitar (Drop D)",
+            Self::Ukulele => "Ukulele",
+            Self::BaritoneUkulele => "Baritone Ukulele",
+            Self::Mandolin => "Mandolin",
+            Self::Piano => "Piano",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct InstrumentError(String);
+
+impl std::error::Error for InstrumentError {}
+
+impl Display for InstrumentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Invalid instrument '{}'", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        for instrument in Instrument::get_all() {
+            assert_eq!(
+                Instrument::from_str(instrument.as_str()).unwrap(),
+                *instrument
+            );
+        }
+        assert_eq!(Instrument::from_str("Uke").unwrap(), Instrument::Ukulele);
+        assert_eq!(
+            Instrument::from_str("Guitar Drop D").unwrap(),
+            Instrument::GuitarDropD
+        );
+        assert!(Instrument::from_str("banjo").is_err());
+    }
+}
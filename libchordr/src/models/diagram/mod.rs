@@ -0,0 +1,133 @@
+//! Chord diagrams for fretted instruments and the piano
+//!
+//! Fingerings are taken from the user's chord definitions (see `ChordDefinition`), the built-in
+//! voicing database or are searched on the fretboard as a last resort
+pub use self::definition::{ChordDefinition, ChordDefinitionError};
+pub use self::fingering::Fingering;
+pub use self::instrument::{Instrument, InstrumentError};
+use crate::models::chord::{Chord, Note};
+use crate::models::meta::BNotation;
+
+mod database;
+mod definition;
+mod fingering;
+mod instrument;
+mod svg;
+mod voicing;
+
+/// Diagram showing how to play a chord
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagram {
+    Fretted(Fingering),
+    /// Keys to press as semitones above the first `C` of the keyboard
+    Keyboard(Vec<u8>),
+}
+
+impl Diagram {
+    /// Build the diagram for the chord on the given instrument
+    ///
+    /// User definitions take precedence over the built-in voicings. They are matched using the
+    /// song's `b_notation`
+    pub fn for_chord(
+        chord: &Chord,
+        instrument: Instrument,
+        definitions: &[ChordDefinition],
+        b_notation: BNotation,
+    ) -> Option<Self> {
+        let tuning = match instrument.tuning() {
+            Some(tuning) => tuning,
+            None => return Self::for_keyboard(chord),
+        };
+
+        definitions
+            .iter()
+            .find(|definition| {
+                definition.fingering().frets().len() == tuning.len()
+                    && definition.matches(chord, b_notation)
+            })
+            .map(|definition| definition.fingering().clone())
+            .or_else(|| database::lookup(chord, instrument))
+            .or_else(|| voicing::find_fingering(chord, instrument))
+            .map(Self::Fretted)
+    }
+
+    /// Stack the chord's notes upwards, starting with the bass note
+    fn for_keyboard(chord: &Chord) -> Option<Self> {
+        let position = |note: Note| (i32::from(note) - 1) as u8;
+        let mut notes = chord.notes()?;
+        if let Some(bass) = chord.bass() {
+            notes.retain(|note| *note != bass);
+            notes.insert(0, bass);
+        }
+
+        let mut keys: Vec<u8> = Vec::with_capacity(notes.len());
+        for note in notes {
+            let key = match keys.last() {
+                None => position(note),
+                Some(previous) => previous + 1 + (position(note) + 11 - previous % 12) % 12,
+            };
+            keys.push(key);
+        }
+
+        Some(Self::Keyboard(keys))
+    }
+
+    /// Render the diagram as SVG image
+    pub fn to_svg(&self) -> String {
+        match self {
+            Diagram::Fretted(fingering) => svg::fretboard(fingering),
+            Diagram::Keyboard(keys) => svg::keyboard(keys),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn chord(input: &str) -> Chord {
+        Chord::try_from(input, BNotation::B).unwrap()
+    }
+
+    #[test]
+    fn for_chord_prefers_definitions() {
+        let definitions = vec![
+            ChordDefinition::from_str("G base-fret 1 frets 3 2 0 0 3 3").unwrap(),
+            ChordDefinition::from_str("G base-fret 1 frets 4 2 3 2").unwrap(),
+        ];
+        let diagram =
+            Diagram::for_chord(&chord("G"), Instrument::Guitar, &definitions, BNotation::B);
+        assert_eq!(
+            diagram,
+            Some(Diagram::Fretted(Fingering::from_compact("320033").unwrap()))
+        );
+
+        // The first definition has the wrong number of strings for the ukulele
+        let diagram =
+            Diagram::for_chord(&chord("G"), Instrument::Ukulele, &definitions, BNotation::B);
+        assert_eq!(
+            diagram,
+            Some(Diagram::Fretted(Fingering::from_compact("4232").unwrap()))
+        );
+
+        let diagram = Diagram::for_chord(&chord("G"), Instrument::Guitar, &[], BNotation::B);
+        assert_eq!(
+            diagram,
+            Some(Diagram::Fretted(Fingering::from_compact("320003").unwrap()))
+        );
+    }
+
+    #[test]
+    fn for_keyboard() {
+        let keys =
+            |input| match Diagram::for_chord(&chord(input), Instrument::Piano, &[], BNotation::B) {
+                Some(Diagram::Keyboard(keys)) => keys,
+                other => panic!("Unexpected diagram {:?}", other),
+            };
+        assert_eq!(keys("C"), vec![0, 4, 7]);
+        assert_eq!(keys("Am"), vec![9, 12, 16]);
+        assert_eq!(keys("C/E"), vec![4, 12, 19]);
+        assert_eq!(keys("B7"), vec![11, 15, 18, 21]);
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt::Write;
+
+use super::fingering::{Fingering, VISIBLE_FRETS};
+
+const STRING_SPACING: u32 = 12;
+const FRET_SPACING: u32 = 14;
+const LEFT: u32 = 18;
+const TOP: u32 = 16;
+const DOT_RADIUS: u32 = 5;
+
+const WHITE_KEY_WIDTH: u32 = 10;
+const WHITE_KEY_HEIGHT: u32 = 40;
+const BLACK_KEY_WIDTH: u32 = 6;
+const BLACK_KEY_HEIGHT: u32 = 24;
+/// Position of the white keys within an octave (starting at `C`)
+const WHITE_KEYS: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+/// Position of the black keys within an octave and the white key they are placed after
+const BLACK_KEYS: [(u8, u32); 5] = [(1, 0), (3, 1), (6, 3), (8, 4), (10, 5)];
+
+/// Draw a fretboard diagram with the strings from left (lowest) to right (highest)
+pub(super) fn fretboard(fingering: &Fingering) -> String {
+    let strings = fingering.frets().len() as u32;
+    let base_fret = fingering.base_fret();
+    let board_width = (strings.max(1) - 1) * STRING_SPACING;
+    let board_height = VISIBLE_FRETS as u32 * FRET_SPACING;
+    let width = LEFT + board_width + DOT_RADIUS + 2;
+    let height = TOP + board_height + 4;
+
+    let mut svg = format!(
+        r#"<svg class="fretboard" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">"#,
+        w = width,
+        h = height
+    );
+    if base_fret == 1 {
+        let _ = write!(
+            svg,
+            r#"<rect class="nut" x="{}" y="{}" width="{}" height="3" fill="currentColor"/>"#,
+            LEFT,
+            TOP - 3,
+            board_width
+        );
+    } else {
+        let _ = write!(
+            svg,
+            r#"<text class="base-fret" x="{}" y="{}" font-size="9" text-anchor="end" fill="currentColor">{}</text>"#,
+            LEFT - 4,
+            TOP + FRET_SPACING / 2 + 3,
+            base_fret
+        );
+    }
+    for fret in 0..=VISIBLE_FRETS as u32 {
+        let y = TOP + fret * FRET_SPACING;
+        let _ = write!(
+            svg,
+            r#"<line class="fret" x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="currentColor"/>"#,
+            LEFT,
+            LEFT + board_width,
+            y = y
+        );
+    }
+    for string in 0..strings {
+        let x = LEFT + string * STRING_SPACING;
+        let _ = write!(
+            svg,
+            r#"<line class="string" x1="{x}" y1="{}" x2="{x}" y2="{}" stroke="currentColor"/>"#,
+            TOP,
+            TOP + board_height,
+            x = x
+        );
+    }
+
+    for (string, fret) in fingering.frets().iter().enumerate() {
+        let x = LEFT + string as u32 * STRING_SPACING;
+        match fret {
+            None => {
+                let _ = write!(
+                    svg,
+                    r#"<text class="muted" x="{}" y="{}" font-size="9" text-anchor="middle" fill="currentColor">×</text>"#,
+                    x,
+                    TOP - 5
+                );
+            }
+            Some(0) => {
+                let _ = write!(
+                    svg,
+                    r#"<circle class="open" cx="{}" cy="{}" r="3" fill="none" stroke="currentColor"/>"#,
+                    x,
+                    TOP - 8
+                );
+            }
+            Some(fret) => {
+                let row = (fret - base_fret) as u32;
+                let y = TOP + row * FRET_SPACING + FRET_SPACING / 2;
+                let _ = write!(
+                    svg,
+                    r#"<circle class="dot" cx="{}" cy="{}" r="{}" fill="currentColor"/>"#,
+                    x, y, DOT_RADIUS
+                );
+                if let Some(Some(finger)) = fingering.fingers().get(string) {
+                    let _ = write!(
+                        svg,
+                        r#"<text class="finger" x="{}" y="{}" font-size="7" text-anchor="middle" fill="white">{}</text>"#,
+                        x,
+                        y + 2,
+                        finger
+                    );
+                }
+            }
+        }
+    }
+    svg.push_str("</svg>");
+
+    svg
+}
+
+/// Draw a keyboard with the given keys (semitones above the first `C`) highlighted
+pub(super) fn keyboard(keys: &[u8]) -> String {
+    let octaves = keys.iter().max().map_or(1, |max| *max as u32 / 12 + 1);
+    let white_keys = octaves * 7;
+    let width = white_keys * WHITE_KEY_WIDTH + 1;
+    let height = WHITE_KEY_HEIGHT + 1;
+
+    let mut svg = format!(
+        r#"<svg class="keyboard" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">"#,
+        w = width,
+        h = height
+    );
+    for octave in 0..octaves {
+        for (index, key) in WHITE_KEYS.iter().enumerate() {
+            let x = (octave * 7 + index as u32) * WHITE_KEY_WIDTH;
+            let pressed = keys.contains(&(octave as u8 * 12 + key));
+            let _ = write!(
+                svg,
+                r#"<rect class="key white{}" x="{}" y="0" width="{}" height="{}" fill="{}" stroke="currentColor"/>"#,
+                if pressed { " pressed" } else { "" },
+                x,
+                WHITE_KEY_WIDTH,
+                WHITE_KEY_HEIGHT,
+                if pressed { "currentColor" } else { "white" }
+            );
+        }
+    }
+    for octave in 0..octaves {
+        for (key, after) in BLACK_KEYS {
+            let x = (octave * 7 + after + 1) * WHITE_KEY_WIDTH - BLACK_KEY_WIDTH / 2;
+            let pressed = keys.contains(&(octave as u8 * 12 + key));
+            let _ = write!(
+                svg,
+                r#"<rect class="key black{}" x="{}" y="0" width="{}" height="{}" fill="{}" stroke="currentColor"/>"#,
+                if pressed { " pressed" } else { "" },
+                x,
+                BLACK_KEY_WIDTH,
+                BLACK_KEY_HEIGHT,
+                if pressed { "gray" } else { "black" }
+            );
+        }
+    }
+    svg.push_str("</svg>");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fretboard_markers() {
+        let svg = fretboard(&Fingering::from_compact("x32010").unwrap());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches(r#"class="dot""#).count(), 3);
+        assert_eq!(svg.matches(r#"class="open""#).count(), 2);
+        assert_eq!(svg.matches(r#"class="muted""#).count(), 1);
+        assert!(svg.contains(r#"class="nut""#));
+
+        let svg = fretboard(&Fingering::from_compact("x 10 12 12 12 10").unwrap());
+        assert!(!svg.contains(r#"class="nut""#));
+        assert!(svg.contains(">10</text>"));
+    }
+
+    #[test]
+    fn keyboard_keys() {
+        let svg = keyboard(&[0, 4, 7]);
+        assert_eq!(svg.matches("pressed").count(), 3);
+        assert_eq!(svg.matches("key white").count(), 7);
+
+        let svg = keyboard(&[9, 12, 16]);
+        assert_eq!(svg.matches("key white").count(), 14);
+        assert_eq!(svg.matches("key black pressed").count(), 0);
+    }
+}
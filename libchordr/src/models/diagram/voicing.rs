@@ -0,0 +1,217 @@
+use super::fingering::{Fingering, VISIBLE_FRETS};
+use super::instrument::Instrument;
+use crate::models::chord::{Chord, Note, TransposableTrait};
+
+/// Highest fret at which a voicing may start
+const MAX_POSITION: u8 = 12;
+/// Number of fingers available for fretting
+const MAX_FINGERS: usize = 4;
+
+/// Search a playable fingering for the chord on a fretted instrument
+///
+/// All positions up to the 12th fret are tried and the fingering with the lowest cost is chosen.
+/// The fifth may be omitted from chords with four or more notes
+pub(super) fn find_fingering(chord: &Chord, instrument: Instrument) -> Option<Fingering> {
+    let tuning = instrument.tuning()?;
+    let mut allowed = chord.notes()?;
+    let bass = chord.bass().unwrap_or_else(|| chord.root());
+    if !allowed.contains(&bass) {
+        allowed.push(bass);
+    }
+
+    let mut required = allowed.clone();
+    let fifth = chord.root().transpose(7);
+    if required.len() >= 4 {
+        required.retain(|note| *note != fifth);
+    }
+    if required.len() > tuning.len() && !instrument.requires_bass_on_lowest_string() {
+        required.retain(|note| *note != chord.root() || *note == bass);
+    }
+    if required.len() > tuning.len() {
+        return None;
+    }
+
+    let search = Search {
+        tuning,
+        instrument,
+        allowed: &allowed,
+        required: &required,
+        bass,
+    };
+    let mut best: Option<(u32, Vec<Option<u8>>)> = None;
+    for position in 1..=MAX_POSITION {
+        let options: Vec<Vec<Option<u8>>> = tuning
+            .iter()
+            .map(|open| search.string_options(*open, position))
+            .collect();
+        let mut frets = Vec::with_capacity(tuning.len());
+        search.visit(&options, &mut frets, &mut best);
+    }
+
+    best.map(|(_, frets)| Fingering::new(frets))
+}
+
+struct Search<'a> {
+    tuning: &'a [Note],
+    instrument: Instrument,
+    allowed: &'a [Note],
+    required: &'a [Note],
+    bass: Note,
+}
+
+impl<'a> Search<'a> {
+    /// Return the frets on the given string that produce a chord tone
+    fn string_options(&self, open: Note, position: u8) -> Vec<Option<u8>> {
+        let mut options = vec![];
+        if self.instrument.max_muted_strings() > 0 {
+            options.push(None);
+        }
+        let frets = std::iter::once(0).chain(position..position + VISIBLE_FRETS);
+        for fret in frets {
+            if self.allowed.contains(&open.transpose(fret as isize)) {
+                options.push(Some(fret));
+            }
+        }
+
+        options
+    }
+
+    fn visit(
+        &self,
+        options: &[Vec<Option<u8>>],
+        frets: &mut Vec<Option<u8>>,
+        best: &mut Option<(u32, Vec<Option<u8>>)>,
+    ) {
+        if frets.len() == options.len() {
+            if let Some(cost) = self.cost(frets) {
+                if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                    *best = Some((cost, frets.clone()));
+                }
+            }
+            return;
+        }
+
+        for option in &options[frets.len()] {
+            // Muted strings are only allowed below the sounding strings
+            if option.is_none() && frets.last().is_some_and(Option::is_some) {
+                continue;
+            }
+            frets.push(*option);
+            self.visit(options, frets, best);
+            frets.pop();
+        }
+    }
+
+    /// Return the cost of playing the frets or `None` if they are not a valid voicing
+    fn cost(&self, frets: &[Option<u8>]) -> Option<u32> {
+        let muted = frets.iter().filter(|fret| fret.is_none()).count();
+        if muted > self.instrument.max_muted_strings() {
+            return None;
+        }
+
+        let notes: Vec<Note> = frets
+            .iter()
+            .zip(self.tuning)
+            .filter_map(|(fret, open)| fret.map(|fret| open.transpose(fret as isize)))
+            .collect();
+        if !self.required.iter().all(|note| notes.contains(note)) {
+            return None;
+        }
+        if self.instrument.requires_bass_on_lowest_string() && notes.first() != Some(&self.bass) {
+            return None;
+        }
+
+        let fretted: Vec<u8> = frets.iter().flatten().copied().filter(|f| *f > 0).collect();
+        let lowest = fretted.iter().min().copied().unwrap_or(0);
+        let (fingers, barre) = if fretted.len() <= MAX_FINGERS {
+            (fretted.len(), false)
+        } else {
+            // A barre covers all strings between the first and the last string at the lowest fret
+            let first = frets.iter().position(|f| *f == Some(lowest))?;
+            let last = frets.iter().rposition(|f| *f == Some(lowest))?;
+            if frets[first..=last]
+                .iter()
+                .any(|f| f.is_none_or(|f| f < lowest))
+            {
+                return None;
+            }
+            (1 + fretted.iter().filter(|f| **f > lowest).count(), true)
+        };
+        if fingers > MAX_FINGERS {
+            return None;
+        }
+
+        // Fretting the lowest fret on strings separated by an open string is awkward, because it
+        // can not be played with a barre
+        let first = frets.iter().position(|f| *f == Some(lowest));
+        let last = frets.iter().rposition(|f| *f == Some(lowest));
+        let awkward = match (first, last) {
+            (Some(first), Some(last)) if lowest > 0 => frets[first..=last].contains(&Some(0)),
+            _ => false,
+        };
+
+        Some(
+            lowest as u32 * 2
+                + muted as u32 * 3
+                + fingers as u32
+                + if barre { 2 } else { 0 }
+                + if awkward { 3 } else { 0 },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::meta::BNotation;
+
+    fn find(chord: &str, instrument: Instrument) -> Option<Fingering> {
+        find_fingering(&Chord::try_from(chord, BNotation::B).unwrap(), instrument)
+    }
+
+    #[test]
+    fn find_guitar() {
+        let test_cases = [
+            ("C", "x32010"),
+            ("Bb", "x13331"),
+            ("G/B", "x20003"),
+            ("E7", "020100"),
+            ("Am", "x02210"),
+        ];
+        for (chord, expected) in test_cases {
+            assert_eq!(
+                find(chord, Instrument::Guitar),
+                Fingering::from_compact(expected),
+                "{}",
+                chord
+            );
+        }
+
+        // The bass note has to be the lowest note
+        let fingering = find("C#m", Instrument::Guitar).unwrap();
+        let tuning = Instrument::Guitar.tuning().unwrap();
+        assert_eq!(
+            fingering.notes(tuning).into_iter().flatten().next(),
+            Some(Note::Cis)
+        );
+    }
+
+    #[test]
+    fn find_ukulele() {
+        assert_eq!(
+            find("C", Instrument::Ukulele),
+            Fingering::from_compact("0003")
+        );
+        assert_eq!(
+            find("Am7", Instrument::Ukulele),
+            Fingering::from_compact("0000")
+        );
+        // Five note chords omit the fifth
+        assert!(find("G9", Instrument::Ukulele).is_some());
+    }
+
+    #[test]
+    fn find_unknown_suffix() {
+        assert_eq!(find("C9Daniel", Instrument::Guitar), None);
+    }
+}
@@ -10,6 +10,9 @@ use std::path::Path;
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum FileType {
     Chorddown,
+    ChordPro,
+    SongBeamer,
+    OpenLyrics,
     Jpeg,
 }
 
@@ -20,14 +23,28 @@ impl FileType {
 
     pub fn path_matches(&self, path: &Path) -> bool {
         match path.extension() {
-            Some(t) => t == self.str_representation(),
+            Some(t) => self.extensions().iter().any(|extension| t == *extension),
             None => false,
         }
     }
 
+    /// Return the file extensions used for this file type
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            FileType::Chorddown => &["chorddown"],
+            FileType::ChordPro => &["cho", "chordpro", "chopro"],
+            FileType::SongBeamer => &["sng"],
+            FileType::OpenLyrics => &["xml"],
+            FileType::Jpeg => &["jpeg"],
+        }
+    }
+
     fn str_representation(&self) -> &str {
         match self {
             FileType::Chorddown => "chorddown",
+            FileType::ChordPro => "chordpro",
+            FileType::SongBeamer => "songbeamer",
+            FileType::OpenLyrics => "openlyrics",
             FileType::Jpeg => "jpeg",
         }
     }
@@ -62,6 +79,9 @@ impl TryFrom<&str> for FileType {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "Chorddown" | "chorddown" => Ok(FileType::Chorddown),
+            "ChordPro" | "chordpro" | "cho" | "chopro" => Ok(FileType::ChordPro),
+            "SongBeamer" | "songbeamer" | "sng" => Ok(FileType::SongBeamer),
+            "OpenLyrics" | "openlyrics" | "xml" => Ok(FileType::OpenLyrics),
             "Jpeg" | "jpeg" => Ok(FileType::Jpeg),
             _ => Err(Error::unknown_error(format!("Invalid FileType {}", value))),
         }
@@ -85,6 +105,15 @@ mod tests {
         let serialized = serde_json::to_string(&FileType::Chorddown).unwrap();
         assert_eq!("\"chorddown\"", serialized);
 
+        let serialized = serde_json::to_string(&FileType::ChordPro).unwrap();
+        assert_eq!("\"chordpro\"", serialized);
+
+        let serialized = serde_json::to_string(&FileType::SongBeamer).unwrap();
+        assert_eq!("\"songbeamer\"", serialized);
+
+        let serialized = serde_json::to_string(&FileType::OpenLyrics).unwrap();
+        assert_eq!("\"openlyrics\"", serialized);
+
         let serialized = serde_json::to_string(&FileType::Jpeg).unwrap();
         assert_eq!("\"jpeg\"", serialized);
     }
@@ -94,7 +123,30 @@ mod tests {
         let deserialized: FileType = serde_json::from_str("\"chorddown\"").unwrap();
         assert_eq!(FileType::Chorddown, deserialized);
 
+        let deserialized: FileType = serde_json::from_str("\"chordpro\"").unwrap();
+        assert_eq!(FileType::ChordPro, deserialized);
+
+        let deserialized: FileType = serde_json::from_str("\"songbeamer\"").unwrap();
+        assert_eq!(FileType::SongBeamer, deserialized);
+
+        let deserialized: FileType = serde_json::from_str("\"openlyrics\"").unwrap();
+        assert_eq!(FileType::OpenLyrics, deserialized);
+
         let deserialized: FileType = serde_json::from_str("\"jpeg\"").unwrap();
         assert_eq!(FileType::Jpeg, deserialized);
     }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(FileType::Chorddown.path_matches(Path::new("song.chorddown")));
+        assert!(!FileType::Chorddown.path_matches(Path::new("song.cho")));
+        assert!(FileType::ChordPro.path_matches(Path::new("song.cho")));
+        assert!(FileType::ChordPro.path_matches(Path::new("song.chordpro")));
+        assert!(FileType::ChordPro.path_matches(Path::new("song.chopro")));
+        assert!(!FileType::ChordPro.path_matches(Path::new("song.chorddown")));
+        assert!(!FileType::ChordPro.path_matches(Path::new("song")));
+        assert!(FileType::SongBeamer.path_matches(Path::new("song.sng")));
+        assert!(!FileType::SongBeamer.path_matches(Path::new("song.cho")));
+        assert!(FileType::OpenLyrics.path_matches(Path::new("song.xml")));
+    }
 }
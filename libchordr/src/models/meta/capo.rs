@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+use crate::models::chord::{Chord, Note, Quality};
+use crate::modification::transposition::TransposableTrait;
+
+const ROMAN_NUMERALS: [&str; 12] = [
+    "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII",
+];
+
+/// Fret at which the capo is placed
+#[derive(Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Capo(u8);
+
+impl Capo {
+    /// Highest fret considered by `Capo::suggest()`
+    pub const MAX_SUGGESTED_FRET: u8 = 7;
+    /// Highest fret accepted by `Capo::new()`
+    pub const MAX_FRET: u8 = 12;
+
+    pub fn new(fret: u8) -> Result<Self, CapoError> {
+        if fret > Self::MAX_FRET {
+            Err(CapoError(fret.to_string()))
+        } else {
+            Ok(Self(fret))
+        }
+    }
+
+    pub fn fret(&self) -> u8 {
+        self.0
+    }
+
+    /// Return the key of the chord shapes played with this capo for the sounding `key`
+    ///
+    /// E.g. a song in `A` is played with `G` shapes on capo 2
+    pub fn shape_key(&self, key: &Chord) -> Chord {
+        key.clone().transpose(-(self.0 as isize))
+    }
+
+    /// Return the capo position that gives the most guitar-friendly shapes for the sounding `key`
+    ///
+    /// Higher frets are slightly penalized. If several positions are equally good, the lowest
+    /// fret is returned
+    pub fn suggest(key: &Chord) -> Self {
+        (0..=Self::MAX_SUGGESTED_FRET)
+            .map(Self)
+            .min_by_key(|capo| shape_difficulty(&capo.shape_key(key)) + capo.0 / 3)
+            .unwrap_or_default()
+    }
+}
+
+/// Return how hard it is to play in the given key with open chord shapes (lower is easier)
+fn shape_difficulty(key: &Chord) -> u8 {
+    if key.quality().is_minor() {
+        match key.root() {
+            Note::E | Note::A => 0,
+            Note::D => 1,
+            Note::B => 3,
+            _ => 5,
+        }
+    } else if key.quality() == Quality::Major {
+        match key.root() {
+            Note::G | Note::C | Note::D => 0,
+            Note::E | Note::A => 1,
+            Note::F => 3,
+            _ => 5,
+        }
+    } else {
+        5
+    }
+}
+
+impl TryFrom<u8> for Capo {
+    type Error = CapoError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Capo> for u8 {
+    fn from(capo: Capo) -> Self {
+        capo.0
+    }
+}
+
+impl TryFrom<&str> for Capo {
+    type Error = CapoError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl FromStr for Capo {
+    type Err = CapoError;
+
+    /// Parse capo values like `2`, `Capo 2`, `2nd fret` or `II`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || CapoError(value.to_string());
+        let trimmed = value.trim();
+        let trimmed = match trimmed.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("capo") => trimmed[4..].trim_start(),
+            _ => trimmed,
+        };
+        let trimmed = trimmed.trim_start_matches([':', ' ']);
+
+        let digits: String = trimmed.chars().take_while(char::is_ascii_digit).collect();
+        if !digits.is_empty() {
+            return Self::new(digits.parse().map_err(|_| error())?).map_err(|_| error());
+        }
+
+        let roman = trimmed.split_whitespace().next().unwrap_or_default();
+        match ROMAN_NUMERALS
+            .iter()
+            .position(|r| r.eq_ignore_ascii_case(roman))
+        {
+            Some(index) => Ok(Self(index as u8 + 1)),
+            None if trimmed.eq_ignore_ascii_case("none") || trimmed.is_empty() => Ok(Self(0)),
+            None => Err(error()),
+        }
+    }
+}
+
+impl Display for Capo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct CapoError(String);
+
+impl std::error::Error for CapoError {}
+
+impl Display for CapoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Invalid capo '{}'", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::meta::BNotation;
+
+    #[test]
+    fn from_str() {
+        let test_cases = [
+            ("2", 2),
+            (" 3 ", 3),
+            ("Capo 4", 4),
+            ("capo: 1", 1),
+            ("5th fret", 5),
+            ("II", 2),
+            ("vii", 7),
+            ("none", 0),
+            ("0", 0),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(Capo::from_str(input).unwrap().fret(), expected, "{}", input);
+        }
+
+        assert!(Capo::from_str("13").is_err());
+        assert!(Capo::from_str("high").is_err());
+    }
+
+    #[test]
+    fn suggest() {
+        let suggest = |key| Capo::suggest(&Chord::try_from(key, BNotation::B).unwrap()).fret();
+        assert_eq!(suggest("G"), 0);
+        assert_eq!(suggest("Am"), 0);
+        assert_eq!(suggest("Bb"), 1);
+        assert_eq!(suggest("Eb"), 1);
+        assert_eq!(suggest("F#"), 2);
+        assert_eq!(suggest("Bbm"), 1);
+        assert_eq!(suggest("Cm"), 3);
+    }
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(serde_json::from_str::<Capo>("3").unwrap(), Capo(3));
+        assert_eq!(serde_json::to_string(&Capo(3)).unwrap(), "3");
+        assert!(serde_json::from_str::<Capo>("40").is_err());
+    }
+
+    #[test]
+    fn shape_key() {
+        let key = Chord::try_from("A", BNotation::B).unwrap();
+        assert_eq!(Capo(2).shape_key(&key).root(), Note::G);
+        assert_eq!(Capo(0).shape_key(&key).root(), Note::A);
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+/// Enum defining if chords are written as letters or relative to the song's key
+#[derive(Deserialize, Serialize, PartialEq, PartialOrd, Clone, Copy, Debug, Default)]
+pub enum ChordNotation {
+    /// Chord names like `C`, `F` or `G/B`
+    #[default]
+    Letter,
+    /// Nashville Number System like `1`, `4`, `5/7` or `5^7`
+    Nashville,
+    /// Roman numerals like `I`, `IV` or `V/vii`
+    Roman,
+}
+
+impl ChordNotation {
+    pub fn get_all() -> &'static [ChordNotation] {
+        &[Self::Letter, Self::Nashville, Self::Roman]
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Letter => "letter",
+            Self::Nashville => "nashville",
+            Self::Roman => "roman",
+        }
+    }
+}
+
+impl TryFrom<&str> for ChordNotation {
+    type Error = ChordNotationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl FromStr for ChordNotation {
+    type Err = ChordNotationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "letter" | "letters" => Ok(Self::Letter),
+            "nashville" | "number" | "numbers" => Ok(Self::Nashville),
+            "roman" => Ok(Self::Roman),
+            _ => Err(ChordNotationError(value.to_string())),
+        }
+    }
+}
+
+impl Display for ChordNotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct ChordNotationError(String);
+
+impl std::error::Error for ChordNotationError {}
+
+impl Display for ChordNotationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Invalid chord-notation '{}'", self.0)
+    }
+}
@@ -0,0 +1,176 @@
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Formatter;
+use std::iter::Map;
+use std::slice::Iter;
+
+type Field = (String, String);
+
+/// Meta information with keywords that are not known to the `Parser` (e.g. `Theme: Grace`)
+///
+/// The keywords are stored as written in the song and in the order of the song, but looked up
+/// case-insensitively. If a keyword is defined multiple times, the last value is kept at the
+/// position of the first definition
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct CustomFields(Vec<Field>);
+
+impl CustomFields {
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    /// Return the value for the keyword
+    pub fn get(&self, keyword: &str) -> Option<&str> {
+        self.position(keyword)
+            .map(|position| self.0[position].1.as_str())
+    }
+
+    /// Set the value for the keyword, replacing the value of a keyword that only differs in case
+    ///
+    /// A replaced field keeps its position and the keyword's first spelling
+    pub fn insert<S1: Into<String>, S2: Into<String>>(&mut self, keyword: S1, value: S2) {
+        let keyword = keyword.into();
+        match self.position(&keyword) {
+            Some(position) => self.0[position].1 = value.into(),
+            None => self.0.push((keyword, value.into())),
+        }
+    }
+
+    pub fn remove(&mut self, keyword: &str) -> Option<String> {
+        let position = self.position(keyword)?;
+        Some(self.0.remove(position).1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Iterate over the keywords and values in the order of the song
+    pub fn iter(&self) -> FieldIter<'_> {
+        self.0.iter().map(|(keyword, value)| (keyword, value))
+    }
+
+    fn position(&self, keyword: &str) -> Option<usize> {
+        let keyword = keyword.trim();
+        self.0
+            .iter()
+            .position(|(existing, _)| existing.eq_ignore_ascii_case(keyword))
+    }
+}
+
+pub type FieldIter<'a> = Map<Iter<'a, Field>, fn(&'a Field) -> (&'a String, &'a String)>;
+
+impl<'a> IntoIterator for &'a CustomFields {
+    type Item = (&'a String, &'a String);
+    type IntoIter = FieldIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for CustomFields {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut fields = Self::new();
+        for (keyword, value) in iter {
+            fields.insert(keyword, value);
+        }
+
+        fields
+    }
+}
+
+impl Serialize for CustomFields {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (keyword, value) in self {
+            map.serialize_entry(keyword, value)?;
+        }
+        map.end()
+    }
+}
+
+struct CustomFieldsVisitor;
+
+impl<'de> Visitor<'de> for CustomFieldsVisitor {
+    type Value = CustomFields;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of custom meta fields")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut fields = CustomFields::new();
+        while let Some((keyword, value)) = access.next_entry::<String, String>()? {
+            fields.insert(keyword, value);
+        }
+
+        Ok(fields)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomFields {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(CustomFieldsVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_get() {
+        let mut fields = CustomFields::new();
+        fields.insert("Theme", "Grace");
+        fields.insert("Bible reference", "Psalm 23");
+        assert_eq!(fields.get("theme"), Some("Grace"));
+        assert_eq!(fields.get(" BIBLE REFERENCE "), Some("Psalm 23"));
+
+        fields.insert("theme", "Hope");
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields.get("Theme"), Some("Hope"));
+        assert_eq!(
+            fields.iter().collect::<Vec<_>>(),
+            vec![
+                (&"Theme".to_owned(), &"Hope".to_owned()),
+                (&"Bible reference".to_owned(), &"Psalm 23".to_owned()),
+            ]
+        );
+        assert_eq!(fields.remove("THEME"), Some("Hope".to_owned()));
+        assert_eq!(fields.get("theme"), None);
+    }
+
+    #[test]
+    fn serialize() {
+        let fields: CustomFields = vec![("Theme", "Grace"), ("Arrangement", "Choir")]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            serde_json::to_string(&fields).unwrap(),
+            r#"{"Theme":"Grace","Arrangement":"Choir"}"#
+        );
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let json = r#"{"Theme":"Grace","Arrangement":"Choir","Bible reference":"Psalm 23"}"#;
+        let fields: CustomFields = serde_json::from_str(json).unwrap();
+        assert_eq!(fields.get("arrangement"), Some("Choir"));
+        assert_eq!(serde_json::to_string(&fields).unwrap(), json);
+    }
+}
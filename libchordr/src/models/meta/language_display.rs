@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+/// Enum defining which languages of a multilingual song are displayed
+///
+/// Translations are written as lines starting with `~` after the lyrics line they translate
+#[derive(Deserialize, Serialize, PartialEq, Eq, PartialOrd, Clone, Copy, Debug, Default)]
+pub enum LanguageDisplay {
+    /// Only the lyrics in the song's primary language
+    Primary,
+    /// The translations instead of the lyrics (lines without translation are kept)
+    Secondary,
+    /// The lyrics followed by their translations
+    #[default]
+    Interleaved,
+}
+
+impl LanguageDisplay {
+    pub fn get_all() -> &'static [LanguageDisplay] {
+        &[Self::Primary, Self::Secondary, Self::Interleaved]
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Primary => "primary",
+            Self::Secondary => "secondary",
+            Self::Interleaved => "interleaved",
+        }
+    }
+}
+
+impl TryFrom<&str> for LanguageDisplay {
+    type Error = LanguageDisplayError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl FromStr for LanguageDisplay {
+    type Err = LanguageDisplayError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "primary" | "1" => Ok(Self::Primary),
+            "secondary" | "translation" | "2" => Ok(Self::Secondary),
+            "interleaved" | "both" => Ok(Self::Interleaved),
+            _ => Err(LanguageDisplayError(value.to_string())),
+        }
+    }
+}
+
+impl Display for LanguageDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct LanguageDisplayError(String);
+
+impl std::error::Error for LanguageDisplayError {}
+
+impl Display for LanguageDisplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Invalid language display '{}'", self.0)
+    }
+}
@@ -4,8 +4,10 @@ use std::fmt::Debug;
 pub use MetaTrait as SongMetaTrait;
 
 use crate::models::chord::Chord;
+use crate::models::diagram::ChordDefinition;
+use crate::models::structure::Arrangement;
 
-use super::{b_notation::BNotation, Tags};
+use super::{b_notation::BNotation, Capo, CustomFields, Tags};
 
 pub trait MetaTrait: Debug {
     fn title(&self) -> Option<String>;
@@ -22,9 +24,31 @@ pub trait MetaTrait: Debug {
     fn tempo(&self) -> Option<String>;
     fn duration(&self) -> Option<String>;
     fn capo(&self) -> Option<String>;
+    /// Return the fret of the `Capo` meta, if it can be parsed
+    fn capo_position(&self) -> Option<Capo> {
+        self.capo().and_then(|capo| capo.parse().ok())
+    }
     fn original_title(&self) -> Option<String>;
     fn alternative_title(&self) -> Option<String>;
     fn ccli_song_id(&self) -> Option<String>;
     fn b_notation(&self) -> BNotation;
     fn tags(&self) -> Tags;
+    /// Return the languages of the lyrics and their translation (e.g. `German, English`)
+    fn language(&self) -> Option<String>;
+    /// Return the chord fingerings defined in the song
+    fn chord_definitions(&self) -> Vec<ChordDefinition>;
+    /// Return the meta information with keywords that are not known (e.g. `Theme: Grace`)
+    fn custom_fields(&self) -> CustomFields;
+    /// Return the value of the custom field with the given keyword (case-insensitive)
+    fn custom_field(&self, keyword: &str) -> Option<String> {
+        self.custom_fields().get(keyword).map(ToOwned::to_owned)
+    }
+    /// Return the section orders declared in the song
+    fn arrangements(&self) -> Vec<Arrangement>;
+    /// Return the arrangement with the given name (case-insensitive)
+    fn arrangement(&self, name: &str) -> Option<Arrangement> {
+        self.arrangements()
+            .into_iter()
+            .find(|arrangement| arrangement.is_named(name))
+    }
 }
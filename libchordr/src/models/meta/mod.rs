@@ -1,9 +1,17 @@
 pub mod b_notation;
+pub mod capo;
+pub mod chord_notation;
+pub mod custom_fields;
+pub mod language_display;
 pub mod meta_trait;
 pub mod semitone_notation;
 pub mod tags;
 
 pub use self::b_notation::BNotation;
+pub use self::capo::Capo;
+pub use self::chord_notation::ChordNotation;
+pub use self::custom_fields::{CustomFields, FieldIter};
+pub use self::language_display::LanguageDisplay;
 pub use self::meta_trait::MetaTrait;
 pub use self::semitone_notation::SemitoneNotation;
 pub use self::tags::Tags;
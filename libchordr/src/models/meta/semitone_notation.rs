@@ -3,18 +3,53 @@ use std::convert::TryFrom;
 use std::fmt::{Display, Error, Formatter};
 use std::str::FromStr;
 
+use crate::models::chord::{Chord, Note};
+use crate::modification::transposition::TransposableTrait;
+
 /// Enum defining if `C#` or `Db` should be used
 #[derive(Deserialize, Serialize, PartialEq, PartialOrd, Clone, Copy, Debug)]
 pub enum SemitoneNotation {
     Sharp,
     Flat,
+    /// Use flats in flat keys and sharps in sharp keys (see `SemitoneNotation::for_key()`)
+    Auto,
 }
 
-impl SemitoneNotation {}
+impl SemitoneNotation {
+    /// Return the notation used for the given key
+    ///
+    /// If the key's root is written with an accidental (e.g. `Db` or `C#m`), its notation is
+    /// used. Otherwise the notation is taken from the circle of fifths, where keys without
+    /// accidentals (C major and A minor) return `None`
+    pub fn for_key(key: &Chord) -> Option<Self> {
+        let is_natural = !matches!(
+            key.root(),
+            Note::Cis | Note::Dis | Note::Fis | Note::Gis | Note::Ais
+        );
+        if !is_natural {
+            if let Some(accidental) = key.accidental() {
+                return Some(accidental);
+            }
+        }
+
+        // Minor keys use the notation of their relative major key
+        let major_key_root = if key.quality().is_minor() {
+            key.root().transpose(3)
+        } else {
+            key.root()
+        };
+
+        match major_key_root {
+            Note::C => None,
+            Note::D | Note::E | Note::Fis | Note::G | Note::A | Note::B => Some(Self::Sharp),
+            Note::Cis | Note::Dis | Note::F | Note::Gis | Note::Ais => Some(Self::Flat),
+        }
+    }
+}
 
 impl Default for SemitoneNotation {
     fn default() -> Self {
-        Self::Sharp
+        Self::Auto
     }
 }
 
@@ -45,6 +80,7 @@ impl FromStr for SemitoneNotation {
         match value {
             "#" | "♯" => Ok(Self::Sharp),
             "b" | "♭" => Ok(Self::Flat),
+            "auto" => Ok(Self::Auto),
             _ => Err(SemitoneNotationError(value.to_string())),
         }
     }
@@ -55,6 +91,7 @@ impl Display for SemitoneNotation {
         match self {
             Self::Sharp => f.write_str("♯"),
             Self::Flat => f.write_str("♭"),
+            Self::Auto => f.write_str("Auto"),
         }
     }
 }
@@ -69,3 +106,59 @@ impl Display for SemitoneNotationError {
         write!(f, "Invalid semitone-notation '{}'", self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::models::meta::BNotation;
+
+    use super::*;
+
+    #[test]
+    fn for_key() {
+        let test_cases = [
+            ("C", None),
+            ("Am", None),
+            ("G", Some(SemitoneNotation::Sharp)),
+            ("E", Some(SemitoneNotation::Sharp)),
+            ("F#", Some(SemitoneNotation::Sharp)),
+            ("F", Some(SemitoneNotation::Flat)),
+            ("Bb", Some(SemitoneNotation::Flat)),
+            ("Db", Some(SemitoneNotation::Flat)),
+            ("C#", Some(SemitoneNotation::Sharp)),
+            ("Gb", Some(SemitoneNotation::Flat)),
+            ("Em", Some(SemitoneNotation::Sharp)),
+            ("C#m", Some(SemitoneNotation::Sharp)),
+            ("Ebm", Some(SemitoneNotation::Flat)),
+            ("D#m", Some(SemitoneNotation::Sharp)),
+            ("Dm", Some(SemitoneNotation::Flat)),
+            ("Gm", Some(SemitoneNotation::Flat)),
+            ("Bbm", Some(SemitoneNotation::Flat)),
+        ];
+        for (key, expected) in test_cases {
+            let chord = Chord::try_from(key, BNotation::B).unwrap();
+            assert_eq!(SemitoneNotation::for_key(&chord), expected, "{}", key);
+        }
+    }
+
+    #[test]
+    fn for_key_without_written_accidental() {
+        // `B` is `B♭` in the H notation
+        let chord = Chord::try_from("B", BNotation::H).unwrap();
+        assert_eq!(
+            SemitoneNotation::for_key(&chord),
+            Some(SemitoneNotation::Flat)
+        );
+
+        // Transposed keys fall back to the circle of fifths
+        let chord = Chord::try_from("Ab", BNotation::B).unwrap().transpose(-2);
+        assert_eq!(
+            SemitoneNotation::for_key(&chord),
+            Some(SemitoneNotation::Sharp)
+        );
+        let chord = Chord::try_from("C#", BNotation::B).unwrap().transpose(-2);
+        assert_eq!(
+            SemitoneNotation::for_key(&chord),
+            Some(SemitoneNotation::Sharp)
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! This module contains the data models provided by `libchord`
 pub mod catalog;
 pub mod chord;
+pub mod diagram;
 pub mod file_type;
 pub mod list;
 pub mod meta;
@@ -1,11 +1,13 @@
 use std::ops;
 use std::slice::Iter;
+use std::time::Duration;
 use std::vec::IntoIter;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
+use crate::models::catalog::{Catalog, CatalogTrait};
 use crate::models::list::{List, ListEntryTrait, ListError, ListTrait};
 use crate::models::song_id::SongId;
 use crate::models::song_list::{SongList, SongListTrait};
@@ -110,6 +112,17 @@ impl Setlist {
     pub fn iter(&self) -> Iter<'_, SetlistEntry> {
         self.songs.iter()
     }
+
+    /// Return the total running time of the songs
+    ///
+    /// Songs missing in the `Catalog` or without a running time are not counted
+    pub fn running_time(&self, catalog: &Catalog) -> Duration {
+        self.songs
+            .iter()
+            .filter_map(|entry| catalog.get(entry.id()))
+            .filter_map(|song| song.meta().running_time())
+            .sum()
+    }
 }
 
 impl SongListTrait for Setlist {
@@ -261,4 +274,40 @@ mod test {
         assert_eq!(list[0], entry("1"));
         assert_eq!(list[4], entry("0"));
     }
+
+    #[test]
+    fn running_time_test() {
+        use crate::analysis::Analyzer;
+        use crate::helper::parse_content;
+        use crate::models::song::Song;
+        use crate::models::song_meta::SongMeta;
+
+        let song = |id: &str, content: &str| {
+            let result = parse_content(content.as_bytes()).unwrap();
+            let meta = SongMeta::new_with_meta_information(
+                SongId::new(id),
+                id.to_owned(),
+                FileType::Chorddown,
+                result.meta_as_ref(),
+            )
+            .with_statistics(
+                Analyzer::new().statistics(result.node_as_ref(), result.meta_as_ref()),
+            );
+
+            Song::new(meta, content)
+        };
+        let catalog = Catalog::new(
+            "1",
+            vec![
+                song("0", "# Song\nDuration: 3:30\n\n## Verse\nSwing low\n"),
+                // 2 bars of 4 beats at 60 BPM
+                song("1", "# Song\nTempo: 60\n\n## Verse\nSwing low\n"),
+            ],
+        );
+
+        assert_eq!(
+            build_setlist().running_time(&catalog),
+            Duration::from_secs(210 + 8)
+        );
+    }
 }
@@ -1,9 +1,14 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
+use crate::analysis::{parse_duration, SongAnalysis, SongStatistics};
 use crate::models::chord::Chord;
+use crate::models::diagram::ChordDefinition;
 use crate::models::list::ListEntryTrait;
 use crate::models::meta::*;
 use crate::models::song_id::SongIdTrait;
+use crate::models::structure::Arrangement;
 
 use super::file_type::FileType;
 use super::song_data::SongData;
@@ -30,12 +35,24 @@ pub struct SongMeta {
     time: Option<String>,
     tempo: Option<String>,
     duration: Option<String>,
-    capo: Option<String>,
+    capo: Option<Capo>,
     original_title: Option<String>,
     alternative_title: Option<String>,
     ccli_song_id: Option<String>,
     b_notation: BNotation,
     tags: Option<Tags>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    chord_definitions: Vec<ChordDefinition>,
+    #[serde(default, skip_serializing_if = "CustomFields::is_empty")]
+    custom_fields: CustomFields,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    arrangements: Vec<Arrangement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    analysis: Option<SongAnalysis>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    statistics: Option<SongStatistics>,
 }
 
 impl SongMeta {
@@ -62,6 +79,12 @@ impl SongMeta {
             ccli_song_id: None,
             b_notation: Default::default(),
             tags: Default::default(),
+            language: None,
+            chord_definitions: vec![],
+            custom_fields: CustomFields::new(),
+            arrangements: vec![],
+            analysis: None,
+            statistics: None,
         }
     }
 
@@ -87,14 +110,68 @@ impl SongMeta {
             time: meta.time(),
             tempo: meta.tempo(),
             duration: meta.duration(),
-            capo: meta.capo(),
+            capo: meta.capo_position(),
             original_title: meta.original_title(),
             alternative_title: meta.alternative_title(),
             ccli_song_id: meta.ccli_song_id(),
             b_notation: meta.b_notation(),
             tags: Some(meta.tags()),
+            language: meta.language(),
+            chord_definitions: meta.chord_definitions(),
+            custom_fields: meta.custom_fields(),
+            arrangements: meta.arrangements(),
+            analysis: None,
+            statistics: None,
+        }
+    }
+
+    /// Attach the chord analysis (e.g. when building the catalog)
+    pub fn with_analysis(self, analysis: SongAnalysis) -> Self {
+        Self {
+            analysis: Some(analysis),
+            ..self
+        }
+    }
+
+    /// Return the chord analysis, if the song was analyzed
+    pub fn analysis(&self) -> Option<&SongAnalysis> {
+        self.analysis.as_ref()
+    }
+
+    /// Attach the song statistics (e.g. when building the catalog)
+    pub fn with_statistics(self, statistics: SongStatistics) -> Self {
+        Self {
+            statistics: Some(statistics),
+            ..self
         }
     }
+
+    /// Return the song statistics, if they have been computed
+    pub fn statistics(&self) -> Option<&SongStatistics> {
+        self.statistics.as_ref()
+    }
+
+    /// Return the running time of the song
+    ///
+    /// A `Duration` header in the form `m:ss` takes precedence over the estimated duration
+    pub fn running_time(&self) -> Option<Duration> {
+        self.duration
+            .as_deref()
+            .and_then(parse_duration)
+            .or_else(|| {
+                self.statistics
+                    .as_ref()
+                    .map(SongStatistics::estimated_duration)
+            })
+    }
+
+    /// Return the key estimated by the analysis, if the song does not declare a key
+    pub fn suggested_key(&self) -> Option<Chord> {
+        self.analysis
+            .as_ref()
+            .and_then(|analysis| analysis.suggested_key())
+            .cloned()
+    }
 }
 
 impl MetaTrait for SongMeta {
@@ -150,7 +227,11 @@ impl MetaTrait for SongMeta {
     }
 
     fn capo(&self) -> Option<String> {
-        self.capo.as_ref().cloned()
+        self.capo.map(|capo| capo.to_string())
+    }
+
+    fn capo_position(&self) -> Option<Capo> {
+        self.capo
     }
 
     fn original_title(&self) -> Option<String> {
@@ -172,6 +253,22 @@ impl MetaTrait for SongMeta {
     fn tags(&self) -> Tags {
         self.tags.clone().unwrap_or_default()
     }
+
+    fn language(&self) -> Option<String> {
+        self.language.as_ref().cloned()
+    }
+
+    fn chord_definitions(&self) -> Vec<ChordDefinition> {
+        self.chord_definitions.clone()
+    }
+
+    fn custom_fields(&self) -> CustomFields {
+        self.custom_fields.clone()
+    }
+
+    fn arrangements(&self) -> Vec<Arrangement> {
+        self.arrangements.clone()
+    }
 }
 
 impl SongIdTrait for SongMeta {}
@@ -193,3 +290,53 @@ impl SongData for SongMeta {
         self.file_type
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helper::parse_content;
+
+    #[test]
+    fn serialize_custom_fields() {
+        let parser_result = parse_content("# Song\nTheme: Grace\n".as_bytes()).unwrap();
+        let meta = SongMeta::new_with_meta_information(
+            SongId::from("song"),
+            "Song".to_owned(),
+            FileType::Chorddown,
+            parser_result.meta_as_ref(),
+        );
+        assert_eq!(meta.custom_field("theme"), Some("Grace".to_owned()));
+
+        let json = serde_json::to_string(&meta).unwrap();
+        assert!(json.contains(r#""custom_fields":{"Theme":"Grace"}"#));
+        let deserialized: SongMeta = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, meta);
+
+        // Catalogs without custom fields can still be read
+        let json = serde_json::to_string(&SongMeta::new(
+            SongId::from("song"),
+            "Song".to_owned(),
+            FileType::Chorddown,
+        ))
+        .unwrap();
+        assert!(!json.contains("custom_fields"));
+        assert!(serde_json::from_str::<SongMeta>(&json).is_ok());
+    }
+    #[test]
+    fn capo_position() {
+        let parser_result = parse_content("# Song\nCapo: II\n".as_bytes()).unwrap();
+        let meta = SongMeta::new_with_meta_information(
+            SongId::from("song"),
+            "Song".to_owned(),
+            FileType::Chorddown,
+            parser_result.meta_as_ref(),
+        );
+        assert_eq!(meta.capo_position(), Some(Capo::new(2).unwrap()));
+        assert_eq!(meta.capo(), Some("2".to_owned()));
+
+        let json = serde_json::to_string(&meta).unwrap();
+        assert!(json.contains(r#""capo":2"#));
+        let deserialized: SongMeta = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, meta);
+    }
+}
@@ -4,7 +4,7 @@ pub use song_settings_map::SongSettingsMap;
 
 use crate::format::Format;
 use crate::models::chord::fmt::Formatting;
-use crate::models::meta::{BNotation, SemitoneNotation};
+use crate::models::meta::{BNotation, Capo, ChordNotation, SemitoneNotation};
 
 mod song_settings_map;
 
@@ -15,6 +15,9 @@ pub struct SongSettings {
     formatting: Formatting,
     #[serde(default)]
     note: String,
+    /// Name of the `Arrangement` the song is rendered in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    arrangement: Option<String>,
 }
 
 impl SongSettings {
@@ -27,6 +30,7 @@ impl SongSettings {
             transpose_semitone,
             formatting,
             note: note.into(),
+            arrangement: None,
         }
     }
     pub fn b_notation(&self) -> BNotation {
@@ -35,6 +39,12 @@ impl SongSettings {
     pub fn semitone_notation(&self) -> SemitoneNotation {
         self.formatting.semitone_notation
     }
+    pub fn chord_notation(&self) -> ChordNotation {
+        self.formatting.chord_notation
+    }
+    pub fn capo(&self) -> Option<Capo> {
+        self.formatting.capo
+    }
     pub fn format(&self) -> Format {
         self.formatting.format
     }
@@ -68,6 +78,17 @@ impl SongSettings {
 
         clone
     }
+
+    pub fn arrangement(&self) -> Option<&str> {
+        self.arrangement.as_deref()
+    }
+
+    pub fn with_arrangement(&self, arrangement: Option<String>) -> Self {
+        let mut clone = self.clone();
+        clone.arrangement = arrangement;
+
+        clone
+    }
 }
 
 impl Default for SongSettings {
@@ -75,3 +96,25 @@ impl Default for SongSettings {
         SongSettings::new(0, Formatting::default(), String::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_arrangement() {
+        let settings = SongSettings::default();
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(!json.contains("arrangement"));
+        assert_eq!(
+            serde_json::from_str::<SongSettings>(&json).unwrap(),
+            settings
+        );
+
+        let settings = settings.with_arrangement(Some("Short".to_owned()));
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains(r#""arrangement":"Short""#));
+        let deserialized = serde_json::from_str::<SongSettings>(&json).unwrap();
+        assert_eq!(deserialized.arrangement(), Some("Short"));
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::repeat_detector::RepeatDetector;
+
+use super::arrangement_error::ArrangementError;
+use super::section_identifier::SectionIdentifier;
+
+/// Named section order of a song (e.g. a short or an acoustic version)
+///
+/// Arrangements are declared in the song's header. The entries refer to the section titles and
+/// may contain repeats:
+///
+/// ```text
+/// Arrangement: Short = Verse 1, 2x Chorus, Outro
+/// ```
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct Arrangement {
+    name: String,
+    entries: Vec<ArrangementEntry>,
+}
+
+/// Section (and the number of times it is played) in an `Arrangement`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArrangementEntry {
+    /// Entry as written in the declaration (e.g. `2x Chorus`)
+    pub text: String,
+    pub identifier: SectionIdentifier,
+    pub count: usize,
+}
+
+impl Arrangement {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn entries(&self) -> &[ArrangementEntry] {
+        &self.entries
+    }
+
+    /// Return if the arrangement has the given name (case-insensitive)
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name.trim())
+    }
+}
+
+impl FromStr for ArrangementEntry {
+    type Err = ArrangementError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let text = value.trim();
+        let (identifier, count) = match RepeatDetector::new().detect(text) {
+            Ok(repeat) => (repeat.identifier, repeat.count),
+            Err(_) => (
+                SectionIdentifier::try_from(text)
+                    .map_err(|_| ArrangementError::InvalidEntry(text.to_owned()))?,
+                1,
+            ),
+        };
+
+        Ok(Self {
+            text: text.to_owned(),
+            identifier,
+            count,
+        })
+    }
+}
+
+impl FromStr for Arrangement {
+    type Err = ArrangementError;
+
+    /// Parse declarations like `Short = Verse 1, 2x Chorus, Outro`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, order) = value.split_once('=').unwrap_or((value, ""));
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ArrangementError::MissingName(value.trim().to_owned()));
+        }
+        let entries = order
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(ArrangementEntry::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if entries.is_empty() {
+            return Err(ArrangementError::Empty(name.to_owned()));
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            entries,
+        })
+    }
+}
+
+impl TryFrom<String> for Arrangement {
+    type Error = ArrangementError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl From<Arrangement> for String {
+    fn from(arrangement: Arrangement) -> Self {
+        arrangement.to_string()
+    }
+}
+
+impl Display for Arrangement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<&str> = self
+            .entries
+            .iter()
+            .map(|entry| entry.text.as_str())
+            .collect();
+        write!(f, "{} = {}", self.name, entries.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        let arrangement = Arrangement::from_str(" Short =Verse 1, 2x Chorus,Outro 3x").unwrap();
+        assert_eq!(arrangement.name(), "Short");
+        assert!(arrangement.is_named("short"));
+        let entries: Vec<(&str, usize)> = arrangement
+            .entries()
+            .iter()
+            .map(|entry| (entry.text.as_str(), entry.count))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![("Verse 1", 1), ("2x Chorus", 2), ("Outro 3x", 3)]
+        );
+        assert_eq!(
+            arrangement.entries()[1].identifier,
+            SectionIdentifier::try_from("Chorus").unwrap()
+        );
+        assert_eq!(
+            arrangement.to_string(),
+            "Short = Verse 1, 2x Chorus, Outro 3x"
+        );
+    }
+
+    #[test]
+    fn from_str_errors() {
+        assert_eq!(
+            Arrangement::from_str("= Verse 1"),
+            Err(ArrangementError::MissingName("= Verse 1".to_owned()))
+        );
+        assert_eq!(
+            Arrangement::from_str("Short = "),
+            Err(ArrangementError::Empty("Short".to_owned()))
+        );
+        assert_eq!(
+            Arrangement::from_str("Short = Verse, ✝"),
+            Err(ArrangementError::InvalidEntry("✝".to_owned()))
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let arrangement = Arrangement::from_str("Short = Verse 1, 2x Chorus").unwrap();
+        let json = serde_json::to_string(&arrangement).unwrap();
+        assert_eq!(json, r#""Short = Verse 1, 2x Chorus""#);
+        assert_eq!(
+            serde_json::from_str::<Arrangement>(&json).unwrap(),
+            arrangement
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArrangementError {
+    /// The declaration has no name (e.g. `= Verse 1, Chorus`)
+    MissingName(String),
+    /// The arrangement does not contain any section
+    Empty(String),
+    /// An entry of the section order can not be used as a section identifier
+    InvalidEntry(String),
+    /// The song does not declare an arrangement with the given name
+    NotFound(String),
+}
+
+impl Display for ArrangementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrangementError::MissingName(value) => {
+                write!(f, "Arrangement '{}' has no name", value)
+            }
+            ArrangementError::Empty(name) => {
+                write!(f, "Arrangement '{}' does not contain any section", name)
+            }
+            ArrangementError::InvalidEntry(entry) => {
+                write!(f, "Arrangement entry '{}' is not a valid section", entry)
+            }
+            ArrangementError::NotFound(name) => write!(f, "Arrangement '{}' not found", name),
+        }
+    }
+}
+
+impl Error for ArrangementError {}
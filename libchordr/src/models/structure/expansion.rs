@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+/// Enum defining how references (`> Chorus`) and repeats (`> 2x Chorus`) are rendered
+///
+/// Any other value than `Expansion::None` renders the song as a linear performance, in which the
+/// referenced sections are inlined
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Expansion {
+    /// References are rendered as written
+    #[default]
+    None,
+    /// Referenced sections are inlined once and annotated with the repeat count (`Chorus (2x)`)
+    Annotated,
+    /// Like `Annotated`, but consecutive plays of a section are collapsed into one annotated
+    /// section
+    Collapsed,
+    /// Every play of a section is rendered in full
+    Full,
+}
+
+impl Expansion {
+    pub fn get_all() -> &'static [Expansion] {
+        &[Self::None, Self::Annotated, Self::Collapsed, Self::Full]
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::None => "none",
+            Self::Annotated => "annotated",
+            Self::Collapsed => "collapsed",
+            Self::Full => "full",
+        }
+    }
+}
+
+impl TryFrom<&str> for Expansion {
+    type Error = ExpansionError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl FromStr for Expansion {
+    type Err = ExpansionError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "none" | "off" => Ok(Self::None),
+            "annotated" | "inline" => Ok(Self::Annotated),
+            "collapsed" | "collapse" => Ok(Self::Collapsed),
+            "full" => Ok(Self::Full),
+            _ => Err(ExpansionError(value.to_string())),
+        }
+    }
+}
+
+impl Display for Expansion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct ExpansionError(String);
+
+impl std::error::Error for ExpansionError {}
+
+impl Display for ExpansionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Invalid expansion '{}'", self.0)
+    }
+}
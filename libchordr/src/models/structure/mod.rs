@@ -1,3 +1,6 @@
+pub use arrangement::{Arrangement, ArrangementEntry};
+pub use arrangement_error::ArrangementError;
+pub use expansion::{Expansion, ExpansionError};
 pub(crate) use reference_resolver::ReferenceResolver;
 pub use section::Section;
 pub use section_identifier::SectionIdentifier;
@@ -5,6 +8,9 @@ pub use section_identifier_error::SectionIdentifierError;
 pub(crate) use section_provider::SectionProvider;
 pub use section_type::SectionType;
 
+mod arrangement;
+mod arrangement_error;
+mod expansion;
 mod reference_resolver;
 mod section;
 mod section_identifier;
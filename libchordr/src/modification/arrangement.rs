@@ -0,0 +1,162 @@
+use log::warn;
+
+use crate::models::structure::{Arrangement, Section, SectionIdentifier, SectionProvider};
+use crate::parser::{Node, ParserResult, SectionType};
+
+pub trait ArrangeableTrait {
+    /// Reorder the sections according to the `Arrangement`
+    fn arrange(self, arrangement: &Arrangement) -> Self;
+}
+
+impl ArrangeableTrait for Node {
+    /// Build a Document with the sections in the order of the `Arrangement`
+    ///
+    /// The title and the meta information before the first section are kept. A section is
+    /// rendered completely when it is played for the first time, further repeats are rendered as
+    /// references (e.g. `> 2x Chorus`). Entries that do not match a section are skipped
+    fn arrange(self, arrangement: &Arrangement) -> Self {
+        let children = match self {
+            Node::Document(children) => children,
+            node => return node,
+        };
+        let first_section = children
+            .iter()
+            .position(|child| SectionProvider::get_section(child).is_some())
+            .unwrap_or(children.len());
+        let sections: Vec<(Section, &Node)> = children
+            .iter()
+            .filter_map(|child| {
+                let section = SectionProvider::get_section(child)?;
+                if section.is_reference {
+                    None
+                } else {
+                    Some((section, child))
+                }
+            })
+            .collect();
+
+        let mut arranged = children[..first_section].to_vec();
+        let mut played: Vec<&SectionIdentifier> = vec![];
+        for entry in arrangement.entries() {
+            let (section, node) = match sections
+                .iter()
+                .find(|(section, _)| section.identifier == entry.identifier)
+            {
+                Some(found) => found,
+                None => {
+                    warn!(
+                        "Section '{}' of arrangement '{}' not found",
+                        entry.text,
+                        arrangement.name()
+                    );
+                    continue;
+                }
+            };
+
+            let mut count = entry.count;
+            if !played.contains(&&section.identifier) {
+                arranged.push((*node).clone());
+                played.push(&section.identifier);
+                count -= 1;
+            }
+            if count > 0 {
+                let text = if count == 1 {
+                    section.title.clone()
+                } else {
+                    format!("{}x {}", count, section.title)
+                };
+                arranged.push(Node::Section {
+                    head: Box::new(Node::quote(text)),
+                    section_type: SectionType::Reference,
+                    children: vec![],
+                });
+                arranged.push(Node::newline());
+            }
+        }
+
+        Node::Document(arranged)
+    }
+}
+
+impl ArrangeableTrait for ParserResult {
+    /// Reorder the sections of the parsed song
+    ///
+    /// The sections keep their source spans. The arranged song does not declare any arrangements
+    /// itself, because they may refer to skipped sections
+    fn arrange(self, arrangement: &Arrangement) -> Self {
+        let mut result = self.map_node(|node| node.arrange(arrangement));
+        result.meta.arrangements.clear();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::converter::{Converter, ConverterTrait};
+    use crate::format::Format;
+    use crate::helper::parse_content;
+    use crate::models::chord::fmt::Formatting;
+
+    const SONG: &str = "# Swing Low
+Arrangement: Short = Chorus, Verse 2, 2x Chorus
+
+##! Chorus
+Swing low
+
+## Verse 1
+I looked over Jordan
+
+## Verse 2
+If you get there before I do
+
+> Chorus
+";
+
+    fn arrange(declaration: &str) -> String {
+        let result = parse_content(SONG.as_bytes())
+            .unwrap()
+            .arrange(&Arrangement::from_str(declaration).unwrap());
+        Converter::new()
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting::with_format(Format::Chorddown),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn arrange_node() {
+        assert_eq!(
+            arrange("Short = Chorus, Verse 2, 2x Chorus, Outro"),
+            "# Swing Low
+
+##! Chorus
+Swing low
+
+## Verse 2
+If you get there before I do
+
+> 2x Chorus
+"
+        );
+    }
+
+    #[test]
+    fn arrange_node_with_repeat_on_first_occurrence() {
+        assert_eq!(
+            arrange("Loop = Chorus 3x"),
+            "# Swing Low
+
+##! Chorus
+Swing low
+
+> 2x Chorus
+"
+        );
+    }
+}
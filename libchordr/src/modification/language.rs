@@ -0,0 +1,170 @@
+use crate::models::meta::LanguageDisplay;
+use crate::parser::{Node, ParserResult};
+use crate::tokenizer::Token;
+
+pub trait LanguageSelectableTrait {
+    /// Select the languages of a song with translations
+    fn select_languages(self, language_display: LanguageDisplay) -> Self;
+}
+
+impl LanguageSelectableTrait for Node {
+    /// Remove the translations or replace the translated lyrics lines
+    ///
+    /// With `LanguageDisplay::Secondary` the chords of a translated line are dropped, because they
+    /// can not be mapped to the translation. Lines without a translation are kept
+    fn select_languages(self, language_display: LanguageDisplay) -> Self {
+        if language_display == LanguageDisplay::Interleaved {
+            return self;
+        }
+
+        match self {
+            Node::Document(children) => Node::Document(select_lines(children, language_display)),
+            Node::Section {
+                head,
+                section_type,
+                children,
+            } => Node::Section {
+                head,
+                section_type,
+                children: select_lines(children, language_display),
+            },
+            node => node,
+        }
+    }
+}
+
+impl LanguageSelectableTrait for ParserResult {
+    /// Select the languages of the parsed song
+    ///
+    /// Lines replaced by their translation have no source span
+    fn select_languages(self, language_display: LanguageDisplay) -> Self {
+        if language_display == LanguageDisplay::Interleaved {
+            return self;
+        }
+
+        self.map_node(|node| node.select_languages(language_display))
+    }
+}
+
+/// Filter the lines of the children of a Document or Section
+fn select_lines(children: Vec<Node>, language_display: LanguageDisplay) -> Vec<Node> {
+    let mut lines: Vec<Vec<Node>> = vec![];
+    for line in split_lines(children) {
+        let translation = match line.as_slice() {
+            [Node::Translation(Token::Translation(text))]
+            | [Node::Translation(Token::Translation(text)), Node::Newline] => text.clone(),
+            _ => {
+                lines.push(
+                    line.into_iter()
+                        .map(|child| child.select_languages(language_display))
+                        .collect(),
+                );
+                continue;
+            }
+        };
+
+        if language_display == LanguageDisplay::Secondary {
+            match lines.last_mut() {
+                Some(previous) if is_lyrics_line(previous) => {
+                    let newline = previous.last() == Some(&Node::Newline);
+                    *previous = vec![Node::text(translation)];
+                    if newline {
+                        previous.push(Node::newline());
+                    }
+                }
+                _ => lines.push(line),
+            }
+        }
+    }
+
+    lines.into_iter().flatten().collect()
+}
+
+/// Split the nodes into lines (including the terminating `Newline`)
+fn split_lines(children: Vec<Node>) -> Vec<Vec<Node>> {
+    let mut lines = vec![];
+    let mut line = vec![];
+    for child in children {
+        let is_newline = child == Node::Newline;
+        line.push(child);
+        if is_newline {
+            lines.push(std::mem::take(&mut line));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+fn is_lyrics_line(line: &[Node]) -> bool {
+    line.iter().any(|node| {
+        matches!(
+            node,
+            Node::ChordTextPair { .. } | Node::ChordStandalone(_) | Node::Text(_)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::{Converter, ConverterTrait};
+    use crate::format::Format;
+    use crate::helper::parse_content;
+    use crate::models::chord::fmt::Formatting;
+
+    const SONG: &str = "# Stille Nacht
+Language: German, English
+
+## Verse 1
+[D]Stille Nacht, [A]heilige Nacht
+~ Silent night, holy night
+Alles schläft, einsam wacht
+
+##! Chorus
+Schlaf in himmlischer Ruh
+~ Sleep in heavenly peace
+";
+
+    fn convert(language_display: LanguageDisplay) -> String {
+        let result = parse_content(SONG.as_bytes())
+            .unwrap()
+            .select_languages(language_display);
+        Converter::new()
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting::with_format(Format::Text),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn select_primary_language() {
+        let output = convert(LanguageDisplay::Primary);
+        assert!(output.contains("Stille Nacht, "));
+        assert!(output.contains("Alles schläft, einsam wacht"));
+        assert!(output.contains("Schlaf in himmlischer Ruh"));
+        assert!(!output.contains("Silent night"));
+        assert!(!output.contains("Sleep in heavenly peace"));
+    }
+
+    #[test]
+    fn select_secondary_language() {
+        let output = convert(LanguageDisplay::Secondary);
+        assert!(output.contains("Silent night, holy night"));
+        assert!(output.contains("Alles schläft, einsam wacht"));
+        assert!(output.contains("Sleep in heavenly peace"));
+        assert!(!output.contains("Stille Nacht, "));
+        assert!(!output.contains("Schlaf in himmlischer Ruh"));
+    }
+
+    #[test]
+    fn select_interleaved_languages() {
+        let output = convert(LanguageDisplay::Interleaved);
+        assert!(output.contains("Silent night, holy night"));
+        assert!(output.contains("heilige Nacht"));
+    }
+}
@@ -1 +1,4 @@
+pub mod arrangement;
+pub mod language;
+pub mod performance;
 pub mod transposition;
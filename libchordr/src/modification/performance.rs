@@ -0,0 +1,322 @@
+use crate::models::structure::{Expansion, ReferenceResolver, Section, SectionProvider};
+use crate::parser::{Node, ParserResult};
+use crate::repeat_detector::RepeatDetector;
+use crate::tokenizer::Token;
+
+pub trait ExpandableTrait {
+    /// Render references and repeats as a linear performance
+    fn expand(self, expansion: Expansion) -> Self;
+}
+
+/// Section played in a performance
+#[derive(Debug)]
+pub(crate) struct Play<'a> {
+    /// Played section (for references the referenced section)
+    pub section: Section,
+    pub node: &'a Node,
+    /// Number of times the section is played in a row
+    pub count: usize,
+}
+
+enum Item<'a> {
+    Play(Play<'a>),
+    Other(&'a Node),
+}
+
+impl ExpandableTrait for Node {
+    /// Inline the referenced sections of the Document
+    ///
+    /// References that can not be resolved are kept as written
+    fn expand(self, expansion: Expansion) -> Self {
+        let children = match (expansion, &self) {
+            (Expansion::None, _) => return self,
+            (_, Node::Document(children)) => children,
+            _ => return self,
+        };
+
+        let mut items = resolve_items(children);
+        if expansion == Expansion::Collapsed {
+            items = collapse(items);
+        }
+
+        let mut expanded = vec![];
+        for item in items {
+            match item {
+                Item::Other(node) => expanded.push(node.clone()),
+                Item::Play(play) if play.count == 1 => expanded.push(play.node.clone()),
+                Item::Play(play) if expansion == Expansion::Full => {
+                    expanded.extend(std::iter::repeat_n(play.node.clone(), play.count))
+                }
+                Item::Play(play) => expanded.push(annotate(play.node, play.count)),
+            }
+        }
+
+        Node::Document(expanded)
+    }
+}
+
+impl ExpandableTrait for ParserResult {
+    /// Render the parsed song as a linear performance
+    ///
+    /// Inlined sections share the source spans of the referenced section
+    fn expand(self, expansion: Expansion) -> Self {
+        if expansion == Expansion::None {
+            return self;
+        }
+
+        self.map_node(|node| node.expand(expansion))
+    }
+}
+
+/// Return the sections of the Document in the order they are played
+///
+/// References are replaced with the referenced sections and the repeat count (e.g. `> 2x Chorus`)
+pub(crate) fn performance(node: &Node) -> Vec<Play<'_>> {
+    match node {
+        Node::Document(children) => resolve_items(children)
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Play(play) => Some(play),
+                Item::Other(_) => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn resolve_items(children: &[Node]) -> Vec<Item<'_>> {
+    let (targets, target_nodes): (Vec<Section>, Vec<&Node>) = children
+        .iter()
+        .filter_map(|child| {
+            let section = SectionProvider::get_section(child)?;
+            if section.is_reference {
+                None
+            } else {
+                Some((section, child))
+            }
+        })
+        .unzip();
+
+    let resolver = ReferenceResolver::new();
+    let mut items = vec![];
+    let mut previous_was_inlined = false;
+    for child in children {
+        // The parser adds a newline after each reference, which is not needed for inlined sections
+        if previous_was_inlined && matches!(child, Node::Newline) {
+            previous_was_inlined = false;
+            continue;
+        }
+        previous_was_inlined = false;
+
+        let section = match SectionProvider::get_section(child) {
+            Some(section) => section,
+            None => {
+                items.push(Item::Other(child));
+                continue;
+            }
+        };
+        if !section.is_reference {
+            items.push(Item::Play(Play {
+                section,
+                node: child,
+                count: 1,
+            }));
+            continue;
+        }
+
+        let target = resolver
+            .resolve_reference(&section, &targets)
+            .and_then(|target| targets.iter().position(|t| std::ptr::eq(t, target)));
+        match target {
+            Some(index) => {
+                let target = &targets[index];
+                let count = if target.identifier == section.identifier {
+                    1
+                } else {
+                    RepeatDetector::new()
+                        .detect(&section.title)
+                        .map_or(1, |repeat| repeat.count.max(1))
+                };
+                items.push(Item::Play(Play {
+                    section: target.clone(),
+                    node: target_nodes[index],
+                    count,
+                }));
+                previous_was_inlined = true;
+            }
+            None => items.push(Item::Other(child)),
+        }
+    }
+
+    items
+}
+
+/// Merge consecutive plays of the same section
+fn collapse(items: Vec<Item<'_>>) -> Vec<Item<'_>> {
+    let mut collapsed: Vec<Item> = vec![];
+    for item in items {
+        if let (Item::Play(play), Some(Item::Play(previous))) = (&item, collapsed.last_mut()) {
+            if previous.section.identifier == play.section.identifier {
+                previous.count += play.count;
+                continue;
+            }
+        }
+        collapsed.push(item);
+    }
+
+    collapsed
+}
+
+/// Return a copy of the section with the repeat count appended to the headline
+fn annotate(node: &Node, count: usize) -> Node {
+    match node {
+        Node::Section {
+            head,
+            section_type,
+            children,
+        } => {
+            let head = match head.as_ref() {
+                Node::Headline(Token::Headline {
+                    level,
+                    text,
+                    modifier,
+                }) => Node::headline(*level, format!("{} ({}x)", text, count), *modifier),
+                head => head.clone(),
+            };
+
+            Node::Section {
+                head: Box::new(head),
+                section_type: *section_type,
+                children: children.clone(),
+            }
+        }
+        node => node.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::{Converter, ConverterTrait};
+    use crate::format::Format;
+    use crate::helper::parse_content;
+    use crate::models::chord::fmt::Formatting;
+
+    const SONG: &str = "# Swing Low
+
+##! Chorus
+Swing low
+
+## Verse 1
+I looked over Jordan
+
+> Chorus
+
+> 2x Chorus
+
+> Bridge
+";
+
+    fn expand(expansion: Expansion) -> String {
+        let result = parse_content(SONG.as_bytes()).unwrap().expand(expansion);
+        Converter::new()
+            .convert(
+                result.node_as_ref(),
+                result.meta_as_ref(),
+                Formatting::with_format(Format::Chorddown),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn expand_none() {
+        assert_eq!(expand(Expansion::None), SONG);
+    }
+
+    #[test]
+    fn expand_annotated() {
+        assert_eq!(
+            expand(Expansion::Annotated),
+            "# Swing Low
+
+##! Chorus
+Swing low
+
+## Verse 1
+I looked over Jordan
+
+##! Chorus
+Swing low
+
+##! Chorus (2x)
+Swing low
+
+> Bridge
+"
+        );
+    }
+
+    #[test]
+    fn expand_collapsed() {
+        assert_eq!(
+            expand(Expansion::Collapsed),
+            "# Swing Low
+
+##! Chorus
+Swing low
+
+## Verse 1
+I looked over Jordan
+
+##! Chorus (3x)
+Swing low
+
+> Bridge
+"
+        );
+    }
+
+    #[test]
+    fn expand_full() {
+        assert_eq!(
+            expand(Expansion::Full),
+            "# Swing Low
+
+##! Chorus
+Swing low
+
+## Verse 1
+I looked over Jordan
+
+##! Chorus
+Swing low
+
+##! Chorus
+Swing low
+
+##! Chorus
+Swing low
+
+> Bridge
+"
+        );
+    }
+
+    #[test]
+    fn performance_order() {
+        let result = parse_content(SONG.as_bytes()).unwrap();
+        let plays: Vec<(String, usize)> = performance(result.node_as_ref())
+            .into_iter()
+            .map(|play| (play.section.title, play.count))
+            .collect();
+        assert_eq!(
+            plays,
+            vec![
+                ("Chorus".to_owned(), 1),
+                ("Verse 1".to_owned(), 1),
+                ("Chorus".to_owned(), 1),
+                ("Chorus".to_owned(), 2),
+            ]
+        );
+    }
+}
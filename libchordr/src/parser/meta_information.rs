@@ -1,6 +1,8 @@
 use crate::models::chord::Chord;
+use crate::models::diagram::ChordDefinition;
 use crate::models::meta::b_notation::BNotation;
-use crate::models::meta::{MetaTrait, Tags};
+use crate::models::meta::{CustomFields, MetaTrait, Tags};
+use crate::models::structure::Arrangement;
 use crate::modification::transposition::TransposableTrait;
 use crate::tokenizer::Meta;
 
@@ -28,9 +30,22 @@ pub struct MetaInformation {
     pub(crate) ccli_song_id: Option<String>,
     pub(crate) b_notation: BNotation,
     pub(crate) tags: Tags,
+    pub(crate) language: Option<String>,
+    pub(crate) chord_definitions: Vec<ChordDefinition>,
+    pub(crate) custom_fields: CustomFields,
+    pub(crate) arrangements: Vec<Arrangement>,
 }
 
 impl MetaInformation {
+    /// Use the given title (e.g. the file name) if the song does not define one
+    pub fn with_default_title<S: Into<String>>(mut self, title: S) -> Self {
+        if self.title.is_none() {
+            self.title = Some(title.into());
+        }
+
+        self
+    }
+
     /// Copy the Meta content into the appropriate field
     pub(crate) fn assign_from_token(&mut self, t: &Meta) {
         match t {
@@ -52,6 +67,10 @@ impl MetaInformation {
             Meta::OriginalKey(content) => self.set_original_key(content.clone()),
             Meta::BNotation(notation) => self.b_notation = *notation,
             Meta::Tags(content) => self.tags = content.clone(),
+            Meta::Language(content) => self.language = Some(content.clone()),
+            Meta::Define(content) => self.add_chord_definition(content),
+            Meta::Arrangement(content) => self.add_arrangement(content),
+            Meta::Custom(keyword, content) => self.custom_fields.insert(keyword, content),
         }
     }
 
@@ -65,6 +84,20 @@ impl MetaInformation {
         }
     }
 
+    fn add_chord_definition(&mut self, content: &str) {
+        match content.parse() {
+            Ok(definition) => self.chord_definitions.push(definition),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
+    fn add_arrangement(&mut self, content: &str) {
+        match content.parse::<Arrangement>() {
+            Ok(arrangement) => self.arrangements.push(arrangement),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
     fn set_key(&mut self, content: String) {
         self.key = Chord::try_from(&content, self.b_notation).ok();
         self.key_raw = Some(content);
@@ -152,6 +185,22 @@ impl MetaTrait for MetaInformation {
     fn tags(&self) -> Tags {
         self.tags.clone()
     }
+
+    fn language(&self) -> Option<String> {
+        self.language.as_ref().cloned()
+    }
+
+    fn chord_definitions(&self) -> Vec<ChordDefinition> {
+        self.chord_definitions.clone()
+    }
+
+    fn custom_fields(&self) -> CustomFields {
+        self.custom_fields.clone()
+    }
+
+    fn arrangements(&self) -> Vec<Arrangement> {
+        self.arrangements.clone()
+    }
 }
 
 impl TransposableTrait for MetaInformation {
@@ -19,6 +19,13 @@ impl MetaParser {
     }
 
     pub fn parse_borrowed(&mut self, tokens: &[Token]) -> Result<MetaInformation, Error> {
+        self.parse_iter(tokens.iter())
+    }
+
+    pub fn parse_iter<'a>(
+        &mut self,
+        tokens: impl Iterator<Item = &'a Token>,
+    ) -> Result<MetaInformation, Error> {
         let mut meta = MetaInformation::default();
         for token in tokens {
             meta = self.visit(token, meta);
@@ -1,10 +1,10 @@
 use crate::error::Error;
 use crate::models::meta::*;
-use crate::parser::meta_parser::MetaParser;
 use crate::parser::node_parser::NodeParser;
-use crate::tokenizer::Token;
+use crate::tokenizer::{SpannedToken, Token};
 
 pub use self::meta_information::MetaInformation;
+pub(crate) use self::meta_parser::MetaParser;
 pub use self::node::Node;
 pub use self::parser_result::ParserResult;
 pub use self::section_type::SectionType;
@@ -30,42 +30,61 @@ impl Parser {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// Parse the given tokens and keep track of their source spans
+    ///
+    /// The spans of the Nodes can be looked up with `ParserResult::span_of()` and errors are
+    /// reported with the span of the offending token
+    pub fn parse_with_spans(&mut self, tokens: Vec<SpannedToken>) -> Result<ParserResult, Error> {
+        let meta = MetaParser::new().parse_iter(tokens.iter().map(|(token, _)| token))?;
+        let mut node_parser = NodeParser::with_b_notation(meta.b_notation);
+        let node = node_parser.parse_with_spans(tokens)?;
+
+        Ok(ParserResult::new(node, meta).with_spans(node_parser.take_spans()))
+    }
 }
 
 impl ParserTrait for Parser {
     type OkType = ParserResult;
 
     fn parse(&mut self, tokens: Vec<Token>) -> Result<ParserResult, Error> {
-        let meta = MetaParser::new().parse_borrowed(&tokens)?;
-        let node = NodeParser::with_b_notation(meta.b_notation).parse(tokens)?;
-
-        Ok(ParserResult::new(node, meta))
+        self.parse_with_spans(tokens.into_iter().map(|token| (token, None)).collect())
     }
 }
 
 impl Parser {
     /// Remove leading and duplicate Newline tokens from the stream
     pub fn cleanup_tokens(tokens: Vec<Token>) -> Vec<Token> {
-        // Initialize to `true` so that leading Newline tokens will be skipped
-        let mut previous_token_was_newline: bool = true;
-
-        tokens
-            .into_iter()
-            .filter(|token| {
-                // Skip multiple Newline tokens
-                if *token == Token::Newline && previous_token_was_newline {
-                    false
-                } else {
-                    previous_token_was_newline = *token == Token::Newline;
-                    true
-                }
-            })
-            .collect()
+        remove_duplicate_newlines(tokens, |token| *token == Token::Newline)
+    }
+
+    /// Remove leading and duplicate Newline tokens from the stream of tokens with spans
+    pub fn cleanup_spanned_tokens(tokens: Vec<SpannedToken>) -> Vec<SpannedToken> {
+        remove_duplicate_newlines(tokens, |(token, _)| *token == Token::Newline)
     }
 }
 
+fn remove_duplicate_newlines<T>(tokens: Vec<T>, is_newline: impl Fn(&T) -> bool) -> Vec<T> {
+    // Initialize to `true` so that leading Newline tokens will be skipped
+    let mut previous_token_was_newline: bool = true;
+
+    tokens
+        .into_iter()
+        .filter(|token| {
+            // Skip multiple Newline tokens
+            if is_newline(token) && previous_token_was_newline {
+                false
+            } else {
+                previous_token_was_newline = is_newline(token);
+                true
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::span::Span;
     use crate::test_helpers::{
         get_test_ast, get_test_ast_small, get_test_tokens, get_test_tokens_small,
     };
@@ -107,6 +126,35 @@ mod tests {
         assert_eq!(parser_result.node(), get_test_ast());
     }
 
+    #[test]
+    fn test_parse_translation() {
+        let mut parser = Parser::new();
+        let result = parser.parse(vec![
+            Token::headline(2, "Verse", Modifier::None),
+            Token::Newline,
+            Token::literal("Swing low"),
+            Token::Newline,
+            Token::translation("Schwing tief"),
+            Token::Newline,
+        ]);
+
+        assert_eq!(
+            result.unwrap().node(),
+            Node::Document(vec![Node::section(
+                2,
+                "Verse",
+                Modifier::None,
+                vec![
+                    Node::newline(),
+                    Node::text("Swing low"),
+                    Node::newline(),
+                    Node::translation("Schwing tief"),
+                    Node::newline(),
+                ]
+            )])
+        );
+    }
+
     #[test]
     fn test_detect_b_notation() {
         let mut parser = Parser::new();
@@ -220,4 +268,142 @@ mod tests {
             vec![Token::chord("H"), Token::newline(),]
         );
     }
+
+    #[test]
+    fn test_parse_with_spans() {
+        use crate::span::Position;
+        use crate::tokenizer::{build_tokenizer, Tokenizer};
+
+        let content = "# Song\n\n## Verse\nSwing [D]low\n";
+        let (tokens, _) = build_tokenizer()
+            .tokenize_with_spans(content.as_bytes())
+            .unwrap();
+        let result = Parser::new().parse_with_spans(tokens).unwrap();
+        let span = |start: (usize, usize), end: (usize, usize)| {
+            Some(Span::new(
+                Position::new(start.0, start.1),
+                Position::new(end.0, end.1),
+            ))
+        };
+
+        let document = result.node_as_ref();
+        assert_eq!(result.span_of(document), span((1, 1), (4, 14)));
+        let verse = match document {
+            Node::Document(children) => &children[1],
+            _ => unreachable!(),
+        };
+        assert_eq!(result.span_of(verse), span((3, 1), (4, 14)));
+        if let Node::Section { head, children, .. } = verse {
+            assert_eq!(result.span_of(head), span((3, 1), (3, 9)));
+            assert_eq!(result.span_of(&children[1]), span((4, 1), (4, 7)));
+            // A chord and its text form one Node
+            assert_eq!(result.span_of(&children[2]), span((4, 7), (4, 13)));
+        } else {
+            panic!("Expected a section, got {:?}", verse);
+        }
+
+        // Nodes outside of the tree have no span
+        assert_eq!(result.span_of(&Node::Newline), None);
+    }
+
+    #[test]
+    fn test_spans_after_transformations() {
+        use crate::models::meta::LanguageDisplay;
+        use crate::models::structure::Expansion;
+        use crate::modification::arrangement::ArrangeableTrait;
+        use crate::modification::language::LanguageSelectableTrait;
+        use crate::modification::performance::ExpandableTrait;
+        use crate::span::Position;
+        use crate::tokenizer::{build_tokenizer, Tokenizer};
+
+        let content = "# Song\nArrangement: Short = Chorus, Verse\n\n## Verse\nSwing [D]low\n~ Schwing tief\n\n##! Chorus\nSweet [G]chariot\n\n> Chorus\n";
+        let parse = || {
+            let (tokens, _) = build_tokenizer()
+                .tokenize_with_spans(content.as_bytes())
+                .unwrap();
+            Parser::new().parse_with_spans(tokens).unwrap()
+        };
+        let line_of = |result: &ParserResult, node: &Node| {
+            result.span_of(node).map(|span| span.start().line())
+        };
+        fn sections(result: &ParserResult) -> Vec<&Node> {
+            match result.node_as_ref() {
+                Node::Document(children) => children
+                    .iter()
+                    .filter(|child| matches!(child, Node::Section { .. }))
+                    .collect(),
+                _ => unreachable!(),
+            }
+        }
+        let section_lines = |result: &ParserResult| match result.node_as_ref() {
+            Node::Document(children) => children
+                .iter()
+                .filter(|child| matches!(child, Node::Section { .. }))
+                .map(|child| line_of(result, child))
+                .collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+
+        let parsed = parse();
+        assert_eq!(
+            section_lines(&parsed),
+            [Some(1), Some(4), Some(8), Some(11)]
+        );
+
+        // Arranged sections keep the spans of their source
+        let arrangement = parsed.meta.arrangement("Short").unwrap();
+        let arranged = parse().arrange(&arrangement);
+        assert_eq!(section_lines(&arranged), [Some(1), Some(8), Some(4)]);
+
+        // The inlined Chorus shares the span of the referenced section
+        let expanded = parse().expand(Expansion::Full);
+        assert_eq!(
+            section_lines(&expanded),
+            [Some(1), Some(4), Some(8), Some(8)]
+        );
+        if let Node::Section { head, children, .. } = sections(&expanded)[3] {
+            let chord = children
+                .iter()
+                .find(|child| matches!(child, Node::ChordTextPair { .. }))
+                .unwrap();
+            assert_eq!(
+                expanded.span_of(chord),
+                Some(Span::new(Position::new(9, 7), Position::new(9, 17)))
+            );
+            assert_eq!(line_of(&expanded, head), Some(8));
+        }
+
+        // Replaced lines have no span, while the other Nodes keep theirs
+        let secondary = parse().select_languages(LanguageDisplay::Secondary);
+        assert_eq!(
+            section_lines(&secondary),
+            [Some(1), Some(4), Some(8), Some(11)]
+        );
+        if let Node::Section { head, children, .. } = sections(&secondary)[1] {
+            assert_eq!(line_of(&secondary, head), Some(4));
+            let translated = children
+                .iter()
+                .find(|child| **child == Node::text("Schwing tief"))
+                .unwrap();
+            assert_eq!(line_of(&secondary, translated), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_with_spans_error() {
+        use crate::span::Position;
+
+        let tokens = vec![
+            (Token::literal("Swing "), None),
+            (
+                Token::chord("Xm"),
+                Some(Span::new(Position::new(3, 7), Position::new(3, 11))),
+            ),
+        ];
+        let error = Parser::new().parse_with_spans(tokens).err().unwrap();
+        assert_eq!(
+            error.span(),
+            Some(Span::new(Position::new(3, 7), Position::new(3, 11)))
+        );
+    }
 }
@@ -22,6 +22,8 @@ pub enum Node {
     Document(Vec<Node>),
     Headline(Token),
     Quote(Token),
+    /// Translation of the previous lyrics line
+    Translation(Token),
     Section {
         head: Box<Node>,
         section_type: SectionType,
@@ -104,6 +106,11 @@ impl Node {
         Node::Text(Token::literal(value.into()))
     }
 
+    #[allow(unused)]
+    pub(crate) fn translation<S: Into<String>>(value: S) -> Self {
+        Node::Translation(Token::translation(value))
+    }
+
     pub(crate) fn quote<S: Into<String>>(value: S) -> Self {
         Node::Quote(Token::quote(value))
     }
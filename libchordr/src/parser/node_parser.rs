@@ -2,101 +2,154 @@ use std::iter::Peekable;
 use std::vec::IntoIter;
 
 use crate::models::chord::Chords;
-use crate::tokenizer::Token;
+use crate::span::Span;
+use crate::tokenizer::{SpannedToken, Token};
 
 pub use super::node::Node;
 pub use super::section_type::SectionType;
 pub use super::*;
 
+type SpannedTokens = Peekable<IntoIter<SpannedToken>>;
+
 pub struct NodeParser {
     b_notation: BNotation,
+    /// Source spans of the parsed Nodes in pre-order
+    spans: Vec<Option<Span>>,
 }
 
 impl ParserTrait for NodeParser {
     type OkType = Node;
 
     fn parse(&mut self, tokens: Vec<Token>) -> Result<Self::OkType, Error> {
-        let mut tokens_iterator = Parser::cleanup_tokens(tokens).into_iter().peekable();
+        self.parse_with_spans(tokens.into_iter().map(|token| (token, None)).collect())
+    }
+}
+
+impl NodeParser {
+    pub fn with_b_notation(b_notation: BNotation) -> Self {
+        Self {
+            b_notation,
+            spans: vec![],
+        }
+    }
 
+    /// Parse the given tokens and record the source span of each Node
+    pub fn parse_with_spans(&mut self, tokens: Vec<SpannedToken>) -> Result<Node, Error> {
+        let mut tokens_iterator = Parser::cleanup_spanned_tokens(tokens)
+            .into_iter()
+            .peekable();
+
+        self.spans.clear();
+        let document_index = self.reserve_span();
+        let mut document_span = None;
         let mut elements = vec![];
 
         while let Some(token) = tokens_iterator.next() {
-            elements.push(self.visit(token, &mut tokens_iterator)?);
+            let (node, span) = self.visit(token, &mut tokens_iterator)?;
+            document_span = merge_spans(document_span, span);
+            elements.push(node);
         }
+        self.spans[document_index] = document_span;
 
         Ok(Node::Document(elements))
     }
-}
 
-impl NodeParser {
-    pub fn with_b_notation(b_notation: BNotation) -> Self {
-        Self { b_notation }
+    /// Return the spans recorded by the last call to `parse_with_spans()`
+    pub fn take_spans(&mut self) -> Vec<Option<Span>> {
+        std::mem::take(&mut self.spans)
+    }
+
+    /// Reserve the slot for the span of the next Node in pre-order
+    fn reserve_span(&mut self) -> usize {
+        self.spans.push(None);
+        self.spans.len() - 1
     }
 
     fn visit(
         &mut self,
-        token: Token,
-        tokens: &mut Peekable<IntoIter<Token>>,
-    ) -> Result<Node, Error> {
+        spanned_token: (Token, Option<Span>),
+        tokens: &mut SpannedTokens,
+    ) -> Result<(Node, Option<Span>), Error> {
+        let (token, span) = spanned_token;
         log::trace!("Visit token: {:?}", token);
-        match token {
-            Token::Chord(_) => self.visit_chord(token, tokens),
+        let index = self.reserve_span();
+        let (node, span) = match token {
+            Token::Chord(_) => self.visit_chord(token, span, tokens)?,
             Token::Headline {
                 level: _,
                 text: _,
                 modifier,
             } => {
+                let head_index = self.reserve_span();
+                self.spans[head_index] = span;
                 let head = Box::new(Node::Headline(token));
 
-                if tokens.peek().is_some() {
-                    let children = self.consume_children(tokens)?;
+                let mut section_span = span;
+                let children = if tokens.peek().is_some() {
+                    let (children, children_span) = self.consume_children(tokens)?;
+                    section_span = merge_spans(section_span, children_span);
+                    children
+                } else {
+                    vec![]
+                };
 
-                    Ok(Node::Section {
+                (
+                    Node::Section {
                         head,
                         children,
                         section_type: modifier.into(),
-                    })
-                } else {
-                    Ok(Node::Section {
-                        head,
+                    },
+                    section_span,
+                )
+            }
+            Token::Meta(meta) => (Node::Meta(meta), span),
+            Token::Literal(_) => (Node::Text(token), span),
+            Token::Translation(_) => (Node::Translation(token), span),
+            Token::Quote(_) => {
+                let head_index = self.reserve_span();
+                self.spans[head_index] = span;
+
+                (
+                    Node::Section {
+                        head: Box::new(Node::Quote(token)),
                         children: vec![],
-                        section_type: modifier.into(),
-                    })
-                }
+                        section_type: SectionType::Reference,
+                    },
+                    span,
+                )
             }
-            Token::Meta(meta) => Ok(Node::Meta(meta)),
-            Token::Literal(_) => Ok(Node::Text(token)),
-            Token::Quote(_) => Ok(Node::Section {
-                head: Box::new(Node::Quote(token)),
-                children: vec![],
-                section_type: SectionType::Reference,
-            }),
-            Token::Newline => Ok(Node::Newline),
-        }
+            Token::Newline => (Node::Newline, span),
+        };
+        self.spans[index] = span;
+
+        Ok((node, span))
     }
 
     /// Collect children while there is an upcoming token and it isn't the start of a new section
     fn consume_children(
         &mut self,
-        tokens: &mut Peekable<IntoIter<Token>>,
-    ) -> Result<Vec<Node>, Error> {
+        tokens: &mut SpannedTokens,
+    ) -> Result<(Vec<Node>, Option<Span>), Error> {
         let mut children = vec![];
+        let mut children_span = None;
 
-        while let Some(token) = tokens.peek() {
+        while let Some((token, _)) = tokens.peek() {
             if token_is_start_of_section(token) {
                 break;
             }
-            let result = self.visit(tokens.next().unwrap(), tokens)?;
-            children.push(result);
+            let (node, span) = self.visit(tokens.next().unwrap(), tokens)?;
+            children_span = merge_spans(children_span, span);
+            children.push(node);
         }
-        Ok(children)
+        Ok((children, children_span))
     }
 
     fn visit_chord(
         &mut self,
         token: Token,
-        tokens: &mut Peekable<IntoIter<Token>>,
-    ) -> Result<Node, Error> {
+        span: Option<Span>,
+        tokens: &mut SpannedTokens,
+    ) -> Result<(Node, Option<Span>), Error> {
         let chords_raw = if let Token::Chord(c) = token {
             c
         } else {
@@ -107,9 +160,10 @@ impl NodeParser {
         let chord_result = Chords::try_from(&chords_raw, self.b_notation);
 
         let chords = match chord_result {
+            Err(error) if span.is_some() => return Err(error.with_span(span)),
             Err(error) => {
                 return match tokens.peek() {
-                    Some(next) => Err(Error::chord_error(format!(
+                    Some((next, _)) => Err(Error::chord_error(format!(
                         "{} (before token {:?})",
                         error, next
                     ))),
@@ -120,20 +174,31 @@ impl NodeParser {
             Ok(chords) => chords,
         };
 
-        if let Some(Token::Literal(_)) = tokens.peek() {
+        if let Some((Token::Literal(_), _)) = tokens.peek() {
             // Consume the next token
-            let text = tokens.next().unwrap();
-
-            let last_in_line = matches!(tokens.peek(), Some(Token::Newline));
-
-            return Ok(Node::ChordTextPair {
-                chords,
-                text,
-                last_in_line,
-            });
+            let (text, text_span) = tokens.next().unwrap();
+
+            let last_in_line = matches!(tokens.peek(), Some((Token::Newline, _)));
+
+            return Ok((
+                Node::ChordTextPair {
+                    chords,
+                    text,
+                    last_in_line,
+                },
+                merge_spans(span, text_span),
+            ));
         }
 
-        Ok(Node::ChordStandalone(chords))
+        Ok((Node::ChordStandalone(chords), span))
+    }
+}
+
+fn merge_spans(a: Option<Span>, b: Option<Span>) -> Option<Span> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.to(b)),
+        (a, None) => a,
+        (None, b) => b,
     }
 }
 
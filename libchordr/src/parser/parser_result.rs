@@ -1,13 +1,56 @@
+use crate::error::Result;
+use crate::models::structure::ArrangementError;
+use crate::modification::arrangement::ArrangeableTrait;
+use crate::span::Span;
+
 use super::{MetaInformation, Node};
 
 pub struct ParserResult {
     pub meta: MetaInformation,
     pub node: Node,
+    /// Source spans of the Nodes in pre-order
+    spans: Vec<Option<Span>>,
 }
 
 impl ParserResult {
     pub fn new(node: Node, meta: MetaInformation) -> Self {
-        Self { meta, node }
+        Self {
+            meta,
+            node,
+            spans: vec![],
+        }
+    }
+
+    /// Attach the source spans of the Nodes (in pre-order)
+    pub(crate) fn with_spans(self, spans: Vec<Option<Span>>) -> Self {
+        Self { spans, ..self }
+    }
+
+    /// Return the spans of the Nodes (in pre-order)
+    pub(crate) fn spans(&self) -> &[Option<Span>] {
+        &self.spans
+    }
+
+    /// Transform the Node tree and carry the source spans over to the transformed tree
+    ///
+    /// Nodes are matched with the Nodes of the original tree by their content. Copied Nodes (e.g.
+    /// the inlined sections of an expanded song) share the span of their source. Nodes created by
+    /// the transformation (e.g. annotated headlines or translated lines) have no span
+    pub(crate) fn map_node<F: FnOnce(Node) -> Node>(self, transform: F) -> Self {
+        let node = transform(self.node.clone());
+        let spans = if self.spans.len() == node_count(&self.node) {
+            let mut spans = vec![];
+            transfer_spans(&node, Some((&self.node, &self.spans)), &mut spans);
+            spans
+        } else {
+            vec![]
+        };
+
+        Self {
+            meta: self.meta,
+            node,
+            spans,
+        }
     }
 
     pub fn node(self) -> Node {
@@ -25,4 +68,164 @@ impl ParserResult {
     pub fn meta_as_ref(&self) -> &MetaInformation {
         &self.meta
     }
+
+    /// Reorder the sections according to the song's arrangement with the given name
+    pub fn with_arrangement(self, name: &str) -> Result<Self> {
+        let arrangement = self
+            .meta
+            .arrangements
+            .iter()
+            .find(|arrangement| arrangement.is_named(name))
+            .cloned()
+            .ok_or_else(|| ArrangementError::NotFound(name.to_owned()))?;
+
+        Ok(self.arrange(&arrangement))
+    }
+
+    /// Return the source span of the given Node
+    ///
+    /// `node` must be a reference into this result's Node tree. `None` is returned if the Node
+    /// is not part of the tree or the source positions are unknown.
+    ///
+    /// The spans are only recorded by the chorddown tokenizer. They survive arranging, expanding
+    /// and selecting the languages, but Nodes created by these transformations have no span
+    pub fn span_of(&self, node: &Node) -> Option<Span> {
+        let mut index = 0;
+        let index = find_pre_order_index(&self.node, node, &mut index)?;
+
+        self.spans.get(index).copied().flatten()
+    }
+}
+
+/// Return the number of Nodes in the tree (i.e. the length of its pre-order)
+fn node_count(node: &Node) -> usize {
+    match node {
+        Node::Document(children) => 1 + children.iter().map(node_count).sum::<usize>(),
+        Node::Section { head, children, .. } => {
+            1 + node_count(head) + children.iter().map(node_count).sum::<usize>()
+        }
+        _ => 1,
+    }
+}
+
+/// Append the spans of `node` in pre-order, taking them from the matching `original` Node and its
+/// spans (starting with the span of `original` itself)
+fn transfer_spans(
+    node: &Node,
+    original: Option<(&Node, &[Option<Span>])>,
+    spans: &mut Vec<Option<Span>>,
+) {
+    let (original, original_spans) = match original {
+        Some((original, original_spans)) if original == node => {
+            spans.extend_from_slice(&original_spans[..node_count(original)]);
+            return;
+        }
+        Some((original, original_spans))
+            if std::mem::discriminant(original) == std::mem::discriminant(node) =>
+        {
+            (Some(original), original_spans)
+        }
+        _ => (None, &[][..]),
+    };
+    spans.push(original.and(original_spans.first().copied().flatten()));
+
+    match (node, original) {
+        (Node::Document(children), Some(Node::Document(original_children))) => {
+            transfer_children_spans(children, original_children, &original_spans[1..], spans)
+        }
+        (
+            Node::Section { head, children, .. },
+            Some(Node::Section {
+                head: original_head,
+                children: original_children,
+                ..
+            }),
+        ) => {
+            transfer_spans(head, Some((original_head, &original_spans[1..])), spans);
+            transfer_children_spans(
+                children,
+                original_children,
+                &original_spans[1 + node_count(original_head)..],
+                spans,
+            )
+        }
+        (Node::Document(children), _) => transfer_children_spans(children, &[], &[], spans),
+        (Node::Section { head, children, .. }, _) => {
+            transfer_spans(head, None, spans);
+            transfer_children_spans(children, &[], &[], spans)
+        }
+        _ => {}
+    }
+}
+
+/// Append the spans of `children`, matching each child with the next equal Node of
+/// `original_children` (sections may also match by their headline or their children)
+fn transfer_children_spans(
+    children: &[Node],
+    original_children: &[Node],
+    original_spans: &[Option<Span>],
+    spans: &mut Vec<Option<Span>>,
+) {
+    let mut offsets = Vec::with_capacity(original_children.len());
+    let mut offset = 0;
+    for original in original_children {
+        offsets.push(offset);
+        offset += node_count(original);
+    }
+
+    let mut cursor = 0;
+    for child in children {
+        let candidates = || (cursor..original_children.len()).chain(0..cursor);
+        let position = candidates()
+            .find(|&index| &original_children[index] == child)
+            .or_else(|| {
+                candidates().find(|&index| is_same_section(child, &original_children[index]))
+            });
+        match position {
+            Some(index) => {
+                transfer_spans(
+                    child,
+                    Some((&original_children[index], &original_spans[offsets[index]..])),
+                    spans,
+                );
+                cursor = index + 1;
+            }
+            None => transfer_spans(child, None, spans),
+        }
+    }
+}
+
+fn is_same_section(node: &Node, original: &Node) -> bool {
+    match (node, original) {
+        (
+            Node::Section { head, children, .. },
+            Node::Section {
+                head: original_head,
+                children: original_children,
+                ..
+            },
+        ) => head == original_head || children == original_children,
+        _ => false,
+    }
+}
+
+fn find_pre_order_index(current: &Node, needle: &Node, index: &mut usize) -> Option<usize> {
+    if std::ptr::eq(current, needle) {
+        return Some(*index);
+    }
+    *index += 1;
+
+    match current {
+        Node::Document(children) => children
+            .iter()
+            .find_map(|child| find_pre_order_index(child, needle, index)),
+        Node::Section { head, children, .. } => {
+            find_pre_order_index(head, needle, index).or_else(|| {
+                children
+                    .iter()
+                    .find_map(|child| find_pre_order_index(child, needle, index))
+            })
+        }
+        _ => None,
+    }
 }
@@ -2,14 +2,25 @@
 pub use crate::error::Error;
 pub use crate::error::Result;
 
+/// Source positions and diagnostics
+pub use crate::diagnostic::{Diagnostic, Severity};
+pub use crate::span::{Position, Span};
+
 /// Tokenization
 pub use crate::tokenizer::build_tokenizer;
+pub use crate::tokenizer::build_tokenizer_for_file_type;
+pub use crate::tokenizer::SpannedToken;
 pub use crate::tokenizer::Token;
 pub use crate::tokenizer::Tokenizer;
+pub use crate::tokenizer::{TokenizerError, TokenizerErrorKind};
 
+pub use crate::converter::BinaryConverterTrait;
+pub use crate::converter::ChorddownFormatter;
 /// Format conversion
 pub use crate::converter::Converter;
 pub use crate::converter::ConverterTrait;
+#[cfg(feature = "pdf")]
+pub use crate::converter::PdfConverter;
 pub use crate::format::Format;
 
 /// Parsing
@@ -18,15 +29,32 @@ pub use crate::parser::Parser;
 pub use crate::parser::ParserResult;
 pub use crate::parser::ParserTrait;
 
+/// Lossless syntax tree for editing tools
+pub use crate::cst::{LineKind, Segment, SyntaxChord, SyntaxLine, SyntaxSection, SyntaxTree};
+
+/// Modification
+pub use crate::modification::arrangement::ArrangeableTrait;
+pub use crate::modification::language::LanguageSelectableTrait;
+pub use crate::modification::performance::ExpandableTrait;
+
+/// Chord analysis
+pub use crate::analysis::{
+    Analyzer, ChordUsage, Difficulty, DifficultyLevel, KeyEstimate, SectionSummary, SongAnalysis,
+    SongStatistics,
+};
+
 /// Search
-pub use crate::search::SearchIndex;
+pub use crate::search::{PrebuiltSearchIndex, Query, SearchIndex, SearchResult};
 
 /// Data structures
-pub use crate::models::catalog::{Catalog, CatalogTrait};
+pub use crate::models::catalog::{Catalog, CatalogPatch, CatalogPatchError, CatalogTrait};
 pub use crate::models::chord::fmt::Formatting;
+pub use crate::models::diagram::Instrument;
 pub use crate::models::file_type::FileType;
 pub use crate::models::list::*;
-pub use crate::models::meta::{BNotation, MetaTrait, SemitoneNotation};
+pub use crate::models::meta::{
+    BNotation, Capo, ChordNotation, CustomFields, LanguageDisplay, MetaTrait, SemitoneNotation,
+};
 #[allow(deprecated)]
 pub use crate::models::record_id_trait::RecordIdTrait;
 pub use crate::models::record_trait::RecordTrait;
@@ -38,9 +66,13 @@ pub use crate::models::song_list::{SongList, SongListTrait};
 pub use crate::models::song_meta::SongMeta;
 pub use crate::models::song_settings::{SongSettings, SongSettingsMap};
 pub use crate::models::song_sorting::SongSorting;
+pub use crate::models::structure::{Arrangement, ArrangementEntry, Expansion};
 pub use crate::models::team::{Team, TeamId};
 pub use crate::models::user::{Credentials, MainData, Password, User, Username};
 
+/// Linting
+pub use crate::lint::{LintConfig, LintMessage, LintReport, LintRule, Linter};
+
 /// Catalog management
 pub use crate::catalog_builder::{CatalogBuildError, CatalogBuildResult, CatalogBuilder};
 
@@ -0,0 +1,52 @@
+/// Return the edit distance between `a` and `b` if it does not exceed `max`
+///
+/// Insertions, deletions, substitutions and transpositions of adjacent characters count as one
+/// edit (optimal string alignment distance)
+pub(super) fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let width = b.len() + 1;
+    let mut rows = vec![0; (a.len() + 1) * width];
+    for (j, cell) in rows.iter_mut().take(width).enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        rows[i * width] = i;
+        let mut row_min = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[(i - 1) * width + j] + 1)
+                .min(rows[i * width + j - 1] + 1)
+                .min(rows[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[(i - 2) * width + j - 2] + 1);
+            }
+            rows[i * width + j] = distance;
+            row_min = row_min.min(distance);
+        }
+        if row_min > max {
+            return None;
+        }
+    }
+
+    Some(rows[a.len() * width + b.len()]).filter(|distance| *distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("chariot", "chariot", 2), Some(0));
+        assert_eq!(edit_distance("chariot", "charot", 2), Some(1));
+        assert_eq!(edit_distance("chariot", "chraiot", 2), Some(1));
+        assert_eq!(edit_distance("chariot", "charity", 2), Some(2));
+        assert_eq!(edit_distance("chariot", "cherry", 2), None);
+        assert_eq!(edit_distance("swing", "swinging", 2), None);
+    }
+}
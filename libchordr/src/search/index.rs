@@ -1,104 +1,460 @@
-use crate::format::Format;
-use crate::models::meta::Tags;
-use crate::prelude::{
-    convert_to_format, Catalog, CatalogTrait, Formatting, ListEntryTrait, MetaTrait, Result, Song,
-    SongId,
-};
-use std::collections::HashMap;
-use std::str::FromStr;
-
-#[derive(Debug)]
-struct IndexEntry {
-    tags: Tags,
-    text: String,
-}
+use std::collections::{BTreeMap, HashMap};
 
-impl IndexEntry {
-    fn search_in_tags(&self, search: &str) -> bool {
-        if self.tags.is_empty() {
-            return false;
-        }
+use serde::{Deserialize, Serialize};
 
-        // Try to parse the whole search string into a list of Tags
-        let search_term_as_tags = match Tags::from_str(search) {
-            Ok(t) => t,
-            Err(_) => return false,
-        };
+use crate::analysis::parse_tempo;
+use crate::helper::parse_content_for_file_type;
+use crate::models::chord::{Chord, Quality};
+use crate::models::meta::BNotation;
+use crate::parser::Node;
+use crate::prelude::{Catalog, CatalogTrait, ListEntryTrait, MetaTrait, Song, SongData, SongId};
+use crate::tokenizer::Token;
 
-        for search_tag in search_term_as_tags.iter() {
-            if self
-                .tags
-                .iter()
-                .find(|t| t.as_str().to_lowercase() == search_tag.as_str())
-                .is_none()
-            {
-                // Return false if one of the searched tags was NOT found
-                return false;
-            }
-        }
+use super::edit_distance::edit_distance;
+use super::normalization::{index_terms, query_term, words};
+use super::query::{Filter, Query};
 
-        true
-    }
+/// BM25 term frequency saturation
+const K1: f32 = 1.2;
+
+/// BM25 field length normalization
+const B: f32 = 0.75;
+
+/// Factor for terms that start with the searched word
+const PREFIX_MATCH_QUALITY: f32 = 0.7;
+
+/// Factor for terms within the allowed edit distance (divided by the distance)
+const FUZZY_MATCH_QUALITY: f32 = 0.5;
+
+/// Minimum length of a searched word to look for terms with typos
+const MIN_FUZZY_LENGTH: usize = 4;
+
+/// Minimum length of a searched word to allow two typos
+const MIN_DOUBLE_TYPO_LENGTH: usize = 8;
+
+const FIELD_COUNT: usize = 4;
 
-    fn contains(&self, search: &str) -> bool {
-        // Search for tags
-        if search.starts_with('#') && self.search_in_tags(search) {
-            return true;
+/// Indexed part of a song
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Title,
+    /// Alternative and original title
+    AlternativeTitle,
+    /// Subtitle, artist, composer, lyricist, album and custom fields
+    Meta,
+    Lyrics,
+}
+
+impl Field {
+    const ALL: [Field; FIELD_COUNT] = [
+        Field::Title,
+        Field::AlternativeTitle,
+        Field::Meta,
+        Field::Lyrics,
+    ];
+
+    fn weight(self) -> f32 {
+        match self {
+            Field::Title => 4.0,
+            Field::AlternativeTitle => 3.0,
+            Field::Meta => 1.5,
+            Field::Lyrics => 1.0,
         }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Document {
+    song_id: SongId,
+    /// Number of words in each field
+    lengths: [u32; FIELD_COUNT],
+    /// Normalized words of the artist
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    artist: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key: Option<Chord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tempo: Option<u32>,
+    /// Normalized tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ccli: Option<String>,
+}
 
-        self.text.contains(search)
+impl Document {
+    fn matches(&self, filter: &Filter) -> bool {
+        match filter {
+            Filter::Artist(artist) => words(artist).iter().all(|word| {
+                let word = query_term(word);
+                self.artist.iter().any(|a| a.starts_with(&word))
+            }),
+            Filter::Key(key) => match (&self.key, parse_key(key)) {
+                (Some(song_key), Some(key)) => {
+                    song_key.root() == key.root() && is_minor(song_key) == is_minor(&key)
+                }
+                _ => false,
+            },
+            Filter::Tempo(comparison) => self.tempo.is_some_and(|tempo| comparison.matches(tempo)),
+            Filter::Tag(tag) => self.tags.contains(&normalize_tag(tag)),
+            Filter::Ccli(ccli) => self.ccli.as_deref() == Some(ccli.trim()),
+        }
     }
 }
 
-type Storage = HashMap<SongId, IndexEntry>;
+/// Occurrences of a term in a document
+///
+/// Postings make up most of a serialized index, hence the short field names
+#[derive(Debug, Deserialize, Serialize)]
+struct Posting {
+    #[serde(rename = "d")]
+    document: usize,
+    #[serde(rename = "f")]
+    frequencies: [u32; FIELD_COUNT],
+    /// Word positions (the fields are separated by a gap, so phrases can not span fields)
+    #[serde(rename = "p")]
+    positions: Vec<u32>,
+}
 
-#[derive(Debug)]
+/// Inverted index of the songs' words
+///
+/// Documents are ranked with BM25F: the term frequencies of the fields are normalized by the
+/// field length and weighted, so that a match in the title counts more than one in the lyrics
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Index {
-    map: Storage,
+    documents: Vec<Document>,
+    terms: BTreeMap<String, Vec<Posting>>,
+    average_lengths: [f32; FIELD_COUNT],
 }
 
 impl Index {
     pub(super) fn build_index(catalog: &Catalog) -> Self {
-        let mut index = Self::with_capacity(catalog.len());
+        let mut index = Self {
+            documents: Vec::with_capacity(catalog.len()),
+            terms: BTreeMap::new(),
+            average_lengths: [0.0; FIELD_COUNT],
+        };
         for song in catalog.iter() {
-            if let Ok(text) = extract_text(song) {
-                index.map.insert(
-                    song.id(),
-                    IndexEntry {
-                        text: text.to_lowercase(),
-                        tags: song.meta().tags(),
-                    },
-                );
+            index.add_song(song);
+        }
+
+        let mut totals = [0u64; FIELD_COUNT];
+        for document in &index.documents {
+            for (total, length) in totals.iter_mut().zip(document.lengths) {
+                *total += length as u64;
             }
         }
+        let count = index.documents.len().max(1) as f32;
+        for (average, total) in index.average_lengths.iter_mut().zip(totals) {
+            *average = total as f32 / count;
+        }
+
         index
     }
 
-    pub(super) fn search(&self, search: &str) -> Vec<&SongId> {
-        self.map
-            .iter()
-            .filter_map(|(song_id, entry)| {
-                if entry.contains(search) {
-                    Some(song_id)
-                } else {
-                    None
-                }
-            })
+    /// Return the IDs of the songs matching the query with their score
+    ///
+    /// Words match terms that are equal, that start with the word or that differ by a typo, with
+    /// decreasing scores. Filters and negations do not contribute to the score
+    pub(super) fn search(&self, query: &Query) -> Vec<(&SongId, f32)> {
+        self.evaluate(query)
+            .into_iter()
+            .map(|(document, score)| (&self.documents[document].song_id, score))
             .collect()
     }
 
-    fn with_capacity(capacity: usize) -> Self {
-        Self {
-            map: HashMap::with_capacity(capacity),
+    fn evaluate(&self, query: &Query) -> HashMap<usize, f32> {
+        match query {
+            Query::Word(word) => self.intersect(words(word).iter().map(|w| self.match_word(w))),
+            Query::Phrase(phrase) => self.match_phrase(phrase),
+            Query::Filter(filter) => self
+                .documents
+                .iter()
+                .enumerate()
+                .filter(|(_, document)| document.matches(filter))
+                .map(|(i, _)| (i, 0.0))
+                .collect(),
+            Query::And(expressions) => self.intersect(expressions.iter().map(|e| self.evaluate(e))),
+            Query::Or(expressions) => {
+                let mut scores: HashMap<usize, f32> = HashMap::new();
+                for expression in expressions {
+                    for (document, score) in self.evaluate(expression) {
+                        *scores.entry(document).or_insert(0.0) += score;
+                    }
+                }
+                scores
+            }
+            Query::Not(expression) => {
+                let excluded = self.evaluate(expression);
+                (0..self.documents.len())
+                    .filter(|document| !excluded.contains_key(document))
+                    .map(|document| (document, 0.0))
+                    .collect()
+            }
+        }
+    }
+
+    /// Return the documents contained in all results with the sum of their scores
+    ///
+    /// Without any result all documents are returned
+    fn intersect<I: Iterator<Item = HashMap<usize, f32>>>(
+        &self,
+        results: I,
+    ) -> HashMap<usize, f32> {
+        let mut scores: Option<HashMap<usize, f32>> = None;
+        for result in results {
+            scores = Some(match scores {
+                None => result,
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(document, score)| {
+                        result.get(&document).map(|other| (document, score + other))
+                    })
+                    .collect(),
+            });
+        }
+
+        scores.unwrap_or_else(|| (0..self.documents.len()).map(|d| (d, 0.0)).collect())
+    }
+
+    fn match_word(&self, word: &str) -> HashMap<usize, f32> {
+        let mut scores: HashMap<usize, f32> = HashMap::new();
+        for (postings, quality) in self.expand(&query_term(word)) {
+            let idf = self.idf(postings.len());
+            for posting in postings {
+                let score = quality * idf * self.term_frequency_score(posting);
+                let best = scores.entry(posting.document).or_insert(0.0);
+                *best = best.max(score);
+            }
         }
+
+        scores
     }
+
+    /// Return the documents containing the words of the phrase in the given order
+    ///
+    /// The words of a phrase have to match exactly
+    fn match_phrase(&self, phrase: &str) -> HashMap<usize, f32> {
+        let terms: Vec<String> = words(phrase).iter().map(|w| query_term(w)).collect();
+        let mut postings = vec![];
+        for term in &terms {
+            match self.terms.get(term) {
+                Some(p) => postings.push(p),
+                None => return HashMap::new(),
+            }
+        }
+        let (first, rest) = match postings.split_first() {
+            Some(p) => p,
+            None => return self.intersect(std::iter::empty()),
+        };
+
+        let mut scores = HashMap::new();
+        for posting in first.iter() {
+            let mut score = self.idf(first.len()) * self.term_frequency_score(posting);
+            let mut following = vec![];
+            for other in rest {
+                match other.iter().find(|p| p.document == posting.document) {
+                    Some(p) => {
+                        score += self.idf(other.len()) * self.term_frequency_score(p);
+                        following.push(p);
+                    }
+                    None => break,
+                }
+            }
+            if following.len() != rest.len() {
+                continue;
+            }
+
+            let in_order = posting.positions.iter().any(|start| {
+                following
+                    .iter()
+                    .enumerate()
+                    .all(|(i, p)| p.positions.contains(&(start + i as u32 + 1)))
+            });
+            if in_order {
+                scores.insert(posting.document, score);
+            }
+        }
+
+        scores
+    }
+
+    fn add_song(&mut self, song: &Song) {
+        let meta = song.meta();
+        let mut meta_text = [
+            meta.subtitle(),
+            meta.artist(),
+            meta.composer(),
+            meta.lyricist(),
+            meta.album(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join("\n");
+        // Custom meta fields (e.g. `Theme: Grace`) are searchable as well
+        for (keyword, value) in &meta.custom_fields() {
+            meta_text.push_str(&format!("\n{}: {}", keyword, value));
+        }
+
+        let fields = [
+            song.title(),
+            [meta.alternative_title(), meta.original_title()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<String>>()
+                .join("\n"),
+            meta_text,
+            extract_lyrics(song),
+        ];
+
+        let document = self.documents.len();
+        let mut postings: HashMap<String, Posting> = HashMap::new();
+        let mut lengths = [0; FIELD_COUNT];
+        let mut position = 0;
+        for (field, text) in fields.iter().enumerate() {
+            for word in words(text) {
+                lengths[field] += 1;
+                for term in index_terms(&word) {
+                    let posting = postings.entry(term).or_insert_with(|| Posting {
+                        document,
+                        frequencies: [0; FIELD_COUNT],
+                        positions: vec![],
+                    });
+                    posting.frequencies[field] += 1;
+                    posting.positions.push(position);
+                }
+                position += 1;
+            }
+            position += 1;
+        }
+        for (term, posting) in postings {
+            self.terms.entry(term).or_default().push(posting);
+        }
+
+        self.documents.push(Document {
+            song_id: song.id(),
+            lengths,
+            artist: meta
+                .artist()
+                .map(|artist| words(&artist).iter().map(|w| query_term(w)).collect())
+                .unwrap_or_default(),
+            key: meta.key(),
+            tempo: meta.tempo().as_deref().and_then(parse_tempo),
+            tags: meta
+                .tags()
+                .iter()
+                .map(|tag| normalize_tag(tag.as_str()))
+                .collect(),
+            ccli: meta.ccli_song_id().map(|ccli| ccli.trim().to_string()),
+        });
+    }
+
+    /// Return the postings of the terms matching the word with the quality of the match
+    fn expand(&self, word: &str) -> Vec<(&Vec<Posting>, f32)> {
+        let mut matches = vec![];
+        for (term, postings) in self.terms.range(word.to_string()..) {
+            if !term.starts_with(word) {
+                break;
+            }
+            let quality = if term == word {
+                1.0
+            } else {
+                PREFIX_MATCH_QUALITY
+            };
+            matches.push((postings, quality));
+        }
+
+        let length = word.chars().count();
+        if length >= MIN_FUZZY_LENGTH {
+            let max_distance = if length >= MIN_DOUBLE_TYPO_LENGTH {
+                2
+            } else {
+                1
+            };
+            for (term, postings) in &self.terms {
+                if term.starts_with(word) {
+                    continue;
+                }
+                if let Some(distance) = edit_distance(word, term, max_distance) {
+                    matches.push((postings, FUZZY_MATCH_QUALITY / distance as f32));
+                }
+            }
+        }
+
+        matches
+    }
+
+    fn idf(&self, document_frequency: usize) -> f32 {
+        let count = self.documents.len() as f32;
+        let document_frequency = document_frequency as f32;
+
+        (1.0 + (count - document_frequency + 0.5) / (document_frequency + 0.5)).ln()
+    }
+
+    fn term_frequency_score(&self, posting: &Posting) -> f32 {
+        let lengths = &self.documents[posting.document].lengths;
+        let mut weighted_frequency = 0.0;
+        for (i, field) in Field::ALL.iter().enumerate() {
+            if posting.frequencies[i] == 0 {
+                continue;
+            }
+            let normalization = if self.average_lengths[i] > 0.0 {
+                1.0 - B + B * lengths[i] as f32 / self.average_lengths[i]
+            } else {
+                1.0
+            };
+            weighted_frequency += field.weight() * posting.frequencies[i] as f32 / normalization;
+        }
+
+        weighted_frequency * (K1 + 1.0) / (weighted_frequency + K1)
+    }
+}
+
+fn parse_key(key: &str) -> Option<Chord> {
+    Chord::try_from(key, BNotation::B)
+        .or_else(|_| Chord::try_from(key, BNotation::H))
+        .ok()
+}
+
+fn is_minor(chord: &Chord) -> bool {
+    chord.quality() == Quality::Minor
 }
 
-fn extract_text(s: &Song) -> Result<String> {
-    let formatting = Formatting {
-        format: Format::Text,
-        ..Formatting::default()
-    };
+fn normalize_tag(tag: &str) -> String {
+    words(tag)
+        .iter()
+        .map(|w| query_term(w))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Return the lyrics, quotes and translations of the song
+fn extract_lyrics(song: &Song) -> String {
+    let mut text = String::new();
+    if let Ok(result) = parse_content_for_file_type(song.src().as_bytes(), song.file_type()) {
+        collect_lyrics(result.node_as_ref(), &mut text);
+    }
+
+    text
+}
 
-    convert_to_format(s.src().as_bytes(), s.meta(), formatting)
+fn collect_lyrics(node: &Node, text: &mut String) {
+    match node {
+        Node::ChordTextPair {
+            text: Token::Literal(literal),
+            ..
+        }
+        | Node::Text(Token::Literal(literal)) => text.push_str(literal),
+        Node::Quote(Token::Quote(quote)) => text.push_str(quote),
+        Node::Translation(Token::Translation(translation)) => text.push_str(translation),
+        Node::Newline => text.push('\n'),
+        Node::Document(children) | Node::Section { children, .. } => {
+            for child in children {
+                collect_lyrics(child, text);
+            }
+            text.push('\n');
+        }
+        _ => {}
+    }
 }
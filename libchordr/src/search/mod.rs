@@ -1,9 +1,39 @@
+//! Full-text search of the songs in a catalog
+//!
+//! See [Query] for the supported query syntax
+use std::cmp::Ordering;
 use std::rc::Rc;
 
-use crate::prelude::{Catalog, CatalogTrait, Song};
+use crate::error::Result;
+use crate::prelude::{Catalog, CatalogTrait, Song, SongData};
 use crate::search::index::Index;
 
+pub use self::prebuilt_search_index::{PrebuiltSearchIndex, SearchIndexError};
+pub use self::query::{Comparison, Filter, Query};
+
+mod edit_distance;
 mod index;
+mod normalization;
+mod prebuilt_search_index;
+mod query;
+
+/// Song found by the [SearchIndex] with its relevance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchResult<'a> {
+    song: &'a Song,
+    score: f32,
+}
+
+impl<'a> SearchResult<'a> {
+    pub fn song(&self) -> &'a Song {
+        self.song
+    }
+
+    /// Return the relevance of the song for the search (higher is better)
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+}
 
 #[derive(Debug)]
 pub struct SearchIndex {
@@ -19,20 +49,56 @@ impl SearchIndex {
         }
     }
 
-    /// Return the [Song]s from the [Catalog] filtered by [search]
-    pub fn search_by_term(&self, search: &str) -> Vec<&Song> {
-        if search.is_empty() || search.trim().is_empty() {
+    /// Build the search index for the [Catalog] from an index built ahead of time
+    ///
+    /// Fails if the [PrebuiltSearchIndex] was built for another revision of the [Catalog] or by
+    /// another version of the library. Use `build_for_catalog()` in that case
+    pub fn from_prebuilt(catalog: Rc<Catalog>, prebuilt: PrebuiltSearchIndex) -> Result<Self> {
+        Ok(Self {
+            index: prebuilt.into_index(&catalog)?,
+            catalog,
+        })
+    }
+
+    /// Return the [Song]s from the [Catalog] matching [search] in rank order
+    ///
+    /// The search is parsed as [Query] (e.g. `key:G tag:advent NOT artist:Hillsong`)
+    pub fn search_by_term(&self, search: &str) -> Vec<SearchResult<'_>> {
+        self.search(&Query::parse(search))
+    }
+
+    /// Return the [Song]s from the [Catalog] matching the [Query] in rank order
+    ///
+    /// Matches in the title and alternative title rank higher than matches in the lyrics. Songs
+    /// with the same score are sorted by title
+    pub fn search(&self, query: &Query) -> Vec<SearchResult<'_>> {
+        if query.is_empty() {
             // If the search is empty return all songs
-            return self.catalog.iter().collect();
+            return self
+                .catalog
+                .iter()
+                .map(|song| SearchResult { song, score: 0.0 })
+                .collect();
         }
 
-        let search_normalized = search.to_lowercase();
-
-        self.index
-            .search(&search_normalized)
+        let mut results: Vec<SearchResult<'_>> = self
+            .index
+            .search(query)
             .into_iter()
-            .filter_map(|song_id| self.catalog.get(song_id))
-            .collect()
+            .filter_map(|(song_id, score)| {
+                self.catalog
+                    .get(song_id)
+                    .map(|song| SearchResult { song, score })
+            })
+            .collect();
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.song.title().cmp(&b.song.title()))
+        });
+
+        results
     }
 }
 
@@ -40,9 +106,47 @@ impl SearchIndex {
 mod test {
     use std::rc::Rc;
 
-    use crate::search::SearchIndex;
+    use crate::helper::parse_content;
+    use crate::models::list::ListEntryTrait;
+    use crate::prelude::{Catalog, FileType, Song, SongMeta};
+    use crate::search::{PrebuiltSearchIndex, SearchIndex};
     use crate::test_helpers::get_test_catalog;
 
+    fn song_ids(search_index: &SearchIndex, search: &str) -> Vec<String> {
+        search_index
+            .search_by_term(search)
+            .iter()
+            .map(|result| result.song().id().to_string())
+            .collect()
+    }
+
+    fn build_catalog() -> Catalog {
+        let song = |id: &str, src: &str| {
+            Song::new(
+                SongMeta::new(id.into(), id.to_string(), FileType::Chorddown),
+                src,
+            )
+        };
+
+        Catalog::new(
+            "search-catalog",
+            vec![
+                song(
+                    "amazing-grace",
+                    "# Amazing Grace\n\n## Verse\n[G]Amazing grace, how [C]sweet the sound\n",
+                ),
+                song(
+                    "chariot",
+                    "# Swing Low\n\n## Verse\nSwing low, sweet chariot, coming for to carry me home\nA band of angels, full of grace\n",
+                ),
+                song(
+                    "ueberschrift",
+                    "# Überschrift\n\n## Verse\nDon't stop, die Straße entlang\n",
+                ),
+            ],
+        )
+    }
+
     #[test]
     fn test_search_by_term() {
         let search_index = SearchIndex::build_for_catalog(Rc::new(get_test_catalog()));
@@ -58,4 +162,172 @@ mod test {
         assert_eq!(search_index.search_by_term("    ").len(), 5);
         assert_eq!(search_index.search_by_term(" \t \n    ").len(), 5);
     }
+
+    #[test]
+    fn test_search_ranks_title_matches_first() {
+        let search_index = SearchIndex::build_for_catalog(Rc::new(build_catalog()));
+        let results = search_index.search_by_term("grace");
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].song().id().as_str(), "amazing-grace");
+        assert!(results[0].score() > results[1].score());
+    }
+
+    #[test]
+    fn test_search_requires_all_words() {
+        let search_index = SearchIndex::build_for_catalog(Rc::new(build_catalog()));
+
+        assert_eq!(song_ids(&search_index, "sweet chariot"), vec!["chariot"]);
+        assert_eq!(
+            song_ids(&search_index, "sweet"),
+            vec!["amazing-grace", "chariot"]
+        );
+    }
+
+    #[test]
+    fn test_search_by_prefix_and_with_typos() {
+        let search_index = SearchIndex::build_for_catalog(Rc::new(build_catalog()));
+
+        assert_eq!(song_ids(&search_index, "char"), vec!["chariot"]);
+        assert_eq!(song_ids(&search_index, "chraiot"), vec!["chariot"]);
+        assert_eq!(
+            song_ids(&search_index, "amazin gracee"),
+            vec!["amazing-grace"]
+        );
+        assert!(song_ids(&search_index, "chorus").is_empty());
+    }
+
+    fn build_meta_catalog() -> Catalog {
+        let song = |id: &str, src: &str| {
+            let result = parse_content(src.as_bytes()).unwrap();
+            let meta = SongMeta::new_with_meta_information(
+                id.into(),
+                result.meta().title.unwrap(),
+                FileType::Chorddown,
+                result.meta_as_ref(),
+            );
+            Song::new(meta, src)
+        };
+
+        Catalog::new(
+            "meta-catalog",
+            vec![
+                song(
+                    "advent-g",
+                    "# O Come\nArtist: Traditional\nKey: G\nTempo: 96 BPM\nTags: #advent\nCCLI Song #: 31054\n\n## Verse\nO come, O come Emmanuel\n",
+                ),
+                song(
+                    "advent-hillsong",
+                    "# Emmanuel\nArtist: Hillsong Worship\nKey: G\nTempo: 128\nTags: #advent, #christmas\n\n## Verse\nEmmanuel, God with us\n",
+                ),
+                song(
+                    "easter",
+                    "# Risen\nArtist: Hillsong United\nKey: Em\nTempo: 140\nTags: #easter\n\n## Verse\nHe is risen, come and see\n",
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_search_with_filters() {
+        let search_index = SearchIndex::build_for_catalog(Rc::new(build_meta_catalog()));
+
+        assert_eq!(
+            song_ids(&search_index, "key:G tag:advent NOT artist:Hillsong"),
+            vec!["advent-g"]
+        );
+        assert_eq!(song_ids(&search_index, "key:Em"), vec!["easter"]);
+        assert!(song_ids(&search_index, "key:E").is_empty());
+        assert_eq!(
+            song_ids(&search_index, "tempo:>100"),
+            vec!["advent-hillsong", "easter"]
+        );
+        assert_eq!(song_ids(&search_index, "tempo:90-100"), vec!["advent-g"]);
+        assert_eq!(song_ids(&search_index, "ccli:31054"), vec!["advent-g"]);
+        assert_eq!(
+            song_ids(&search_index, "#christmas"),
+            vec!["advent-hillsong"]
+        );
+        assert_eq!(
+            song_ids(&search_index, "artist:\"hillsong united\""),
+            vec!["easter"]
+        );
+    }
+
+    #[test]
+    fn test_search_with_operators_and_phrases() {
+        let search_index = SearchIndex::build_for_catalog(Rc::new(build_meta_catalog()));
+
+        assert_eq!(
+            song_ids(&search_index, "risen OR tag:christmas"),
+            vec!["easter", "advent-hillsong"]
+        );
+        assert_eq!(
+            song_ids(&search_index, "\"come emmanuel\""),
+            vec!["advent-g"]
+        );
+        assert!(song_ids(&search_index, "\"emmanuel come\"").is_empty());
+        assert_eq!(
+            song_ids(&search_index, "come NOT (risen OR artist:traditional)"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            song_ids(&search_index, "emmanuel NOT tag:christmas"),
+            vec!["advent-g"]
+        );
+    }
+
+    #[test]
+    fn test_search_with_prebuilt_index() {
+        let catalog = Rc::new(build_meta_catalog());
+        let prebuilt = PrebuiltSearchIndex::build_for_catalog(&catalog);
+        assert_eq!(prebuilt.revision(), "meta-catalog");
+
+        let serialized = serde_json::to_string(&prebuilt).unwrap();
+        let prebuilt: PrebuiltSearchIndex = serde_json::from_str(&serialized).unwrap();
+        let search_index = SearchIndex::from_prebuilt(catalog.clone(), prebuilt).unwrap();
+        let built_index = SearchIndex::build_for_catalog(catalog);
+
+        for search in [
+            "emmanuel",
+            "emanuel",
+            "\"come emmanuel\"",
+            "key:G tag:advent NOT artist:Hillsong",
+            "key:em OR tempo:<100",
+            "ccli:31054",
+        ] {
+            assert_eq!(
+                search_index.search_by_term(search),
+                built_index.search_by_term(search),
+                "{}",
+                search
+            );
+        }
+    }
+
+    #[test]
+    fn test_prebuilt_index_for_other_revision() {
+        let prebuilt = PrebuiltSearchIndex::build_for_catalog(&build_meta_catalog());
+        let result = SearchIndex::from_prebuilt(Rc::new(build_catalog()), prebuilt);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Search index for revision 'meta-catalog' can not be used for catalog revision 'search-catalog'"
+        );
+    }
+
+    #[test]
+    fn test_search_with_umlauts_and_apostrophes() {
+        let search_index = SearchIndex::build_for_catalog(Rc::new(build_catalog()));
+
+        assert_eq!(song_ids(&search_index, "Überschrift"), vec!["ueberschrift"]);
+        assert_eq!(
+            song_ids(&search_index, "ueberschrift"),
+            vec!["ueberschrift"]
+        );
+        assert_eq!(song_ids(&search_index, "uberschrift"), vec!["ueberschrift"]);
+        assert_eq!(song_ids(&search_index, "strasse"), vec!["ueberschrift"]);
+        assert_eq!(song_ids(&search_index, "dont"), vec!["ueberschrift"]);
+        assert_eq!(song_ids(&search_index, "don’t"), vec!["ueberschrift"]);
+    }
 }
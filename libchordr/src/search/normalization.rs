@@ -0,0 +1,167 @@
+//! Tokenisation and normalisation of the text and the search terms
+//!
+//! Words are lowercased, apostrophes are removed (`don't` becomes `dont`) and diacritics are
+//! folded (`é` becomes `e`). Both precomposed characters and combining marks are supported.
+//! German umlauts are indexed as both variants (`über` is indexed as `uber` and `ueber`), so that
+//! searches with and without the umlaut find the word
+
+/// Combining diaeresis (e.g. the decomposed form of `ü` is `u` followed by this mark)
+const COMBINING_DIAERESIS: char = '\u{0308}';
+
+/// Split the input into normalized words
+///
+/// Umlauts are kept, use `index_terms()` or `query_term()` to fold them
+pub(super) fn words(input: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    for c in input.chars() {
+        if is_apostrophe(c) {
+            continue;
+        }
+        if c == COMBINING_DIAERESIS {
+            if let Some(umlaut) = word.chars().last().and_then(compose_umlaut) {
+                word.pop();
+                word.push(umlaut);
+            }
+            continue;
+        }
+        if is_combining_mark(c) {
+            continue;
+        }
+
+        for c in c.to_lowercase() {
+            if is_combining_mark(c) {
+                continue;
+            }
+            if !c.is_alphanumeric() {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+            match fold(c) {
+                Some(folded) => word.push_str(folded),
+                None => word.push(c),
+            }
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Return the terms under which the word is indexed
+pub(super) fn index_terms(word: &str) -> Vec<String> {
+    if word.contains(is_umlaut) {
+        vec![fold_umlauts(word, false), fold_umlauts(word, true)]
+    } else {
+        vec![word.to_string()]
+    }
+}
+
+/// Return the term to look up for a word of the search
+pub(super) fn query_term(word: &str) -> String {
+    fold_umlauts(word, false)
+}
+
+fn fold_umlauts(word: &str, expand: bool) -> String {
+    let mut output = String::with_capacity(word.len() + 2);
+    for c in word.chars() {
+        match c {
+            'ä' => output.push('a'),
+            'ö' => output.push('o'),
+            'ü' => output.push('u'),
+            _ => {
+                output.push(c);
+                continue;
+            }
+        }
+        if expand {
+            output.push('e');
+        }
+    }
+
+    output
+}
+
+fn is_umlaut(c: char) -> bool {
+    matches!(c, 'ä' | 'ö' | 'ü')
+}
+
+fn compose_umlaut(c: char) -> Option<char> {
+    match c {
+        'a' => Some('ä'),
+        'o' => Some('ö'),
+        'u' => Some('ü'),
+        _ => None,
+    }
+}
+
+fn is_apostrophe(c: char) -> bool {
+    matches!(c, '\'' | '’' | '‘' | 'ʼ' | '`' | '´')
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}')
+}
+
+/// Return the replacement of a lowercase character with diacritics or a ligature
+fn fold(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'ù' | 'ú' | 'û' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        'æ' => "ae",
+        'œ' => "oe",
+        'ß' => "ss",
+        'þ' => "th",
+        'ﬁ' => "fi",
+        'ﬂ' => "fl",
+        _ => return None,
+    };
+
+    Some(folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words("Don't  stop—believin’!"),
+            vec!["dont", "stop", "believin"]
+        );
+        assert_eq!(words("Café crème, Æon"), vec!["cafe", "creme", "aeon"]);
+        assert_eq!(words("Straße"), vec!["strasse"]);
+        // Decomposed umlaut and accent
+        assert_eq!(words("U\u{0308}ber Cafe\u{0301}"), vec!["über", "cafe"]);
+    }
+
+    #[test]
+    fn test_umlaut_terms() {
+        assert_eq!(index_terms("über"), vec!["uber", "ueber"]);
+        assert_eq!(index_terms("nacht"), vec!["nacht"]);
+        assert_eq!(query_term("über"), "uber");
+        assert_eq!(query_term("ueber"), "ueber");
+    }
+}
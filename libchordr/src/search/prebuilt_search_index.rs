@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{Catalog, CatalogTrait};
+
+use super::index::Index;
+
+/// Format version of the serialized index (changes with the indexing rules of each release)
+const INDEX_VERSION: &str = concat!("1:", env!("CARGO_PKG_VERSION"));
+
+/// Search index built ahead of time, so that clients do not have to index the songs themselves
+///
+/// The index is built for one revision of a `Catalog` (e.g. by `chordr build-catalog`) and can be
+/// serialized next to it. Use `SearchIndex::from_prebuilt()` to search the `Catalog` with it
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PrebuiltSearchIndex {
+    version: String,
+    revision: String,
+    index: Index,
+}
+
+impl PrebuiltSearchIndex {
+    pub fn build_for_catalog(catalog: &Catalog) -> Self {
+        Self {
+            version: INDEX_VERSION.to_string(),
+            revision: catalog.revision(),
+            index: Index::build_index(catalog),
+        }
+    }
+
+    /// Return the revision of the `Catalog` the index was built for
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// Return the index if it was built for the `Catalog` by this version of the library
+    pub(super) fn into_index(self, catalog: &Catalog) -> Result<Index, SearchIndexError> {
+        if self.version != INDEX_VERSION {
+            return Err(SearchIndexError::VersionMismatch(
+                INDEX_VERSION.to_string(),
+                self.version,
+            ));
+        }
+        if self.revision != catalog.revision() {
+            return Err(SearchIndexError::RevisionMismatch(
+                self.revision,
+                catalog.revision(),
+            ));
+        }
+
+        Ok(self.index)
+    }
+}
+
+/// Error raised if a `PrebuiltSearchIndex` can not be used for a `Catalog`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchIndexError {
+    /// The index was built by another version of the library (expected, actual)
+    VersionMismatch(String, String),
+    /// The index was built for another revision of the `Catalog` (index, catalog)
+    RevisionMismatch(String, String),
+}
+
+impl Display for SearchIndexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchIndexError::VersionMismatch(expected, actual) => write!(
+                f,
+                "Search index version '{}' is not supported (expected '{}')",
+                actual, expected
+            ),
+            SearchIndexError::RevisionMismatch(index, catalog) => write!(
+                f,
+                "Search index for revision '{}' can not be used for catalog revision '{}'",
+                index, catalog
+            ),
+        }
+    }
+}
+
+impl Error for SearchIndexError {}
@@ -0,0 +1,429 @@
+//! Query language of the song search
+//!
+//! A query consists of words, quoted phrases and field filters, which are combined with `AND`
+//! (also implied between adjacent expressions), `OR` and `NOT`. Parentheses group expressions:
+//!
+//! ```text
+//! key:G tag:advent NOT artist:Hillsong
+//! "amazing grace" OR (tempo:>120 tag:celebration)
+//! ```
+//!
+//! Supported filters are `artist:`, `key:`, `tempo:` (`tempo:96`, `tempo:>100`, `tempo:<=90`
+//! or `tempo:80-100`), `tag:` (or `#advent`) and `ccli:`. Filter values may be quoted
+//! (`artist:"Hillsong United"`). The parser never fails: unknown filters are searched as words
+//! and unbalanced quotes or parentheses are closed at the end of the input
+
+use std::fmt::{Display, Formatter};
+
+/// Parsed search query
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Word that has to appear in the song (matched by prefix and with typos)
+    Word(String),
+    /// Words that have to appear in the given order
+    Phrase(String),
+    Filter(Filter),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+}
+
+/// Filter on a meta field of the song
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// All words have to appear in the artist (matched by prefix)
+    Artist(String),
+    Key(String),
+    Tempo(Comparison),
+    Tag(String),
+    Ccli(String),
+}
+
+/// Comparison of a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal(u32),
+    Greater(u32),
+    GreaterOrEqual(u32),
+    Less(u32),
+    LessOrEqual(u32),
+    /// Inclusive range
+    Between(u32, u32),
+}
+
+impl Comparison {
+    pub fn matches(&self, value: u32) -> bool {
+        match *self {
+            Comparison::Equal(v) => value == v,
+            Comparison::Greater(v) => value > v,
+            Comparison::GreaterOrEqual(v) => value >= v,
+            Comparison::Less(v) => value < v,
+            Comparison::LessOrEqual(v) => value <= v,
+            Comparison::Between(min, max) => value >= min && value <= max,
+        }
+    }
+
+    fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let number = |value: &str| value.trim().parse::<u32>().ok();
+        let comparison = if let Some(value) = input.strip_prefix(">=") {
+            Comparison::GreaterOrEqual(number(value)?)
+        } else if let Some(value) = input.strip_prefix("<=") {
+            Comparison::LessOrEqual(number(value)?)
+        } else if let Some(value) = input.strip_prefix('>') {
+            Comparison::Greater(number(value)?)
+        } else if let Some(value) = input.strip_prefix('<') {
+            Comparison::Less(number(value)?)
+        } else if let Some((min, max)) = input.split_once('-') {
+            let (min, max) = (number(min)?, number(max)?);
+            Comparison::Between(min.min(max), min.max(max))
+        } else {
+            Comparison::Equal(number(input.strip_prefix('=').unwrap_or(input))?)
+        };
+
+        Some(comparison)
+    }
+}
+
+impl Query {
+    /// Parse the search input
+    pub fn parse(input: &str) -> Self {
+        let tokens = lex(input);
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let mut expressions = vec![];
+        while !parser.is_at_end() {
+            match parser.parse_or() {
+                Some(expression) => expressions.push(expression),
+                // Skip a closing parenthesis without an opening one
+                None => parser.position += 1,
+            }
+        }
+
+        simplify(Query::And(expressions))
+    }
+
+    /// Return if the query does not contain any condition (all songs match)
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Query::And(expressions) if expressions.is_empty())
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |f: &mut Formatter<'_>, expressions: &[Query], separator: &str| {
+            for (i, expression) in expressions.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(separator)?;
+                }
+                match expression {
+                    Query::And(_) | Query::Or(_) => write!(f, "({})", expression)?,
+                    _ => write!(f, "{}", expression)?,
+                }
+            }
+            Ok(())
+        };
+        match self {
+            Query::Word(word) => f.write_str(word),
+            Query::Phrase(phrase) => write!(f, "\"{}\"", phrase),
+            Query::Filter(filter) => write!(f, "{}", filter),
+            Query::And(expressions) => join(f, expressions, " "),
+            Query::Or(expressions) => join(f, expressions, " OR "),
+            Query::Not(expression) => match expression.as_ref() {
+                Query::And(_) | Query::Or(_) => write!(f, "NOT ({})", expression),
+                _ => write!(f, "NOT {}", expression),
+            },
+        }
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = |value: &str| {
+            if value.contains(char::is_whitespace) {
+                format!("\"{}\"", value)
+            } else {
+                value.to_string()
+            }
+        };
+        match self {
+            Filter::Artist(artist) => write!(f, "artist:{}", value(artist)),
+            Filter::Key(key) => write!(f, "key:{}", key),
+            Filter::Tempo(comparison) => match comparison {
+                Comparison::Equal(v) => write!(f, "tempo:{}", v),
+                Comparison::Greater(v) => write!(f, "tempo:>{}", v),
+                Comparison::GreaterOrEqual(v) => write!(f, "tempo:>={}", v),
+                Comparison::Less(v) => write!(f, "tempo:<{}", v),
+                Comparison::LessOrEqual(v) => write!(f, "tempo:<={}", v),
+                Comparison::Between(min, max) => write!(f, "tempo:{}-{}", min, max),
+            },
+            Filter::Tag(tag) => write!(f, "tag:{}", value(tag)),
+            Filter::Ccli(ccli) => write!(f, "ccli:{}", ccli),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lexeme {
+    Word(String),
+    Phrase(String),
+    /// Field name and value
+    Field(String, String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn lex(input: &str) -> Vec<Lexeme> {
+    let mut lexemes = vec![];
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                lexemes.push(Lexeme::Open);
+            }
+            ')' => {
+                chars.next();
+                lexemes.push(Lexeme::Close);
+            }
+            '"' => {
+                chars.next();
+                lexemes.push(Lexeme::Phrase(read_quoted(&mut chars)));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                let quoted_value = word.ends_with(':') && chars.peek() == Some(&'"');
+                if quoted_value {
+                    chars.next();
+                    word.push_str(&read_quoted(&mut chars));
+                }
+
+                lexemes.push(match word.as_str() {
+                    "AND" | "&&" => Lexeme::And,
+                    "OR" | "||" => Lexeme::Or,
+                    "NOT" => Lexeme::Not,
+                    _ => match word.split_once(':') {
+                        Some((field, value)) if !field.is_empty() && !value.is_empty() => {
+                            Lexeme::Field(field.to_lowercase(), value.to_string())
+                        }
+                        _ => Lexeme::Word(word),
+                    },
+                });
+            }
+        }
+    }
+
+    lexemes
+}
+
+fn read_quoted(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut value = String::new();
+    for c in chars.by_ref() {
+        if c == '"' {
+            break;
+        }
+        value.push(c);
+    }
+
+    value
+}
+
+struct Parser<'a> {
+    tokens: &'a [Lexeme],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn is_at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Lexeme> {
+        self.tokens.get(self.position)
+    }
+
+    fn parse_or(&mut self) -> Option<Query> {
+        let mut expressions = vec![];
+        expressions.extend(self.parse_and());
+        while self.peek() == Some(&Lexeme::Or) {
+            self.position += 1;
+            expressions.extend(self.parse_and());
+        }
+
+        match expressions.len() {
+            0 => None,
+            1 => expressions.pop(),
+            _ => Some(Query::Or(expressions)),
+        }
+    }
+
+    fn parse_and(&mut self) -> Option<Query> {
+        let mut expressions = vec![];
+        loop {
+            match self.peek() {
+                None | Some(Lexeme::Or) | Some(Lexeme::Close) => break,
+                Some(Lexeme::And) => self.position += 1,
+                _ => expressions.extend(self.parse_unary()),
+            }
+        }
+
+        match expressions.len() {
+            0 => None,
+            1 => expressions.pop(),
+            _ => Some(Query::And(expressions)),
+        }
+    }
+
+    fn parse_unary(&mut self) -> Option<Query> {
+        let lexeme = self.peek()?.clone();
+        self.position += 1;
+        match lexeme {
+            Lexeme::Not => match self.peek() {
+                None | Some(Lexeme::Or) | Some(Lexeme::Close) => None,
+                _ => self
+                    .parse_unary()
+                    .map(|expression| Query::Not(Box::new(expression))),
+            },
+            Lexeme::Open => {
+                let expression = self.parse_or();
+                if self.peek() == Some(&Lexeme::Close) {
+                    self.position += 1;
+                }
+                expression
+            }
+            Lexeme::Word(word) => Some(word_query(&word)),
+            Lexeme::Phrase(phrase) => Some(Query::Phrase(phrase)),
+            Lexeme::Field(field, value) => Some(field_query(&field, value)),
+            Lexeme::And | Lexeme::Or | Lexeme::Close => None,
+        }
+    }
+}
+
+fn word_query(word: &str) -> Query {
+    match word.strip_prefix('#') {
+        Some(tag) if !tag.is_empty() => Query::Filter(Filter::Tag(tag.to_string())),
+        _ => Query::Word(word.to_string()),
+    }
+}
+
+fn field_query(field: &str, value: String) -> Query {
+    let filter = match field {
+        "artist" => Filter::Artist(value),
+        "key" => Filter::Key(value),
+        "tag" => Filter::Tag(value.trim_start_matches('#').to_string()),
+        "ccli" => Filter::Ccli(value),
+        "tempo" => match Comparison::parse(&value) {
+            Some(comparison) => Filter::Tempo(comparison),
+            None => return Query::Word(format!("{}:{}", field, value)),
+        },
+        _ => return Query::Word(format!("{}:{}", field, value)),
+    };
+
+    Query::Filter(filter)
+}
+
+/// Unwrap `And` and `Or` expressions with a single element
+fn simplify(query: Query) -> Query {
+    match query {
+        Query::And(mut expressions) if expressions.len() == 1 => simplify(expressions.remove(0)),
+        Query::Or(mut expressions) if expressions.len() == 1 => simplify(expressions.remove(0)),
+        query => query,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: &str) -> Query {
+        Query::Word(value.to_string())
+    }
+
+    fn filter(filter: Filter) -> Query {
+        Query::Filter(filter)
+    }
+
+    #[test]
+    fn parse_words() {
+        assert_eq!(Query::parse("grace"), word("grace"));
+        assert_eq!(
+            Query::parse("amazing grace"),
+            Query::And(vec![word("amazing"), word("grace")])
+        );
+        assert!(Query::parse("  ").is_empty());
+    }
+
+    #[test]
+    fn parse_filters() {
+        assert_eq!(
+            Query::parse("key:G tag:advent NOT artist:Hillsong"),
+            Query::And(vec![
+                filter(Filter::Key("G".to_string())),
+                filter(Filter::Tag("advent".to_string())),
+                Query::Not(Box::new(filter(Filter::Artist("Hillsong".to_string())))),
+            ])
+        );
+        assert_eq!(
+            Query::parse("tempo:>100"),
+            filter(Filter::Tempo(Comparison::Greater(100)))
+        );
+        assert_eq!(
+            Query::parse("tempo:80-100"),
+            filter(Filter::Tempo(Comparison::Between(80, 100)))
+        );
+        assert_eq!(
+            Query::parse("artist:\"Hillsong United\""),
+            filter(Filter::Artist("Hillsong United".to_string()))
+        );
+        assert_eq!(
+            Query::parse("#advent"),
+            filter(Filter::Tag("advent".to_string()))
+        );
+        assert_eq!(Query::parse("tempo:fast"), word("tempo:fast"));
+        assert_eq!(Query::parse("theme:grace"), word("theme:grace"));
+    }
+
+    #[test]
+    fn parse_operators_and_phrases() {
+        assert_eq!(
+            Query::parse("\"sweet chariot\" OR (key:D AND NOT swing)"),
+            Query::Or(vec![
+                Query::Phrase("sweet chariot".to_string()),
+                Query::And(vec![
+                    filter(Filter::Key("D".to_string())),
+                    Query::Not(Box::new(word("swing"))),
+                ]),
+            ])
+        );
+        // Unbalanced quotes and parentheses are tolerated
+        assert_eq!(
+            Query::parse("(swing \"low sweet"),
+            Query::And(vec![word("swing"), Query::Phrase("low sweet".to_string())])
+        );
+        assert_eq!(
+            Query::parse("swing ) low"),
+            Query::And(vec![word("swing"), word("low")])
+        );
+    }
+
+    #[test]
+    fn display_query() {
+        let input = "\"sweet chariot\" OR (key:D NOT swing tempo:>=90)";
+        assert_eq!(Query::parse(input).to_string(), input);
+    }
+}
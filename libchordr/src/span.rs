@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result};
+
+/// Position inside a source text
+///
+/// Lines and columns start at `1`. Columns count characters, not bytes
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Return the position after the given character
+    pub(crate) fn advance(self, character: char) -> Self {
+        if character == '\n' {
+            Self::new(self.line + 1, 1)
+        } else {
+            Self::new(self.line, self.column + 1)
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Range inside a source text
+///
+/// `start` is inclusive, `end` is exclusive
+#[derive(
+    Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default,
+)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Return a span covering both `self` and `other`
+    pub fn to(self, other: Span) -> Self {
+        Self::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(&self.start, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance() {
+        let position = Position::default().advance('a').advance('ä');
+        assert_eq!(position, Position::new(1, 3));
+        assert_eq!(position.advance('\n'), Position::new(2, 1));
+    }
+
+    #[test]
+    fn to() {
+        let first = Span::new(Position::new(1, 3), Position::new(1, 6));
+        let second = Span::new(Position::new(2, 1), Position::new(2, 4));
+        assert_eq!(
+            first.to(second),
+            Span::new(Position::new(1, 3), Position::new(2, 4))
+        );
+        assert_eq!(second.to(first), first.to(second));
+        assert_eq!(first.to(second).to_string(), "1:3");
+    }
+}
@@ -322,5 +322,9 @@ pub fn get_test_metadata() -> MetaInformation {
         ccli_song_id: None,
         b_notation: BNotation::B,
         tags: Tags::from(vec![Tag::new("oldie"), Tag::new("Jordan")]),
+        language: None,
+        chord_definitions: vec![],
+        custom_fields: Default::default(),
+        arrangements: vec![],
     }
 }
@@ -0,0 +1,168 @@
+const DIRECTIVE_START: char = '{';
+const DIRECTIVE_END: char = '}';
+const LABEL_ATTRIBUTE: &str = "label=";
+
+/// A ChordPro directive like `{title: Swing Low Sweet Chariot}` or `{start_of_chorus}`
+#[derive(Debug, PartialEq, Clone)]
+pub(super) struct Directive {
+    name: String,
+    value: Option<String>,
+}
+
+impl Directive {
+    /// Try to read a directive from the given (trimmed) line
+    ///
+    /// `None` is returned if `line` is not enclosed in curly braces
+    pub(super) fn parse(line: &str) -> Option<Self> {
+        let inner = line
+            .strip_prefix(DIRECTIVE_START)?
+            .strip_suffix(DIRECTIVE_END)?
+            .trim();
+
+        let (name, value) = match inner.find(|c: char| c == ':' || c.is_whitespace()) {
+            Some(position) => {
+                let (name, rest) = inner.split_at(position);
+                let rest = rest.strip_prefix(':').unwrap_or(rest).trim();
+
+                (name, if rest.is_empty() { None } else { Some(rest) })
+            }
+            None => (inner, None),
+        };
+        if name.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            name: canonical_name(&name.to_lowercase()).to_owned(),
+            value: value.map(ToOwned::to_owned),
+        })
+    }
+
+    /// Return the canonical (long) name of the directive
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the raw value of the directive
+    pub(super) fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Return the label of a section directive
+    ///
+    /// Both the ChordPro 5 (`{start_of_chorus: Chorus 2}`) and the ChordPro 6 syntax
+    /// (`{start_of_chorus label="Chorus 2"}`) are supported
+    pub(super) fn label(&self) -> Option<&str> {
+        let value = self.value()?;
+        match value.strip_prefix(LABEL_ATTRIBUTE) {
+            Some(quoted) => {
+                let label = quoted.trim().trim_matches('"');
+                if label.is_empty() {
+                    None
+                } else {
+                    Some(label)
+                }
+            }
+            None => Some(value),
+        }
+    }
+}
+
+/// Map the abbreviated directive names to their long form
+fn canonical_name(name: &str) -> &str {
+    match name {
+        "t" => "title",
+        "st" => "subtitle",
+        "c" => "comment",
+        "ci" => "comment_italic",
+        "cb" => "comment_box",
+        "soc" => "start_of_chorus",
+        "eoc" => "end_of_chorus",
+        "sov" => "start_of_verse",
+        "eov" => "end_of_verse",
+        "sob" => "start_of_bridge",
+        "eob" => "end_of_bridge",
+        "sot" => "start_of_tab",
+        "eot" => "end_of_tab",
+        "sog" => "start_of_grid",
+        "eog" => "end_of_grid",
+        "ns" => "new_song",
+        "np" => "new_page",
+        "npp" => "new_physical_page",
+        "colb" => "column_break",
+        "col" => "columns",
+        "g" => "grid",
+        "ng" => "no_grid",
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            Directive::parse("{title: Swing Low Sweet Chariot}"),
+            Some(Directive {
+                name: "title".to_owned(),
+                value: Some("Swing Low Sweet Chariot".to_owned())
+            })
+        );
+        assert_eq!(
+            Directive::parse("{t:Swing Low}"),
+            Some(Directive {
+                name: "title".to_owned(),
+                value: Some("Swing Low".to_owned())
+            })
+        );
+        assert_eq!(
+            Directive::parse("{SOC}"),
+            Some(Directive {
+                name: "start_of_chorus".to_owned(),
+                value: None
+            })
+        );
+        assert_eq!(
+            Directive::parse("{ end_of_chorus }"),
+            Some(Directive {
+                name: "end_of_chorus".to_owned(),
+                value: None
+            })
+        );
+        assert_eq!(
+            Directive::parse("{meta: artist Wallace Willis}"),
+            Some(Directive {
+                name: "meta".to_owned(),
+                value: Some("artist Wallace Willis".to_owned())
+            })
+        );
+        assert_eq!(Directive::parse("{}"), None);
+        assert_eq!(Directive::parse("title: No braces"), None);
+        assert_eq!(Directive::parse("{title: Unclosed"), None);
+    }
+
+    #[test]
+    fn label_test() {
+        assert_eq!(
+            Directive::parse("{start_of_chorus: Chorus 2}")
+                .unwrap()
+                .label(),
+            Some("Chorus 2")
+        );
+        assert_eq!(
+            Directive::parse(r#"{start_of_chorus label="Chorus 2"}"#)
+                .unwrap()
+                .label(),
+            Some("Chorus 2")
+        );
+        assert_eq!(Directive::parse("{start_of_chorus}").unwrap().label(), None);
+        assert_eq!(
+            Directive::parse(r#"{start_of_verse label=""}"#)
+                .unwrap()
+                .label(),
+            None
+        );
+    }
+}
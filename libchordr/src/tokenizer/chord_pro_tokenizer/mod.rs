@@ -0,0 +1,487 @@
+use std::io::BufRead;
+
+use crate::error::Error;
+
+use super::tokenizer_error::{TokenizerError, TokenizerErrorKind};
+use super::{Meta, Modifier, Token, Tokenizer};
+use crate::span::{Position, Span};
+
+use self::directive::Directive;
+
+mod directive;
+
+const COMMENT_START: char = '#';
+const CHORD_START: char = '[';
+const CHORD_END: char = ']';
+const ANNOTATION_MARK: char = '*';
+
+/// Tokenizer for [ChordPro](https://www.chordpro.org/) files
+///
+/// The tokenizer emits the same `Token`s as the `ChorddownTokenizer`, so that the resulting
+/// stream can be handled by the regular `Parser` and every converter
+pub(crate) struct ChordProTokenizer {}
+
+impl ChordProTokenizer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Tokenizer for ChordProTokenizer {
+    fn tokenize<R: BufRead>(&self, input: R) -> Result<(Vec<Token>, Vec<TokenizerError>), Error> {
+        let mut state = State::new();
+        for line in input.lines() {
+            let line = line?;
+            log::trace!("Tokenize line `{}`", line.trim_end());
+            state.line += 1;
+            state.line_length = line.chars().count();
+            state.visit_line(&line);
+        }
+
+        Ok((state.tokens, state.warnings))
+    }
+}
+
+struct State {
+    tokens: Vec<Token>,
+    warnings: Vec<TokenizerError>,
+    /// Flag if the lyrics belong to an open section
+    ///
+    /// ChordPro allows lyrics outside of `{start_of_*}`/`{end_of_*}` blocks. Those will be
+    /// wrapped in an implicit verse section
+    in_section: bool,
+    verse_count: usize,
+    /// Number and length of the current line, used for the warnings' spans
+    line: usize,
+    line_length: usize,
+}
+
+impl State {
+    fn new() -> Self {
+        Self {
+            tokens: vec![],
+            warnings: vec![],
+            in_section: false,
+            verse_count: 0,
+            line: 0,
+            line_length: 0,
+        }
+    }
+
+    fn visit_line(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            self.tokens.push(Token::newline());
+            return;
+        }
+        if trimmed.starts_with(COMMENT_START) {
+            return;
+        }
+        if let Some(directive) = Directive::parse(trimmed) {
+            self.visit_directive(directive);
+            return;
+        }
+
+        if !self.in_section {
+            let title = self.next_verse_title();
+            self.open_section(title, Modifier::None);
+        }
+        self.visit_lyrics(line.trim_end());
+        self.tokens.push(Token::newline());
+    }
+
+    fn visit_directive(&mut self, directive: Directive) {
+        let value = directive.value().unwrap_or_default().trim();
+        match directive.name() {
+            "title" => {
+                self.tokens.push(Token::headline(1, value, Modifier::None));
+                self.tokens.push(Token::newline());
+                self.in_section = false;
+            }
+            "meta" => {
+                let (keyword, content) =
+                    value.split_once(char::is_whitespace).unwrap_or((value, ""));
+                self.push_meta(keyword, content);
+            }
+            "comment" | "comment_italic" | "comment_box" | "highlight" => {
+                self.push_quote(value);
+            }
+            "chorus" => {
+                self.push_quote(directive.label().unwrap_or("Chorus"));
+            }
+            "start_of_chorus" => {
+                self.open_section(directive.label().unwrap_or("Chorus"), Modifier::Chorus)
+            }
+            "start_of_bridge" => {
+                self.open_section(directive.label().unwrap_or("Bridge"), Modifier::Bridge)
+            }
+            "start_of_verse" => {
+                let title = match directive.label() {
+                    Some(label) => label.to_owned(),
+                    None => self.next_verse_title(),
+                };
+                self.open_section(title, Modifier::None)
+            }
+            name if name.starts_with("start_of_") => {
+                let title = match directive.label() {
+                    Some(label) => label.to_owned(),
+                    None => capitalize(name.trim_start_matches("start_of_")),
+                };
+                self.open_section(title, Modifier::None)
+            }
+            name if name.starts_with("end_of_") => self.in_section = false,
+            name if is_layout_directive(name) => { /* Layout directives have no equivalent */ }
+            name => match build_meta(name, value) {
+                Some(meta) => self.push_meta_token(meta),
+                None => {
+                    log::debug!("Unsupported ChordPro directive '{}'", name);
+                    self.warn(TokenizerErrorKind::UnsupportedDirective)
+                }
+            },
+        }
+    }
+
+    fn visit_lyrics(&mut self, line: &str) {
+        let mut rest = line;
+        while let Some(start) = rest.find(CHORD_START) {
+            let (text, chord_and_rest) = rest.split_at(start);
+            self.push_literal(text);
+
+            let chord_content = &chord_and_rest[CHORD_START.len_utf8()..];
+            match chord_content.find(CHORD_END) {
+                Some(end) => {
+                    let chord = &chord_content[..end];
+                    if let Some(annotation) = chord.strip_prefix(ANNOTATION_MARK) {
+                        // Annotations like `[*Coda]` are no chords
+                        self.push_literal(annotation);
+                    } else if !chord.trim().is_empty() {
+                        self.tokens.push(Token::chord(chord.trim()));
+                    }
+                    rest = &chord_content[end + CHORD_END.len_utf8()..];
+                }
+                None => {
+                    // Keep the text of the unclosed chord including the bracket
+                    self.warn(TokenizerErrorKind::UnclosedChord);
+                    self.push_literal(chord_and_rest);
+                    rest = "";
+                }
+            }
+        }
+        self.push_literal(rest);
+    }
+
+    fn open_section<S: Into<String>>(&mut self, title: S, modifier: Modifier) {
+        self.tokens.push(Token::headline(2, title, modifier));
+        self.tokens.push(Token::newline());
+        self.in_section = true;
+    }
+
+    fn next_verse_title(&mut self) -> String {
+        self.verse_count += 1;
+
+        format!("Verse {}", self.verse_count)
+    }
+
+    fn push_meta(&mut self, keyword: &str, content: &str) {
+        match build_meta(keyword, content) {
+            Some(meta) => self.push_meta_token(meta),
+            None => self.push_meta_token(Meta::custom(keyword, content.trim())),
+        }
+    }
+
+    fn warn(&mut self, kind: TokenizerErrorKind) {
+        let span = Span::new(
+            Position::new(self.line, 1),
+            Position::new(self.line, self.line_length + 1),
+        );
+        self.warnings.push(TokenizerError::new(kind, Some(span)))
+    }
+
+    fn push_meta_token(&mut self, meta: Meta) {
+        self.tokens.push(Token::Meta(meta));
+        self.tokens.push(Token::newline());
+    }
+
+    fn push_quote(&mut self, text: &str) {
+        // A quote starts a new (reference) section in the parsed tree, so following lyrics
+        // must open a new section
+        self.tokens.push(Token::quote(text));
+        self.tokens.push(Token::newline());
+        self.in_section = false;
+    }
+
+    fn push_literal(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.tokens.push(Token::literal(text));
+        }
+    }
+}
+
+/// Build the `Meta` for a ChordPro meta data name (e.g. `artist` or `original_title`)
+fn build_meta(keyword: &str, content: &str) -> Option<Meta> {
+    match keyword.to_lowercase().as_str() {
+        "ccli" => Some(Meta::ccli_song_id(content.trim())),
+        keyword => Meta::from_keyword_and_content(&keyword.replace('_', "-"), content),
+    }
+}
+
+fn is_layout_directive(name: &str) -> bool {
+    matches!(
+        name,
+        "new_song"
+            | "new_page"
+            | "new_physical_page"
+            | "column_break"
+            | "columns"
+            | "pagetype"
+            | "grid"
+            | "no_grid"
+            | "chord"
+            | "titles"
+            | "image"
+    ) || name.ends_with("font")
+        || name.ends_with("size")
+        || name.ends_with("colour")
+}
+
+fn capitalize(input: &str) -> String {
+    let mut characters = input.chars();
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{Parser, ParserTrait, SectionType};
+
+    use super::*;
+
+    #[test]
+    fn test_tokenize_long() {
+        use Token::Newline;
+        let content = include_str!("../../../tests/resources/swing_low_sweet_chariot.cho");
+        let (tokens, warnings) = ChordProTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Newline,
+                Token::headline(1, "Swing Low Sweet Chariot", Modifier::None),
+                Newline,
+                Newline,
+                Token::headline(2, "Chorus", Modifier::Chorus),
+                Newline,
+                Token::literal("Swing "),
+                Token::chord("D"),
+                Token::literal("low, sweet "),
+                Token::chord("G"),
+                Token::literal("chari"),
+                Token::chord("D"),
+                Token::literal("ot,"),
+                Newline,
+                Token::literal("Comin’ for to carry me "),
+                Token::chord("A7"),
+                Token::literal("home."),
+                Newline,
+                Token::literal("Swing "),
+                Token::chord("D7"),
+                Token::literal("low, sweet "),
+                Token::chord("G"),
+                Token::literal("chari"),
+                Token::chord("D"),
+                Token::literal("ot,"),
+                Newline,
+                Token::literal("Comin’ for to "),
+                Token::chord("A7"),
+                Token::literal("carry me "),
+                Token::chord("D"),
+                Token::literal("home."),
+                Newline,
+                Newline,
+                Token::headline(2, "Verse 1", Modifier::None),
+                Newline,
+                Token::literal("I "),
+                Token::chord("D"),
+                Token::literal("looked over Jordan, and "),
+                Token::chord("G"),
+                Token::literal("what did I "),
+                Token::chord("D"),
+                Token::literal("see,"),
+                Newline,
+                Token::literal("Comin’ for to carry me "),
+                Token::chord("A7"),
+                Token::literal("home."),
+                Newline,
+                Token::literal("A "),
+                Token::chord("D"),
+                Token::literal("band of angels "),
+                Token::chord("G"),
+                Token::literal("comin’ after "),
+                Token::chord("D"),
+                Token::literal("me,"),
+                Newline,
+                Token::literal("Comin’ for to "),
+                Token::chord("A7"),
+                Token::literal("carry me "),
+                Token::chord("D"),
+                Token::literal("home."),
+                Newline,
+                Newline,
+                Token::quote("Chorus"),
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_long() {
+        let content = include_str!("../../../tests/resources/swing_low_sweet_chariot.cho");
+        let (tokens, _warnings) = ChordProTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        let parser_result = Parser::new().parse(tokens).unwrap();
+        assert_eq!(
+            Some("Swing Low Sweet Chariot".to_string()),
+            parser_result.meta().title
+        );
+
+        let sections = parser_result.node_as_ref().get_sections();
+        assert_eq!(3, sections.len());
+        assert_eq!(SectionType::Chorus, sections[0].section_type);
+        assert_eq!("Chorus", &sections[0].title);
+        assert_eq!(SectionType::Verse, sections[1].section_type);
+        assert_eq!("Verse 1", &sections[1].title);
+        assert_eq!(SectionType::Reference, sections[2].section_type);
+        assert_eq!("Chorus", &sections[2].title);
+    }
+
+    #[test]
+    fn test_tokenize_meta() {
+        use Token::Newline;
+        let content = r"{subtitle: Traditional}
+{artist: Wallace Willis}
+{key: D}
+{meta: composer Wallace Willis}
+{meta: original_title Swing Low}
+{ccli: 123456}
+{define: Am base-fret 1 frets x 0 2 2 1 0}
+{meta: theme Grace}
+{x_unknown: Value}
+";
+        let (tokens, warnings) = ChordProTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert_eq!(1, warnings.len());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Meta(Meta::subtitle("Traditional")),
+                Newline,
+                Token::Meta(Meta::artist("Wallace Willis")),
+                Newline,
+                Token::Meta(Meta::key("D")),
+                Newline,
+                Token::Meta(Meta::composer("Wallace Willis")),
+                Newline,
+                Token::Meta(Meta::original_title("Swing Low")),
+                Newline,
+                Token::Meta(Meta::ccli_song_id("123456")),
+                Newline,
+                Token::Meta(Meta::define("Am base-fret 1 frets x 0 2 2 1 0")),
+                Newline,
+                Token::Meta(Meta::custom("theme", "Grace")),
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_sections() {
+        use Token::Newline;
+        let content = r#"{sob}
+[Em]Bridge
+{eob}
+{start_of_verse label="The Verse"}
+Verse
+{end_of_verse}
+{start_of_tab}
+e|--0--|
+{end_of_tab}
+{chorus}
+"#;
+        let (tokens, warnings) = ChordProTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(2, "Bridge", Modifier::Bridge),
+                Newline,
+                Token::chord("Em"),
+                Token::literal("Bridge"),
+                Newline,
+                Token::headline(2, "The Verse", Modifier::None),
+                Newline,
+                Token::literal("Verse"),
+                Newline,
+                Token::headline(2, "Tab", Modifier::None),
+                Newline,
+                Token::literal("e|--0--|"),
+                Newline,
+                Token::quote("Chorus"),
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_lyrics() {
+        use Token::Newline;
+        let content = "[D]Swing low [*Coda]sweet [G]chari[D\n[]Comin’";
+        let (tokens, warnings) = ChordProTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert_eq!(1, warnings.len());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(2, "Verse 1", Modifier::None),
+                Newline,
+                Token::chord("D"),
+                Token::literal("Swing low "),
+                Token::literal("Coda"),
+                Token::literal("sweet "),
+                Token::chord("G"),
+                Token::literal("chari"),
+                Token::literal("[D"),
+                Newline,
+                Token::literal("Comin’"),
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unclosed_chord() {
+        let (tokens, warnings) = ChordProTokenizer::new()
+            .tokenize("Swing [D low".as_bytes())
+            .unwrap();
+        assert_eq!(1, warnings.len());
+        assert_eq!(TokenizerErrorKind::UnclosedChord, warnings[0].kind());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(2, "Verse 1", Modifier::None),
+                Token::Newline,
+                Token::literal("Swing "),
+                Token::literal("[D low"),
+                Token::Newline,
+            ]
+        );
+    }
+}
@@ -8,3 +8,4 @@ pub(super) const QUOTE_START: char = '>';
 pub(super) const COLON: char = ':';
 pub(super) const CHORUS_MARK: char = '!';
 pub(super) const BRIDGE_MARK: char = '-';
+pub(super) const TRANSLATION_START: char = '~';
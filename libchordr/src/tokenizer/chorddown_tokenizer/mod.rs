@@ -3,7 +3,7 @@ use std::io::BufRead;
 use crate::error::Error;
 
 use super::tokenizer_error::TokenizerError;
-use super::{Token, Tokenizer};
+use super::{SpannedToken, Token, Tokenizer};
 
 use self::scanner::Scanner;
 use self::state_machine::Fsm;
@@ -24,17 +24,26 @@ impl ChorddownTokenizer {
 
 impl Tokenizer for ChorddownTokenizer {
     fn tokenize<R: BufRead>(&self, input: R) -> Result<(Vec<Token>, Vec<TokenizerError>), Error> {
-        let lexemes_vec = Scanner::new().scan(input)?;
-        let lexemes = lexemes_vec.iter().peekable();
-        let mut tokens: Vec<Token> = vec![];
-        let mut fsm = Fsm::new();
+        let (tokens, warnings) = self.tokenize_with_spans(input)?;
 
-        for lexeme in lexemes {
-            if let Some(changed_state) = fsm.characterize_lexeme(lexeme) {
-                let token = fsm.build_token();
+        Ok((
+            tokens.into_iter().map(|(token, _)| token).collect(),
+            warnings,
+        ))
+    }
 
-                if let Some(token) = token {
-                    tokens.push(token);
+    fn tokenize_with_spans<R: BufRead>(
+        &self,
+        input: R,
+    ) -> Result<(Vec<SpannedToken>, Vec<TokenizerError>), Error> {
+        let lexemes = Scanner::new().scan_with_spans(input)?;
+        let mut tokens = vec![];
+        let mut fsm = Fsm::new();
+
+        for (lexeme, span) in &lexemes {
+            if let Some(changed_state) = fsm.characterize_lexeme(lexeme, *span) {
+                if let Some((token, span)) = fsm.build_token() {
+                    tokens.push((token, Some(span)));
                 }
                 fsm.set_state(changed_state);
             }
@@ -49,6 +58,7 @@ mod tests {
     use crate::models::meta::tags::Tag;
     use crate::models::meta::{BNotation, Tags};
     use crate::test_helpers::get_test_tokens;
+    use crate::tokenizer::tokenizer_error::TokenizerErrorKind;
     use crate::tokenizer::{Meta, Modifier};
 
     use super::*;
@@ -161,6 +171,54 @@ Key: Cm
         assert_eq!(tokens.get(5), Some(&Token::Newline));
     }
 
+    #[test]
+    fn test_tokenize_meta_with_colon() {
+        let (tokens, _warnings) = ChorddownTokenizer::new()
+            .tokenize("Duration: 3:30\n".as_bytes())
+            .unwrap();
+        assert_eq!(tokens.first(), Some(&Token::Meta(Meta::duration("3:30"))));
+    }
+
+    #[test]
+    fn test_tokenize_custom_meta() {
+        let content = "# Song\nTheme: Grace\nBible reference: Psalm 23:1\n\nTheme: Hope\n";
+        let (tokens, _warnings) = ChorddownTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(1, "Song", Modifier::None),
+                Token::Newline,
+                Token::Meta(Meta::custom("Theme", "Grace")),
+                Token::Newline,
+                Token::Meta(Meta::custom("Bible reference", "Psalm 23:1")),
+                Token::Newline,
+                Token::Newline,
+                // Outside of the header unknown keywords are lyrics
+                Token::literal("Theme: Hope"),
+                Token::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_translation() {
+        let content = "Swing low\n~ Schwing tief\n";
+        let (tokens, _warnings) = ChorddownTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::literal("Swing low"),
+                Token::Newline,
+                Token::translation("Schwing tief"),
+                Token::Newline,
+            ]
+        );
+    }
+
     #[test]
     fn test_tokenize_newline() {
         let content = "\n\n\n";
@@ -272,9 +330,12 @@ Key: C#m
     #[test]
     fn test_tokenize_meta_tags() {
         let content = r"Tags: #tag1 #tag2";
-        let (token_lines, _warnings) = ChorddownTokenizer::new()
+        let (token_lines, warnings) = ChorddownTokenizer::new()
             .tokenize(content.as_bytes())
             .unwrap();
+        assert!(!warnings
+            .iter()
+            .any(|warning| warning.kind() == TokenizerErrorKind::UnexpectedHeaderStart));
         assert_eq!(1, token_lines.len());
         assert_eq!(
             token_lines,
@@ -288,6 +349,31 @@ Key: C#m
         );
     }
 
+    #[test]
+    fn test_tokenize_meta_define() {
+        let content = "Define: Am 1 x 0 2 2 1 0\n{define: G base-fret 1 frets 3 2 0 0 0 3}";
+        let (token_lines, _warnings) = ChorddownTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert_eq!(
+            token_lines,
+            vec![
+                Token::Meta(Meta::define("Am 1 x 0 2 2 1 0")),
+                Token::Newline,
+                Token::Meta(Meta::define("G base-fret 1 frets 3 2 0 0 0 3")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_braces_only_for_define() {
+        let content = "{capo: 2}";
+        let (token_lines, _warnings) = ChorddownTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert_eq!(token_lines, vec![Token::literal("{capo: 2}")]);
+    }
+
     #[test]
     fn test_tokenize_pre_chorus() {
         let content = r"##- Pre-chorus";
@@ -335,4 +421,53 @@ Key: C#m
             vec![Token::headline(2, "Play Loud!!", Modifier::None)]
         );
     }
+
+    #[test]
+    fn test_tokenize_with_spans() {
+        use crate::span::{Position, Span};
+
+        let content = "# Song\nSwing [D]low\n";
+        let (tokens, warnings) = ChorddownTokenizer::new()
+            .tokenize_with_spans(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let span = |start: (usize, usize), end: (usize, usize)| {
+            Some(Span::new(
+                Position::new(start.0, start.1),
+                Position::new(end.0, end.1),
+            ))
+        };
+        assert_eq!(
+            tokens,
+            vec![
+                (
+                    Token::headline(1, "Song", Modifier::None),
+                    span((1, 1), (1, 7))
+                ),
+                (Token::newline(), span((1, 7), (1, 8))),
+                (Token::literal("Swing "), span((2, 1), (2, 7))),
+                (Token::chord("D"), span((2, 7), (2, 10))),
+                (Token::literal("low"), span((2, 10), (2, 13))),
+                (Token::newline(), span((2, 13), (2, 14))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_warning_spans() {
+        use crate::span::{Position, Span};
+        use crate::tokenizer::TokenizerErrorKind;
+
+        let content = "# Song\nSwing [D low\n";
+        let (_tokens, warnings) = ChorddownTokenizer::new()
+            .tokenize_with_spans(content.as_bytes())
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec![TokenizerError::new(
+                TokenizerErrorKind::UnclosedChord,
+                Some(Span::new(Position::new(2, 7), Position::new(2, 13)))
+            )]
+        );
+    }
 }
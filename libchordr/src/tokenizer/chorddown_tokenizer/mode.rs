@@ -6,6 +6,7 @@ pub enum Mode {
     Header = 4,
     Newline = 10,
     Quote = 6,
+    Translation = 5,
     Literal = 0,
     Bof = 100,
     Eof = 110,
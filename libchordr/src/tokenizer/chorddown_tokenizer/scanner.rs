@@ -1,6 +1,7 @@
 use std::io::BufRead;
 
 use crate::error::Error;
+use crate::span::{Position, Span};
 use crate::tokenizer::chorddown_tokenizer::lexeme::Lexeme;
 
 use super::keywords::{
@@ -11,17 +12,35 @@ const CARRIAGE_RETURN: char = '\r';
 const LITERAL_BUFFER_CAPACITY: usize = 20;
 
 pub struct Scanner {
-    lexemes: Vec<Lexeme>,
+    lexemes: Vec<(Lexeme, Span)>,
+    /// Start of the literal currently collected in the literal buffer
+    literal_start: Position,
+    position: Position,
 }
 
 impl Scanner {
     pub fn new() -> Self {
         Self {
             lexemes: Vec::with_capacity(400),
+            literal_start: Position::default(),
+            position: Position::default(),
         }
     }
 
-    pub fn scan<R: BufRead>(mut self, mut input: R) -> Result<Vec<Lexeme>, Error> {
+    #[cfg(test)]
+    pub fn scan<R: BufRead>(self, input: R) -> Result<Vec<Lexeme>, Error> {
+        Ok(self
+            .scan_with_spans(input)?
+            .into_iter()
+            .map(|(lexeme, _)| lexeme)
+            .collect())
+    }
+
+    /// Scan the input and return each `Lexeme` together with its position in the source
+    pub fn scan_with_spans<R: BufRead>(
+        mut self,
+        mut input: R,
+    ) -> Result<Vec<(Lexeme, Span)>, Error> {
         let mut literal_buffer = String::with_capacity(LITERAL_BUFFER_CAPACITY);
 
         let mut line = String::new();
@@ -40,8 +59,14 @@ impl Scanner {
                     COLON => self.build_n_push(&mut literal_buffer, Lexeme::Colon),
                     CHORUS_MARK => self.build_n_push(&mut literal_buffer, Lexeme::ChorusMark),
                     BRIDGE_MARK => self.build_n_push(&mut literal_buffer, Lexeme::BridgeMark),
-                    _ => literal_buffer.push(current_character),
+                    _ => {
+                        if literal_buffer.is_empty() {
+                            self.literal_start = self.position;
+                        }
+                        literal_buffer.push(current_character)
+                    }
                 }
+                self.position = self.position.advance(current_character);
             }
 
             line.clear()
@@ -57,7 +82,11 @@ impl Scanner {
     fn build_n_push(&mut self, literal_buffer: &mut String, lexeme: Lexeme) {
         self.build_n_drain(literal_buffer);
 
-        self.lexemes.push(lexeme)
+        let end = match lexeme {
+            Lexeme::Eof => self.position,
+            _ => Position::new(self.position.line(), self.position.column() + 1),
+        };
+        self.lexemes.push((lexeme, Span::new(self.position, end)))
     }
 
     /// Build a `Lexeme::Literal` from the current content of `literal_buffer`, push it to the
@@ -65,10 +94,13 @@ impl Scanner {
     #[inline(always)]
     fn build_n_drain(&mut self, literal_buffer: &mut String) {
         if !literal_buffer.is_empty() {
-            self.lexemes.push(Lexeme::Literal(std::mem::replace(
-                literal_buffer,
-                String::with_capacity(LITERAL_BUFFER_CAPACITY),
-            )));
+            self.lexemes.push((
+                Lexeme::Literal(std::mem::replace(
+                    literal_buffer,
+                    String::with_capacity(LITERAL_BUFFER_CAPACITY),
+                )),
+                Span::new(self.literal_start, self.position),
+            ));
         }
     }
 }
@@ -250,4 +282,27 @@ Tags: #low #sweet
             ]
         );
     }
+
+    #[test]
+    fn scan_with_spans_test() {
+        let content = "# Song\nSwing [D]low";
+        let lexemes = Scanner::new().scan_with_spans(content.as_bytes()).unwrap();
+        let span =
+            |line, start, end| Span::new(Position::new(line, start), Position::new(line, end));
+
+        assert_eq!(
+            lexemes,
+            vec![
+                (H, span(1, 1, 2)),
+                (lit(" Song"), span(1, 2, 7)),
+                (NL, span(1, 7, 8)),
+                (lit("Swing "), span(2, 1, 7)),
+                (CS, span(2, 7, 8)),
+                (lit("D"), span(2, 8, 9)),
+                (CE, span(2, 9, 10)),
+                (lit("low"), span(2, 10, 13)),
+                (EOF, span(2, 13, 13)),
+            ]
+        );
+    }
 }
@@ -1,8 +1,10 @@
 use std::convert::TryFrom;
 
-use crate::tokenizer::tokenizer_error::TokenizerError;
+use crate::span::{Position, Span};
+use crate::tokenizer::tokenizer_error::{TokenizerError, TokenizerErrorKind};
 use crate::tokenizer::{Meta, Modifier, Token};
 
+use super::keywords::TRANSLATION_START;
 use super::lexeme::Lexeme;
 use super::mode::Mode;
 
@@ -11,6 +13,19 @@ pub(crate) struct Fsm {
     literal_buffer: String,
     header_level: u8,
     header_modifier: Option<Modifier>,
+    /// Start of the token that is currently built
+    token_start: Position,
+    /// End of the lexeme before the current one
+    previous_end: Position,
+    lexeme_span: Span,
+    /// Flag if the current lexeme is the last part of the current token (e.g. the closing `]`)
+    closes_token: bool,
+    /// Flag if the song's header (title and meta information) is not finished yet
+    ///
+    /// Only inside of the header unknown `Keyword: value` lines are treated as custom meta fields
+    in_header: bool,
+    has_header_content: bool,
+    previous_token_is_newline: bool,
     pub warnings: Vec<TokenizerError>,
 }
 
@@ -21,6 +36,13 @@ impl Fsm {
             literal_buffer: String::new(),
             header_level: 0,
             header_modifier: None,
+            token_start: Position::default(),
+            previous_end: Position::default(),
+            lexeme_span: Span::default(),
+            closes_token: false,
+            in_header: true,
+            has_header_content: false,
+            previous_token_is_newline: true,
             warnings: vec![],
         }
     }
@@ -28,7 +50,11 @@ impl Fsm {
     /// Return the [Mode] that is signaled by `lexeme` if it did change
     ///
     /// `None` is returned if there is no change to the current [Mode] (=`self.state`)
-    pub fn characterize_lexeme(&mut self, lexeme: &Lexeme) -> Option<Mode> {
+    pub fn characterize_lexeme(&mut self, lexeme: &Lexeme, span: Span) -> Option<Mode> {
+        self.previous_end = self.lexeme_span.end();
+        self.lexeme_span = span;
+        self.closes_token = false;
+
         match self.state {
             Mode::Bof | Mode::Newline => match lexeme {
                 Lexeme::HeaderStart => {
@@ -38,11 +64,15 @@ impl Fsm {
                 Lexeme::Newline => Some(Mode::Newline),
                 Lexeme::ChordStart => Some(Mode::Chord),
                 Lexeme::ChordEnd => {
-                    self.warnings.push(TokenizerError::UnexpectedChordEnd);
+                    self.warn(TokenizerErrorKind::UnexpectedChordEnd);
 
                     Some(Mode::Literal)
                 }
                 Lexeme::QuoteStart => Some(Mode::Quote),
+                Lexeme::Literal(text) if text.starts_with(TRANSLATION_START) => {
+                    self.append_lexeme(lexeme);
+                    Some(Mode::Translation)
+                }
                 Lexeme::Colon | Lexeme::ChorusMark | Lexeme::BridgeMark | Lexeme::Literal(_) => {
                     self.append_lexeme(lexeme);
                     Some(Mode::Literal)
@@ -59,22 +89,25 @@ impl Fsm {
                     }
                     Lexeme::Newline => {
                         // Unclosed chord
-                        self.warnings.push(TokenizerError::UnclosedChord);
+                        self.warn_for_token(TokenizerErrorKind::UnclosedChord);
                         Some(Mode::Newline)
                     }
                     Lexeme::ChordStart => {
                         // Nested chord
                         self.append_lexeme(lexeme);
-                        self.warnings.push(TokenizerError::NestedChord);
+                        self.warn(TokenizerErrorKind::NestedChord);
                         None
                     }
-                    Lexeme::ChordEnd => Some(Mode::Literal),
+                    Lexeme::ChordEnd => {
+                        self.closes_token = true;
+                        Some(Mode::Literal)
+                    }
                     Lexeme::QuoteStart
                     | Lexeme::Colon
                     | Lexeme::ChorusMark
                     | Lexeme::BridgeMark => {
                         self.append_lexeme(lexeme);
-                        self.warnings.push(TokenizerError::InvalidChordCharacter);
+                        self.warn(TokenizerErrorKind::InvalidChordCharacter);
                         None
                     }
                     Lexeme::Literal(_) => {
@@ -82,7 +115,7 @@ impl Fsm {
                         None
                     }
                     Lexeme::Eof => {
-                        self.warnings.push(TokenizerError::UnexpectedEndOfFile);
+                        self.warn_for_token(TokenizerErrorKind::UnexpectedEndOfFile);
                         Fsm::build_eof()
                     }
                 }
@@ -131,7 +164,8 @@ impl Fsm {
                     Lexeme::Eof => Fsm::build_eof(),
                 }
             }
-            Mode::Quote => match lexeme {
+            // Translations are plain text, so brackets are not treated as chords
+            Mode::Quote | Mode::Translation => match lexeme {
                 Lexeme::Newline => Some(Mode::Newline),
                 Lexeme::HeaderStart
                 | Lexeme::ChordStart
@@ -150,7 +184,10 @@ impl Fsm {
                 match lexeme {
                     Lexeme::Newline => Some(Mode::Newline),
                     Lexeme::HeaderStart => {
-                        self.warnings.push(TokenizerError::UnexpectedHeaderStart);
+                        // `#` marks the tags in a `Tags: #oldie #Jordan` line
+                        if !self.is_in_tags_meta() {
+                            self.warn(TokenizerErrorKind::UnexpectedHeaderStart);
+                        }
                         self.append_lexeme(lexeme);
                         None
                     }
@@ -158,7 +195,7 @@ impl Fsm {
                     Lexeme::ChordStart => Some(Mode::Chord),
                     Lexeme::ChordEnd => {
                         // Chord End without an opening bracket
-                        self.warnings.push(TokenizerError::UnexpectedChordEnd);
+                        self.warn(TokenizerErrorKind::UnexpectedChordEnd);
 
                         None
                     }
@@ -171,7 +208,7 @@ impl Fsm {
                         None
                     }
                     Lexeme::Eof => {
-                        self.warnings.push(TokenizerError::UnexpectedEndOfFile);
+                        self.warn(TokenizerErrorKind::UnexpectedEndOfFile);
                         Fsm::build_eof()
                     }
                 }
@@ -180,23 +217,73 @@ impl Fsm {
         }
     }
 
+    /// Add a warning for the current lexeme
+    fn warn(&mut self, kind: TokenizerErrorKind) {
+        self.warnings
+            .push(TokenizerError::new(kind, Some(self.lexeme_span)))
+    }
+
+    /// Add a warning for the token that is currently built
+    fn warn_for_token(&mut self, kind: TokenizerErrorKind) {
+        let span = Span::new(self.token_start, self.previous_end);
+        self.warnings.push(TokenizerError::new(kind, Some(span)))
+    }
+
     fn build_eof() -> Option<Mode> {
         Some(Mode::Eof)
     }
 
-    pub fn build_token(&mut self) -> Option<Token> {
-        match self.state {
+    /// Build the token for the current state together with its position in the source
+    pub fn build_token(&mut self) -> Option<(Token, Span)> {
+        let end = if self.closes_token {
+            self.lexeme_span.end()
+        } else {
+            self.previous_end
+        };
+        let span = Span::new(self.token_start, end);
+
+        let token = match self.state {
             Mode::Header => self.build_token_from_header(),
             Mode::Chord => Some(Token::chord(self.consume_buffer())),
             Mode::Newline => Some(Token::newline()),
             Mode::Quote => Some(Token::quote(self.consume_buffer().trim_start())),
+            Mode::Translation => Some(Token::translation(
+                self.consume_buffer()
+                    .trim_start_matches(TRANSLATION_START)
+                    .trim(),
+            )),
             Mode::Literal => self.build_token_from_literal(),
             Mode::Bof => None,
             Mode::Eof => unreachable!(),
+        };
+
+        if let Some(token) = &token {
+            self.update_header_state(token);
         }
+
+        token.map(|token| (token, span))
+    }
+
+    /// The header ends with the first blank line after the title and meta information or the
+    /// first other token
+    fn update_header_state(&mut self, token: &Token) {
+        match token {
+            Token::Newline if self.previous_token_is_newline && self.has_header_content => {
+                self.in_header = false
+            }
+            Token::Newline => {}
+            Token::Headline { level: 1, .. } | Token::Meta(_) => self.has_header_content = true,
+            _ => self.in_header = false,
+        }
+        self.previous_token_is_newline = *token == Token::Newline;
     }
 
     pub fn set_state(&mut self, state: Mode) {
+        self.token_start = if self.closes_token {
+            self.lexeme_span.end()
+        } else {
+            self.lexeme_span.start()
+        };
         self.state = state
     }
 
@@ -224,14 +311,29 @@ impl Fsm {
         }
     }
 
+    /// Return if the literal that is currently built is a `Tags` meta line
+    fn is_in_tags_meta(&self) -> bool {
+        match self.literal_buffer.split_once(':') {
+            Some((keyword, _)) => matches!(
+                Meta::from_keyword_and_content(keyword, ""),
+                Some(Meta::Tags(_))
+            ),
+            None => false,
+        }
+    }
+
     fn build_token_from_literal(&mut self) -> Option<Token> {
         let literal = self.consume_buffer();
         if literal.is_empty() {
             return None;
         }
-        match Meta::try_from(&literal) {
-            Ok(meta) => Some(Token::Meta(meta)),
-            Err(_) => Some(Token::Literal(literal)),
+        if let Ok(meta) = Meta::try_from(&literal) {
+            return Some(Token::Meta(meta));
+        }
+        let starts_line = self.previous_token_is_newline;
+        match Meta::custom_from_line(&literal) {
+            Some(meta) if self.in_header && starts_line => Some(Token::Meta(meta)),
+            _ => Some(Token::Literal(literal)),
         }
     }
 
@@ -26,10 +26,18 @@ pub enum Meta {
     CCLISongId(String),
     BNotation(BNotation),
     Tags(Tags),
+    /// Languages of the lyrics and their translation (e.g. `German, English`)
+    Language(String),
+    /// Chord fingering in the syntax of ChordPro's `define` directive (see `ChordDefinition`)
+    Define(String),
+    /// Section order declaration (see `Arrangement`)
+    Arrangement(String),
+    /// Meta information with a keyword that is not known (e.g. `Theme: Grace`)
+    Custom(String, String),
 }
 
 impl Meta {
-    fn from_keyword_and_content(word: &str, content: &str) -> Option<Self> {
+    pub(crate) fn from_keyword_and_content(word: &str, content: &str) -> Option<Self> {
         let content = content.trim();
         match word.trim().to_lowercase().as_str() {
             "artist" => Some(Self::artist(content)),
@@ -54,11 +62,14 @@ impl Meta {
                 Some(Self::b_notation(content))
             }
             "tags" => Some(Self::tags(content)),
+            "language" | "languages" | "lang" => Some(Self::language(content)),
+            "define" => Some(Self::define(content)),
+            "arrangement" => Some(Self::arrangement(content)),
             _ => None,
         }
     }
 
-    pub fn keyword(&self) -> &'static str {
+    pub fn keyword(&self) -> &str {
         match self {
             Self::Artist(_) => "Artist",
             Self::Composer(_) => "Composer",
@@ -78,6 +89,10 @@ impl Meta {
             Self::CCLISongId(_) => "CCLI Song #",
             Self::BNotation(_) => "B-Notation",
             Self::Tags(_) => "Tags",
+            Self::Language(_) => "Language",
+            Self::Define(_) => "Define",
+            Self::Arrangement(_) => "Arrangement",
+            Self::Custom(keyword, _) => keyword,
         }
     }
 
@@ -101,6 +116,10 @@ impl Meta {
             Self::CCLISongId(c) => c.to_owned(),
             Self::BNotation(c) => c.to_string(),
             Self::Tags(c) => c.to_string(),
+            Self::Language(c) => c.to_owned(),
+            Self::Define(c) => c.to_owned(),
+            Self::Arrangement(c) => c.to_owned(),
+            Self::Custom(_, c) => c.to_owned(),
         }
     }
 
@@ -180,21 +199,68 @@ impl Meta {
             Err(_) => Default::default(),
         })
     }
+
+    pub fn language<S: Into<String>>(content: S) -> Self {
+        Self::Language(content.into())
+    }
+
+    pub fn define<S: Into<String>>(content: S) -> Self {
+        Self::Define(content.into())
+    }
+
+    pub fn arrangement<S: Into<String>>(content: S) -> Self {
+        Self::Arrangement(content.into())
+    }
+
+    pub fn custom<S1: Into<String>, S2: Into<String>>(keyword: S1, content: S2) -> Self {
+        Self::Custom(keyword.into(), content.into())
+    }
+
+    /// Build a `Meta::Custom` from a line that looks like meta information (e.g. `Theme: Grace`)
+    pub(crate) fn custom_from_line(line: &str) -> Option<Self> {
+        if !looks_like_meta(line) {
+            return None;
+        }
+        let (keyword, content) = line.split_once(':')?;
+
+        Some(Self::custom(keyword.trim(), content.trim()))
+    }
+}
+
+/// Return if the text looks like a meta line with an unknown keyword (e.g. `Arranger: Jane`)
+pub(crate) fn looks_like_meta(text: &str) -> bool {
+    match text.split_once(':') {
+        Some((keyword, _)) => {
+            let keyword = keyword.trim();
+            !keyword.is_empty()
+                && keyword.len() <= 30
+                && keyword
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' || c == '#')
+        }
+        None => false,
+    }
 }
 
 impl TryFrom<&str> for Meta {
     type Error = ();
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let parts = value.split(':').collect::<Vec<&str>>();
-        if parts.len() < 2 {
-            return Err(());
-        }
+        let value = value.trim();
+        // Allow the ChordPro style directive `{define: Am 1 x 0 2 2 1 0}`
+        if let Some(directive) = value.strip_prefix('{').and_then(|v| v.strip_suffix('}')) {
+            let (keyword, content) = directive.split_once(':').ok_or(())?;
 
-        match Self::from_keyword_and_content(parts.get(0).unwrap(), parts.get(1).unwrap()) {
-            Some(p) => Ok(p),
-            None => Err(()),
+            return match Self::from_keyword_and_content(keyword, content) {
+                Some(define @ Self::Define(_)) => Ok(define),
+                _ => Err(()),
+            };
         }
+
+        // The content may contain colons itself (e.g. `Duration: 3:30`)
+        let (keyword, content) = value.split_once(':').ok_or(())?;
+
+        Self::from_keyword_and_content(keyword, content).ok_or(())
     }
 }
 
@@ -1,25 +1,94 @@
 use std::io::BufRead;
 
 use crate::error::Error;
+use crate::models::file_type::FileType;
+use crate::span::Span;
 
+use self::chord_pro_tokenizer::ChordProTokenizer;
 use self::chorddown_tokenizer::ChorddownTokenizer;
 pub use self::meta::Meta;
 pub use self::modifier::Modifier;
+pub(crate) use self::open_lyrics_tokenizer::structure_for_chord_suffix;
+use self::open_lyrics_tokenizer::OpenLyricsTokenizer;
+use self::song_beamer_tokenizer::SongBeamerTokenizer;
 pub use self::token::Token;
-use self::tokenizer_error::TokenizerError;
+pub use self::tokenizer_error::{TokenizerError, TokenizerErrorKind};
 
+mod chord_pro_tokenizer;
 mod chorddown_tokenizer;
 mod meta;
 mod modifier;
+mod open_lyrics_tokenizer;
+mod song_beamer_tokenizer;
 mod token;
 mod tokenizer_error;
 
+/// Token together with its position in the source (if known)
+pub type SpannedToken = (Token, Option<Span>);
+
 pub trait Tokenizer {
     /// Tokenize the given input
     fn tokenize<R: BufRead>(&self, input: R) -> Result<(Vec<Token>, Vec<TokenizerError>), Error>;
+
+    /// Tokenize the given input and return the position of each token in the source
+    ///
+    /// Tokenizers that do not keep track of the source positions return `None` spans
+    fn tokenize_with_spans<R: BufRead>(
+        &self,
+        input: R,
+    ) -> Result<(Vec<SpannedToken>, Vec<TokenizerError>), Error> {
+        let (tokens, warnings) = self.tokenize(input)?;
+
+        Ok((
+            tokens.into_iter().map(|token| (token, None)).collect(),
+            warnings,
+        ))
+    }
 }
 
 /// Build a new Tokenizer instance
 pub fn build_tokenizer() -> impl Tokenizer {
     ChorddownTokenizer::new()
 }
+
+/// Build a new Tokenizer instance for the given input file type
+pub fn build_tokenizer_for_file_type(file_type: FileType) -> impl Tokenizer {
+    FileTypeTokenizer { file_type }
+}
+
+/// Tokenizer that dispatches to the implementation matching the file type
+struct FileTypeTokenizer {
+    file_type: FileType,
+}
+
+impl Tokenizer for FileTypeTokenizer {
+    fn tokenize<R: BufRead>(&self, input: R) -> Result<(Vec<Token>, Vec<TokenizerError>), Error> {
+        match self.file_type {
+            FileType::Chorddown => ChorddownTokenizer::new().tokenize(input),
+            FileType::ChordPro => ChordProTokenizer::new().tokenize(input),
+            FileType::SongBeamer => SongBeamerTokenizer::new().tokenize(input),
+            FileType::OpenLyrics => OpenLyricsTokenizer::new().tokenize(input),
+            FileType::Jpeg => Err(Error::file_type_error(format!(
+                "Files of type {} can not be tokenized",
+                self.file_type
+            ))),
+        }
+    }
+
+    fn tokenize_with_spans<R: BufRead>(
+        &self,
+        input: R,
+    ) -> Result<(Vec<SpannedToken>, Vec<TokenizerError>), Error> {
+        match self.file_type {
+            FileType::Chorddown => ChorddownTokenizer::new().tokenize_with_spans(input),
+            _ => {
+                let (tokens, warnings) = self.tokenize(input)?;
+
+                Ok((
+                    tokens.into_iter().map(|token| (token, None)).collect(),
+                    warnings,
+                ))
+            }
+        }
+    }
+}
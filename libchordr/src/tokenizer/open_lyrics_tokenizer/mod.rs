@@ -0,0 +1,651 @@
+use std::io::BufRead;
+
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, XmlEvent};
+use xml::ParserConfig;
+
+use crate::error::Error;
+
+use super::tokenizer_error::TokenizerError;
+use super::{Meta, Modifier, Token, Tokenizer};
+
+const ROOT_ELEMENT: &str = "song";
+const VERSE_ORDER_SEPARATOR: char = ' ';
+
+/// Tokenizer for [OpenLyrics](https://docs.openlyrics.org/) XML files as used by
+/// [OpenLP](https://openlp.org/)
+///
+/// The verse names (`v1`, `c`, `b`, ...) are translated into section titles and verses which are
+/// repeated in the `<verseOrder>` are emitted as references (`Token::Quote`)
+pub(crate) struct OpenLyricsTokenizer {}
+
+impl OpenLyricsTokenizer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Tokenizer for OpenLyricsTokenizer {
+    fn tokenize<R: BufRead>(&self, input: R) -> Result<(Vec<Token>, Vec<TokenizerError>), Error> {
+        let parser = EventReader::new_with_config(
+            input,
+            ParserConfig::new()
+                .whitespace_to_characters(true)
+                .cdata_to_characters(true),
+        );
+
+        let mut document = Document::default();
+        let mut path: Vec<String> = vec![];
+        for event in parser {
+            let event = event.map_err(|e| {
+                Error::parser_error(format!("Could not read OpenLyrics document: {}", e))
+            })?;
+            match event {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    if path.is_empty() && name.local_name != ROOT_ELEMENT {
+                        return Err(Error::parser_error(format!(
+                            "Invalid OpenLyrics document: Unexpected root element '{}'",
+                            name.local_name
+                        )));
+                    }
+                    document.visit_start(&name.local_name, &attributes, &path);
+                    path.push(name.local_name);
+                }
+                XmlEvent::EndElement { name } => {
+                    path.pop();
+                    document.visit_end(&name.local_name);
+                }
+                XmlEvent::Characters(text) => document.visit_text(&text, &path),
+                _ => {}
+            }
+        }
+
+        Ok((document.into_tokens(), vec![]))
+    }
+}
+
+#[derive(Default)]
+struct Verse {
+    name: String,
+    tokens: Vec<Token>,
+}
+
+#[derive(Default)]
+struct Document {
+    /// The titles of the song and a flag if it is the original title
+    titles: Vec<(String, bool)>,
+    /// The author names and their type (`words`, `music`, ...)
+    authors: Vec<(String, Option<String>)>,
+    copyright: String,
+    ccli_song_id: String,
+    released: String,
+    key: String,
+    tempo: String,
+    verse_order: String,
+    themes: Vec<String>,
+    verses: Vec<Verse>,
+    /// Tokens of the line that is currently read
+    line: Vec<Token>,
+}
+
+impl Document {
+    fn visit_start(&mut self, name: &str, attributes: &[OwnedAttribute], path: &[String]) {
+        match name {
+            "title" => self.titles.push((
+                String::new(),
+                get_attribute(attributes, "original") == Some("true"),
+            )),
+            "author" => self.authors.push((
+                String::new(),
+                get_attribute(attributes, "type").map(ToOwned::to_owned),
+            )),
+            "theme" => self.themes.push(String::new()),
+            "verse" => self.verses.push(Verse {
+                name: get_attribute(attributes, "name")
+                    .unwrap_or_default()
+                    .to_owned(),
+                tokens: vec![],
+            }),
+            "lines" => {
+                // Separate multiple `<lines>` elements with an empty line
+                if let Some(verse) = self.verses.last_mut() {
+                    if !verse.tokens.is_empty() {
+                        verse.tokens.push(Token::newline());
+                    }
+                }
+            }
+            "chord" if is_in_lines(path) => {
+                if let Some(chord) = get_chord(attributes) {
+                    self.line.push(Token::chord(chord));
+                }
+            }
+            "br" if is_in_lines(path) => self.finish_line(),
+            _ => {}
+        }
+    }
+
+    fn visit_end(&mut self, name: &str) {
+        if name == "lines" {
+            self.finish_line()
+        }
+    }
+
+    fn visit_text(&mut self, text: &str, path: &[String]) {
+        if is_in_lines(path) {
+            if !path.iter().any(|element| element == "comment") {
+                self.line.push(Token::literal(normalize_whitespace(text)));
+            }
+            return;
+        }
+
+        let target = match path.last().map(String::as_str) {
+            Some("title") => self.titles.last_mut().map(|(title, _)| title),
+            Some("author") => self.authors.last_mut().map(|(author, _)| author),
+            Some("theme") => self.themes.last_mut(),
+            Some("copyright") => Some(&mut self.copyright),
+            Some("ccliNo") => Some(&mut self.ccli_song_id),
+            Some("released") => Some(&mut self.released),
+            Some("key") => Some(&mut self.key),
+            Some("tempo") => Some(&mut self.tempo),
+            Some("verseOrder") => Some(&mut self.verse_order),
+            _ => None,
+        };
+        if let Some(target) = target {
+            target.push_str(text)
+        }
+    }
+
+    /// Move the tokens of the current line into the current verse
+    fn finish_line(&mut self) {
+        let mut line = std::mem::take(&mut self.line);
+        if let Some(Token::Literal(text)) = line.first_mut() {
+            *text = text.trim_start().to_owned();
+        }
+        if let Some(Token::Literal(text)) = line.last_mut() {
+            *text = text.trim_end().to_owned();
+        }
+
+        if let Some(verse) = self.verses.last_mut() {
+            verse.tokens.extend(
+                line.into_iter()
+                    .filter(|token| !matches!(token, Token::Literal(text) if text.is_empty())),
+            );
+            verse.tokens.push(Token::newline());
+        }
+    }
+
+    fn into_tokens(self) -> Vec<Token> {
+        let mut tokens = vec![];
+        self.build_meta_tokens(&mut tokens);
+        self.build_verse_tokens(&mut tokens);
+
+        tokens
+    }
+
+    fn build_meta_tokens(&self, tokens: &mut Vec<Token>) {
+        let mut titles = self
+            .titles
+            .iter()
+            .filter(|(title, original)| !original && !title.trim().is_empty())
+            .map(|(title, _)| title.trim());
+        if let Some(title) = titles.next() {
+            tokens.push(Token::headline(1, title, Modifier::None));
+            tokens.push(Token::newline());
+        }
+
+        let mut push_meta = |meta: Meta| {
+            tokens.push(Token::Meta(meta));
+            tokens.push(Token::newline());
+        };
+        if let Some(v) = titles.next() {
+            push_meta(Meta::alternative_title(v));
+        }
+        if let Some((v, _)) = self.titles.iter().find(|(_, original)| *original) {
+            push_meta(Meta::original_title(v.trim()));
+        }
+
+        let artists = self.authors_of_type(None);
+        if !artists.is_empty() {
+            push_meta(Meta::artist(artists));
+        }
+        let composers = self.authors_of_type(Some("music"));
+        if !composers.is_empty() {
+            push_meta(Meta::composer(composers));
+        }
+        let lyricists = self.authors_of_type(Some("words"));
+        if !lyricists.is_empty() {
+            push_meta(Meta::lyricist(lyricists));
+        }
+        if !self.copyright.trim().is_empty() {
+            push_meta(Meta::copyright(self.copyright.trim()));
+        }
+        if !self.released.trim().is_empty() {
+            push_meta(Meta::year(self.released.trim()));
+        }
+        if !self.key.trim().is_empty() {
+            push_meta(Meta::key(self.key.trim()));
+        }
+        if !self.tempo.trim().is_empty() {
+            push_meta(Meta::tempo(self.tempo.trim()));
+        }
+        if !self.ccli_song_id.trim().is_empty() {
+            push_meta(Meta::ccli_song_id(self.ccli_song_id.trim()));
+        }
+        if !self.themes.is_empty() {
+            let tags = self
+                .themes
+                .iter()
+                .map(|theme| format!("#{}", theme.trim()))
+                .collect::<Vec<String>>()
+                .join(" ");
+            push_meta(Meta::tags(tags));
+        }
+    }
+
+    fn build_verse_tokens(&self, tokens: &mut Vec<Token>) {
+        let mut emitted: Vec<&str> = vec![];
+        let verse_order = self
+            .verse_order
+            .split(VERSE_ORDER_SEPARATOR)
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty());
+
+        let push_verse = |tokens: &mut Vec<Token>, verse: &Verse| {
+            let (title, modifier) = title_for_verse_name(&verse.name);
+            tokens.push(Token::headline(2, title, modifier));
+            tokens.push(Token::newline());
+            tokens.extend(verse.tokens.iter().cloned());
+            tokens.push(Token::newline());
+        };
+
+        for entry in verse_order {
+            if emitted.contains(&entry) {
+                let (title, _) = title_for_verse_name(entry);
+                tokens.push(Token::quote(title));
+                tokens.push(Token::newline());
+                tokens.push(Token::newline());
+            } else if let Some(verse) = self.verses.iter().find(|verse| verse.name == entry) {
+                push_verse(tokens, verse);
+                emitted.push(&verse.name);
+            } else {
+                log::debug!("No verse found for verse order entry '{}'", entry);
+            }
+        }
+
+        // Verses that are not part of the verse order are appended in their original order
+        for verse in &self.verses {
+            if !emitted.contains(&verse.name.as_str()) {
+                push_verse(tokens, verse);
+                emitted.push(&verse.name);
+            }
+        }
+    }
+
+    fn authors_of_type(&self, author_type: Option<&str>) -> String {
+        self.authors
+            .iter()
+            .filter(|(name, t)| t.as_deref() == author_type && !name.trim().is_empty())
+            .map(|(name, _)| name.trim())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
+fn get_attribute<'a>(attributes: &'a [OwnedAttribute], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name.local_name == name)
+        .map(|attribute| attribute.value.as_str())
+}
+
+/// Return the chord of a `<chord>` element
+///
+/// OpenLyrics 0.8 stores the chord in the `name` attribute, 0.9 splits it into `root`, `structure`
+/// and `bass` (e.g. `<chord root="D" structure="dom7" bass="F#"/>` for `D7/F#`)
+fn get_chord(attributes: &[OwnedAttribute]) -> Option<String> {
+    if let Some(name) = get_attribute(attributes, "name") {
+        return Some(name.to_owned());
+    }
+
+    let mut chord = get_attribute(attributes, "root")?.to_owned();
+    if let Some(structure) = get_attribute(attributes, "structure") {
+        chord.push_str(chord_suffix_for_structure(structure));
+    }
+    if let Some(bass) = get_attribute(attributes, "bass") {
+        chord.push('/');
+        chord.push_str(bass);
+    }
+
+    Some(chord)
+}
+
+/// Chord suffixes and their OpenLyrics 0.9 structure in interval notation
+const CHORD_STRUCTURES: [(&str, &str); 17] = [
+    ("", "3-5"),
+    ("m", "m3-5"),
+    ("7", "3-5-m7"),
+    ("maj7", "3-5-7"),
+    ("m7", "m3-5-m7"),
+    ("dim", "m3-d5"),
+    ("aug", "3-a5"),
+    ("sus2", "2-5"),
+    ("sus4", "4-5"),
+    ("5", "5"),
+    ("6", "3-5-6"),
+    ("m6", "m3-5-6"),
+    ("9", "3-5-m7-9"),
+    ("add9", "3-5-9"),
+    ("7sus4", "4-5-m7"),
+    ("m7b5", "m3-d5-m7"),
+    ("dim7", "m3-d5-d7"),
+];
+
+/// Names some applications use instead of the interval notation
+const CHORD_STRUCTURE_NAMES: [(&str, &str); 10] = [
+    ("", "major"),
+    ("m", "minor"),
+    ("m", "min"),
+    ("7", "dom7"),
+    ("maj7", "maj7"),
+    ("m7", "min7"),
+    ("dim", "dim"),
+    ("aug", "aug"),
+    ("sus2", "sus2"),
+    ("sus4", "sus4"),
+];
+
+/// Translate an OpenLyrics 0.9 chord structure into the chord suffix (e.g. `min7` into `m7`)
+///
+/// Both the names and the interval notation (e.g. `m3-5-m7`) of the common chords are supported,
+/// other structures are used as suffix unchanged
+fn chord_suffix_for_structure(structure: &str) -> &str {
+    let structure = structure.trim();
+    if structure.is_empty() {
+        return "";
+    }
+
+    CHORD_STRUCTURES
+        .iter()
+        .chain(CHORD_STRUCTURE_NAMES.iter())
+        .find(|(_, s)| *s == structure)
+        .map_or(structure, |(suffix, _)| suffix)
+}
+
+/// Return the OpenLyrics 0.9 structure for the chord suffix (e.g. `3-5-m7` for `7`)
+///
+/// `None` is returned for suffixes without a known structure
+pub(crate) fn structure_for_chord_suffix(suffix: &str) -> Option<&'static str> {
+    CHORD_STRUCTURES
+        .iter()
+        .find(|(s, _)| *s == suffix)
+        .map(|(_, structure)| *structure)
+}
+
+fn is_in_lines(path: &[String]) -> bool {
+    path.iter().any(|element| element == "lines")
+}
+
+/// Collapse the line breaks and indention of a pretty printed document into a single space
+fn normalize_whitespace(text: &str) -> String {
+    if !text.contains('\n') {
+        return text.to_owned();
+    }
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut whitespace = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() {
+            whitespace.push(c);
+            continue;
+        }
+        if whitespace.contains('\n') {
+            normalized.push(' ');
+        } else {
+            normalized.push_str(&whitespace);
+        }
+        whitespace.clear();
+        normalized.push(c);
+    }
+    if whitespace.contains('\n') {
+        normalized.push(' ');
+    } else {
+        normalized.push_str(&whitespace);
+    }
+
+    normalized
+}
+
+/// Translate an OpenLyrics verse name (e.g. `v1`, `c` or `b2`) into a section title
+fn title_for_verse_name(name: &str) -> (String, Modifier) {
+    let mut characters = name.chars();
+    let prefix = characters.next().map(|c| c.to_ascii_lowercase());
+    let number = characters.as_str();
+
+    let (label, modifier) = match prefix {
+        Some('v') => ("Verse", Modifier::None),
+        Some('c') => ("Chorus", Modifier::Chorus),
+        Some('b') => ("Bridge", Modifier::Bridge),
+        Some('p') => ("Pre-Chorus", Modifier::None),
+        Some('i') => ("Intro", Modifier::None),
+        Some('e') => ("Ending", Modifier::None),
+        Some('o') => ("Other", Modifier::None),
+        _ => return (name.to_owned(), Modifier::None),
+    };
+
+    // Only verses keep the number of the first section (e.g. `Verse 1` but `Chorus`)
+    if number.is_empty() || (number == "1" && prefix != Some('v')) {
+        (label.to_owned(), modifier)
+    } else {
+        (format!("{} {}", label, number), modifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::converter::Converter;
+    use crate::format::Format;
+    use crate::models::chord::fmt::Formatting;
+    use crate::parser::{Parser, ParserTrait, SectionType};
+    use crate::test_helpers::{get_test_ast, get_test_metadata};
+
+    use super::*;
+
+    #[test]
+    fn test_tokenize_long() {
+        use Token::Newline;
+        let content = include_str!("../../../tests/resources/swing_low_sweet_chariot.xml");
+        let (tokens, warnings) = OpenLyricsTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(1, "Swing Low Sweet Chariot", Modifier::None),
+                Newline,
+                Token::Meta(Meta::alternative_title("Swing Low")),
+                Newline,
+                Token::Meta(Meta::composer("Wallace Willis")),
+                Newline,
+                Token::Meta(Meta::lyricist("Wallace Willis")),
+                Newline,
+                Token::Meta(Meta::copyright("Public Domain")),
+                Newline,
+                Token::Meta(Meta::key("D")),
+                Newline,
+                Token::Meta(Meta::ccli_song_id("1234567")),
+                Newline,
+                Token::Meta(Meta::tags("#Spiritual")),
+                Newline,
+                Token::headline(2, "Chorus", Modifier::Chorus),
+                Newline,
+                Token::literal("Swing "),
+                Token::chord("D"),
+                Token::literal("low, sweet "),
+                Token::chord("G"),
+                Token::literal("chari"),
+                Token::chord("D"),
+                Token::literal("ot,"),
+                Newline,
+                Token::literal("Comin’ for to carry me "),
+                Token::chord("A7"),
+                Token::literal("home."),
+                Newline,
+                Newline,
+                Token::literal("Swing "),
+                Token::chord("D7"),
+                Token::literal("low, sweet "),
+                Token::chord("G"),
+                Token::literal("chari"),
+                Token::chord("D"),
+                Token::literal("ot,"),
+                Newline,
+                Token::literal("Comin’ for to "),
+                Token::chord("A7"),
+                Token::literal("carry me "),
+                Token::chord("D"),
+                Token::literal("home."),
+                Newline,
+                Newline,
+                Token::headline(2, "Verse 1", Modifier::None),
+                Newline,
+                Token::literal("I "),
+                Token::chord("D"),
+                Token::literal("looked over Jordan, and "),
+                Token::chord("G"),
+                Token::literal("what did I "),
+                Token::chord("D"),
+                Token::literal("see,"),
+                Newline,
+                Token::literal("Comin’ for to carry me "),
+                Token::chord("A7"),
+                Token::literal("home."),
+                Newline,
+                Newline,
+                Token::quote("Chorus"),
+                Newline,
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_long() {
+        let content = include_str!("../../../tests/resources/swing_low_sweet_chariot.xml");
+        let (tokens, _warnings) = OpenLyricsTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        let parser_result = Parser::new().parse(tokens).unwrap();
+        assert_eq!(
+            Some("Swing Low Sweet Chariot".to_string()),
+            parser_result.meta().title
+        );
+
+        let sections = parser_result.node_as_ref().get_sections();
+        assert_eq!(3, sections.len());
+        assert_eq!(SectionType::Chorus, sections[0].section_type);
+        assert_eq!("Chorus", &sections[0].title);
+        assert_eq!(SectionType::Verse, sections[1].section_type);
+        assert_eq!("Verse 1", &sections[1].title);
+        assert_eq!(SectionType::Reference, sections[2].section_type);
+        assert_eq!("Chorus", &sections[2].title);
+    }
+
+    #[test]
+    fn test_tokenize_invalid() {
+        assert!(OpenLyricsTokenizer::new()
+            .tokenize("<html><body></body></html>".as_bytes())
+            .is_err());
+        assert!(OpenLyricsTokenizer::new()
+            .tokenize("<song><properties>".as_bytes())
+            .is_err());
+    }
+
+    #[test]
+    fn test_title_for_verse_name() {
+        assert_eq!(
+            ("Verse 1".to_owned(), Modifier::None),
+            title_for_verse_name("v1")
+        );
+        assert_eq!(
+            ("Chorus".to_owned(), Modifier::Chorus),
+            title_for_verse_name("c")
+        );
+        assert_eq!(
+            ("Chorus".to_owned(), Modifier::Chorus),
+            title_for_verse_name("c1")
+        );
+        assert_eq!(
+            ("Bridge 2".to_owned(), Modifier::Bridge),
+            title_for_verse_name("b2")
+        );
+        assert_eq!(
+            ("Pre-Chorus".to_owned(), Modifier::None),
+            title_for_verse_name("p")
+        );
+        assert_eq!(("x".to_owned(), Modifier::None), title_for_verse_name("x"));
+    }
+
+    #[test]
+    fn test_tokenize_version_0_9_chords() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<song xmlns="http://openlyrics.info/namespace/2009/song" version="0.9">
+  <properties><titles><title>Chords</title></titles></properties>
+  <lyrics>
+    <verse name="v1">
+      <lines><chord root="D"/>Swing <chord root="B" structure="min7"/>low, <chord root="G" structure="3-5-7" bass="A"/>sweet <chord root="A" structure="dom7"/>chari<chord name="D"/>ot</lines>
+    </verse>
+  </lyrics>
+</song>"#;
+        let (tokens, warnings) = OpenLyricsTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+
+        let chords: Vec<&Token> = tokens
+            .iter()
+            .filter(|token| matches!(token, Token::Chord(_)))
+            .collect();
+        assert_eq!(
+            chords,
+            vec![
+                &Token::chord("D"),
+                &Token::chord("Bm7"),
+                &Token::chord("Gmaj7/A"),
+                &Token::chord("A7"),
+                &Token::chord("D"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let converter = Converter::get_converter(Format::OpenLyrics);
+        let source = converter
+            .convert(
+                &get_test_ast(),
+                &get_test_metadata(),
+                Formatting::with_format(Format::OpenLyrics),
+            )
+            .unwrap();
+
+        let (tokens, warnings) = OpenLyricsTokenizer::new()
+            .tokenize(source.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        let parser_result = Parser::new().parse(tokens).unwrap();
+
+        assert_eq!(
+            converter
+                .convert(
+                    parser_result.node_as_ref(),
+                    parser_result.meta_as_ref(),
+                    Formatting::with_format(Format::OpenLyrics),
+                )
+                .unwrap(),
+            source
+        );
+    }
+}
@@ -0,0 +1,483 @@
+use std::io::BufRead;
+
+use crate::error::Error;
+
+use super::tokenizer_error::TokenizerError;
+use super::{Meta, Modifier, Token, Tokenizer};
+
+const BOM: char = '\u{feff}';
+const HEADER_START: char = '#';
+const HEADER_ASSIGNMENT: char = '=';
+const SLIDE_SEPARATOR: &str = "---";
+const PAGE_SEPARATOR: &str = "--";
+const VERSE_ORDER_SEPARATOR: char = ',';
+/// Prefix of the lines in the second language of a multilingual song (`#LangCount=2`)
+const TRANSLATION_PREFIX: &str = "##2";
+
+/// Labels SongBeamer recognizes as the first line of a slide
+const SECTION_LABELS: &[&str] = &[
+    "verse",
+    "vers",
+    "strophe",
+    "chorus",
+    "refrain",
+    "pre-chorus",
+    "pre-refrain",
+    "bridge",
+    "pre-bridge",
+    "intro",
+    "outro",
+    "coda",
+    "ending",
+    "interlude",
+    "zwischenspiel",
+    "instrumental",
+    "misc",
+    "part",
+    "teil",
+    "unknown",
+    "unbekannt",
+];
+
+/// Tokenizer for [SongBeamer](https://www.songbeamer.de/) `.sng` files
+///
+/// The header names and the layout mirror the output of the `SongBeamerConverter`, so that a
+/// converted file can be read back. Sections which are repeated in the `#VerseOrder` header are
+/// emitted as references (`Token::Quote`)
+pub(crate) struct SongBeamerTokenizer {}
+
+impl SongBeamerTokenizer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Tokenizer for SongBeamerTokenizer {
+    fn tokenize<R: BufRead>(&self, input: R) -> Result<(Vec<Token>, Vec<TokenizerError>), Error> {
+        let mut headers: Vec<(String, String)> = vec![];
+        let mut slides: Vec<Vec<String>> = vec![];
+        for line in input.lines() {
+            let line = line?;
+            let line = line.trim_start_matches(BOM).trim_end();
+            log::trace!("Tokenize line `{}`", line);
+
+            if line == SLIDE_SEPARATOR {
+                slides.push(vec![]);
+                continue;
+            }
+            if line == PAGE_SEPARATOR {
+                // A new page of the same section
+                continue;
+            }
+            match slides.last_mut() {
+                Some(slide) => slide.push(line.to_owned()),
+                None => {
+                    if let Some((name, value)) = parse_header(line) {
+                        headers.push((name.to_owned(), value.to_owned()))
+                    }
+                }
+            }
+        }
+
+        let mut state = State::new(&headers);
+        state.visit_headers(&headers);
+        state.visit_slides(&slides);
+
+        Ok((state.tokens, state.warnings))
+    }
+}
+
+/// A section of the song made up of one or more slides
+struct SlideSection {
+    title: String,
+    lines: Vec<String>,
+}
+
+struct State {
+    tokens: Vec<Token>,
+    warnings: Vec<TokenizerError>,
+    verse_order: Vec<String>,
+    verse_count: usize,
+}
+
+impl State {
+    fn new(headers: &[(String, String)]) -> Self {
+        let verse_order = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("VerseOrder"))
+            .map(|(_, value)| {
+                value
+                    .split(VERSE_ORDER_SEPARATOR)
+                    .map(str::trim)
+                    .filter(|entry| !entry.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            tokens: vec![],
+            warnings: vec![],
+            verse_order,
+            verse_count: 0,
+        }
+    }
+
+    fn visit_headers(&mut self, headers: &[(String, String)]) {
+        for (name, value) in headers {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            match name.to_lowercase().as_str() {
+                "title" => {
+                    self.tokens.push(Token::headline(1, value, Modifier::None));
+                    self.tokens.push(Token::newline());
+                }
+                "otitle" => self.push_meta(Meta::original_title(value)),
+                "subtitle" => self.push_meta(Meta::subtitle(value)),
+                "artist" => self.push_meta(Meta::artist(value)),
+                "melody" => self.push_meta(Meta::composer(value)),
+                "author" => self.push_meta(Meta::lyricist(value)),
+                "(c)" => self.push_meta(Meta::copyright(value)),
+                "album" => self.push_meta(Meta::album(value)),
+                "year" => self.push_meta(Meta::year(value)),
+                "key" => self.push_meta(Meta::key(value)),
+                "time" => self.push_meta(Meta::time(value)),
+                "tempo" => self.push_meta(Meta::tempo(value)),
+                "duration" => self.push_meta(Meta::duration(value)),
+                "capo" => self.push_meta(Meta::capo(value)),
+                "ccli" => self.push_meta(Meta::ccli_song_id(value)),
+                _ => {
+                    // SongBeamer stores a lot of presentation settings (fonts, backgrounds, ...)
+                    // in the header, which have no equivalent
+                    log::debug!("Ignore SongBeamer header '{}'", name)
+                }
+            }
+        }
+    }
+
+    fn visit_slides(&mut self, slides: &[Vec<String>]) {
+        let mut sections = self.collect_sections(slides);
+
+        for entry in self.verse_order.clone() {
+            match sections.iter().position(|section| section.title == entry) {
+                Some(index) => {
+                    let section = sections.remove(index);
+                    self.push_section(&section);
+                }
+                None => {
+                    if self.has_emitted_section(&entry) {
+                        self.tokens.push(Token::quote(entry));
+                        self.tokens.push(Token::newline());
+                        self.tokens.push(Token::newline());
+                    } else {
+                        log::debug!("No slide found for verse order entry '{}'", entry);
+                    }
+                }
+            }
+        }
+
+        // Slides that are not part of the verse order are appended in their original order
+        for section in sections {
+            self.push_section(&section);
+        }
+    }
+
+    fn collect_sections(&mut self, slides: &[Vec<String>]) -> Vec<SlideSection> {
+        let mut sections: Vec<SlideSection> = vec![];
+        for slide in slides {
+            let mut lines = slide.iter().skip_while(|line| line.is_empty()).peekable();
+            let first_line = match lines.peek() {
+                Some(line) => line.as_str(),
+                None => continue,
+            };
+
+            if self.is_section_label(first_line) {
+                let title = first_line.to_owned();
+                lines.next();
+                sections.push(SlideSection {
+                    title,
+                    lines: lines.cloned().collect(),
+                });
+            } else if let Some(section) = sections.last_mut() {
+                // A slide without label continues the previous section
+                section.lines.extend(lines.cloned());
+            } else {
+                let title = self.next_verse_title();
+                sections.push(SlideSection {
+                    title,
+                    lines: lines.cloned().collect(),
+                });
+            }
+        }
+
+        sections
+    }
+
+    fn is_section_label(&self, line: &str) -> bool {
+        if self.verse_order.iter().any(|entry| entry == line) {
+            return true;
+        }
+
+        SECTION_LABELS.contains(&strip_counter(&line.to_lowercase()))
+    }
+
+    fn has_emitted_section(&self, title: &str) -> bool {
+        self.tokens.iter().any(|token| match token {
+            Token::Headline { level: 2, text, .. } => text == title,
+            _ => false,
+        })
+    }
+
+    fn push_section(&mut self, section: &SlideSection) {
+        self.tokens.push(Token::headline(
+            2,
+            &section.title,
+            modifier_for_title(&section.title),
+        ));
+        self.tokens.push(Token::newline());
+
+        let content_length = section
+            .lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |position| position + 1);
+        for line in &section.lines[..content_length] {
+            if let Some(translation) = line.strip_prefix(TRANSLATION_PREFIX) {
+                self.tokens.push(Token::translation(translation.trim()));
+            } else if !line.is_empty() {
+                self.tokens.push(Token::literal(line));
+            }
+            self.tokens.push(Token::newline());
+        }
+        self.tokens.push(Token::newline());
+    }
+
+    fn push_meta(&mut self, meta: Meta) {
+        self.tokens.push(Token::Meta(meta));
+        self.tokens.push(Token::newline());
+    }
+
+    fn next_verse_title(&mut self) -> String {
+        self.verse_count += 1;
+
+        format!("Verse {}", self.verse_count)
+    }
+}
+
+fn parse_header(line: &str) -> Option<(&str, &str)> {
+    line.strip_prefix(HEADER_START)?
+        .split_once(HEADER_ASSIGNMENT)
+}
+
+/// Remove a trailing counter like `1` or `2b` from the label (e.g. `verse 2b` becomes `verse`)
+fn strip_counter(label: &str) -> &str {
+    match label.trim().rsplit_once(' ') {
+        Some((name, counter)) if counter.starts_with(|c: char| c.is_ascii_digit()) => name.trim(),
+        _ => label.trim().trim_end_matches(|c: char| c.is_ascii_digit()),
+    }
+}
+
+fn modifier_for_title(title: &str) -> Modifier {
+    let title = title.to_lowercase();
+    if title.starts_with("chorus") || title.starts_with("refrain") {
+        Modifier::Chorus
+    } else if title.starts_with("bridge") {
+        Modifier::Bridge
+    } else {
+        Modifier::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::converter::Converter;
+    use crate::format::Format;
+    use crate::models::chord::fmt::Formatting;
+    use crate::parser::{Parser, ParserTrait, SectionType};
+    use crate::test_helpers::{get_test_ast, get_test_metadata};
+
+    use super::*;
+
+    #[test]
+    fn test_tokenize_long() {
+        use Token::Newline;
+        let content = include_str!("../../../tests/resources/swing_low_sweet_chariot.sng");
+        let (tokens, warnings) = SongBeamerTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(1, "Swing Low Sweet Chariot", Modifier::None),
+                Newline,
+                Token::headline(2, "Chorus", Modifier::Chorus),
+                Newline,
+                Token::literal("Swing low, sweet chariot,"),
+                Newline,
+                Token::literal("Comin’ for to carry me home."),
+                Newline,
+                Token::literal("Swing low, sweet chariot,"),
+                Newline,
+                Token::literal("Comin’ for to carry me home."),
+                Newline,
+                Newline,
+                Token::headline(2, "Verse 1", Modifier::None),
+                Newline,
+                Token::literal("I looked over Jordan, and what did I see,"),
+                Newline,
+                Token::literal("Comin’ for to carry me home."),
+                Newline,
+                Token::literal("A band of angels comin’ after me,"),
+                Newline,
+                Token::literal("Comin’ for to carry me home."),
+                Newline,
+                Newline,
+                Token::quote("Chorus"),
+                Newline,
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_long() {
+        let content = include_str!("../../../tests/resources/swing_low_sweet_chariot.sng");
+        let (tokens, _warnings) = SongBeamerTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        let parser_result = Parser::new().parse(tokens).unwrap();
+        assert_eq!(
+            Some("Swing Low Sweet Chariot".to_string()),
+            parser_result.meta().title
+        );
+
+        let sections = parser_result.node_as_ref().get_sections();
+        assert_eq!(3, sections.len());
+        assert_eq!(SectionType::Chorus, sections[0].section_type);
+        assert_eq!("Chorus", &sections[0].title);
+        assert_eq!(SectionType::Verse, sections[1].section_type);
+        assert_eq!("Verse 1", &sections[1].title);
+        assert_eq!(SectionType::Reference, sections[2].section_type);
+        assert_eq!("Chorus", &sections[2].title);
+    }
+
+    #[test]
+    fn test_tokenize_meta() {
+        use Token::Newline;
+        let content = "\u{feff}#LangCount=1
+#Editor=SongBeamer 5.17
+#Title=Swing Low
+#OTitle=Swing Low Sweet Chariot
+#Melody=Wallace Willis
+#Author=Wallace Willis
+#(c)=Public Domain
+#Key=D
+#CCLI=123456
+#BackgroundImage=Backgrounds\\Clouds.jpg
+";
+        let (tokens, warnings) = SongBeamerTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(1, "Swing Low", Modifier::None),
+                Newline,
+                Token::Meta(Meta::original_title("Swing Low Sweet Chariot")),
+                Newline,
+                Token::Meta(Meta::composer("Wallace Willis")),
+                Newline,
+                Token::Meta(Meta::lyricist("Wallace Willis")),
+                Newline,
+                Token::Meta(Meta::copyright("Public Domain")),
+                Newline,
+                Token::Meta(Meta::key("D")),
+                Newline,
+                Token::Meta(Meta::ccli_song_id("123456")),
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_slides() {
+        use Token::Newline;
+        let content = "#Title=Song
+---
+Just some lyrics
+---
+Refrain
+Chorus line 1
+--
+Chorus line 2
+---
+Chorus line 3
+---
+Strophe 2b
+Verse line
+";
+        let (tokens, warnings) = SongBeamerTokenizer::new()
+            .tokenize(content.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::headline(1, "Song", Modifier::None),
+                Newline,
+                Token::headline(2, "Verse 1", Modifier::None),
+                Newline,
+                Token::literal("Just some lyrics"),
+                Newline,
+                Newline,
+                Token::headline(2, "Refrain", Modifier::Chorus),
+                Newline,
+                Token::literal("Chorus line 1"),
+                Newline,
+                Token::literal("Chorus line 2"),
+                Newline,
+                Token::literal("Chorus line 3"),
+                Newline,
+                Newline,
+                Token::headline(2, "Strophe 2b", Modifier::None),
+                Newline,
+                Token::literal("Verse line"),
+                Newline,
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let converter = Converter::get_converter(Format::SongBeamer);
+        let source = converter
+            .convert(
+                &get_test_ast(),
+                &get_test_metadata(),
+                Formatting::with_format(Format::SongBeamer),
+            )
+            .unwrap();
+
+        let (tokens, warnings) = SongBeamerTokenizer::new()
+            .tokenize(source.as_bytes())
+            .unwrap();
+        assert!(warnings.is_empty());
+        let parser_result = Parser::new().parse(tokens).unwrap();
+
+        assert_eq!(
+            converter
+                .convert(
+                    parser_result.node_as_ref(),
+                    parser_result.meta_as_ref(),
+                    Formatting::with_format(Format::SongBeamer),
+                )
+                .unwrap(),
+            source
+        );
+    }
+}
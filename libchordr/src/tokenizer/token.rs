@@ -12,6 +12,8 @@ pub enum Token {
     Meta(Meta),
     Chord(String),
     Quote(String),
+    /// Translation of the previous lyrics line
+    Translation(String),
     Newline,
 }
 
@@ -41,4 +43,8 @@ impl Token {
     pub fn quote<S: Into<String>>(value: S) -> Self {
         Token::Quote(value.into())
     }
+
+    pub fn translation<S: Into<String>>(value: S) -> Self {
+        Token::Translation(value.into())
+    }
 }
@@ -1,27 +1,63 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result};
 
-#[derive(Debug)]
-pub enum TokenizerError {
+use crate::span::Span;
+
+/// Warning raised during tokenization
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenizerError {
+    kind: TokenizerErrorKind,
+    span: Option<Span>,
+}
+
+impl TokenizerError {
+    pub fn new(kind: TokenizerErrorKind, span: Option<Span>) -> Self {
+        Self { kind, span }
+    }
+
+    pub fn kind(&self) -> TokenizerErrorKind {
+        self.kind
+    }
+
+    /// Return the position of the problem in the source, if the tokenizer tracks positions
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+impl Display for TokenizerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(&self.kind, f)
+    }
+}
+
+impl Error for TokenizerError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerErrorKind {
     UnclosedChord,
     NestedChord,
     InvalidChordCharacter,
     UnexpectedChordEnd,
     UnexpectedHeaderStart,
     UnexpectedEndOfFile,
+    UnsupportedDirective,
 }
 
-impl Display for TokenizerError {
+impl Display for TokenizerErrorKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
-            TokenizerError::UnclosedChord => f.write_str("UnclosedChord"),
-            TokenizerError::NestedChord => f.write_str("NestedChord"),
-            TokenizerError::InvalidChordCharacter => f.write_str("InvalidChordCharacter"),
-            TokenizerError::UnexpectedChordEnd => f.write_str("UnexpectedChordEnd"),
-            TokenizerError::UnexpectedHeaderStart => f.write_str("UnexpectedHeaderStart"),
-            TokenizerError::UnexpectedEndOfFile => f.write_str("UnexpectedEndOfFile"),
+            TokenizerErrorKind::UnclosedChord => f.write_str("Unclosed chord"),
+            TokenizerErrorKind::NestedChord => f.write_str("Nested chord"),
+            TokenizerErrorKind::InvalidChordCharacter => {
+                f.write_str("Invalid character inside chord")
+            }
+            TokenizerErrorKind::UnexpectedChordEnd => {
+                f.write_str("Unexpected `]` without an opening `[`")
+            }
+            TokenizerErrorKind::UnexpectedHeaderStart => f.write_str("Unexpected `#`"),
+            TokenizerErrorKind::UnexpectedEndOfFile => f.write_str("Unexpected end of file"),
+            TokenizerErrorKind::UnsupportedDirective => f.write_str("Unsupported directive"),
         }
     }
 }
-
-impl Error for TokenizerError {}
@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use libchordr::models::catalog::{Catalog, CatalogTrait};
+
+/// Recently served catalogs used to build deltas for clients with an older revision
+pub struct CatalogHistory {
+    capacity: usize,
+    catalogs: Mutex<VecDeque<Catalog>>,
+}
+
+impl CatalogHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            catalogs: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Remember the catalog (the oldest catalog is dropped if the history is full)
+    pub fn record(&self, catalog: &Catalog) {
+        let mut catalogs = match self.catalogs.lock() {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+        if catalogs.back().map(CatalogTrait::revision) == Some(catalog.revision()) {
+            return;
+        }
+
+        catalogs.retain(|c| c.revision() != catalog.revision());
+        if catalogs.len() >= self.capacity {
+            catalogs.pop_front();
+        }
+        catalogs.push_back(catalog.clone());
+    }
+
+    /// Return the catalog with the given revision
+    pub fn get(&self, revision: &str) -> Option<Catalog> {
+        self.catalogs
+            .lock()
+            .ok()?
+            .iter()
+            .find(|c| c.revision() == revision)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let history = CatalogHistory::new(2);
+        history.record(&Catalog::new("1", vec![]));
+        history.record(&Catalog::new("2", vec![]));
+        history.record(&Catalog::new("2", vec![]));
+        assert!(history.get("1").is_some());
+
+        history.record(&Catalog::new("3", vec![]));
+        assert!(history.get("1").is_none());
+        assert!(history.get("2").is_some());
+        assert!(history.get("3").is_some());
+    }
+}
@@ -7,4 +7,8 @@ pub struct Config {
 
     /// Path to the static files (e.g. stylesheets, JavaScript, images)
     pub static_files_dir: String,
+
+    /// Path to the cache of parsed songs to speed up building the catalog
+    #[serde(default)]
+    pub catalog_cache_file: Option<String>,
 }
@@ -14,23 +14,27 @@ use std::path::Path;
 use diesel::SqliteConnection;
 use rocket::fairing::AdHoc;
 use rocket::fs::{FileServer, NamedFile};
-use rocket::response::status;
+use rocket::response::{content, status};
 use rocket::serde::json::Json;
 use rocket::{http, Build, Rocket, State};
 use rocket_sync_db_pools::database;
 
-use libchordr::models::catalog::Catalog;
+use libchordr::models::catalog::{Catalog, CatalogPatch, CatalogTrait};
 use libchordr::prelude::{CatalogBuilder, FileType};
 
+use crate::catalog_history::CatalogHistory;
 use crate::config::Config;
+use crate::search_index_cache::SearchIndexCache;
 
 mod authentication;
+mod catalog_history;
 mod config;
 mod cors;
 mod domain;
 mod error;
 mod routes;
 mod schema;
+mod search_index_cache;
 #[cfg(test)]
 mod test_helpers;
 mod traits;
@@ -60,13 +64,76 @@ async fn html_fallback(config: &State<Config>) -> io::Result<NamedFile> {
     NamedFile::open(Path::new(&config.static_files_dir).join("index.html")).await
 }
 
+/// Number of catalog revisions kept to build deltas
+const CATALOG_HISTORY_SIZE: usize = 20;
+
 #[get("/catalog.json")]
-fn catalog(config: &State<Config>) -> Result<Json<Catalog>, status::Custom<String>> {
-    match CatalogBuilder::new().build_catalog_for_directory(
-        &config.song_dir,
-        FileType::Chorddown,
-        true,
-    ) {
+fn catalog(
+    config: &State<Config>,
+    history: &State<CatalogHistory>,
+) -> Result<Json<Catalog>, status::Custom<String>> {
+    let catalog = build_catalog(config)?;
+    history.record(&catalog);
+
+    Ok(Json(catalog))
+}
+
+/// Return the changes since the catalog revision the client knows
+///
+/// If the revision is unknown (e.g. after a restart) the client has to fetch `/catalog.json`
+#[get("/catalog-delta.json?<since>")]
+fn catalog_delta(
+    since: String,
+    config: &State<Config>,
+    history: &State<CatalogHistory>,
+) -> Result<Json<CatalogPatch>, status::Custom<String>> {
+    let catalog = build_catalog(config)?;
+    history.record(&catalog);
+
+    let base = if catalog.revision() == since {
+        catalog.clone()
+    } else {
+        match history.get(&since) {
+            Some(base) => base,
+            None => {
+                return Err(status::Custom(
+                    http::Status::NotFound,
+                    format!("Catalog revision '{}' is not known", since),
+                ))
+            }
+        }
+    };
+
+    Ok(Json(base.diff(&catalog)))
+}
+
+/// Return the search index for the current catalog, so that clients do not have to build it
+///
+/// The index is only built once per catalog revision
+#[get("/search-index.json")]
+fn search_index(
+    config: &State<Config>,
+    history: &State<CatalogHistory>,
+    search_index_cache: &State<SearchIndexCache>,
+) -> Result<content::RawJson<String>, status::Custom<String>> {
+    let catalog = build_catalog(config)?;
+    history.record(&catalog);
+
+    match search_index_cache.get_or_build(&catalog) {
+        Ok(json) => Ok(content::RawJson(json)),
+        Err(e) => Err(status::Custom(
+            http::Status::InternalServerError,
+            e.to_string(),
+        )),
+    }
+}
+
+fn build_catalog(config: &Config) -> Result<Catalog, status::Custom<String>> {
+    let catalog_builder = match &config.catalog_cache_file {
+        Some(cache_file) => CatalogBuilder::with_cache_file(cache_file),
+        None => CatalogBuilder::new(),
+    };
+    match catalog_builder.build_catalog_for_directory(&config.song_dir, FileType::Chorddown, true) {
         Err(e) => Err(status::Custom(
             http::Status::InternalServerError,
             e.to_string(),
@@ -78,7 +145,7 @@ fn catalog(config: &State<Config>) -> Result<Json<Catalog>, status::Custom<Strin
                 }
             }
 
-            Ok(Json(catalog_result.catalog))
+            Ok(catalog_result.catalog)
         }
     }
 }
@@ -98,6 +165,8 @@ fn rocket_build() -> Rocket<Build> {
     rocket::build()
         .attach(cors::Cors::new(vec!["http://localhost:8080"]))
         .attach(DbConn::fairing())
+        .manage(CatalogHistory::new(CATALOG_HISTORY_SIZE))
+        .manage(SearchIndexCache::new())
         .attach(AdHoc::on_ignite("Database Migrations", run_db_migrations))
         .attach(AdHoc::on_ignite(
             "Build application configuration",
@@ -110,7 +179,7 @@ fn rocket_build() -> Rocket<Build> {
             let config = build_application_config(&rocket);
             rocket.mount("/", FileServer::from(config.static_files_dir).rank(1))
         }))
-        .mount("/", routes![index, catalog])
+        .mount("/", routes![index, catalog, catalog_delta, search_index])
         .mount("/api/status", routes::status::get_routes())
         .mount("/api/setlist", routes::setlist::get_routes())
         .mount("/api/user", routes::user::get_routes())
@@ -0,0 +1,62 @@
+use std::sync::Mutex;
+
+use libchordr::models::catalog::{Catalog, CatalogTrait};
+use libchordr::prelude::PrebuiltSearchIndex;
+
+/// Serialized search index of the latest catalog revision
+///
+/// Indexing all songs is expensive, so the index is only built once per catalog revision
+pub struct SearchIndexCache {
+    /// Catalog revision and the serialized index
+    index: Mutex<Option<(String, String)>>,
+}
+
+impl SearchIndexCache {
+    pub fn new() -> Self {
+        Self {
+            index: Mutex::new(None),
+        }
+    }
+
+    /// Return the serialized search index for the catalog
+    ///
+    /// The index is only built if the catalog's revision changed since the last call
+    pub fn get_or_build(&self, catalog: &Catalog) -> serde_json::Result<String> {
+        let mut index = match self.index.lock() {
+            Ok(i) => i,
+            Err(_) => return build_index(catalog),
+        };
+        match index.as_ref() {
+            Some((revision, json)) if *revision == catalog.revision() => Ok(json.clone()),
+            _ => {
+                let json = build_index(catalog)?;
+                *index = Some((catalog.revision(), json.clone()));
+
+                Ok(json)
+            }
+        }
+    }
+}
+
+fn build_index(catalog: &Catalog) -> serde_json::Result<String> {
+    serde_json::to_string(&PrebuiltSearchIndex::build_for_catalog(catalog))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_or_build() {
+        let cache = SearchIndexCache::new();
+        let first = cache.get_or_build(&Catalog::new("1", vec![])).unwrap();
+        assert!(first.contains(r#""revision":"1""#));
+        assert_eq!(
+            first,
+            cache.get_or_build(&Catalog::new("1", vec![])).unwrap()
+        );
+
+        let second = cache.get_or_build(&Catalog::new("2", vec![])).unwrap();
+        assert!(second.contains(r#""revision":"2""#));
+    }
+}
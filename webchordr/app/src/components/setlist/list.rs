@@ -94,6 +94,9 @@ impl Component for List {
             };
 
             let setlist_prop = Rc::new(setlist.clone());
+            let running_time = state
+                .catalog()
+                .map(|catalog| setlist.running_time(&catalog));
 
             html! {
                 <li key={key}>
@@ -101,6 +104,7 @@ impl Component for List {
                         {on_load_click}
                         {on_delete_click}
                         setlist={setlist_prop}
+                        {running_time}
                         {highlight}
                     />
                 </li>
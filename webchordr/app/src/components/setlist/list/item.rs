@@ -1,4 +1,5 @@
 use std::rc::Rc;
+use std::time::Duration;
 use yew::prelude::*;
 use yew::Callback;
 
@@ -8,6 +9,8 @@ use webchordr_common::helpers::Class;
 #[derive(Properties, Clone, PartialEq)]
 pub struct ItemProps {
     pub setlist: Rc<Setlist>,
+    /// Total running time of the songs (if the catalog is loaded)
+    pub running_time: Option<Duration>,
     pub highlight: bool,
     pub on_load_click: Callback<Rc<Setlist>>,
     pub on_delete_click: Callback<Rc<Setlist>>,
@@ -40,6 +43,15 @@ impl Component for Item {
             format!("key {}", key)
         };
 
+        let running_time = match ctx.props().running_time {
+            Some(running_time) if !running_time.is_zero() => html! {
+                <span class="setlist-list-item-running-time" title="Estimated running time">
+                    {format_running_time(running_time)}
+                </span>
+            },
+            _ => html! {},
+        };
+
         let setlist_for_loading = setlist.clone();
         let on_load_click_prop = ctx
             .props()
@@ -55,7 +67,7 @@ impl Component for Item {
         html! {
             <div class={class} key={key}>
                 <div class="button-group -compact">
-                    <button class="setlist-list-item-load" data-v={key.to_string()} onclick={on_load_click_prop}>{text}</button>
+                    <button class="setlist-list-item-load" data-v={key.to_string()} onclick={on_load_click_prop}>{text}{running_time}</button>
                     <button class="setlist-list-item-delete" data-v={key.to_string()} onclick={on_delete_click_prop} title="Remove">
                         <i class="im im-trash-can"></i>
                         <span class="sr-only">{"Remove"}</span>
@@ -65,3 +77,18 @@ impl Component for Item {
         }
     }
 }
+
+/// Format the running time as `h:mm:ss` or `m:ss`
+fn format_running_time(running_time: Duration) -> String {
+    let seconds = running_time.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
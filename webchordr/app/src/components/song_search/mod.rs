@@ -4,14 +4,17 @@ use gloo_timers::callback::Timeout;
 use libchordr::models::meta::tags::Tag;
 use libchordr::models::meta::MetaTrait;
 use log::{debug, info};
+use wasm_bindgen_futures::spawn_local;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew::Component;
 
 use libchordr::models::catalog::*;
 use libchordr::models::list::ListEntryTrait;
-use libchordr::prelude::{SearchIndex, Song, SongData, SongSorting};
+use libchordr::prelude::{PrebuiltSearchIndex, Query, SearchIndex, Song, SongData, SongSorting};
+use libchordr::search::Filter;
 use webchordr_common::components::link::Link;
+use webchordr_common::fetch_helper::fetch;
 use webchordr_common::route::AppRoute;
 use webchordr_song_list::Item as SongItem;
 
@@ -23,6 +26,7 @@ pub struct SongSearch {
     search: String,
     catalog_revision: String,
     search_index: Option<SearchIndex>,
+    loading_search_index: bool,
     timeout: Option<Timeout>,
 }
 
@@ -33,14 +37,19 @@ pub struct SongSearchProps {
 }
 
 impl SongSearch {
-    /// Return the [Song]s from the [Catalog] filtered by [self.search]
+    /// Return the [Song]s from the [Catalog] matching the query in [self.search] in rank order
     fn get_filtered_songs<'a>(&'a self, props: &'a SongSearchProps) -> Vec<&'a Song> {
-        if self.search.is_empty() {
+        let query = Query::parse(&self.search);
+        if query.is_empty() {
             return self.get_all_songs(props);
         }
 
         match &self.search_index {
-            Some(index) => index.search_by_term(&self.search).sort_by_title(),
+            Some(index) => index
+                .search(&query)
+                .into_iter()
+                .map(|result| result.song())
+                .collect(),
             None => self.get_all_songs(props),
         }
     }
@@ -50,7 +59,10 @@ impl SongSearch {
     }
 
     fn needs_to_build_search_index(&self) -> bool {
-        !self.search.is_empty() && !self.search.trim().is_empty() && self.search_index.is_none()
+        !self.search.is_empty()
+            && !self.search.trim().is_empty()
+            && self.search_index.is_none()
+            && !self.loading_search_index
     }
 
     fn get_back_link(&self, ctx: &Context<Self>) -> Html {
@@ -74,17 +86,30 @@ impl SongSearch {
                 <input type="search"
                        value={self.search.clone()}
                        {oninput}
-                       placeholder="Search"/>
+                       placeholder="Search (e.g. key:G tag:advent NOT artist:Hillsong)"/>
             </>
         }) as Html
     }
 
+    /// Return the current search extended by a filter for the given [Tag]
+    fn search_with_tag(&self, tag: &Tag) -> String {
+        let filter = Query::Filter(Filter::Tag(tag.to_string_without_hashtag())).to_string();
+        let search = self.search.trim();
+        if search.is_empty() {
+            filter
+        } else if search.split_whitespace().any(|part| part == filter) {
+            search.to_string()
+        } else {
+            format!("{} {}", search, filter)
+        }
+    }
+
     fn render_tags(&self, ctx: &Context<Self>) -> Html {
         let render_tag = |tag: Tag| {
-            let tag_string = tag.to_string();
+            let search = self.search_with_tag(&tag);
             let on_click = ctx
                 .link()
-                .callback(move |_: MouseEvent| Msg::SearchChange(tag_string.clone()));
+                .callback(move |_: MouseEvent| Msg::SearchChange(search.clone()));
 
             html! {
                 <button type="button" role="button" onclick={on_click} key={tag.to_string()}>
@@ -118,7 +143,8 @@ impl SongSearch {
 pub enum Msg {
     SearchChange(String),
     Debounce(String),
-    BuildSearchIndex,
+    LoadSearchIndex,
+    SearchIndexLoaded(Option<PrebuiltSearchIndex>),
 }
 
 impl Component for SongSearch {
@@ -130,6 +156,7 @@ impl Component for SongSearch {
             search: String::new(),
             catalog_revision: ctx.props().catalog.revision(),
             search_index: None,
+            loading_search_index: false,
             timeout: None,
         }
     }
@@ -158,8 +185,27 @@ impl Component for SongSearch {
                 true
             }
 
-            Msg::BuildSearchIndex => {
-                self.search_index = Some(build_search_index_from_props(ctx.props()));
+            Msg::LoadSearchIndex => {
+                // Prefer the prebuilt search index over indexing the songs on the device
+                self.loading_search_index = true;
+                let on_load = ctx.link().callback(Msg::SearchIndexLoaded);
+                let uri = format!("/search-index.json?{}", self.catalog_revision);
+                spawn_local(async move {
+                    match fetch::<PrebuiltSearchIndex>(&uri).await {
+                        Ok(prebuilt) => on_load.emit(Some(prebuilt)),
+                        Err(e) => {
+                            debug!("Could not fetch the search index: {}", e);
+                            on_load.emit(None)
+                        }
+                    }
+                });
+
+                false
+            }
+
+            Msg::SearchIndexLoaded(prebuilt) => {
+                self.loading_search_index = false;
+                self.search_index = Some(build_search_index_from_props(ctx.props(), prebuilt));
 
                 true
             }
@@ -170,6 +216,7 @@ impl Component for SongSearch {
         let catalog_changed = ctx.props().catalog.revision() != self.catalog_revision;
         if catalog_changed {
             self.search_index = None;
+            self.loading_search_index = false;
             self.catalog_revision = ctx.props().catalog.revision();
         }
         true
@@ -191,7 +238,7 @@ impl Component for SongSearch {
 
         let props = ctx.props();
         let songs = if self.needs_to_build_search_index() {
-            ctx.link().send_message(Msg::BuildSearchIndex);
+            ctx.link().send_message(Msg::LoadSearchIndex);
 
             self.get_all_songs(props)
         } else {
@@ -219,7 +266,17 @@ impl Component for SongSearch {
     }
 }
 
-fn build_search_index_from_props(props: &SongSearchProps) -> SearchIndex {
+fn build_search_index_from_props(
+    props: &SongSearchProps,
+    prebuilt: Option<PrebuiltSearchIndex>,
+) -> SearchIndex {
+    if let Some(prebuilt) = prebuilt {
+        match SearchIndex::from_prebuilt(props.catalog.clone(), prebuilt) {
+            Ok(search_index) => return search_index,
+            Err(e) => info!("Could not use the prebuilt search index: {}", e),
+        }
+    }
+
     debug!("Build search index");
     let search_index = SearchIndex::build_for_catalog(props.catalog.clone());
     debug!("Did build search index");
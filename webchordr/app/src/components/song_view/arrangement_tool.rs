@@ -0,0 +1,62 @@
+use libchordr::prelude::Arrangement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ArrangementToolProps {
+    pub arrangements: Vec<Arrangement>,
+    /// Name of the selected arrangement (`None` for the order of the document)
+    pub arrangement: Option<String>,
+    pub on_change: Callback<Option<String>>,
+}
+
+pub struct ArrangementTool {}
+
+impl Component for ArrangementTool {
+    type Message = ();
+    type Properties = ArrangementToolProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        if props.arrangements.is_empty() {
+            return html! {};
+        }
+
+        let button_class = |active: bool| {
+            if active {
+                vec!["discreet", "-active"]
+            } else {
+                vec!["discreet"]
+            }
+        };
+        let select_original = props.on_change.reform(|_| None);
+        let buttons = props.arrangements.iter().map(|arrangement| {
+            let name = arrangement.name().to_owned();
+            let is_active = props
+                .arrangement
+                .as_deref()
+                .is_some_and(|selected| arrangement.is_named(selected));
+            let title = arrangement.to_string();
+            let select = {
+                let name = name.clone();
+                props.on_change.reform(move |_| Some(name.clone()))
+            };
+
+            html! {
+                <button class={button_class(is_active)} onclick={select} title={title}>{name}</button>
+            }
+        });
+
+        html! {
+            <div class="arrangement-tool">
+                <div title="Select the arrangement" class="button-group -compact">
+                    <button class={button_class(props.arrangement.is_none())} onclick={select_original} title="Order of the song">{"Original"}</button>
+                    {for buttons}
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use libchordr::models::meta::Capo;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CapoToolProps {
+    pub capo: Option<Capo>,
+    /// Capo position suggested for the song's (transposed) key
+    pub suggested_capo: Option<Capo>,
+
+    pub on_change: Callback<Option<Capo>>,
+}
+
+pub struct CapoTool {}
+
+impl Component for CapoTool {
+    type Message = ();
+    type Properties = CapoToolProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let fret = ctx.props().capo.map_or(0, |c| c.fret());
+        let capo_down = ctx
+            .props()
+            .on_change
+            .reform(move |_| fret.checked_sub(1).and_then(build_capo));
+        let capo_up = ctx.props().on_change.reform(move |_| build_capo(fret + 1));
+
+        let value = if fret == 0 {
+            html! {<span class="value">{"-"}</span>}
+        } else {
+            html! {<span class="value">{fret}</span>}
+        };
+
+        let suggestion = match ctx.props().suggested_capo {
+            Some(suggested) if suggested.fret() != fret => {
+                let select_suggested = ctx.props().on_change.reform(move |_| Some(suggested));
+                html! {
+                    <button class="discreet suggestion" onclick={select_suggested} title="Use the capo position with the most guitar-friendly shapes">
+                        {format!("→ {}", suggested)}
+                    </button>
+                }
+            }
+            _ => html! {},
+        };
+
+        html! {
+            <div class="capo-tool">
+                <div title="Show the chord shapes to play with a capo">
+                    <span class="icon">{"Capo"}</span>
+                    <button class="discreet" disabled={fret == 0} onclick={capo_down}><i class="im im-angle-left"></i></button>
+                    {value}
+                    <button class="discreet" disabled={fret >= Capo::MAX_FRET} onclick={capo_up}><i class="im im-angle-right"></i></button>
+                    {suggestion}
+                    <span class="sr-only">{"Capo position"}</span>
+                </div>
+            </div>
+        }
+    }
+}
+
+fn build_capo(fret: u8) -> Option<Capo> {
+    if fret == 0 {
+        None
+    } else {
+        Capo::new(fret).ok()
+    }
+}
@@ -0,0 +1,44 @@
+use libchordr::models::diagram::Instrument;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ChordDiagramToolProps {
+    pub chord_diagrams: Option<Instrument>,
+    pub on_change: Callback<Option<Instrument>>,
+}
+
+pub struct ChordDiagramTool {}
+
+impl Component for ChordDiagramTool {
+    type Message = ();
+    type Properties = ChordDiagramToolProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let button = |instrument: Option<Instrument>, label: &'static str, title: &'static str| {
+            let mut class = vec!["discreet"];
+            if ctx.props().chord_diagrams == instrument {
+                class.push("-active");
+            }
+            let onclick = ctx.props().on_change.reform(move |_| instrument);
+
+            html! {
+                <button class={class} onclick={onclick} title={title}>{label}</button>
+            }
+        };
+
+        html! {
+            <div class="chord-diagram-tool">
+                <div title="Show chord diagrams" class="button-group -compact">
+                    {button(None, "–", "Hide chord diagrams")}
+                    {button(Some(Instrument::Guitar), "Gtr", "Guitar chord diagrams")}
+                    {button(Some(Instrument::Ukulele), "Uke", "Ukulele chord diagrams")}
+                    {button(Some(Instrument::Piano), "Key", "Piano chord diagrams")}
+                </div>
+            </div>
+        }
+    }
+}
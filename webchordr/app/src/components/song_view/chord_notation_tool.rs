@@ -0,0 +1,44 @@
+use libchordr::models::meta::ChordNotation;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ChordNotationToolProps {
+    pub chord_notation: ChordNotation,
+    pub on_change: Callback<ChordNotation>,
+}
+
+pub struct ChordNotationTool {}
+
+impl Component for ChordNotationTool {
+    type Message = ();
+    type Properties = ChordNotationToolProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let mut letter_class = vec!["discreet"];
+        let mut nashville_class = vec!["discreet"];
+        let mut roman_class = vec!["discreet"];
+        match ctx.props().chord_notation {
+            ChordNotation::Letter => letter_class.push("-active"),
+            ChordNotation::Nashville => nashville_class.push("-active"),
+            ChordNotation::Roman => roman_class.push("-active"),
+        }
+
+        let select_letter = ctx.props().on_change.reform(|_| ChordNotation::Letter);
+        let select_nashville = ctx.props().on_change.reform(|_| ChordNotation::Nashville);
+        let select_roman = ctx.props().on_change.reform(|_| ChordNotation::Roman);
+
+        html! {
+            <div class="chord-notation-tool">
+                <div title="Select the chord notation" class="button-group -compact">
+                    <button class={letter_class} onclick={select_letter} title="Chord names">{"C"}</button>
+                    <button class={nashville_class} onclick={select_nashville} title="Nashville numbers relative to the song's key">{"1"}</button>
+                    <button class={roman_class} onclick={select_roman} title="Roman numerals relative to the song's key">{"I"}</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use libchordr::prelude::Expansion;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ExpansionToolProps {
+    pub expansion: Expansion,
+    pub on_change: Callback<Expansion>,
+}
+
+pub struct ExpansionTool {}
+
+impl Component for ExpansionTool {
+    type Message = ();
+    type Properties = ExpansionToolProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let buttons = Expansion::get_all().iter().map(|expansion| {
+            let expansion = *expansion;
+            let class = if ctx.props().expansion == expansion {
+                vec!["discreet", "-active"]
+            } else {
+                vec!["discreet"]
+            };
+            let (label, title) = match expansion {
+                Expansion::None => ("›", "Show references as written"),
+                Expansion::Annotated => ("2x", "Inline references and annotate repeats"),
+                Expansion::Collapsed => ("∑", "Inline references and collapse consecutive repeats"),
+                Expansion::Full => ("»", "Write out every repeat in full"),
+            };
+            let select = ctx.props().on_change.reform(move |_| expansion);
+
+            html! {
+                <button class={class} onclick={select} title={title}>{label}</button>
+            }
+        });
+
+        html! {
+            <div class="expansion-tool">
+                <div title="Select how references and repeats are shown" class="button-group -compact">
+                    {for buttons}
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use libchordr::prelude::LanguageDisplay;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct LanguageToolProps {
+    /// Languages declared by the song (e.g. `German, English`)
+    pub languages: Option<String>,
+    pub language_display: LanguageDisplay,
+    pub on_change: Callback<LanguageDisplay>,
+}
+
+pub struct LanguageTool {}
+
+impl Component for LanguageTool {
+    type Message = ();
+    type Properties = LanguageToolProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let languages = match &ctx.props().languages {
+            Some(languages) => languages,
+            None => return html! {},
+        };
+        let mut names = languages.split(',').map(str::trim);
+        let primary = names.next().unwrap_or("Primary").to_owned();
+        let secondary = names.next().unwrap_or("Translation").to_owned();
+
+        let buttons = LanguageDisplay::get_all().iter().map(|language_display| {
+            let language_display = *language_display;
+            let class = if ctx.props().language_display == language_display {
+                vec!["discreet", "-active"]
+            } else {
+                vec!["discreet"]
+            };
+            let (label, title) = match language_display {
+                LanguageDisplay::Primary => ("1", format!("Show {}", primary)),
+                LanguageDisplay::Secondary => ("2", format!("Show {}", secondary)),
+                LanguageDisplay::Interleaved => {
+                    ("1+2", format!("Show {} and {}", primary, secondary))
+                }
+            };
+            let select = ctx.props().on_change.reform(move |_| language_display);
+
+            html! {
+                <button class={class} onclick={select} title={title}>{label}</button>
+            }
+        });
+
+        html! {
+            <div class="language-tool">
+                <div title="Select the languages to display" class="button-group -compact">
+                    {for buttons}
+                </div>
+            </div>
+        }
+    }
+}
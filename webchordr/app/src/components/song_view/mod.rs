@@ -7,8 +7,15 @@ use yew::virtual_dom::VNode;
 use yew::Component;
 
 use libchordr::models::song_settings::SongSettings;
+use libchordr::modification::transposition::TransposableTrait;
 use libchordr::prelude::*;
 
+use crate::components::song_view::arrangement_tool::ArrangementTool;
+use crate::components::song_view::capo_tool::CapoTool;
+use crate::components::song_view::chord_diagram_tool::ChordDiagramTool;
+use crate::components::song_view::chord_notation_tool::ChordNotationTool;
+use crate::components::song_view::expansion_tool::ExpansionTool;
+use crate::components::song_view::language_tool::LanguageTool;
 use crate::components::song_view::semitone_notation_tool::SemitoneNotationTool;
 use crate::state::SongInfo;
 
@@ -17,7 +24,13 @@ use self::setlist_tool::Setlist;
 pub use self::song_notes::SongNotes;
 use self::transpose_tool::TransposeTool;
 
+mod arrangement_tool;
+mod capo_tool;
+mod chord_diagram_tool;
+mod chord_notation_tool;
+mod expansion_tool;
 mod home_tool;
+mod language_tool;
 mod semitone_notation_tool;
 mod setlist_tool;
 mod song_notes;
@@ -51,6 +64,12 @@ pub enum Msg {
     TransposeSet(isize),
     SetlistChange(bool),
     SemitoneNotationChange(SemitoneNotation),
+    ChordNotationChange(ChordNotation),
+    CapoChange(Option<Capo>),
+    ChordDiagramsChange(Option<Instrument>),
+    ArrangementChange(Option<String>),
+    ExpansionChange(Expansion),
+    LanguageDisplayChange(LanguageDisplay),
 }
 
 pub struct SongView {}
@@ -73,6 +92,12 @@ impl Component for SongView {
             }
             Msg::TransposeSet(v) => self.change_transpose(ctx, v),
             Msg::SemitoneNotationChange(s) => self.change_semitone_notation(ctx, s),
+            Msg::ChordNotationChange(c) => self.change_chord_notation(ctx, c),
+            Msg::CapoChange(c) => self.change_capo(ctx, c),
+            Msg::ChordDiagramsChange(i) => self.change_chord_diagrams(ctx, i),
+            Msg::ArrangementChange(a) => self.change_arrangement(ctx, a),
+            Msg::ExpansionChange(e) => self.change_expansion(ctx, e),
+            Msg::LanguageDisplayChange(l) => self.change_language_display(ctx, l),
             Msg::SetlistChange(flag) => {
                 let song = &ctx.props().song_info.song;
                 info!("Set Song {} on setlist: {:?}", song.id(), flag);
@@ -98,7 +123,37 @@ impl Component for SongView {
         );
 
         let semitone_notation = ctx.props().song_info.song_settings.semitone_notation();
+        let chord_notation = ctx.props().song_info.song_settings.chord_notation();
         let transpose_semitone = ctx.props().song_info.song_settings.transpose_semitone();
+        let capo = ctx.props().song_info.song_settings.capo();
+        let chord_diagrams = ctx
+            .props()
+            .song_info
+            .song_settings
+            .formatting()
+            .chord_diagrams;
+        let arrangement = ctx
+            .props()
+            .song_info
+            .song_settings
+            .arrangement()
+            .map(ToOwned::to_owned);
+        let arrangements = ctx.props().song_info.song.meta().arrangements();
+        let expansion = ctx.props().song_info.song_settings.formatting().expansion;
+        let languages = ctx.props().song_info.song.meta().language();
+        let language_display = ctx
+            .props()
+            .song_info
+            .song_settings
+            .formatting()
+            .language_display;
+        let suggested_capo = ctx
+            .props()
+            .song_info
+            .song
+            .meta()
+            .key()
+            .map(|key| Capo::suggest(&key.transpose(transpose_semitone)));
 
         let detail = self.convert_song_to_html_node(ctx);
         let transpose_up = ctx.link().callback(|_| Msg::TransposeUp);
@@ -106,6 +161,12 @@ impl Component for SongView {
         let transpose_set = ctx.link().callback(Msg::TransposeSet);
         let setlist_change = ctx.link().callback(Msg::SetlistChange);
         let semitone_notation_set = ctx.link().callback(Msg::SemitoneNotationChange);
+        let chord_notation_set = ctx.link().callback(Msg::ChordNotationChange);
+        let capo_set = ctx.link().callback(Msg::CapoChange);
+        let chord_diagrams_set = ctx.link().callback(Msg::ChordDiagramsChange);
+        let arrangement_set = ctx.link().callback(Msg::ArrangementChange);
+        let expansion_set = ctx.link().callback(Msg::ExpansionChange);
+        let language_display_set = ctx.link().callback(Msg::LanguageDisplayChange);
 
         let setlist_tool = if ctx.props().enable_setlists {
             html! {
@@ -129,11 +190,38 @@ impl Component for SongView {
                         on_click_down={transpose_down}
                         on_set={transpose_set}
                     />
+                    <CapoTool
+                        capo={capo}
+                        suggested_capo={suggested_capo}
+                        on_change={capo_set}
+                    />
+                    <ArrangementTool
+                        arrangements={arrangements}
+                        arrangement={arrangement}
+                        on_change={arrangement_set}
+                    />
+                    <ExpansionTool
+                        expansion={expansion}
+                        on_change={expansion_set}
+                    />
+                    <LanguageTool
+                        languages={languages}
+                        language_display={language_display}
+                        on_change={language_display_set}
+                    />
                     {setlist_tool}
                     <SemitoneNotationTool
                         semitone_notation={semitone_notation}
                         on_change={semitone_notation_set}
                     />
+                    <ChordNotationTool
+                        chord_notation={chord_notation}
+                        on_change={chord_notation_set}
+                    />
+                    <ChordDiagramTool
+                        chord_diagrams={chord_diagrams}
+                        on_change={chord_diagrams_set}
+                    />
                 </div>
             </div>
         }
@@ -151,24 +239,13 @@ impl SongView {
         use chrono::Utc;
 
         let props = &ctx.props();
-        let transpose_semitone = props.song_info.song_settings.transpose_semitone();
-        let formatting = props.song_info.song_settings.formatting();
 
         let start = Utc::now().time();
-        let converter_result = if transpose_semitone != 0 {
-            transpose_and_convert_to_format(
-                props.song_info.song.src().as_bytes(),
-                transpose_semitone,
-                props.song_info.song.meta(),
-                formatting,
-            )
-        } else {
-            convert_to_format(
-                props.song_info.song.src().as_bytes(),
-                props.song_info.song.meta(),
-                formatting,
-            )
-        };
+        let converter_result = convert_with_settings(
+            props.song_info.song.src().as_bytes(),
+            props.song_info.song.file_type(),
+            &props.song_info.song_settings,
+        );
         let end = Utc::now().time();
         debug!(
             "Converted the song in {:?}ms",
@@ -229,4 +306,95 @@ impl SongView {
                 .with_formatting(formatting),
         );
     }
+
+    fn change_chord_notation(&mut self, ctx: &Context<Self>, c: ChordNotation) {
+        let formatting = Formatting {
+            chord_notation: c,
+            ..ctx.props().song_info.song_settings.formatting()
+        };
+
+        info!("Change formatting to {:?}", formatting);
+        self.send_change(
+            ctx,
+            ctx.props()
+                .song_info
+                .song_settings
+                .with_formatting(formatting),
+        );
+    }
+
+    fn change_capo(&mut self, ctx: &Context<Self>, capo: Option<Capo>) {
+        let formatting = Formatting {
+            capo,
+            ..ctx.props().song_info.song_settings.formatting()
+        };
+
+        info!("Change formatting to {:?}", formatting);
+        self.send_change(
+            ctx,
+            ctx.props()
+                .song_info
+                .song_settings
+                .with_formatting(formatting),
+        );
+    }
+
+    fn change_chord_diagrams(&mut self, ctx: &Context<Self>, chord_diagrams: Option<Instrument>) {
+        let formatting = Formatting {
+            chord_diagrams,
+            ..ctx.props().song_info.song_settings.formatting()
+        };
+
+        info!("Change formatting to {:?}", formatting);
+        self.send_change(
+            ctx,
+            ctx.props()
+                .song_info
+                .song_settings
+                .with_formatting(formatting),
+        );
+    }
+
+    fn change_expansion(&mut self, ctx: &Context<Self>, expansion: Expansion) {
+        let formatting = Formatting {
+            expansion,
+            ..ctx.props().song_info.song_settings.formatting()
+        };
+
+        info!("Change formatting to {:?}", formatting);
+        self.send_change(
+            ctx,
+            ctx.props()
+                .song_info
+                .song_settings
+                .with_formatting(formatting),
+        );
+    }
+
+    fn change_language_display(&mut self, ctx: &Context<Self>, language_display: LanguageDisplay) {
+        let formatting = Formatting {
+            language_display,
+            ..ctx.props().song_info.song_settings.formatting()
+        };
+
+        info!("Change formatting to {:?}", formatting);
+        self.send_change(
+            ctx,
+            ctx.props()
+                .song_info
+                .song_settings
+                .with_formatting(formatting),
+        );
+    }
+
+    fn change_arrangement(&mut self, ctx: &Context<Self>, arrangement: Option<String>) {
+        info!("Change arrangement to {:?}", arrangement);
+        self.send_change(
+            ctx,
+            ctx.props()
+                .song_info
+                .song_settings
+                .with_arrangement(arrangement),
+        );
+    }
 }
@@ -18,20 +18,23 @@ impl Component for SemitoneNotationTool {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let mut auto_class = vec!["discreet"];
         let mut sharp_class = vec!["discreet"];
         let mut flat_class = vec!["discreet"];
-        if ctx.props().semitone_notation == SemitoneNotation::Sharp {
-            sharp_class.push("-active");
-        } else {
-            flat_class.push("-active");
+        match ctx.props().semitone_notation {
+            SemitoneNotation::Auto => auto_class.push("-active"),
+            SemitoneNotation::Sharp => sharp_class.push("-active"),
+            SemitoneNotation::Flat => flat_class.push("-active"),
         }
 
+        let select_auto = ctx.props().on_change.reform(|_| SemitoneNotation::Auto);
         let select_sharp = ctx.props().on_change.reform(|_| SemitoneNotation::Sharp);
         let select_flat = ctx.props().on_change.reform(|_| SemitoneNotation::Flat);
 
         html! {
             <div class="semitone-notation-tool">
                 <div title="Select the semitone notation" class="button-group -compact">
+                    <button class={auto_class} onclick={select_auto} title="Use the notation of the song's key">{SemitoneNotation::Auto.to_string()}</button>
                     <button class={sharp_class} onclick={select_sharp}>{SemitoneNotation::Sharp.to_string()}</button>
                     <button class={flat_class} onclick={select_flat}>{SemitoneNotation::Flat.to_string()}</button>
                 </div>
@@ -2,7 +2,7 @@ use self::bs::BrowserStorageBackend;
 use crate::browser_storage::BrowserStorage;
 use crate::fetch_helper::fetch;
 use crate::WebError;
-use libchordr::prelude::Catalog;
+use libchordr::prelude::{Catalog, CatalogPatch, CatalogTrait};
 use webchordr_common::tri::Tri;
 
 pub struct CatalogWebRepository {
@@ -30,7 +30,43 @@ impl CatalogWebRepository {
         }
     }
 
+    /// Fetch the changes since the revision of the given `Catalog` and apply them
+    ///
+    /// Return `None` if the server does not provide deltas or does not know the revision
+    async fn fetch_catalog_delta(&self, catalog: &Catalog) -> Option<Catalog> {
+        let uri = format!(
+            "/catalog-delta.json?since={}",
+            js_sys::encode_uri_component(&catalog.revision())
+        );
+        let patch = match fetch::<CatalogPatch>(&uri).await {
+            Ok(patch) => patch,
+            Err(error) => {
+                log::debug!("Could not fetch the catalog delta: {}", error);
+                return None;
+            }
+        };
+
+        match catalog.apply(&patch) {
+            Ok(catalog) => Some(catalog),
+            Err(error) => {
+                log::warn!("Could not apply the catalog delta: {}", error);
+                None
+            }
+        }
+    }
+
     pub async fn load(&mut self) -> Tri<Catalog, WebError> {
+        // Only download the changes if a Catalog has been stored before
+        if let Tri::Some(stored) = self.backend.load() {
+            if let Some(c) = self.fetch_catalog_delta(&stored).await {
+                if c.revision() != stored.revision() {
+                    let _ = self.backend.store(&c);
+                }
+
+                return Tri::Some(c);
+            }
+        }
+
         match self.fetch_catalog(true).await {
             Tri::Some(c) => {
                 // Store/cache the loaded Catalog